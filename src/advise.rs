@@ -0,0 +1,72 @@
+use std::io;
+
+use crate::Zerometry;
+
+impl Zerometry<'_> {
+    /// Hint the OS that the bytes of the shape are going to be read sequentially, with `madvise`.
+    /// When the zerometry is read from a memory-mapped file it lets the OS read ahead more aggressively during
+    /// the bulk scans of an index. The hint is applied to all the pages the shape is stored on.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::Point;
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Point::new(2.35, 48.85).into()).unwrap();
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    /// zerometry.advise_sequential().unwrap();
+    /// ```
+    pub fn advise_sequential(&self) -> io::Result<()> {
+        advise(self.as_bytes(), libc::MADV_SEQUENTIAL)
+    }
+
+    /// Hint the OS that the bytes of the shape are going to be read in a random order, with `madvise`.
+    /// When the zerometry is read from a memory-mapped file it stops the OS from reading ahead pages that
+    /// won't be used, which is what happens when only a few shapes of an index are related.
+    pub fn advise_random(&self) -> io::Result<()> {
+        advise(self.as_bytes(), libc::MADV_RANDOM)
+    }
+}
+
+/// Call `madvise` on the pages containing the bytes, `madvise` only accepts an address aligned on a page.
+fn advise(bytes: &[u8], advice: libc::c_int) -> io::Result<()> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    // SAFETY: sysconf doesn't have any precondition
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let start = bytes.as_ptr() as usize;
+    let page_start = start - start % page_size;
+    let len = start + bytes.len() - page_start;
+    // SAFETY: These advices never change the content of the memory, only how it's paged in
+    let ret = unsafe { libc::madvise(page_start as *mut libc::c_void, len, advice) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString};
+
+    use super::*;
+
+    #[test]
+    fn advise_the_pages() {
+        // Large enough to span several pages
+        let line = LineString::from((0..1000).map(|i| (i as f64 / 10., 0.)).collect::<Vec<_>>());
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::LineString(line)).unwrap();
+        let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+        zerometry.advise_sequential().unwrap();
+        zerometry.advise_random().unwrap();
+
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::LineString(LineString::new(vec![])))
+            .unwrap();
+        let empty = Zerometry::try_from_bytes(&buffer).unwrap();
+        empty.advise_random().unwrap();
+    }
+}
@@ -0,0 +1,321 @@
+//! Implementation of the [`geo`] algorithm traits so generic code can be written over geo and zerometry types.
+//!
+//! The area and length are computed directly on the serialized coords. The centroids are computed by
+//! converting the shape back to geo since there is no way to do it without allocating anyway.
+//! The length is exposed through [`geo::line_measures::LengthMeasurable`], the replacement of the deprecated
+//! `EuclideanLength` trait, which means you can call `Euclidean.length(&zine)`.
+//!
+//! For coordinates expressed in degrees, the geodesic measures are also available: the great-circle length
+//! is obtained with `Haversine.length(&zine)`, and the spherical-excess area in square meters with the
+//! [`geo::ChamberlainDuquetteArea`] trait.
+
+use geo::line_measures::LengthMeasurable;
+use geo::{Area, Centroid, ChamberlainDuquetteArea, Distance, Point};
+
+use crate::{
+    Coords, Zerometry, Zine, Zoint, Zollection, Zolygon, ZultiLines, ZultiPoints, ZultiPolygons,
+};
+
+/// Twice the signed area of a ring with the shoelace formula.
+fn twice_signed_ring_area(coords: &Coords) -> f64 {
+    coords
+        .iter()
+        .zip(coords.iter().skip(1))
+        .map(|(a, b)| a.lng() * b.lat() - b.lng() * a.lat())
+        .sum()
+}
+
+/// Same value as the private `geo::EQUATORIAL_EARTH_RADIUS` used by geo for the [`ChamberlainDuquetteArea`].
+const EQUATORIAL_EARTH_RADIUS: f64 = 6_378_137.0;
+
+/// Signed spherical-excess area of a closed ring in square meters, ported from geo to work on the [`Coords`].
+fn spherical_ring_area(coords: &Coords) -> f64 {
+    let len = coords.len();
+    if len <= 2 {
+        return 0.;
+    }
+    let total: f64 = (0..len)
+        .map(|i| {
+            let lower = &coords[i];
+            let middle = &coords[(i + 1) % len];
+            let upper = &coords[(i + 2) % len];
+            (upper.lng().to_radians() - lower.lng().to_radians()) * middle.lat().to_radians().sin()
+        })
+        .sum();
+    total * EQUATORIAL_EARTH_RADIUS * EQUATORIAL_EARTH_RADIUS / -2.
+}
+
+fn line_length(coords: &Coords, metric_space: &impl Distance<f64, Point<f64>, Point<f64>>) -> f64 {
+    coords
+        .iter()
+        .zip(coords.iter().skip(1))
+        .map(|(a, b)| metric_space.distance(Point::from(a.to_geo()), Point::from(b.to_geo())))
+        .sum()
+}
+
+impl Area<f64> for Zolygon<'_> {
+    fn signed_area(&self) -> f64 {
+        twice_signed_ring_area(self.coords()) / 2.
+    }
+
+    fn unsigned_area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+}
+
+impl Area<f64> for ZultiPolygons<'_> {
+    fn signed_area(&self) -> f64 {
+        self.polygons().map(|polygon| polygon.signed_area()).sum()
+    }
+
+    fn unsigned_area(&self) -> f64 {
+        self.polygons().map(|polygon| polygon.unsigned_area()).sum()
+    }
+}
+
+impl Area<f64> for Zollection<'_> {
+    fn signed_area(&self) -> f64 {
+        self.polygons().signed_area()
+    }
+
+    fn unsigned_area(&self) -> f64 {
+        self.polygons().unsigned_area()
+    }
+}
+
+impl Area<f64> for Zerometry<'_> {
+    fn signed_area(&self) -> f64 {
+        match self {
+            Zerometry::Point(_)
+            | Zerometry::MultiPoints(_)
+            | Zerometry::Line(_)
+            | Zerometry::MultiLines(_) => 0.,
+            Zerometry::Polygon(a) => a.signed_area(),
+            Zerometry::MultiPolygon(a) => a.signed_area(),
+            Zerometry::Collection(a) => a.signed_area(),
+        }
+    }
+
+    fn unsigned_area(&self) -> f64 {
+        match self {
+            Zerometry::Point(_)
+            | Zerometry::MultiPoints(_)
+            | Zerometry::Line(_)
+            | Zerometry::MultiLines(_) => 0.,
+            Zerometry::Polygon(a) => a.unsigned_area(),
+            Zerometry::MultiPolygon(a) => a.unsigned_area(),
+            Zerometry::Collection(a) => a.unsigned_area(),
+        }
+    }
+}
+
+impl ChamberlainDuquetteArea<f64> for Zolygon<'_> {
+    fn chamberlain_duquette_signed_area(&self) -> f64 {
+        spherical_ring_area(self.coords())
+    }
+
+    fn chamberlain_duquette_unsigned_area(&self) -> f64 {
+        self.chamberlain_duquette_signed_area().abs()
+    }
+}
+
+impl ChamberlainDuquetteArea<f64> for ZultiPolygons<'_> {
+    fn chamberlain_duquette_signed_area(&self) -> f64 {
+        self.polygons()
+            .map(|polygon| polygon.chamberlain_duquette_signed_area())
+            .sum()
+    }
+
+    fn chamberlain_duquette_unsigned_area(&self) -> f64 {
+        self.polygons()
+            .map(|polygon| polygon.chamberlain_duquette_unsigned_area())
+            .sum()
+    }
+}
+
+impl ChamberlainDuquetteArea<f64> for Zollection<'_> {
+    fn chamberlain_duquette_signed_area(&self) -> f64 {
+        self.polygons().chamberlain_duquette_signed_area()
+    }
+
+    fn chamberlain_duquette_unsigned_area(&self) -> f64 {
+        self.polygons().chamberlain_duquette_unsigned_area()
+    }
+}
+
+impl ChamberlainDuquetteArea<f64> for Zerometry<'_> {
+    fn chamberlain_duquette_signed_area(&self) -> f64 {
+        match self {
+            Zerometry::Point(_)
+            | Zerometry::MultiPoints(_)
+            | Zerometry::Line(_)
+            | Zerometry::MultiLines(_) => 0.,
+            Zerometry::Polygon(a) => a.chamberlain_duquette_signed_area(),
+            Zerometry::MultiPolygon(a) => a.chamberlain_duquette_signed_area(),
+            Zerometry::Collection(a) => a.chamberlain_duquette_signed_area(),
+        }
+    }
+
+    fn chamberlain_duquette_unsigned_area(&self) -> f64 {
+        match self {
+            Zerometry::Point(_)
+            | Zerometry::MultiPoints(_)
+            | Zerometry::Line(_)
+            | Zerometry::MultiLines(_) => 0.,
+            Zerometry::Polygon(a) => a.chamberlain_duquette_unsigned_area(),
+            Zerometry::MultiPolygon(a) => a.chamberlain_duquette_unsigned_area(),
+            Zerometry::Collection(a) => a.chamberlain_duquette_unsigned_area(),
+        }
+    }
+}
+
+impl LengthMeasurable<f64> for Zine<'_> {
+    fn length(&self, metric_space: &impl Distance<f64, Point<f64>, Point<f64>>) -> f64 {
+        line_length(self.coords(), metric_space)
+    }
+}
+
+impl LengthMeasurable<f64> for ZultiLines<'_> {
+    fn length(&self, metric_space: &impl Distance<f64, Point<f64>, Point<f64>>) -> f64 {
+        self.lines().map(|line| line.length(metric_space)).sum()
+    }
+}
+
+impl Centroid for Zoint<'_> {
+    type Output = Point<f64>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo()
+    }
+}
+
+impl Centroid for ZultiPoints<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+impl Centroid for Zine<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+impl Centroid for ZultiLines<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+impl Centroid for Zolygon<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+impl Centroid for ZultiPolygons<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+impl Centroid for Zollection<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+impl Centroid for Zerometry<'_> {
+    type Output = Option<Point<f64>>;
+
+    fn centroid(&self) -> Self::Output {
+        self.to_geo().centroid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Euclidean, Haversine, Length, MultiPolygon, line_string, polygon};
+    use geo_types::{Geometry, MultiLineString};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn area_matches_geo() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 3.), (x: 0., y: 3.)];
+        let reversed = polygon![(x: 0., y: 0.), (x: 0., y: 2.), (x: 2., y: 2.), (x: 2., y: 0.)];
+        let multi = MultiPolygon::new(vec![polygon, reversed]);
+
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::MultiPolygon(multi.clone()))
+            .unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+        assert_eq!(zerometry.signed_area(), multi.signed_area());
+        assert_eq!(zerometry.unsigned_area(), multi.unsigned_area());
+        assert_compact_debug_snapshot!((zerometry.signed_area(), zerometry.unsigned_area()), @"(8.0, 16.0)");
+        assert_compact_debug_snapshot!(zerometry.centroid(), @"Some(POINT(1.75 1.375))");
+    }
+
+    #[test]
+    fn length_matches_geo() {
+        let lines = MultiLineString::new(vec![
+            line_string![(x: 0., y: 0.), (x: 3., y: 4.)],
+            line_string![(x: 0., y: 0.), (x: 0., y: 1.), (x: 1., y: 1.)],
+        ]);
+        let mut buffer = Vec::new();
+        ZultiLines::write_from_geometry(&mut buffer, &lines).unwrap();
+        let zulti_lines = unsafe { ZultiLines::from_bytes(&buffer) };
+
+        assert_eq!(Euclidean.length(&zulti_lines), Euclidean.length(&lines));
+        assert_compact_debug_snapshot!(Euclidean.length(&zulti_lines), @"7.0");
+        assert_compact_debug_snapshot!(Euclidean.length(&zulti_lines.get(0).unwrap()), @"5.0");
+    }
+
+    #[test]
+    fn geodesic_area_of_known_states() {
+        // Colorado and Wyoming are bounded by parallels and meridians, their official total areas
+        // are 269,837 km² and 253,335 km².
+        let colorado = polygon![(x: -109.05, y: 37.), (x: -102.05, y: 37.), (x: -102.05, y: 41.), (x: -109.05, y: 41.)];
+        let wyoming = polygon![(x: -111.05, y: 41.), (x: -111.05, y: 45.), (x: -104.05, y: 45.), (x: -104.05, y: 41.)];
+
+        for (polygon, expected) in [(colorado, 269_837e6), (wyoming, 253_335e6)] {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(polygon.clone()))
+                .unwrap();
+            let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+            assert_eq!(
+                zerometry.chamberlain_duquette_signed_area(),
+                polygon.chamberlain_duquette_signed_area()
+            );
+            let area = zerometry.chamberlain_duquette_unsigned_area();
+            assert!((area - expected).abs() / expected < 0.005, "{area}");
+        }
+    }
+
+    #[test]
+    fn great_circle_length() {
+        // From Paris to London
+        let line = line_string![(x: 2.3522, y: 48.8566), (x: -0.1276, y: 51.5072)];
+        let mut buffer = Vec::new();
+        Zine::write_from_geometry(&mut buffer, &line).unwrap();
+        let zine = unsafe { Zine::from_bytes(&buffer) };
+
+        assert_eq!(Haversine.length(&zine), Haversine.length(&line));
+        assert_compact_debug_snapshot!(Haversine.length(&zine).round(), @"343530.0");
+    }
+}
@@ -0,0 +1,278 @@
+use std::{fmt, mem, ops::Deref, slice};
+
+use bytemuck::cast_slice;
+
+use crate::{
+    Zerometry, ZerometryError,
+    endianness::{Direction, swap_byte_order},
+    error::check_aligned,
+    header::{is_foreign, to_native_tag},
+};
+
+/// Bytes guaranteed to be aligned on 64 bits, as required by [`Zerometry::from_bytes`].
+///
+/// The bytes coming from a memory map or a database are not always aligned. [`Self::new`] borrows the data
+/// when it's already aligned and only copies it in an aligned allocation when it's not.
+/// On big-endian platforms, the zerometries written with [`crate::WriteOptions::little_endian`] are also
+/// copied and converted to the native endianness.
+///
+/// ```
+/// use zerometry::{AlignedBytes, Zerometry};
+/// use geo_types::{Geometry, Point};
+///
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(1.0, 2.0))).unwrap();
+/// // Shift the zerometry by one byte to lose the alignment
+/// let mut unaligned = vec![0];
+/// unaligned.extend_from_slice(&buffer);
+///
+/// let aligned = AlignedBytes::new(&unaligned[1..]);
+/// assert!(aligned.is_copied());
+/// let zerometry = unsafe { Zerometry::from_bytes(&aligned).unwrap() };
+/// assert_eq!(zerometry.to_geo(), Geometry::Point(Point::new(1.0, 2.0)));
+/// ```
+#[derive(Clone)]
+pub struct AlignedBytes<'a> {
+    inner: Inner<'a>,
+}
+
+#[derive(Clone)]
+enum Inner<'a> {
+    Borrowed(&'a [u8]),
+    // The words are padded with zeros, only the first `len` bytes are exposed
+    Copied { words: Vec<u64>, len: usize },
+}
+
+impl<'a> AlignedBytes<'a> {
+    /// Borrow the data if it's aligned on 64 bits, or copy it in an aligned allocation otherwise.
+    /// A little-endian zerometry is always copied and converted on a big-endian platform.
+    pub fn new(data: &'a [u8]) -> Self {
+        let inner = if is_foreign(data) {
+            Inner::convert(data)
+        } else if check_aligned(data).is_ok() {
+            Inner::Borrowed(data)
+        } else {
+            Inner::copy(data)
+        };
+        Self { inner }
+    }
+
+    /// Return `true` if the data was not aligned or not in the native endianness and had to be copied.
+    #[inline]
+    pub fn is_copied(&self) -> bool {
+        matches!(self.inner, Inner::Copied { .. })
+    }
+
+    /// Return the aligned bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.inner {
+            Inner::Borrowed(data) => data,
+            Inner::Copied { words, len } => &cast_slice(words)[..*len],
+        }
+    }
+
+    /// Copy the data if it was borrowed, to get rid of the lifetime.
+    pub fn into_owned(self) -> AlignedBytes<'static> {
+        let inner = match self.inner {
+            Inner::Borrowed(data) => Inner::copy(data),
+            Inner::Copied { words, len } => Inner::Copied { words, len },
+        };
+        AlignedBytes { inner }
+    }
+}
+
+impl Inner<'_> {
+    /// Copy a little-endian zerometry and convert it to the native endianness.
+    /// If it's corrupted it's copied as-is and the error will be returned when reading it.
+    fn convert(data: &[u8]) -> Inner<'static> {
+        let mut inner = Inner::copy(data);
+        if let Inner::Copied { words, len } = &mut inner {
+            let bytes = &mut bytemuck::cast_slice_mut::<u64, u8>(words)[..*len];
+            if swap_byte_order(bytes, Direction::ToNative).is_err() {
+                return Inner::copy(data);
+            }
+            words[0] = to_native_tag(words[0]);
+        }
+        inner
+    }
+
+    fn copy(data: &[u8]) -> Inner<'static> {
+        let mut words = vec![0_u64; data.len().div_ceil(mem::size_of::<u64>())];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..data.len()].copy_from_slice(data);
+        Inner::Copied {
+            words,
+            len: data.len(),
+        }
+    }
+}
+
+impl Deref for AlignedBytes<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for AlignedBytes<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for AlignedBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlignedBytes")
+            .field("copied", &self.is_copied())
+            .field("len", &self.as_bytes().len())
+            .finish()
+    }
+}
+
+impl<'a> Zerometry<'a> {
+    /// Same as [`Self::from_bytes`] but the data doesn't need to be aligned on 64 bits.
+    /// If it's not, it's copied in the `storage` first, which must outlive the returned zerometry.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(1.0, 2.0))).unwrap();
+    /// let mut unaligned = vec![0];
+    /// unaligned.extend_from_slice(&buffer);
+    ///
+    /// let mut storage = None;
+    /// let zerometry = unsafe { Zerometry::from_bytes_copy_if_unaligned(&unaligned[1..], &mut storage).unwrap() };
+    /// assert_eq!(zerometry.to_geo(), Geometry::Point(Point::new(1.0, 2.0)));
+    /// ```
+    ///
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method
+    pub unsafe fn from_bytes_copy_if_unaligned<'b: 'a>(
+        data: &'b [u8],
+        storage: &'a mut Option<AlignedBytes<'b>>,
+    ) -> Result<Self, ZerometryError> {
+        let aligned = storage.insert(AlignedBytes::new(data));
+        unsafe { Self::from_bytes(aligned.as_bytes()) }
+    }
+}
+
+/// Return the bytes going from the start of `first` to the end of `last`, followed by `padding` bytes.
+/// It's used to give back the bytes a shape was read from, since the shapes only keep their parts.
+///
+/// # Panics
+/// If `last` doesn't end exactly `len` bytes after the start of `first`, which happens when a shape is built
+/// with one of the `new` constructors from parts that don't follow each other in memory.
+pub(crate) fn contiguous<'a, F: ?Sized, L: ?Sized>(
+    first: &'a F,
+    last: &'a L,
+    len: usize,
+    padding: usize,
+) -> &'a [u8] {
+    let start = (first as *const F).cast::<u8>();
+    let end = (last as *const L)
+        .cast::<u8>()
+        .wrapping_add(mem::size_of_val(last));
+    assert_eq!(
+        start.wrapping_add(len),
+        end,
+        "The shape was not built from contiguous bytes"
+    );
+    // SAFETY: The parts were borrowed from the same slice of bytes, of at least `len + padding` bytes
+    unsafe { slice::from_raw_parts(start, len + padding) }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn borrow_or_copy() {
+        let words = [
+            u64::from_ne_bytes(*b"zerometr"),
+            u64::from_ne_bytes(*b"y......."),
+        ];
+        let data: &[u8] = cast_slice(&words);
+
+        let aligned = AlignedBytes::new(&data[..9]);
+        assert_compact_debug_snapshot!(aligned, @"AlignedBytes { copied: false, len: 9 }");
+        assert_eq!(aligned.as_ptr(), data.as_ptr());
+
+        let copied = AlignedBytes::new(&data[1..]);
+        assert_compact_debug_snapshot!(copied, @"AlignedBytes { copied: true, len: 15 }");
+        assert_eq!(copied.as_ptr() as usize % mem::size_of::<u64>(), 0);
+        assert_eq!(&*copied, &data[1..]);
+
+        let owned = aligned.into_owned();
+        assert_compact_debug_snapshot!((owned.is_copied(), std::str::from_utf8(&owned).unwrap()), @r#"(true, "zerometry")"#);
+    }
+
+    #[test]
+    fn shapes_as_bytes() {
+        use geo_types::{
+            Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+            Point, polygon,
+        };
+
+        use crate::WriteOptions;
+
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let points = MultiPoint::from(vec![
+            Point::new(3.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+        ]);
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            points.clone().into(),
+            line.clone().into(),
+            MultiLineString::new(vec![line.clone(), line.clone()]).into(),
+            square.clone().into(),
+            MultiPolygon::new(vec![square.clone()]).into(),
+            MultiPolygon::new(Vec::new()).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                points.clone().into(),
+                line.into(),
+                square.into(),
+            ])),
+        ];
+        let sorted = WriteOptions {
+            sort_points: true,
+            ..WriteOptions::default()
+        };
+        for (geometry, options) in geometries
+            .iter()
+            .map(|geometry| (geometry, WriteOptions::default()))
+            .chain([(&geometries[1], sorted)])
+        {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, geometry, options).unwrap();
+            let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+            // Skip the tag
+            assert_eq!(zerometry.as_bytes(), &buffer[8..], "{geometry:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The shape was not built from contiguous bytes")]
+    fn as_bytes_on_scattered_parts() {
+        use crate::{BoundingBox, Coords, Zine};
+
+        // There is a gap between the bounding box and the coords
+        let data = [0.0, 0.0, 1.0, 1.0, 9.0, 9.0, 0.0, 0.0, 1.0, 1.0];
+        let zine = unsafe {
+            Zine::new(
+                BoundingBox::from_slice(&data[..4]),
+                Coords::from_slice(&data[6..]),
+            )
+        };
+        zine.as_bytes();
+    }
+}
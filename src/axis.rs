@@ -0,0 +1,145 @@
+use geo::CoordsIter;
+use geo_types::Geometry;
+
+use crate::{Zerometry, ZerometryError};
+
+/// The order of the axes in a pair of numbers representing a coordinate.
+///
+/// Zerometry always stores and exposes the coordinates in the `lng, lat` order, which is the
+/// same as the `x, y` order of [`geo_types`]: the longitude is the `x` and the latitude is the `y`.
+/// Everywhere in the crate, `lng()` and `x()` return the same value, as well as `lat()` and `y()`,
+/// and the [`fmt::Debug`](std::fmt::Debug) implementations always name them `lng` and `lat`.
+///
+/// This type helps converting coordinates coming from, or going to, formats using the other convention.
+///
+/// ```
+/// use zerometry::AxisOrder;
+///
+/// // GeoRSS stores the coordinates as `lat lng`
+/// let coord = AxisOrder::LatLng.to_geo((45.256, -71.92));
+/// assert_eq!(coord, geo_types::coord! { x: -71.92, y: 45.256 });
+/// assert_eq!(AxisOrder::LatLng.from_lng_lat((coord.x, coord.y)), (45.256, -71.92));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AxisOrder {
+    /// The longitude, or `x`, comes first. That's the convention used by zerometry, geo and GeoJSON.
+    #[default]
+    LngLat,
+    /// The latitude, or `y`, comes first. That's the convention used by GeoRSS and many GML feeds.
+    LatLng,
+}
+
+impl AxisOrder {
+    /// Reorder a pair of numbers following `self` to the `lng, lat` order.
+    #[inline]
+    pub fn to_lng_lat(self, (first, second): (f64, f64)) -> (f64, f64) {
+        match self {
+            AxisOrder::LngLat => (first, second),
+            AxisOrder::LatLng => (second, first),
+        }
+    }
+
+    /// Reorder a `lng, lat` pair of numbers following `self`.
+    #[inline]
+    pub fn from_lng_lat(self, (lng, lat): (f64, f64)) -> (f64, f64) {
+        match self {
+            AxisOrder::LngLat => (lng, lat),
+            AxisOrder::LatLng => (lat, lng),
+        }
+    }
+
+    /// Convert a pair of numbers following `self` to a [`geo_types::Coord`].
+    #[inline]
+    pub fn to_geo(self, pair: (f64, f64)) -> geo_types::Coord<f64> {
+        let (x, y) = self.to_lng_lat(pair);
+        geo_types::Coord { x, y }
+    }
+}
+
+impl Zerometry<'_> {
+    /// Check that every coordinate of the geometry is in the `lng, lat` order required by GeoJSON (RFC 7946),
+    /// by checking that the longitudes are in `[-180, 180]` and the latitudes in `[-90, 90]`.
+    ///
+    /// When some coordinates are out of range but all of them would fit once swapped, a
+    /// [`ZerometryError::SwappedLngLat`] is returned since the geometry was most likely written in the `lat, lng` order.
+    /// Otherwise a [`ZerometryError::InvalidLngLat`] is returned with the first coordinate out of range.
+    /// Note that the swapped coordinates can only be detected when their latitude, once swapped, is out of range.
+    ///
+    /// It's ran on every write with [`crate::WriteOptions::check_lng_lat`].
+    ///
+    /// ```
+    /// use zerometry::{Zerometry, ZerometryError};
+    /// use geo_types::{Geometry, LineString};
+    ///
+    /// // A line in Sydney, written in the `lat, lng` order
+    /// let line = LineString::from(vec![(-33.87, 151.21), (-33.86, 151.2)]);
+    /// let err = Zerometry::check_lng_lat(&Geometry::LineString(line)).unwrap_err();
+    /// assert!(matches!(err, ZerometryError::SwappedLngLat { lng: -33.87, lat: 151.21 }));
+    /// ```
+    pub fn check_lng_lat(geometry: &Geometry<f64>) -> Result<(), ZerometryError> {
+        fn is_valid(lng: f64, lat: f64) -> bool {
+            (-180.0..=180.0).contains(&lng) && (-90.0..=90.0).contains(&lat)
+        }
+
+        let Some(invalid) = geometry
+            .coords_iter()
+            .find(|coord| !is_valid(coord.x, coord.y))
+        else {
+            return Ok(());
+        };
+        let (lng, lat) = invalid.x_y();
+        if geometry
+            .coords_iter()
+            .all(|coord| is_valid(coord.y, coord.x))
+        {
+            Err(ZerometryError::SwappedLngLat { lng, lat })
+        } else {
+            Err(ZerometryError::InvalidLngLat { lng, lat })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{MultiPoint, Point};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for order in [AxisOrder::LngLat, AxisOrder::LatLng] {
+            let pair = (1.0, 2.0);
+            assert_eq!(order.from_lng_lat(order.to_lng_lat(pair)), pair);
+        }
+        assert_eq!(AxisOrder::default().to_lng_lat((1.0, 2.0)), (1.0, 2.0));
+        assert_eq!(AxisOrder::LatLng.to_lng_lat((1.0, 2.0)), (2.0, 1.0));
+    }
+
+    #[test]
+    fn check_lng_lat() {
+        let check = |points: Vec<(f64, f64)>| {
+            Zerometry::check_lng_lat(&Geometry::MultiPoint(MultiPoint::from(points)))
+        };
+        assert_compact_debug_snapshot!(check(vec![(-180.0, -90.0), (180.0, 90.0)]), @"Ok(())");
+        // Paris and Sydney in the `lat, lng` order
+        assert_compact_debug_snapshot!(check(vec![(48.85, 2.35), (-33.87, 151.21)]), @"Err(SwappedLngLat { lng: -33.87, lat: 151.21 })");
+        // Out of range in both orders
+        assert_compact_debug_snapshot!(check(vec![(48.85, 2.35), (200.0, 10.0)]), @"Err(InvalidLngLat { lng: 200.0, lat: 10.0 })");
+        assert_compact_debug_snapshot!(check(vec![(f64::NAN, 0.0)]), @"Err(InvalidLngLat { lng: NaN, lat: 0.0 })");
+
+        let point = Geometry::Point(Point::new(2.35, 148.85));
+        let err = Zerometry::write_from_geometry_with_options(
+            &mut Vec::new(),
+            &point,
+            crate::WriteOptions {
+                check_lng_lat: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert_compact_debug_snapshot!(err, @"SwappedLngLat { lng: 2.35, lat: 148.85 }");
+        // Without the option the coordinates are written as-is, they may be planar
+        Zerometry::write_from_geometry(&mut Vec::new(), &point).unwrap();
+    }
+}
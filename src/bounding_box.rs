@@ -1,5 +1,6 @@
 use core::fmt;
 use std::{
+    cmp::Ordering,
     io::{self, Write},
     mem,
     ops::RangeInclusive,
@@ -8,10 +9,30 @@ use std::{
 use geo_types::Point;
 
 use crate::{
-    COORD_SIZE_IN_BYTES, Coord, Coords, InputRelation, OutputRelation, RelationBetweenShapes,
+    AxisOrder, COORD_SIZE_IN_BYTES, Coord, Coords, InputRelation, Interval, OutputRelation,
+    RelationBetweenShapes, Segment,
 };
 
-pub(crate) const BOUNDING_BOX_SIZE_IN_BYTES: usize = COORD_SIZE_IN_BYTES * 2;
+/// The number of bytes used by a [`BoundingBox`]: its bottom left corner followed by its top right corner.
+/// Every shape except the points starts with its bounding box, right after the header.
+pub const BOUNDING_BOX_SIZE_IN_BYTES: usize = COORD_SIZE_IN_BYTES * 2;
+
+/// The bounding box written for the shapes without any coordinate, see [`BoundingBox::is_empty`].
+/// Its corners are infinite and inverted: it contains nothing and vanishes when merged with another bounding box
+/// by taking the `min` of the bottom left corners and the `max` of the top right corners.
+pub(crate) const EMPTY_BOUNDING_BOX: [f64; 4] = [
+    f64::INFINITY,
+    f64::INFINITY,
+    f64::NEG_INFINITY,
+    f64::NEG_INFINITY,
+];
+
+/// Return `true` if the bottom left corner is below and on the left of the top right corner,
+/// or if it's the [`EMPTY_BOUNDING_BOX`].
+pub(crate) fn is_valid_bounding_box(bounding_box: [f64; 4]) -> bool {
+    let [left, bottom, right, top] = bounding_box;
+    (left <= right && bottom <= top) || bounding_box == EMPTY_BOUNDING_BOX
+}
 
 /// Bounding box of a Zerometry.
 ///
@@ -53,7 +74,7 @@ impl BoundingBox {
     /// The Coords must:
     /// - Contains at least two elements
     /// - Be aligned on 64 bits
-    /// - Have it's first coordinate smaller than the second one
+    /// - Have it's first coordinate smaller than the second one, or be empty
     #[inline]
     pub unsafe fn from_coords(coords: &Coords) -> &Self {
         debug_assert_eq!(
@@ -63,12 +84,13 @@ impl BoundingBox {
             coords.len()
         );
         debug_assert!(
-            coords[0].lng() <= coords[1].lng(),
-            "Bounding box must have the left side before the right side"
-        );
-        debug_assert!(
-            coords[0].lat() <= coords[1].lat(),
-            "Bounding box must have the bottom side before the top side"
+            is_valid_bounding_box([
+                coords[0].lng(),
+                coords[0].lat(),
+                coords[1].lng(),
+                coords[1].lat()
+            ]),
+            "Bounding box must have the bottom left corner before the top right corner"
         );
         unsafe { mem::transmute(coords) }
     }
@@ -77,7 +99,7 @@ impl BoundingBox {
     /// The Coords must:
     /// - Contains at least two elements
     /// - Be aligned on 64 bits
-    /// - Have it's first coordinate smaller than the second one
+    /// - Have it's first coordinate smaller than the second one, or be empty
     #[inline]
     pub unsafe fn from_coords_mut(coords: &mut Coords) -> &mut Self {
         debug_assert_eq!(
@@ -87,23 +109,29 @@ impl BoundingBox {
             coords.len()
         );
         debug_assert!(
-            coords[0].lng() <= coords[1].lng(),
-            "Bounding box must have the left side before the right side"
-        );
-        debug_assert!(
-            coords[0].lat() <= coords[1].lat(),
-            "Bounding box must have the bottom side before the top side"
+            is_valid_bounding_box([
+                coords[0].lng(),
+                coords[0].lat(),
+                coords[1].lng(),
+                coords[1].lat()
+            ]),
+            "Bounding box must have the bottom left corner before the top right corner"
         );
         unsafe { mem::transmute(coords) }
     }
 
     /// Write the bounding boxe that contains all the specified points to a buffer.
+    /// Without any point, an [empty](Self::is_empty) bounding box that contains nothing is written.
     pub fn write_from_geometry(
         writer: &mut impl Write,
         mut points: impl Iterator<Item = Point<f64>>,
     ) -> Result<(), io::Error> {
-        // if there is no points we ends up with an empty bouding box in 0,0 and on points in the polygon
-        let first_point = points.next().unwrap_or_default();
+        let Some(first_point) = points.next() else {
+            for value in EMPTY_BOUNDING_BOX {
+                writer.write_all(&value.to_ne_bytes())?;
+            }
+            return Ok(());
+        };
         let mut top = first_point.y();
         let mut bottom = first_point.y();
         let mut left = first_point.x();
@@ -184,6 +212,47 @@ impl BoundingBox {
         self.bottom()..=self.top()
     }
 
+    /// Return `true` if the bounding box belongs to a shape without any coordinate.
+    /// It contains nothing and is disjoint from every other bounding box.
+    /// The shapes written before the version 2 of the format use a bounding box made of zeros instead,
+    /// see [`crate::FORMAT_VERSION`].
+    ///
+    /// ```
+    /// use zerometry::Zine;
+    /// use geo_types::LineString;
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zine::write_from_geometry(&mut buffer, &LineString::new(Vec::new())).unwrap();
+    /// let zine = Zine::try_from_bytes(&buffer).unwrap();
+    /// assert!(zine.bounding_box().is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.left() > self.right()
+    }
+
+    /// Return the longitude range contained in the bounding box as an [`Interval`].
+    /// The bounding box must not be [empty](Self::is_empty).
+    #[inline]
+    pub fn horizontal_interval(&self) -> Interval {
+        Interval::new(self.left(), self.right())
+    }
+    /// Return the latitude range contained in the bounding box as an [`Interval`].
+    /// The bounding box must not be [empty](Self::is_empty).
+    #[inline]
+    pub fn vertical_interval(&self) -> Interval {
+        Interval::new(self.bottom(), self.top())
+    }
+
+    /// Return `true` if the bounding box has no area, that's when it's reduced to a point,
+    /// or to a horizontal or vertical segment. An [empty](Self::is_empty) bounding box is not degenerate.
+    #[inline]
+    pub fn is_degenerate(&self) -> bool {
+        !self.is_empty()
+            && (self.horizontal_interval().is_degenerate()
+                || self.vertical_interval().is_degenerate())
+    }
+
     /// Return `true` if the coord is contained in the bounding box
     #[inline]
     pub fn contains_coord(&self, coord: &Coord) -> bool {
@@ -191,12 +260,99 @@ impl BoundingBox {
             && self.horizontal_range().contains(&coord.lng())
     }
 
+    /// Return `true` if the bounding box of the segment doesn't touch this bounding box,
+    /// in which case they can't intersect.
+    #[inline]
+    pub(crate) fn disjoint_from_segment(&self, segment: &Segment) -> bool {
+        let (start, end) = (segment.start(), segment.end());
+        start.lng().max(end.lng()) < self.left()
+            || start.lng().min(end.lng()) > self.right()
+            || start.lat().max(end.lat()) < self.bottom()
+            || start.lat().min(end.lat()) > self.top()
+    }
+
     /// Convert the bounding box to a [`geo_types::Rect`].
     /// Note: Converting that back to a [`crate::Zerometry`] will produce a [`crate::Zolygon`]
+    /// An [empty](Self::is_empty) bounding box has no equivalent and becomes a rectangle covering the whole plane.
     #[inline]
     pub fn to_geo(&self) -> geo_types::Rect<f64> {
         geo_types::Rect::new(self.bottom_left().to_geo(), self.top_right().to_geo())
     }
+
+    /// Compare the bottom left corners of two bounding boxes along the first axis of `axis`, then along the
+    /// second one to break the ties. Sorting the bounding boxes this way is the first step of a sweep line.
+    /// The floats are compared with [`f64::total_cmp`], which makes it a total order.
+    ///
+    /// ```
+    /// use zerometry::{AxisOrder, BoundingBox};
+    ///
+    /// let a = unsafe { BoundingBox::from_slice(&[0.0, 5.0, 1.0, 6.0]) };
+    /// let b = unsafe { BoundingBox::from_slice(&[2.0, 0.0, 3.0, 1.0]) };
+    /// assert!(a.cmp_by(b, AxisOrder::LngLat).is_lt());
+    /// assert!(a.cmp_by(b, AxisOrder::LatLng).is_gt());
+    /// ```
+    #[inline]
+    pub fn cmp_by(&self, other: &BoundingBox, axis: AxisOrder) -> Ordering {
+        let (first, second) = axis.from_lng_lat((self.left(), self.bottom()));
+        let (other_first, other_second) = axis.from_lng_lat((other.left(), other.bottom()));
+        first
+            .total_cmp(&other_first)
+            .then(second.total_cmp(&other_second))
+    }
+
+    /// Return the position of the center of the bounding box on a Hilbert curve covering the whole world,
+    /// from `-180, -90` to `180, 90`. Shapes close to each other are likely to get close indexes, which makes
+    /// it a good key to sort shapes spatially, to build pages of an index or to improve the locality of a storage.
+    /// The centers out of the world are clamped on its border.
+    ///
+    /// ```
+    /// use zerometry::{Zerometry, ZerometryBuf};
+    /// use geo_types::{Geometry, polygon};
+    ///
+    /// let square = |x: f64, y: f64| {
+    ///     let polygon = polygon![(x: x, y: y), (x: x + 1., y: y), (x: x + 1., y: y + 1.), (x: x, y: y + 1.)];
+    ///     ZerometryBuf::from(Geometry::Polygon(polygon))
+    /// };
+    /// let mut shapes = vec![square(100.0, 40.0), square(-5.0, 10.0), square(101.0, 41.0)];
+    /// shapes.sort_by_cached_key(|shape| {
+    ///     shape.as_zerometry().to_polygon().unwrap().bounding_box().hilbert_index()
+    /// });
+    /// // The two squares close to each other end up next to each other
+    /// let position = |shape| shapes.iter().position(|s| *s == shape).unwrap();
+    /// assert_eq!(position(square(100.0, 40.0)).abs_diff(position(square(101.0, 41.0))), 1);
+    /// ```
+    pub fn hilbert_index(&self) -> u64 {
+        const SIDE: f64 = (1_u64 << 32) as f64;
+        let quantize = |value: f64, min: f64, max: f64| {
+            // The cast saturates, which clamps the values out of the world
+            ((value - min) / (max - min) * SIDE).min(SIDE - 1.0) as u64
+        };
+        let x = quantize((self.left() + self.right()) / 2.0, -180.0, 180.0);
+        let y = quantize((self.bottom() + self.top()) / 2.0, -90.0, 90.0);
+        hilbert_index(x, y)
+    }
+}
+
+/// Return the distance along a Hilbert curve of side 2^32 of the cell at `x`, `y`.
+fn hilbert_index(mut x: u64, mut y: u64) -> u64 {
+    const SIDE: u64 = 1 << 32;
+    let mut index = 0;
+    let mut s = SIDE / 2;
+    while s > 0 {
+        let rx = (x & s != 0) as u64;
+        let ry = (y & s != 0) as u64;
+        index += s * s * ((3 * rx) ^ ry);
+        // Rotate the quadrant so the curve stays continuous
+        if ry == 0 {
+            if rx == 1 {
+                x = SIDE - 1 - x;
+                y = SIDE - 1 - y;
+            }
+            mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    index
 }
 
 impl fmt::Debug for BoundingBox {
@@ -220,42 +376,87 @@ impl RelationBetweenShapes<Coord> for BoundingBox {
 }
 
 impl RelationBetweenShapes<BoundingBox> for BoundingBox {
+    /// The axes are compared independently, a bounding box intersects another one only if their ranges
+    /// overlap on both axes. That also holds for the [degenerate](BoundingBox::is_degenerate) bounding boxes
+    /// where looking at which corners are contained is not enough: two collinear segments
+    /// never contain each other's corners, yet they may or may not touch.
+    ///
+    /// The same goes for the interior of a bounding box, which is made of the interiors of its ranges.
+    /// It's used to compute the touches and crosses, the bounding boxes being considered as a polygon,
+    /// a segment or a point depending on how degenerate they are.
     #[inline]
     fn relation(&self, other: &BoundingBox, relation: InputRelation) -> OutputRelation {
         let relation = relation.to_false();
+        if self.is_empty() || other.is_empty() {
+            return relation.make_disjoint_if_set();
+        }
+
+        let (self_horizontal, self_vertical) =
+            (self.horizontal_interval(), self.vertical_interval());
+        let (other_horizontal, other_vertical) =
+            (other.horizontal_interval(), other.vertical_interval());
+
+        if !self_vertical.overlaps(&other_vertical) || !self_horizontal.overlaps(&other_horizontal)
+        {
+            return relation.make_disjoint_if_set();
+        }
+        let covers =
+            self_vertical.contains(&other_vertical) && self_horizontal.contains(&other_horizontal);
+        let covered_by =
+            other_vertical.contains(&self_vertical) && other_horizontal.contains(&self_horizontal);
+        let mut output = if covers {
+            relation.make_strict_contains_if_set().make_covers_if_set()
+        } else if covered_by {
+            relation.make_strict_contained_if_set()
+        } else {
+            relation.make_intersect_if_set()
+        };
+        if covered_by {
+            output = output.make_covered_by_if_set();
+        }
 
-        let self_vertical_range = self.vertical_range();
-        let self_horizontal_range = self.horizontal_range();
-        let other_vertical_range = other.vertical_range();
-        let other_horizontal_range = other.horizontal_range();
-
-        match (
-            self_vertical_range.contains(other_vertical_range.start()),
-            self_vertical_range.contains(other_vertical_range.end()),
-            self_horizontal_range.contains(other_horizontal_range.start()),
-            self_horizontal_range.contains(other_horizontal_range.end()),
-        ) {
-            (true, true, true, true) => relation.make_strict_contains_if_set(),
-            (false, false, false, false) => {
-                match (
-                    other_vertical_range.contains(self_vertical_range.start()),
-                    other_vertical_range.contains(self_vertical_range.end()),
-                    other_horizontal_range.contains(self_horizontal_range.start()),
-                    other_horizontal_range.contains(self_horizontal_range.end()),
-                ) {
-                    (true, true, true, true) => relation.make_strict_contained_if_set(),
-                    (false, false, false, false) => relation.make_disjoint_if_set(),
-                    _ => relation.make_intersect_if_set(),
+        let interiors = interiors_overlap(&self_horizontal, &other_horizontal)
+            && interiors_overlap(&self_vertical, &other_vertical);
+        let dimension = |horizontal: Interval, vertical: Interval| {
+            usize::from(!horizontal.is_degenerate()) + usize::from(!vertical.is_degenerate())
+        };
+        let crosses = interiors
+            && match dimension(self_horizontal, self_vertical)
+                .cmp(&dimension(other_horizontal, other_vertical))
+            {
+                Ordering::Less => !covered_by,
+                Ordering::Greater => !covers,
+                // Only two perpendicular segments meet at a single point
+                Ordering::Equal => {
+                    dimension(self_horizontal, self_vertical) == 1
+                        && self_horizontal.is_degenerate() != other_horizontal.is_degenerate()
                 }
-            }
-            _ => relation.make_intersect_if_set(),
+            };
+        if !interiors {
+            output = output.make_touches_if_set();
+        }
+        if crosses {
+            output = output.make_crosses_if_set();
         }
+        output
+    }
+}
+
+/// Return `true` if the interiors of the ranges share a value. The interior of a range is the range without
+/// its bounds, except for a degenerate range which is its own interior.
+fn interiors_overlap(a: &Interval, b: &Interval) -> bool {
+    match (a.is_degenerate(), b.is_degenerate()) {
+        (false, false) => a.start() < b.end() && b.start() < a.end(),
+        (true, false) => b.start() < a.start() && a.start() < b.end(),
+        (false, true) => a.start() < b.start() && b.start() < a.end(),
+        (true, true) => a.start() == b.start(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use bytemuck::cast_slice;
+    use insta::assert_compact_debug_snapshot;
 
     use super::*;
 
@@ -264,16 +465,16 @@ mod tests {
         let data = [1.0, 2.0, 3.0, 4.0];
         let bb = unsafe { BoundingBox::from_bytes(cast_slice(&data)) };
         insta::assert_debug_snapshot!(bb, @r"
-        BoundingBox {
-            bottom_left: Coord {
-                x: 1.0,
-                y: 2.0,
-            },
-            top_right: Coord {
-                x: 3.0,
-                y: 4.0,
-            },
-        }
+            BoundingBox {
+                bottom_left: Coord {
+                    lng: 1.0,
+                    lat: 2.0,
+                },
+                top_right: Coord {
+                    lng: 3.0,
+                    lat: 4.0,
+                },
+            }
         ");
     }
 
@@ -310,16 +511,16 @@ mod tests {
         let data = [1.0, 2.0, 3.0, 4.0];
         let bb = unsafe { BoundingBox::from_slice(&data) };
         insta::assert_debug_snapshot!(bb, @r"
-        BoundingBox {
-            bottom_left: Coord {
-                x: 1.0,
-                y: 2.0,
-            },
-            top_right: Coord {
-                x: 3.0,
-                y: 4.0,
-            },
-        }
+            BoundingBox {
+                bottom_left: Coord {
+                    lng: 1.0,
+                    lat: 2.0,
+                },
+                top_right: Coord {
+                    lng: 3.0,
+                    lat: 4.0,
+                },
+            }
         ");
     }
 
@@ -371,4 +572,166 @@ mod tests {
         assert!(bb.contained(unsafe { BoundingBox::from_slice(&[-1.0, -1.0, 11.0, 11.0]) }));
         assert!(bb.disjoint(unsafe { BoundingBox::from_slice(&[11.0, 11.0, 12.0, 12.0]) }));
     }
+
+    #[test]
+    fn test_bounding_box_relation_cross_and_touch() {
+        let bb = |slice: &'static [f64; 4]| unsafe { BoundingBox::from_slice(slice) };
+        let horizontal = bb(&[0.0, 4.0, 10.0, 6.0]);
+        let vertical = bb(&[4.0, 0.0, 6.0, 10.0]);
+        // A plus sign where neither box contains a corner of the other
+        let relation = InputRelation::all_with_topology();
+        assert_compact_debug_snapshot!(horizontal.relation(vertical, relation), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: Some(false), crosses: Some(false), covers: Some(false), covered_by: Some(false), approximate: false }");
+        assert_compact_debug_snapshot!(vertical.relation(horizontal, relation), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: Some(false), crosses: Some(false), covers: Some(false), covered_by: Some(false), approximate: false }");
+
+        let square = bb(&[0.0, 0.0, 10.0, 10.0]);
+        // Sharing an edge
+        assert!(square.intersects(bb(&[10.0, 2.0, 20.0, 8.0])));
+        assert!(square.intersects(bb(&[-10.0, 0.0, 0.0, 10.0])));
+        assert!(square.intersects(bb(&[2.0, 10.0, 8.0, 20.0])));
+        // Sharing a corner
+        assert!(square.intersects(bb(&[10.0, 10.0, 20.0, 20.0])));
+        assert!(square.intersects(bb(&[-10.0, -10.0, 0.0, 0.0])));
+        assert!(square.intersects(bb(&[10.0, -10.0, 20.0, 0.0])));
+        // Right next to the edge and the corner
+        assert!(square.disjoint(bb(&[10.5, 2.0, 20.0, 8.0])));
+        assert!(square.disjoint(bb(&[10.5, 10.5, 20.0, 20.0])));
+    }
+
+    #[test]
+    fn test_bounding_box_topology() {
+        let bb = |slice: &'static [f64; 4]| unsafe { BoundingBox::from_slice(slice) };
+        let topology = |shape: &BoundingBox, other: &BoundingBox| {
+            let output = shape.relation(other, InputRelation::all_with_topology());
+            let swapped = other.relation(shape, InputRelation::all_with_topology());
+            assert_eq!(
+                (
+                    output.touches,
+                    output.crosses,
+                    output.covers,
+                    output.covered_by
+                ),
+                (
+                    swapped.touches,
+                    swapped.crosses,
+                    swapped.covered_by,
+                    swapped.covers
+                )
+            );
+            (
+                output.touches.unwrap(),
+                output.crosses.unwrap(),
+                output.covers.unwrap(),
+                output.covered_by.unwrap(),
+            )
+        };
+
+        let square = bb(&[0.0, 0.0, 10.0, 10.0]);
+        // Sharing an edge or a corner, inside, the same box and disjoint
+        assert_compact_debug_snapshot!(topology(square, bb(&[10.0, 2.0, 20.0, 8.0])), @"(true, false, false, false)");
+        assert_compact_debug_snapshot!(topology(square, bb(&[10.0, 10.0, 20.0, 20.0])), @"(true, false, false, false)");
+        assert_compact_debug_snapshot!(topology(square, bb(&[0.0, 2.0, 5.0, 8.0])), @"(false, false, true, false)");
+        assert_compact_debug_snapshot!(topology(square, square), @"(false, false, true, true)");
+        assert_compact_debug_snapshot!(topology(square, bb(&[10.5, 2.0, 20.0, 8.0])), @"(false, false, false, false)");
+        // A segment going through the square, inside of it, and along its edge
+        assert_compact_debug_snapshot!(topology(square, bb(&[-5.0, 5.0, 15.0, 5.0])), @"(false, true, false, false)");
+        assert_compact_debug_snapshot!(topology(square, bb(&[2.0, 5.0, 8.0, 5.0])), @"(false, false, true, false)");
+        assert_compact_debug_snapshot!(topology(square, bb(&[0.0, 0.0, 10.0, 0.0])), @"(true, false, true, false)");
+        // A point inside the square and on its edge
+        assert_compact_debug_snapshot!(topology(square, bb(&[5.0, 5.0, 5.0, 5.0])), @"(false, false, true, false)");
+        assert_compact_debug_snapshot!(topology(square, bb(&[10.0, 5.0, 10.0, 5.0])), @"(true, false, true, false)");
+
+        let segment = bb(&[0.0, 0.0, 10.0, 0.0]);
+        // Perpendicular segments crossing and ending on each other, collinear segments overlapping and ending
+        // on each other
+        assert_compact_debug_snapshot!(topology(segment, bb(&[5.0, -1.0, 5.0, 1.0])), @"(false, true, false, false)");
+        assert_compact_debug_snapshot!(topology(segment, bb(&[5.0, 0.0, 5.0, 1.0])), @"(true, false, false, false)");
+        assert_compact_debug_snapshot!(topology(segment, bb(&[5.0, 0.0, 15.0, 0.0])), @"(false, false, false, false)");
+        assert_compact_debug_snapshot!(topology(segment, bb(&[10.0, 0.0, 15.0, 0.0])), @"(true, false, false, false)");
+    }
+
+    #[test]
+    fn test_bounding_box_degenerate() {
+        let bb = |slice: &'static [f64; 4]| unsafe { BoundingBox::from_slice(slice) };
+        assert!(!bb(&[0.0, 0.0, 10.0, 10.0]).is_degenerate());
+        assert!(bb(&[5.0, 5.0, 5.0, 5.0]).is_degenerate());
+        assert!(bb(&[0.0, 0.0, 10.0, 0.0]).is_degenerate());
+        assert!(bb(&[0.0, 0.0, 0.0, 10.0]).is_degenerate());
+
+        // Collinear segments
+        let segment = bb(&[0.0, 0.0, 10.0, 0.0]);
+        assert!(segment.disjoint(bb(&[11.0, 0.0, 20.0, 0.0])));
+        assert!(segment.intersects(bb(&[10.0, 0.0, 20.0, 0.0])));
+        assert!(segment.intersects(bb(&[5.0, 0.0, 20.0, 0.0])));
+        assert!(segment.contains(bb(&[2.0, 0.0, 3.0, 0.0])));
+        assert!(segment.contained(bb(&[-1.0, 0.0, 11.0, 0.0])));
+        // Parallel segments
+        assert!(segment.disjoint(bb(&[0.0, 1.0, 10.0, 1.0])));
+        // Crossing segments
+        assert!(segment.intersects(bb(&[5.0, -1.0, 5.0, 1.0])));
+        assert!(segment.disjoint(bb(&[11.0, -1.0, 11.0, 1.0])));
+
+        // Points
+        let point = bb(&[5.0, 5.0, 5.0, 5.0]);
+        assert!(point.contains(bb(&[5.0, 5.0, 5.0, 5.0])));
+        assert!(point.disjoint(bb(&[5.0, 6.0, 5.0, 6.0])));
+        assert!(point.contained(bb(&[0.0, 0.0, 10.0, 10.0])));
+        assert!(point.contained(bb(&[5.0, 0.0, 5.0, 10.0])));
+        assert!(point.disjoint(bb(&[0.0, 0.0, 10.0, 4.0])));
+
+        // Boxes sharing an axis range but far apart on the other one
+        let square = bb(&[0.0, 0.0, 10.0, 10.0]);
+        assert!(square.disjoint(bb(&[2.0, 20.0, 3.0, 30.0])));
+        assert!(square.disjoint(bb(&[20.0, 2.0, 30.0, 3.0])));
+        assert!(square.intersects(bb(&[2.0, 5.0, 3.0, 30.0])));
+    }
+
+    #[test]
+    fn test_bounding_box_sort_keys() {
+        let boxes = [
+            [0.0, 0.0, 1.0, 1.0],
+            [0.0, -1.0, 1.0, 1.0],
+            [-1.0, 2.0, 0.0, 3.0],
+            [170.0, 80.0, 190.0, 95.0],
+        ];
+        let boxes: Vec<_> = boxes
+            .iter()
+            .map(|data| unsafe { BoundingBox::from_slice(data) })
+            .collect();
+        let mut sorted = boxes.clone();
+        sorted.sort_by(|a, b| a.cmp_by(b, AxisOrder::LngLat));
+        insta::assert_compact_debug_snapshot!(sorted.iter().map(|bb| (bb.left(), bb.bottom())).collect::<Vec<_>>(), @"[(-1.0, 2.0), (0.0, -1.0), (0.0, 0.0), (170.0, 80.0)]");
+        sorted.sort_by(|a, b| a.cmp_by(b, AxisOrder::LatLng));
+        insta::assert_compact_debug_snapshot!(sorted.iter().map(|bb| (bb.left(), bb.bottom())).collect::<Vec<_>>(), @"[(0.0, -1.0), (0.0, 0.0), (-1.0, 2.0), (170.0, 80.0)]");
+
+        insta::assert_compact_debug_snapshot!(boxes.iter().map(|bb| bb.hilbert_index()).collect::<Vec<_>>(), @"[9223616622365014272, 9223437937401745152, 9218911242216589040, 12299260574648957957]");
+        // The four quadrants of the world are visited in order
+        let quadrants =
+            [[-90.0, -45.0], [-90.0, 45.0], [90.0, 45.0], [90.0, -45.0]].map(|[x, y]| {
+                let data = [x, y, x, y];
+                unsafe { BoundingBox::from_slice(&data) }.hilbert_index() >> 62
+            });
+        assert_eq!(quadrants, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_bounding_box_empty() {
+        let mut buffer = Vec::new();
+        BoundingBox::write_from_geometry(&mut buffer, std::iter::empty()).unwrap();
+        let empty = unsafe { BoundingBox::from_bytes(&buffer) };
+        assert!(empty.is_empty());
+        assert!(!empty.is_degenerate());
+        // An empty shape is not located in 0, 0 anymore
+        let origin = [0.0, 0.0];
+        assert!(!empty.contains_coord(unsafe { Coord::from_slice(&origin) }));
+        let world = unsafe { BoundingBox::from_slice(&[-180.0, -90.0, 180.0, 90.0]) };
+        assert!(!world.is_empty());
+        assert!(empty.disjoint(world));
+        assert!(world.disjoint(empty));
+        assert!(empty.disjoint(empty));
+
+        assert!(crate::error::split_bounding_box(&buffer).is_ok());
+        let inverted: &[u8] = cast_slice(&[1.0, 0.0, 0.0, 1.0]);
+        let error = crate::error::split_bounding_box(inverted).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"InvalidBoundingBox");
+    }
 }
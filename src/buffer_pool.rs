@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+
+/// The maximum number of idle buffers kept by a [`BufferPool`], the other ones are dropped.
+const MAX_IDLE_BUFFERS: usize = 8;
+
+thread_local! {
+    static THREAD_LOCAL_POOL: RefCell<BufferPool> = const { RefCell::new(BufferPool::new()) };
+}
+
+/// A pool of reusable byte buffers used to temporarily convert the [`geo_types`] shapes to zerometries.
+///
+/// Computing the relation between a zerometry and a [`geo_types::Geometry`] requires to serialize the geometry
+/// first. Instead of allocating a new buffer for every call, the relations between the zerometries and the
+/// [`geo_types`] shapes use a thread-local pool, which means that once warmed up, these conversions don't
+/// allocate anymore. It's the only place the crate uses it: the relations between two zerometries don't
+/// need any buffer, and the other allocations, like the ones done by the [`crate::RelationStrategy`]
+/// implementations, don't go through the pool.
+/// A pool can also be created and passed around by the caller to convert its own shapes.
+///
+/// ```
+/// use zerometry::{BufferPool, Zerometry};
+/// use geo_types::{Geometry, Point};
+///
+/// let mut pool = BufferPool::new();
+/// for i in 0..10 {
+///     let point = Geometry::Point(Point::new(i as f64, 0.0));
+///     let geometry = pool.with_buffer(|buffer| {
+///         Zerometry::write_from_geometry(buffer, &point).unwrap();
+///         let zerometry = unsafe { Zerometry::from_bytes(buffer).unwrap() };
+///         zerometry.to_geo()
+///     });
+///     assert_eq!(geometry, point);
+/// }
+/// assert_eq!(pool.idle_buffers(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct BufferPool {
+    buffers: Vec<Vec<u8>>,
+}
+
+impl BufferPool {
+    /// Create an empty pool, it doesn't allocate until a buffer is given back.
+    pub const fn new() -> Self {
+        Self {
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Take an empty buffer from the pool, or allocate a new one if the pool is empty.
+    pub fn take(&mut self) -> Vec<u8> {
+        self.buffers.pop().unwrap_or_default()
+    }
+
+    /// Give back a buffer to the pool, it's cleared but keeps its capacity.
+    pub fn give_back(&mut self, mut buffer: Vec<u8>) {
+        if self.buffers.len() < MAX_IDLE_BUFFERS {
+            buffer.clear();
+            self.buffers.push(buffer);
+        }
+    }
+
+    /// Return the number of buffers currently waiting to be reused.
+    pub fn idle_buffers(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Call the closure with an empty buffer of the pool and give it back once the closure returns.
+    pub fn with_buffer<R>(&mut self, f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+        let mut buffer = self.take();
+        let ret = f(&mut buffer);
+        self.give_back(buffer);
+        ret
+    }
+
+    /// Same as [`Self::with_buffer`] but using the pool of the current thread.
+    /// The pool isn't borrowed while the closure runs so it can be called recursively.
+    pub fn with_thread_local_buffer<R>(f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+        let mut buffer = THREAD_LOCAL_POOL.with_borrow_mut(BufferPool::take);
+        let ret = f(&mut buffer);
+        THREAD_LOCAL_POOL.with_borrow_mut(|pool| pool.give_back(buffer));
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuse_buffers() {
+        let mut pool = BufferPool::new();
+        let mut buffer = pool.take();
+        buffer.extend_from_slice(&[1; 100]);
+        let capacity = buffer.capacity();
+        pool.give_back(buffer);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), capacity);
+        assert_eq!(pool.idle_buffers(), 0);
+
+        for _ in 0..MAX_IDLE_BUFFERS + 2 {
+            pool.give_back(Vec::new());
+        }
+        assert_eq!(pool.idle_buffers(), MAX_IDLE_BUFFERS);
+    }
+
+    #[test]
+    fn recursive_thread_local_buffers() {
+        BufferPool::with_thread_local_buffer(|outer| {
+            outer.push(1);
+            BufferPool::with_thread_local_buffer(|inner| {
+                assert!(inner.is_empty());
+                inner.push(2);
+            });
+            assert_eq!(outer, &[1]);
+        });
+        BufferPool::with_thread_local_buffer(|buffer| assert!(buffer.is_empty()));
+    }
+}
@@ -0,0 +1,191 @@
+use std::mem;
+
+use geo_types::{Rect, coord};
+
+use crate::{Coord, Coords, Zerometry, bounding_box::EMPTY_BOUNDING_BOX};
+
+/// Return the bounding box of the area where two versions of a shape differ, or `None` if they're the same.
+/// It lets an incremental index only process the region that changed instead of the whole shape.
+///
+/// The points, lines and rings of both versions are compared in order. The coords that were moved, added or
+/// removed are part of the region, along with their neighbours on the lines and rings since the segments between
+/// them changed too. A member that exists in only one version and a shape that changed of kind are entirely part
+/// of the region.
+///
+/// ```
+/// use zerometry::{ZerometryBuf, changed_region};
+/// use geo_types::{Geometry, LineString, Rect, coord};
+///
+/// let old = ZerometryBuf::from(Geometry::LineString(LineString::from(vec![(0., 0.), (1., 0.), (2., 0.), (3., 0.), (4., 0.)])));
+/// let new = ZerometryBuf::from(Geometry::LineString(LineString::from(vec![(0., 0.), (1., 0.), (2., 1.), (3., 0.), (4., 0.)])));
+/// let region = changed_region(&old.as_zerometry(), &new.as_zerometry());
+/// assert_eq!(region, Some(Rect::new(coord! { x: 1., y: 0. }, coord! { x: 3., y: 1. })));
+/// assert_eq!(changed_region(&old.as_zerometry(), &old.as_zerometry()), None);
+/// ```
+pub fn changed_region(old: &Zerometry, new: &Zerometry) -> Option<Rect<f64>> {
+    let mut region = Region::default();
+    if mem::discriminant(old) != mem::discriminant(new) {
+        for part in parts(old).iter().chain(&parts(new)) {
+            region.extend(&part.coords);
+        }
+        return region.to_rect();
+    }
+
+    let old_parts = parts(old);
+    let new_parts = parts(new);
+    for index in 0..old_parts.len().max(new_parts.len()) {
+        match (old_parts.get(index), new_parts.get(index)) {
+            (Some(old), Some(new)) => region.extend_with_changes(old, new),
+            (Some(part), None) | (None, Some(part)) => region.extend(&part.coords),
+            (None, None) => unreachable!(),
+        }
+    }
+    region.to_rect()
+}
+
+/// The coords of a point, line or ring of a shape.
+struct Part<'a> {
+    coords: Vec<&'a Coord>,
+    /// `true` if the consecutive coords are linked by a segment.
+    connected: bool,
+}
+
+fn parts<'a>(zerometry: &Zerometry<'a>) -> Vec<Part<'a>> {
+    let connected = |coords: &'a Coords| Part {
+        coords: coords.iter().collect(),
+        connected: true,
+    };
+    match zerometry {
+        Zerometry::Point(point) => vec![Part {
+            coords: vec![point.coord()],
+            connected: false,
+        }],
+        Zerometry::MultiPoints(points) => vec![Part {
+            coords: points.coords().iter().collect(),
+            connected: false,
+        }],
+        Zerometry::Line(line) => vec![connected(line.coords())],
+        Zerometry::Polygon(polygon) => vec![connected(polygon.coords())],
+        Zerometry::MultiLines(_) | Zerometry::MultiPolygon(_) => {
+            lines_and_rings(zerometry).map(connected).collect()
+        }
+        Zerometry::Collection(collection) => {
+            let mut parts = parts(&Zerometry::MultiPoints(collection.points()));
+            parts.extend(lines_and_rings(zerometry).map(connected));
+            parts
+        }
+    }
+}
+
+/// Return the coords of the lines followed by the rings of the shape.
+fn lines_and_rings<'a>(zerometry: &Zerometry<'a>) -> impl Iterator<Item = &'a Coords> + use<'a> {
+    let lines = match zerometry {
+        Zerometry::MultiLines(lines) => Some(*lines),
+        Zerometry::Collection(collection) => Some(collection.lines()),
+        _ => None,
+    };
+    lines
+        .into_iter()
+        .flat_map(|lines| (0..lines.len()).map(move |index| lines.get(index).unwrap().coords()))
+        .chain(zerometry.rings())
+}
+
+/// The bounds of the changed region, in the same order as a [`crate::BoundingBox`].
+struct Region([f64; 4]);
+
+impl Default for Region {
+    fn default() -> Self {
+        Self(EMPTY_BOUNDING_BOX)
+    }
+}
+
+impl Region {
+    fn extend(&mut self, coords: &[&Coord]) {
+        let [left, bottom, right, top] = &mut self.0;
+        for coord in coords {
+            *left = left.min(coord.lng());
+            *bottom = bottom.min(coord.lat());
+            *right = right.max(coord.lng());
+            *top = top.max(coord.lat());
+        }
+    }
+
+    /// Extend the region with the coords that differ between both versions of a part.
+    fn extend_with_changes(&mut self, old: &Part, new: &Part) {
+        let same =
+            |(old, new): (&&Coord, &&Coord)| old.lng() == new.lng() && old.lat() == new.lat();
+        let prefix = old
+            .coords
+            .iter()
+            .zip(&new.coords)
+            .take_while(|pair| same(*pair))
+            .count();
+        if prefix == old.coords.len() && prefix == new.coords.len() {
+            return;
+        }
+        let shortest = old.coords.len().min(new.coords.len());
+        let suffix = old
+            .coords
+            .iter()
+            .rev()
+            .zip(new.coords.iter().rev())
+            .take(shortest - prefix)
+            .take_while(|pair| same(*pair))
+            .count();
+
+        for coords in [&old.coords, &new.coords] {
+            let (mut start, mut end) = (prefix, coords.len() - suffix);
+            // The segments linking the changed coords to their neighbours changed too
+            if old.connected {
+                start = start.saturating_sub(1);
+                end = (end + 1).min(coords.len());
+            }
+            self.extend(&coords[start..end]);
+        }
+    }
+
+    fn to_rect(&self) -> Option<Rect<f64>> {
+        let [left, bottom, right, top] = self.0;
+        (left <= right && bottom <= top)
+            .then(|| Rect::new(coord! { x: left, y: bottom }, coord! { x: right, y: top }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString, MultiPoint, MultiPolygon, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::ZerometryBuf;
+
+    fn region(old: Geometry<f64>, new: Geometry<f64>) -> Option<Rect<f64>> {
+        let old = ZerometryBuf::from(old);
+        let new = ZerometryBuf::from(new);
+        changed_region(&old.as_zerometry(), &new.as_zerometry())
+    }
+
+    #[test]
+    fn changed_regions() {
+        // A point was removed from the middle of the line
+        let old = LineString::from(vec![(0., 0.), (1., 1.), (2., 5.), (3., 1.), (4., 0.)]);
+        let new = LineString::from(vec![(0., 0.), (1., 1.), (3., 1.), (4., 0.)]);
+        assert_compact_debug_snapshot!(region(old.into(), new.into()), @"Some(RECT(1.0 1.0,3.0 5.0))");
+
+        // Only the moved point of the multi points changed
+        let old = MultiPoint::from(vec![(0., 0.), (5., 5.), (9., 9.)]);
+        let new = MultiPoint::from(vec![(0., 0.), (6., 5.), (9., 9.)]);
+        assert_compact_debug_snapshot!(region(old.into(), new.into()), @"Some(RECT(5.0 5.0,6.0 5.0))");
+
+        // A polygon was added to the multi polygon
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let triangle = polygon![(x: 5., y: 5.), (x: 6., y: 5.), (x: 6., y: 6.)];
+        let old = MultiPolygon::new(vec![square.clone()]);
+        let new = MultiPolygon::new(vec![square.clone(), triangle]);
+        assert_compact_debug_snapshot!(region(old.clone().into(), new.into()), @"Some(RECT(5.0 5.0,6.0 6.0))");
+        assert_compact_debug_snapshot!(region(old.clone().into(), old.into()), @"None");
+
+        // The kind changed
+        assert_compact_debug_snapshot!(region(square.into(), Point::new(3., 3.).into()), @"Some(RECT(0.0 0.0,3.0 3.0))");
+    }
+}
@@ -0,0 +1,136 @@
+use std::mem;
+
+use crate::{Zerometry, ZerometryError, header::Header};
+
+/// The size of the checksum stored after a zerometry, the CRC32 is stored as a `u64` to keep the zerometries
+/// that follow it aligned on 64 bits.
+pub(crate) const CHECKSUM_SIZE_IN_BYTES: usize = mem::size_of::<u64>();
+
+/// The lookup table of the IEEE CRC32, the one used by zlib and PNG, computed at compile time.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < table.len() {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Compute the IEEE CRC32 of the bytes.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(u32::MAX, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Verify the checksum stored at the end of the zerometry against all the bytes preceding it.
+pub(crate) fn verify_checksum(data: &[u8], stored: u32) -> Result<(), ZerometryError> {
+    let computed = crc32(&data[..data.len() - CHECKSUM_SIZE_IN_BYTES]);
+    if computed == stored {
+        Ok(())
+    } else {
+        Err(ZerometryError::ChecksumMismatch { stored, computed })
+    }
+}
+
+impl Zerometry<'_> {
+    /// Recompute the checksum stored at the end of a zerometry written with [`crate::WriteOptions::checksum`],
+    /// it must be called after editing the bytes in place, for example with [`crate::ZolygonMut`].
+    /// Nothing is done if the zerometry has no checksum.
+    ///
+    /// ```
+    /// use zerometry::{WriteOptions, Zerometry, ZolygonMut};
+    /// use geo_types::{Geometry, coord, polygon};
+    ///
+    /// let triangle = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)];
+    /// let options = WriteOptions { checksum: true, ..WriteOptions::default() };
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry_with_options(&mut buffer, &Geometry::Polygon(triangle), options).unwrap();
+    ///
+    /// let mut zolygon = ZolygonMut::try_from_zerometry_bytes(&mut buffer).unwrap();
+    /// zolygon.set_coord(2, coord! { x: 2.0, y: 2.0 }).unwrap();
+    /// assert!(Zerometry::try_from_bytes(&buffer).is_err());
+    ///
+    /// Zerometry::refresh_checksum(&mut buffer).unwrap();
+    /// assert!(Zerometry::try_from_bytes(&buffer).is_ok());
+    /// ```
+    pub fn refresh_checksum(data: &mut [u8]) -> Result<(), ZerometryError> {
+        if Header::parse(data)?.checksum.is_none() {
+            return Ok(());
+        }
+        let (data, trailer) = data.split_at_mut(data.len() - CHECKSUM_SIZE_IN_BYTES);
+        trailer.copy_from_slice(&(crc32(data) as u64).to_ne_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, MultiPolygon, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::WriteOptions;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let multi_polygon = Geometry::MultiPolygon(MultiPolygon::new(vec![square.clone(); 2]));
+        let options = WriteOptions {
+            checksum: true,
+            generation: Some(3),
+            bounding_box_groups: true,
+            ..WriteOptions::default()
+        };
+        let mut without = Vec::new();
+        Zerometry::write_from_geometry(&mut without, &multi_polygon).unwrap();
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &multi_polygon, options).unwrap();
+
+        let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+        assert_eq!(zerometry.to_geo(), multi_polygon);
+        assert_eq!(Zerometry::read_generation(&buffer).unwrap(), Some(3));
+        assert!(buffer.len() > without.len());
+
+        // Flip a bit of a coord
+        let coord = buffer.len() - CHECKSUM_SIZE_IN_BYTES - 1;
+        buffer[coord] ^= 1;
+        let error = Zerometry::try_from_bytes(&buffer).unwrap_err();
+        assert!(matches!(error, ZerometryError::ChecksumMismatch { .. }));
+        assert!(unsafe { Zerometry::from_bytes(&buffer) }.is_err());
+        // Once the checksum is refreshed, it's the validation that notices the ring is not closed anymore
+        Zerometry::refresh_checksum(&mut buffer).unwrap();
+        let error = Zerometry::try_from_bytes(&buffer).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"UnclosedRing");
+
+        // Corrupting the checksum is detected as well
+        let mut buffer = Vec::new();
+        let point = Geometry::Point(Point::new(1.0, 2.0));
+        let options = WriteOptions {
+            checksum: true,
+            ..WriteOptions::default()
+        };
+        Zerometry::write_from_geometry_with_options(&mut buffer, &point, options).unwrap();
+        let trailer = buffer.len() - CHECKSUM_SIZE_IN_BYTES;
+        buffer[trailer..].copy_from_slice(&0xdead_beef_u64.to_ne_bytes());
+        let error = Zerometry::try_from_bytes(&buffer).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"ChecksumMismatch { stored: 3735928559, computed: 2307016 }");
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{InputRelation, OutputRelation, RelationBetweenShapes, Zolygon};
+
+/// The complement of a shape: everything outside of it.
+///
+/// The relations are computed against the exterior of the shape, which lets a "not within this region" filter use
+/// [`RelationBetweenShapes::contains`] directly instead of negating the flags returned for the region itself.
+/// The complement is unbounded, it's never contained in another shape and the complement of an empty polygon
+/// contains everything.
+///
+/// ```
+/// use zerometry::{Complement, RelationBetweenShapes, Zerometry, zolygon};
+/// use geo_types::Point;
+///
+/// let france = zolygon![(-5., 42.), (8., 42.), (8., 51.), (-5., 51.)];
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &Point::new(13.40, 52.52).into()).unwrap();
+/// let berlin = Zerometry::try_from_bytes(&buffer).unwrap();
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &Point::new(2.35, 48.85).into()).unwrap();
+/// let paris = Zerometry::try_from_bytes(&buffer).unwrap();
+///
+/// // Berlin is not in France
+/// assert!(Complement(france).contains(&berlin));
+/// assert!(!Complement(france).contains(&paris));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Complement<T>(pub T);
+
+impl<'a, Other: ?Sized> RelationBetweenShapes<Other> for Complement<Zolygon<'a>>
+where
+    Zolygon<'a>: RelationBetweenShapes<Other>,
+{
+    fn relation(&self, other: &Other, relation: InputRelation) -> OutputRelation {
+        // Everything must be known about the polygon to deduce the relation with its exterior
+        let polygon = self.0.relation(
+            other,
+            InputRelation {
+                budget: relation.budget,
+                ..InputRelation::all()
+            },
+        );
+        let outside = polygon.strict_contains.map(|inside| !inside);
+        // The boundary of the polygon goes through the other shape
+        let crossed = polygon
+            .intersect
+            .zip(polygon.contained)
+            .map(|(intersect, contained)| intersect || contained);
+
+        OutputRelation {
+            contains: relation.contains.then_some(outside).flatten(),
+            strict_contains: relation
+                .strict_contains
+                .then_some(polygon.disjoint)
+                .flatten(),
+            contained: relation.contained.then_some(false),
+            strict_contained: relation.strict_contained.then_some(false),
+            intersect: relation.intersect.then_some(crossed).flatten(),
+            disjoint: relation
+                .disjoint
+                .then_some(polygon.strict_contains)
+                .flatten(),
+            // The outside of a polygon is an area, it can't overlap anything
+            overlaps: relation.overlaps.then_some(false),
+            // Whether the shape stays inside the polygon while reaching its boundary can't be deduced
+            touches: None,
+            // The exterior and interior of the polygon are swapped but its boundary is the same
+            crosses: relation.crosses.then_some(polygon.crosses).flatten(),
+            // The shape stays out of the interior of the polygon
+            covers: relation
+                .covers
+                .then_some(polygon.disjoint.zip(polygon.touches))
+                .flatten()
+                .map(|(disjoint, touches)| disjoint || touches),
+            covered_by: relation.covered_by.then_some(false),
+            approximate: polygon.approximate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{Zerometry, zolygon};
+
+    #[test]
+    fn relation_with_the_outside() {
+        let square = zolygon![(0., 0.), (10., 0.), (10., 10.), (0., 10.)];
+        let outside = Complement(square);
+        let shapes = [
+            Point::new(5., 5.).into(),
+            Point::new(20., 20.).into(),
+            LineString::from(vec![(5., 5.), (20., 5.)]).into(),
+            polygon![(x: 2., y: 2.), (x: 4., y: 2.), (x: 4., y: 4.)].into(),
+            polygon![(x: -5., y: -5.), (x: 15., y: -5.), (x: 15., y: 15.), (x: -5., y: 15.)].into(),
+            polygon![(x: 20., y: 20.), (x: 30., y: 20.), (x: 30., y: 30.)].into(),
+        ];
+        let relations: Vec<_> = shapes
+            .iter()
+            .map(|shape| {
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, shape).unwrap();
+                let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+                let relation = outside.all_relation(&zerometry);
+                (
+                    relation.contains.unwrap(),
+                    relation.strict_contains.unwrap(),
+                    relation.intersect.unwrap(),
+                    relation.disjoint.unwrap(),
+                )
+            })
+            .collect();
+        assert_compact_debug_snapshot!(relations, @"[(false, false, false, true), (true, true, false, false), (true, false, true, false), (false, false, false, true), (true, false, true, false), (true, true, false, false)]");
+
+        // Nothing is outside the plane
+        let empty = Complement(zolygon![]);
+        let relation = empty.relation(
+            &square,
+            InputRelation {
+                strict_contains: true,
+                contained: true,
+                ..InputRelation::none()
+            },
+        );
+        assert_compact_debug_snapshot!(relation, @"OutputRelation { contains: None, strict_contains: Some(true), contained: Some(false), strict_contained: None, intersect: None, disjoint: None, overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Coords, Zerometry};
+
+/// A summary of the shape of a [`Zerometry`], retrieved with [`Zerometry::complexity`].
+/// It's meant to choose how to process a shape, for example to send the very complex shapes through a prepared
+/// or parallel path and keep the simple ones on the scalar fast path.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Complexity {
+    /// Number of coordinates stored, including the closing coordinate of the polygons.
+    pub points: usize,
+    /// Number of points, lines and polygons the shape is made of. A single point, line or polygon is one child.
+    pub children: usize,
+    /// Area of the bounding box of the shape, zero for an empty shape.
+    pub bbox_area: f64,
+    /// Average euclidean length of the segments of the lines and polygons, zero if there is no segment.
+    pub avg_segment_len: f64,
+}
+
+impl Zerometry<'_> {
+    /// Return a summary of the shape, computed in a single pass over the coordinates without any allocation.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, polygon};
+    ///
+    /// let square = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 0., y: 2.)];
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(square)).unwrap();
+    ///
+    /// let complexity = Zerometry::try_from_bytes(&buffer).unwrap().complexity();
+    /// assert_eq!((complexity.points, complexity.children), (5, 1));
+    /// assert_eq!((complexity.bbox_area, complexity.avg_segment_len), (4.0, 2.0));
+    /// ```
+    pub fn complexity(&self) -> Complexity {
+        let bbox_area = if self.is_empty() {
+            0.0
+        } else {
+            let (left, bottom, right, top) = self.bounds();
+            (right - left) * (top - bottom)
+        };
+        let (segments, length) = self.segments_length();
+        Complexity {
+            points: self.coords_count(),
+            children: self.children_count(),
+            bbox_area,
+            avg_segment_len: if segments == 0 {
+                0.0
+            } else {
+                length / segments as f64
+            },
+        }
+    }
+
+    fn children_count(&self) -> usize {
+        match self {
+            Zerometry::Point(_) | Zerometry::Line(_) | Zerometry::Polygon(_) => 1,
+            Zerometry::MultiPoints(a) => a.len(),
+            Zerometry::MultiLines(a) => a.len(),
+            Zerometry::MultiPolygon(a) => a.len(),
+            Zerometry::Collection(a) => a.points().len() + a.lines().len() + a.polygons().len(),
+        }
+    }
+
+    /// Return the number of segments of the shape and their total length.
+    fn segments_length(&self) -> (usize, f64) {
+        let ring = |coords: &Coords| {
+            coords
+                .consecutive_pairs()
+                .fold((0, 0.0), |(segments, length), pair| {
+                    (
+                        segments + 1,
+                        length + (pair[2] - pair[0]).hypot(pair[3] - pair[1]),
+                    )
+                })
+        };
+        let sum = |(segments, length): (usize, f64), (other_segments, other_length)| {
+            (segments + other_segments, length + other_length)
+        };
+        match self {
+            Zerometry::Point(_) | Zerometry::MultiPoints(_) => (0, 0.0),
+            Zerometry::Line(a) => ring(a.coords()),
+            Zerometry::MultiLines(a) => a
+                .lines()
+                .map(|line| ring(line.coords()))
+                .fold((0, 0.0), sum),
+            Zerometry::Polygon(a) => ring(a.coords()),
+            Zerometry::MultiPolygon(a) => a
+                .polygons()
+                .map(|polygon| ring(polygon.coords()))
+                .fold((0, 0.0), sum),
+            Zerometry::Collection(a) => sum(
+                Zerometry::MultiLines(a.lines()).segments_length(),
+                Zerometry::MultiPolygon(a.polygons()).segments_length(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPoint, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use crate::Zerometry;
+
+    #[test]
+    fn complexity() {
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            MultiPoint::<f64>::new(Vec::new()).into(),
+            LineString::from(vec![(0.0, 0.0), (3.0, 4.0), (3.0, 5.0)]).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Point::new(10.0, 10.0).into(),
+                LineString::from(vec![(0.0, 0.0), (3.0, 4.0)]).into(),
+                polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into(),
+            ])),
+        ];
+        let complexities: Vec<_> = geometries
+            .iter()
+            .map(|geometry| {
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, geometry).unwrap();
+                Zerometry::try_from_bytes(&buffer).unwrap().complexity()
+            })
+            .collect();
+        assert_compact_debug_snapshot!(complexities, @"[Complexity { points: 1, children: 1, bbox_area: 0.0, avg_segment_len: 0.0 }, Complexity { points: 0, children: 0, bbox_area: 0.0, avg_segment_len: 0.0 }, Complexity { points: 3, children: 1, bbox_area: 15.0, avg_segment_len: 3.0 }, Complexity { points: 8, children: 3, bbox_area: 100.0, avg_segment_len: 1.8 }]");
+    }
+}
@@ -1,9 +1,15 @@
 use core::fmt;
 
-pub(crate) const COORD_SIZE_IN_BYTES: usize = std::mem::size_of::<f64>() * 2;
+use bytemuck::cast_slice;
+
+use crate::AxisOrder;
+
+/// The number of bytes used by a [`Coord`]: its longitude followed by its latitude, both stored as `f64`.
+pub const COORD_SIZE_IN_BYTES: usize = std::mem::size_of::<f64>() * 2;
 pub(crate) const COORD_SIZE_IN_FLOATS: usize = 2;
 
 /// Represent a coordinate. The first float is the longitude, or x, and the second is the latitude, or y.
+/// See [`AxisOrder`] to convert from or to the other conventions.
 #[repr(transparent)]
 pub struct Coord {
     data: [f64],
@@ -23,7 +29,9 @@ impl<'a> Coord {
             data.as_ptr() as usize % std::mem::align_of::<f64>() == 0,
             "data is not aligned"
         );
-        unsafe { std::mem::transmute(data) }
+        // The slice must be converted first, otherwise it would keep the number of bytes as its length
+        let slice: &[f64] = cast_slice(data);
+        unsafe { std::mem::transmute(slice) }
     }
 
     /// # Safety
@@ -61,16 +69,21 @@ impl<'a> Coord {
         &mut self.data[1]
     }
 
-    /// Return `x`
+    /// Return `x`, which is the same as the longitude.
     pub fn x(&self) -> f64 {
         self.lng()
     }
 
-    /// Return `y`
+    /// Return `y`, which is the same as the latitude.
     pub fn y(&self) -> f64 {
         self.lat()
     }
 
+    /// Return the coordinate as a pair of numbers following the specified [`AxisOrder`].
+    pub fn to_pair(&self, order: AxisOrder) -> (f64, f64) {
+        order.from_lng_lat((self.lng(), self.lat()))
+    }
+
     /// Convert the [`Coord`] to a [`geo_types::Coord`]
     pub fn to_geo(&self) -> geo_types::Coord<f64> {
         geo_types::Coord {
@@ -83,8 +96,8 @@ impl<'a> Coord {
 impl fmt::Debug for Coord {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Coord")
-            .field("x", &self.lng())
-            .field("y", &self.lat())
+            .field("lng", &self.lng())
+            .field("lat", &self.lat())
             .finish()
     }
 }
@@ -141,6 +154,6 @@ mod tests {
     fn debug_impl_support_precision_settings() {
         let data = [1.123456789, 2.987654321];
         let coord = unsafe { Coord::from_bytes(cast_slice(&data)) };
-        insta::assert_snapshot!(format!("{:.2?}", coord), @"Coord { x: 1.12, y: 2.99 }");
+        insta::assert_snapshot!(format!("{:.2?}", coord), @"Coord { lng: 1.12, lat: 2.99 }");
     }
 }
@@ -115,7 +115,7 @@ mod tests {
             vec![(1.0, 2.0), (3.0, 4.0)]
         );
         // Debug+iter works
-        insta::assert_snapshot!(format!("{:?}", coords), @"[Coord { x: 1.0, y: 2.0 }, Coord { x: 3.0, y: 4.0 }]");
+        insta::assert_snapshot!(format!("{:?}", coords), @"[Coord { lng: 1.0, lat: 2.0 }, Coord { lng: 3.0, lat: 4.0 }]");
     }
 
     #[test]
@@ -161,7 +161,7 @@ mod tests {
             vec![(1.0, 2.0), (3.0, 4.0)]
         );
         // Debug+iter works
-        insta::assert_snapshot!(format!("{:?}", coords), @"[Coord { x: 1.0, y: 2.0 }, Coord { x: 3.0, y: 4.0 }]");
+        insta::assert_snapshot!(format!("{:?}", coords), @"[Coord { lng: 1.0, lat: 2.0 }, Coord { lng: 3.0, lat: 4.0 }]");
     }
 
     #[test]
@@ -0,0 +1,270 @@
+use std::mem;
+
+use crate::{BoundingBox, Coord, RelationBetweenShapes, ZerometryError, Zolygon};
+
+/// A small grid laid over the bounding box of a polygon telling for each cell if it's entirely inside,
+/// entirely outside or crossed by the polygon.
+/// It lets the point-in-polygon test decide with a single bit test for most of the points and only the
+/// points falling in a cell crossed by the polygon need the exact ray casting.
+///
+/// The binary format is the size of the grid as a `u64`, followed by the bitmap of the cells
+/// entirely inside the polygon and the bitmap of the cells entirely outside of the polygon.
+/// The cells are stored row by row starting from the bottom left, and both bitmaps are stored in `u64`s.
+#[derive(Clone, Copy)]
+pub(crate) struct CoverageMask<'a> {
+    size: usize,
+    inside: &'a [u64],
+    outside: &'a [u64],
+}
+
+impl<'a> CoverageMask<'a> {
+    /// Create a mask of `size` × `size` cells from its two bitmaps.
+    pub fn new(size: usize, inside: &'a [u64], outside: &'a [u64]) -> Result<Self, ZerometryError> {
+        let words = bitmap_words(size).unwrap_or(usize::MAX);
+        let found = inside.len().min(outside.len());
+        if found < words {
+            return Err(ZerometryError::Truncated {
+                expected: words.saturating_mul(mem::size_of::<u64>()),
+                found: found * mem::size_of::<u64>(),
+            });
+        }
+        Ok(Self {
+            size,
+            inside,
+            outside,
+        })
+    }
+
+    /// Return the number of bytes used by the two bitmaps in the header.
+    #[inline]
+    pub fn bitmaps_len_in_bytes(&self) -> usize {
+        2 * bitmap_words(self.size).unwrap_or(0) * mem::size_of::<u64>()
+    }
+
+    /// Compute the mask of the polygon and write it in the buffer.
+    pub fn write_from_zolygon(writer: &mut Vec<u8>, zolygon: &Zolygon, size: usize) {
+        let cells = size * size;
+        let mut boundary = vec![false; cells];
+        let bounding_box = zolygon.bounding_box();
+        let (width, height) = cell_size(bounding_box, size);
+
+        if width > 0.0 && height > 0.0 {
+            // The cells are slightly enlarged to be sure the floating point errors can't let a segment
+            // touch a cell without being detected
+            let (margin_x, margin_y) = (width * 1e-6, height * 1e-6);
+            for segment in zolygon.segments() {
+                let (start, end) = (segment.start(), segment.end());
+                let (first_column, last_column) = (
+                    column(bounding_box, size, start.lng().min(end.lng()) - margin_x),
+                    column(bounding_box, size, start.lng().max(end.lng()) + margin_x),
+                );
+                let (first_row, last_row) = (
+                    row(bounding_box, size, start.lat().min(end.lat()) - margin_y),
+                    row(bounding_box, size, start.lat().max(end.lat()) + margin_y),
+                );
+                for row in first_row..=last_row {
+                    for column in first_column..=last_column {
+                        let min = (
+                            bounding_box.left() + column as f64 * width - margin_x,
+                            bounding_box.bottom() + row as f64 * height - margin_y,
+                        );
+                        let max = (
+                            bounding_box.left() + (column + 1) as f64 * width + margin_x,
+                            bounding_box.bottom() + (row + 1) as f64 * height + margin_y,
+                        );
+                        if segment_intersects_rect(
+                            (start.lng(), start.lat()),
+                            (end.lng(), end.lat()),
+                            min,
+                            max,
+                        ) {
+                            boundary[row * size + column] = true;
+                        }
+                    }
+                }
+            }
+        } else {
+            // Nothing can be decided on a flat polygon
+            boundary.fill(true);
+        }
+
+        let words = bitmap_words(size).unwrap();
+        let mut inside = vec![0_u64; words];
+        let mut outside = vec![0_u64; words];
+        for (cell, _) in boundary
+            .iter()
+            .enumerate()
+            .filter(|(_, boundary)| !**boundary)
+        {
+            let (row, column) = (cell / size, cell % size);
+            let center = [
+                bounding_box.left() + (column as f64 + 0.5) * width,
+                bounding_box.bottom() + (row as f64 + 0.5) * height,
+            ];
+            let center = unsafe { Coord::from_slice(&center) };
+            let bitmap = if zolygon.contains(center) {
+                &mut inside
+            } else {
+                &mut outside
+            };
+            bitmap[cell / 64] |= 1 << (cell % 64);
+        }
+
+        writer.extend_from_slice(&(size as u64).to_ne_bytes());
+        for word in inside.iter().chain(&outside) {
+            writer.extend_from_slice(&word.to_ne_bytes());
+        }
+    }
+
+    /// Return `Some(true)` if the coord is inside the polygon, `Some(false)` if it's outside and `None`
+    /// if it falls in a cell crossed by the polygon and the exact test must be ran.
+    /// The coord must be contained in the bounding box.
+    #[inline]
+    pub fn classify(&self, bounding_box: &BoundingBox, coord: &Coord) -> Option<bool> {
+        let (width, height) = cell_size(bounding_box, self.size);
+        if self.size == 0 || width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let cell = row(bounding_box, self.size, coord.lat()) * self.size
+            + column(bounding_box, self.size, coord.lng());
+        let (word, bit) = (cell / 64, 1 << (cell % 64));
+        if self.inside[word] & bit != 0 {
+            Some(true)
+        } else if self.outside[word] & bit != 0 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Return the number of `u64` used by each bitmap of a mask of the specified size.
+pub(crate) fn bitmap_words(size: usize) -> Option<usize> {
+    Some(size.checked_mul(size)?.div_ceil(64))
+}
+
+fn cell_size(bounding_box: &BoundingBox, size: usize) -> (f64, f64) {
+    (
+        (bounding_box.right() - bounding_box.left()) / size as f64,
+        (bounding_box.top() - bounding_box.bottom()) / size as f64,
+    )
+}
+
+fn column(bounding_box: &BoundingBox, size: usize, lng: f64) -> usize {
+    let ratio = (lng - bounding_box.left()) / (bounding_box.right() - bounding_box.left());
+    ((ratio * size as f64) as usize).min(size - 1)
+}
+
+fn row(bounding_box: &BoundingBox, size: usize, lat: f64) -> usize {
+    let ratio = (lat - bounding_box.bottom()) / (bounding_box.top() - bounding_box.bottom());
+    ((ratio * size as f64) as usize).min(size - 1)
+}
+
+/// Liang–Barsky clipping of the segment against the rectangle, return `true` if any part of the
+/// segment is inside the rectangle.
+fn segment_intersects_rect(
+    start: (f64, f64),
+    end: (f64, f64),
+    min: (f64, f64),
+    max: (f64, f64),
+) -> bool {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let (mut enter, mut exit) = (0.0_f64, 1.0_f64);
+    for (p, q) in [
+        (-dx, start.0 - min.0),
+        (dx, max.0 - start.0),
+        (-dy, start.1 - min.1),
+        (dy, max.1 - start.1),
+    ] {
+        if p == 0.0 {
+            // The segment is parallel to this edge and outside of it
+            if q < 0.0 {
+                return false;
+            }
+        } else {
+            let ratio = q / p;
+            if p < 0.0 {
+                if ratio > exit {
+                    return false;
+                }
+                enter = enter.max(ratio);
+            } else {
+                if ratio < enter {
+                    return false;
+                }
+                exit = exit.min(ratio);
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use crate::{WriteOptions, Zerometry};
+
+    use super::*;
+
+    #[test]
+    fn mask_agrees_with_ray_casting() {
+        // A concave polygon with a deep notch
+        let polygon = polygon![
+            (x: 0., y: 0.),
+            (x: 10., y: 0.),
+            (x: 10., y: 10.),
+            (x: 6., y: 10.),
+            (x: 5., y: 2.),
+            (x: 4., y: 10.),
+            (x: 0., y: 10.),
+        ];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(polygon.clone())).unwrap();
+        let Zerometry::Polygon(exact) = (unsafe { Zerometry::from_bytes(&buffer).unwrap() }) else {
+            unreachable!()
+        };
+        let mut masked_buffer = Vec::new();
+        let options = WriteOptions {
+            coverage_mask: Some(16),
+            ..WriteOptions::default()
+        };
+        Zerometry::write_from_geometry_with_options(&mut masked_buffer, &polygon.into(), options)
+            .unwrap();
+        let Zerometry::Polygon(masked) =
+            (unsafe { Zerometry::from_bytes(&masked_buffer).unwrap() })
+        else {
+            unreachable!()
+        };
+        assert!(masked.has_coverage_mask());
+        assert!(!exact.has_coverage_mask());
+        assert_eq!(exact.to_geo(), masked.to_geo());
+
+        let mask = masked.coverage_mask().unwrap();
+        let mut decided = 0;
+        // The probes are shifted to not fall exactly on the horizontal edges where the ray casting is ambiguous
+        for x in 0..100 {
+            for y in 0..100 {
+                let coord = [(x as f64 + 0.5) / 10.0, (y as f64 + 0.5) / 10.0];
+                let coord = unsafe { Coord::from_slice(&coord) };
+                let expected = exact.contains(coord);
+                if let Some(inside) = mask.classify(masked.bounding_box(), coord) {
+                    decided += 1;
+                    assert_eq!(inside, expected, "{coord:?}");
+                }
+                assert_eq!(masked.contains(coord), expected, "{coord:?}");
+            }
+        }
+        assert!(decided > 100 * 100 / 2, "{decided}");
+    }
+
+    #[test]
+    fn invalid_mask() {
+        let words = [0_u64; 3];
+        let err = CoverageMask::new(9, &words[..1], &words[1..])
+            .err()
+            .unwrap();
+        assert_compact_debug_snapshot!(err, @"Truncated { expected: 16, found: 8 }");
+    }
+}
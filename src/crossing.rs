@@ -0,0 +1,249 @@
+use geo::line_intersection::{LineIntersection, line_intersection};
+
+use crate::{Coord, RelationBetweenShapes, Zine, Zolygon};
+
+/// Tell if a line goes into or out of a polygon when it crosses its boundary, see [`Zine::crossings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossingDirection {
+    /// The line was outside of the polygon before the crossing and is inside after.
+    Entering,
+    /// The line was inside of the polygon before the crossing and is outside after.
+    Exiting,
+}
+
+/// A point where a line crosses the boundary of a polygon, see [`Zine::crossings`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossing {
+    /// The index of the segment of the line on which the crossing happens.
+    /// When the crossing happens exactly on a point of the line, it's the segment starting at this point.
+    pub segment: usize,
+    /// The position of the crossing.
+    pub coord: geo_types::Coord<f64>,
+    /// Whether the line enters or exits the polygon.
+    pub direction: CrossingDirection,
+}
+
+/// A point where the line meets the boundary of the polygon.
+/// The position is the index of the segment plus the ratio of the segment traveled.
+#[derive(Clone, Copy)]
+struct Breakpoint {
+    position: f64,
+    coord: geo_types::Coord<f64>,
+    /// True if the line follows the boundary of the polygon from this breakpoint to the next one.
+    along_boundary: bool,
+}
+
+impl<'a> Zine<'a> {
+    /// Return the points where the line crosses the boundary of the polygon, ordered from the start to
+    /// the end of the line, and tell for each of them if the line is entering or exiting the polygon.
+    ///
+    /// The line only crosses the boundary when it goes from one side to the other:
+    /// - Touching the boundary and going back to the same side is not a crossing.
+    /// - The line starting or ending on the boundary is not a crossing.
+    /// - When the line follows the boundary for a while, the crossing is reported where it leaves the boundary.
+    ///
+    /// ```
+    /// use zerometry::{CrossingDirection, Zerometry};
+    /// use geo_types::{Geometry, LineString, coord, polygon};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let square = polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)];
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(square)).unwrap();
+    /// let Zerometry::Polygon(square) = (unsafe { Zerometry::from_bytes(&buffer).unwrap() }) else { unreachable!() };
+    ///
+    /// let mut buffer = Vec::new();
+    /// let route = LineString::from(vec![(-5.0, 5.0), (5.0, 5.0), (15.0, 5.0)]);
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::LineString(route)).unwrap();
+    /// let Zerometry::Line(route) = (unsafe { Zerometry::from_bytes(&buffer).unwrap() }) else { unreachable!() };
+    ///
+    /// let crossings = route.crossings(&square);
+    /// assert_eq!(crossings.len(), 2);
+    /// assert_eq!((crossings[0].segment, crossings[0].coord), (0, coord! { x: 0.0, y: 5.0 }));
+    /// assert_eq!(crossings[0].direction, CrossingDirection::Entering);
+    /// assert_eq!((crossings[1].segment, crossings[1].coord), (1, coord! { x: 10.0, y: 5.0 }));
+    /// assert_eq!(crossings[1].direction, CrossingDirection::Exiting);
+    /// ```
+    pub fn crossings(&self, zolygon: &Zolygon) -> Vec<Crossing> {
+        if self.len() < 2
+            || zolygon.is_empty()
+            || self.bounding_box().disjoint(zolygon.bounding_box())
+        {
+            return Vec::new();
+        }
+
+        let mut breakpoints = Vec::new();
+        for (index, segment) in self.segments().enumerate() {
+            let line = geo_types::Line::new(segment.start().to_geo(), segment.end().to_geo());
+            for edge in zolygon.segments() {
+                let edge = geo_types::Line::new(edge.start().to_geo(), edge.end().to_geo());
+                match line_intersection(line, edge) {
+                    None => (),
+                    Some(LineIntersection::SinglePoint { intersection, .. }) => {
+                        breakpoints.push(Breakpoint {
+                            position: index as f64 + ratio(line, intersection),
+                            coord: intersection,
+                            along_boundary: false,
+                        });
+                    }
+                    Some(LineIntersection::Collinear { intersection }) => {
+                        let (start, end) = (
+                            ratio(line, intersection.start),
+                            ratio(line, intersection.end),
+                        );
+                        let (start, end) = if start <= end {
+                            ((start, intersection.start), (end, intersection.end))
+                        } else {
+                            ((end, intersection.end), (start, intersection.start))
+                        };
+                        breakpoints.push(Breakpoint {
+                            position: index as f64 + start.0,
+                            coord: start.1,
+                            along_boundary: start.0 < end.0,
+                        });
+                        breakpoints.push(Breakpoint {
+                            position: index as f64 + end.0,
+                            coord: end.1,
+                            along_boundary: false,
+                        });
+                    }
+                }
+            }
+        }
+        breakpoints.sort_by(|a, b| a.position.total_cmp(&b.position));
+        // The same point can be found on two consecutive edges of the polygon or two consecutive segments of the line
+        breakpoints.dedup_by(|next, previous| {
+            if next.position == previous.position {
+                previous.along_boundary |= next.along_boundary;
+                true
+            } else {
+                false
+            }
+        });
+
+        let end = (self.len() - 1) as f64;
+        let mut crossings = Vec::new();
+        // The side of the polygon the line was on before the current breakpoint and the
+        // breakpoint where it left this side
+        let mut previous: Option<bool> = None;
+        let mut last_position = 0.0;
+        let mut last_breakpoint: Option<Breakpoint> = None;
+        for breakpoint in breakpoints.iter().copied().chain([Breakpoint {
+            position: end,
+            coord: self.coords()[self.len() - 1].to_geo(),
+            along_boundary: false,
+        }]) {
+            let following_boundary = last_breakpoint.is_some_and(|b| b.along_boundary);
+            if breakpoint.position > last_position && !following_boundary {
+                let middle = self.interpolate((last_position + breakpoint.position) / 2.0);
+                let middle = [middle.x, middle.y];
+                let inside = zolygon.contains(unsafe { Coord::from_slice(&middle) });
+                if let (Some(was_inside), Some(crossed)) = (previous, last_breakpoint)
+                    && was_inside != inside
+                {
+                    crossings.push(Crossing {
+                        segment: (crossed.position as usize).min(self.len() - 2),
+                        coord: crossed.coord,
+                        direction: if inside {
+                            CrossingDirection::Entering
+                        } else {
+                            CrossingDirection::Exiting
+                        },
+                    });
+                }
+                previous = Some(inside);
+            }
+            last_position = breakpoint.position;
+            last_breakpoint = Some(breakpoint);
+        }
+        crossings
+    }
+
+    /// Return the coord at the specified position, which is the index of a segment plus the ratio of the segment traveled.
+    fn interpolate(&self, position: f64) -> geo_types::Coord<f64> {
+        let index = (position as usize).min(self.len() - 2);
+        let ratio = position - index as f64;
+        let (start, end) = (
+            self.coords()[index].to_geo(),
+            self.coords()[index + 1].to_geo(),
+        );
+        start + (end - start) * ratio
+    }
+}
+
+/// Return the ratio of the segment to travel to reach the point, which must be on the segment.
+fn ratio(line: geo_types::Line<f64>, point: geo_types::Coord<f64>) -> f64 {
+    let delta = line.delta();
+    let length = delta.x * delta.x + delta.y * delta.y;
+    if length == 0.0 {
+        return 0.0;
+    }
+    let offset = point - line.start;
+    ((offset.x * delta.x + offset.y * delta.y) / length).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::Zerometry;
+
+    fn crossings(line: Vec<(f64, f64)>) -> Vec<(usize, (f64, f64), CrossingDirection)> {
+        // A square with a notch on its top
+        let polygon = polygon![
+            (x: 0., y: 0.),
+            (x: 10., y: 0.),
+            (x: 10., y: 10.),
+            (x: 6., y: 10.),
+            (x: 5., y: 5.),
+            (x: 4., y: 10.),
+            (x: 0., y: 10.),
+        ];
+        let mut polygon_buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut polygon_buffer, &Geometry::Polygon(polygon)).unwrap();
+        let Zerometry::Polygon(zolygon) =
+            (unsafe { Zerometry::from_bytes(&polygon_buffer).unwrap() })
+        else {
+            unreachable!()
+        };
+        let mut line_buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut line_buffer, &LineString::from(line).into()).unwrap();
+        let Zerometry::Line(zine) = (unsafe { Zerometry::from_bytes(&line_buffer).unwrap() })
+        else {
+            unreachable!()
+        };
+        zine.crossings(&zolygon)
+            .into_iter()
+            .map(|crossing| (crossing.segment, crossing.coord.x_y(), crossing.direction))
+            .collect()
+    }
+
+    #[test]
+    fn crossing_through_the_notch() {
+        assert_compact_debug_snapshot!(crossings(vec![(-1.0, 8.0), (11.0, 8.0)]), @"[(0, (0.0, 8.0), Entering), (0, (4.4, 8.0), Exiting), (0, (5.6, 8.0), Entering), (0, (10.0, 8.0), Exiting)]");
+        assert_compact_debug_snapshot!(crossings(vec![(11.0, 8.0), (-1.0, 8.0)]), @"[(0, (10.0, 8.0), Entering), (0, (5.6, 8.0), Exiting), (0, (4.4, 8.0), Entering), (0, (0.0, 8.0), Exiting)]");
+        // Starting inside and coming back through the tip of the notch
+        assert_compact_debug_snapshot!(crossings(vec![(2.0, 2.0), (5.0, 12.0), (5.0, -2.0)]), @"[(0, (4.16, 9.2), Exiting), (1, (5.0, 5.0), Entering), (1, (5.0, 0.0), Exiting)]");
+    }
+
+    #[test]
+    fn touching_is_not_crossing() {
+        // Going through the bottom of the notch
+        assert_compact_debug_snapshot!(crossings(vec![(3.0, 3.0), (5.0, 5.0), (7.0, 3.0)]), @"[]");
+        // Touching the notch from outside
+        assert_compact_debug_snapshot!(crossings(vec![(5.0, 12.0), (5.0, 5.0), (5.0, 12.0)]), @"[]");
+        // Starting and ending on the boundary
+        assert_compact_debug_snapshot!(crossings(vec![(0.0, 5.0), (10.0, 5.0)]), @"[]");
+        // Outside and along the bottom edge
+        assert_compact_debug_snapshot!(crossings(vec![(-2.0, -1.0), (2.0, 0.0), (8.0, 0.0), (12.0, -1.0)]), @"[]");
+    }
+
+    #[test]
+    fn leaving_the_boundary() {
+        // Following the bottom edge from outside before going inside
+        assert_compact_debug_snapshot!(crossings(vec![(-2.0, 0.0), (3.0, 0.0), (3.0, 3.0)]), @"[(1, (3.0, 0.0), Entering)]");
+        // Through a vertex of the polygon
+        assert_compact_debug_snapshot!(crossings(vec![(-1.0, -1.0), (5.0, 5.0)]), @"[(0, (0.0, 0.0), Entering)]");
+    }
+}
@@ -0,0 +1,540 @@
+use geo::{Distance, Euclidean, Geometry};
+
+use crate::{Coord, Coords, RelationBetweenShapes, Segment, Zerometry, Zoint};
+
+/// Compute the planar distance between two shapes, in the unit of their coordinates.
+/// Shapes that intersect or are contained in each other are at a distance of zero, and an empty shape is at an
+/// infinite distance of everything.
+///
+/// It's implemented for every pair of shapes of this crate. Unlike [`distance_matrix`] it never converts the shapes
+/// to [`geo_types::Geometry`]: the points, lines and rings of both shapes are compared directly, and the ones whose
+/// bounding boxes are farther than the closest distance found so far are skipped.
+///
+/// ```
+/// use zerometry::{DistanceBetweenShapes, Zerometry};
+/// use geo_types::{Geometry, Point, polygon};
+///
+/// let write = |geometry: Geometry| {
+///     let mut buffer = Vec::new();
+///     Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+///     buffer
+/// };
+/// let stores = [
+///     write(polygon![(x: 10., y: 0.), (x: 12., y: 0.), (x: 12., y: 2.), (x: 10., y: 2.)].into()),
+///     write(polygon![(x: 0., y: 3.), (x: 1., y: 3.), (x: 1., y: 4.), (x: 0., y: 4.)].into()),
+///     write(Point::new(-5.0, 0.0).into()),
+/// ];
+/// let stores: Vec<Zerometry> = stores.iter().map(|store| Zerometry::try_from_bytes(store).unwrap()).collect();
+/// let me = write(Point::new(0.0, 0.0).into());
+/// let me = Zerometry::try_from_bytes(&me).unwrap();
+///
+/// let mut nearest: Vec<(usize, f64)> = stores.iter().map(|store| me.distance(store)).enumerate().collect();
+/// nearest.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+/// assert_eq!(nearest, [(1, 3.0), (2, 5.0), (0, 10.0)]);
+/// ```
+pub trait DistanceBetweenShapes<Other: ?Sized> {
+    /// Return the planar distance between `self` and `other`.
+    fn distance(&self, other: &Other) -> f64;
+}
+
+impl<'a, 'b, Shape, Other> DistanceBetweenShapes<Other> for Shape
+where
+    Shape: Copy + Into<Zerometry<'a>>,
+    Other: Copy + Into<Zerometry<'b>>,
+{
+    fn distance(&self, other: &Other) -> f64 {
+        let shape: Zerometry = (*self).into();
+        shape.planar_distance(&(*other).into())
+    }
+}
+
+/// Return the euclidean distance between every query and every target, row by row: the distance between
+/// `queries[i]` and `targets[j]` is stored at the index `i * targets.len() + j`.
+/// Shapes that intersect or are contained in each other are at a distance of zero, and an empty shape is at an
+/// infinite distance of everything.
+///
+/// The shapes are converted to [`geo_types::Geometry`] once instead of once per pair, and the intersection of the
+/// shapes is only checked when their bounding boxes overlap.
+/// With the `rayon` feature the rows are computed in parallel.
+///
+/// ```
+/// use zerometry::{Zerometry, distance_matrix};
+/// use geo_types::Point;
+///
+/// let points: Vec<Vec<u8>> = [(0.0, 0.0), (3.0, 4.0)]
+///     .into_iter()
+///     .map(|point| {
+///         let mut buffer = Vec::new();
+///         Zerometry::write_from_geometry(&mut buffer, &Point::from(point).into()).unwrap();
+///         buffer
+///     })
+///     .collect();
+/// let points: Vec<Zerometry> = points.iter().map(|point| Zerometry::try_from_bytes(point).unwrap()).collect();
+/// assert_eq!(distance_matrix(&points, &points), [0.0, 5.0, 5.0, 0.0]);
+/// ```
+pub fn distance_matrix(queries: &[Zerometry], targets: &[Zerometry]) -> Vec<f64> {
+    let mut matrix = vec![0.0; queries.len() * targets.len()];
+    if targets.is_empty() {
+        return matrix;
+    }
+    let geo_targets: Vec<Geometry<f64>> = targets.iter().map(Zerometry::to_geo).collect();
+    let fill_row = |(query, row): (&Zerometry, &mut [f64])| {
+        let geo_query = query.to_geo();
+        for ((target, geo_target), distance) in targets.iter().zip(&geo_targets).zip(row) {
+            *distance = query.distance_with(&geo_query, target, geo_target);
+        }
+    };
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        queries
+            .par_iter()
+            .zip(matrix.par_chunks_mut(targets.len()))
+            .for_each(fill_row);
+    }
+    #[cfg(not(feature = "rayon"))]
+    queries
+        .iter()
+        .zip(matrix.chunks_mut(targets.len()))
+        .for_each(fill_row);
+
+    matrix
+}
+
+impl<'a> Zerometry<'a> {
+    /// Return `true` if the euclidean distance between both shapes is strictly lower than the threshold.
+    /// Shapes that intersect or are contained in each other are at a distance of zero.
+    ///
+    /// The bounding boxes are used to answer without computing the exact distance when possible,
+    /// which makes this method way cheaper than computing the distance for the shapes that are far away
+    /// or very close to each other.
+    /// An empty shape is never closer than anything.
+    pub fn closer_than(&self, other: &Zerometry, threshold: f64) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false;
+        }
+
+        let (left, bottom, right, top) = self.bounds();
+        let (other_left, other_bottom, other_right, other_top) = other.bounds();
+
+        // The closest points of the shapes can't be closer than the bounding boxes
+        let dx = (other_left - right).max(left - other_right).max(0.0);
+        let dy = (other_bottom - top).max(bottom - other_top).max(0.0);
+        if dx.hypot(dy) >= threshold {
+            return false;
+        }
+
+        // And can't be farther than the opposite corners of the bounding boxes
+        let dx = (other_right - left).max(right - other_left);
+        let dy = (other_top - bottom).max(top - other_bottom);
+        if dx.hypot(dy) < threshold {
+            return true;
+        }
+
+        Euclidean.distance(&self.to_geo(), &other.to_geo()) < threshold
+    }
+
+    /// Return the distance between both shapes, their conversion to geo is only used when they don't intersect.
+    fn distance_with(
+        &self,
+        geo_self: &Geometry<f64>,
+        other: &Zerometry,
+        geo_other: &Geometry<f64>,
+    ) -> f64 {
+        if self.is_empty() || other.is_empty() {
+            return f64::INFINITY;
+        }
+        if let (Zerometry::Point(a), Zerometry::Point(b)) = (self, other) {
+            return (a.lng() - b.lng()).hypot(a.lat() - b.lat());
+        }
+
+        let (left, bottom, right, top) = self.bounds();
+        let (other_left, other_bottom, other_right, other_top) = other.bounds();
+        let overlap = left <= other_right
+            && other_left <= right
+            && bottom <= other_top
+            && other_bottom <= top;
+        if overlap && self.any_relation(other).any_relation() {
+            return 0.0;
+        }
+
+        Euclidean.distance(geo_self, geo_other)
+    }
+
+    /// Return the distance between both shapes without converting them to geo.
+    fn planar_distance(&self, other: &Zerometry) -> f64 {
+        if self.is_empty() || other.is_empty() {
+            return f64::INFINITY;
+        }
+        if let (Zerometry::Point(a), Zerometry::Point(b)) = (self, other) {
+            return (a.lng() - b.lng()).hypot(a.lat() - b.lat());
+        }
+        if bounds_distance(self.bounds(), other.bounds()) == 0.0
+            && self.any_relation(other).any_relation()
+        {
+            return 0.0;
+        }
+
+        // The shapes are disjoint, their distance is the distance between their closest members
+        let members = Member::of(self);
+        let other_members = Member::of(other);
+        let mut pairs: Vec<_> = members
+            .iter()
+            .flat_map(|member| {
+                other_members
+                    .iter()
+                    .map(move |other| (bounds_distance(member.bounds, other.bounds), member, other))
+            })
+            .collect();
+        pairs.sort_by(|(a, _, _), (b, _, _)| a.total_cmp(b));
+
+        let mut closest = f64::INFINITY;
+        for (lower_bound, member, other) in pairs {
+            if lower_bound >= closest {
+                break;
+            }
+            closest = closest.min(member.distance(other, closest));
+        }
+        closest
+    }
+
+    /// Return the point of the shape that is the farthest from the specified coord, or `None` if the shape is empty.
+    /// Since the farthest point of a line or a polygon is always one of its vertices, only the vertices are considered.
+    /// If multiple points are at the same distance the first one is returned.
+    pub fn farthest_point_from(&self, coord: &Coord) -> Option<Zoint<'a>> {
+        let mut farthest: Option<(f64, &'a Coord)> = None;
+        self.for_each_coord(&mut |candidate| {
+            let distance = (candidate.lng() - coord.lng()).hypot(candidate.lat() - coord.lat());
+            if farthest.is_none_or(|(max, _)| distance > max) {
+                farthest = Some((distance, candidate));
+            }
+        });
+        farthest.map(|(_, coord)| Zoint::new(coord))
+    }
+
+    /// Return the `(left, bottom, right, top)` bounds of the shape.
+    pub(crate) fn bounds(&self) -> (f64, f64, f64, f64) {
+        let bounding_box = match self {
+            Zerometry::Point(a) => return (a.lng(), a.lat(), a.lng(), a.lat()),
+            Zerometry::MultiPoints(a) => a.bounding_box(),
+            Zerometry::Line(a) => a.bounding_box(),
+            Zerometry::MultiLines(a) => a.bounding_box(),
+            Zerometry::Polygon(a) => a.bounding_box(),
+            Zerometry::MultiPolygon(a) => a.bounding_box(),
+            Zerometry::Collection(a) => a.bounding_box(),
+        };
+        (
+            bounding_box.left(),
+            bounding_box.bottom(),
+            bounding_box.right(),
+            bounding_box.top(),
+        )
+    }
+
+    /// Call the closure on all the coords of the shape.
+    pub(crate) fn for_each_coord(&self, f: &mut dyn FnMut(&'a Coord)) {
+        match self {
+            Zerometry::Point(a) => f(a.coord()),
+            Zerometry::MultiPoints(a) => a.coords().iter().for_each(f),
+            Zerometry::Line(a) => a.coords().iter().for_each(f),
+            Zerometry::MultiLines(a) => {
+                for index in 0..a.len() {
+                    a.get(index).unwrap().coords().iter().for_each(&mut *f);
+                }
+            }
+            Zerometry::Polygon(a) => a.coords().iter().for_each(f),
+            Zerometry::MultiPolygon(a) => {
+                for index in 0..a.len() {
+                    a.get(index).unwrap().coords().iter().for_each(&mut *f);
+                }
+            }
+            Zerometry::Collection(a) => {
+                Zerometry::MultiPoints(a.points()).for_each_coord(f);
+                Zerometry::MultiLines(a.lines()).for_each_coord(f);
+                Zerometry::MultiPolygon(a.polygons()).for_each_coord(f);
+            }
+        }
+    }
+}
+
+/// A point, line or ring of a shape along with its `(left, bottom, right, top)` bounds.
+struct Member<'a> {
+    kind: MemberKind<'a>,
+    bounds: (f64, f64, f64, f64),
+}
+
+enum MemberKind<'a> {
+    Point(&'a Coord),
+    Chain(&'a Coords),
+}
+
+impl<'a> Member<'a> {
+    /// Return the points, lines and rings of the shape.
+    fn of(zerometry: &Zerometry<'a>) -> Vec<Self> {
+        let mut members = Vec::new();
+        let points = match zerometry {
+            Zerometry::MultiPoints(points) => Some(*points),
+            Zerometry::Collection(collection) => Some(collection.points()),
+            _ => None,
+        };
+        if let Zerometry::Point(point) = zerometry {
+            members.push(Self::point(point.coord()));
+        }
+        members.extend(
+            points
+                .iter()
+                .flat_map(|points| points.coords().iter())
+                .map(Self::point),
+        );
+
+        let line = match zerometry {
+            Zerometry::Line(line) => Some(line.coords()),
+            _ => None,
+        };
+        let lines = match zerometry {
+            Zerometry::MultiLines(lines) => Some(*lines),
+            Zerometry::Collection(collection) => Some(collection.lines()),
+            _ => None,
+        };
+        let chains = line
+            .into_iter()
+            .chain(lines.into_iter().flat_map(|lines| {
+                (0..lines.len()).map(move |index| lines.get(index).unwrap().coords())
+            }))
+            .chain(zerometry.rings());
+        for coords in chains {
+            match coords.len() {
+                0 => (),
+                1 => members.push(Self::point(&coords[0])),
+                _ => members.push(Self {
+                    kind: MemberKind::Chain(coords),
+                    bounds: coords_bounds(coords.iter()),
+                }),
+            }
+        }
+        members
+    }
+
+    fn point(coord: &'a Coord) -> Self {
+        Self {
+            kind: MemberKind::Point(coord),
+            bounds: (coord.lng(), coord.lat(), coord.lng(), coord.lat()),
+        }
+    }
+
+    /// Return the distance between both members, the segments that can't be closer than `closest` are skipped.
+    fn distance(&self, other: &Member, closest: f64) -> f64 {
+        match (&self.kind, &other.kind) {
+            (MemberKind::Point(a), MemberKind::Point(b)) => {
+                (a.lng() - b.lng()).hypot(a.lat() - b.lat())
+            }
+            (MemberKind::Point(point), MemberKind::Chain(chain))
+            | (MemberKind::Chain(chain), MemberKind::Point(point)) => segments(chain)
+                .fold(closest, |closest, segment| {
+                    closest.min(point_segment_distance(point, &segment))
+                }),
+            (MemberKind::Chain(a), MemberKind::Chain(b)) => {
+                let mut closest = closest;
+                for segment in segments(a) {
+                    let bounds = coords_bounds([segment.start(), segment.end()].into_iter());
+                    if bounds_distance(bounds, other.bounds) >= closest {
+                        continue;
+                    }
+                    for other in segments(b) {
+                        let other_bounds = coords_bounds([other.start(), other.end()].into_iter());
+                        if bounds_distance(bounds, other_bounds) < closest {
+                            closest = closest.min(segment_distance(&segment, &other));
+                        }
+                    }
+                }
+                closest
+            }
+        }
+    }
+}
+
+fn segments(coords: &Coords) -> impl Iterator<Item = Segment<'_>> {
+    coords
+        .consecutive_pairs()
+        // SAFETY: The pairs returned by coords are aligned and contains only two points
+        .map(|coords| unsafe { Segment::from_slice(coords) })
+}
+
+fn coords_bounds<'a>(coords: impl Iterator<Item = &'a Coord>) -> (f64, f64, f64, f64) {
+    coords.fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(left, bottom, right, top), coord| {
+            (
+                left.min(coord.lng()),
+                bottom.min(coord.lat()),
+                right.max(coord.lng()),
+                top.max(coord.lat()),
+            )
+        },
+    )
+}
+
+/// Return the distance between two `(left, bottom, right, top)` bounds, zero if they overlap.
+fn bounds_distance(a: (f64, f64, f64, f64), b: (f64, f64, f64, f64)) -> f64 {
+    let (left, bottom, right, top) = a;
+    let (other_left, other_bottom, other_right, other_top) = b;
+    let dx = (other_left - right).max(left - other_right).max(0.0);
+    let dy = (other_bottom - top).max(bottom - other_top).max(0.0);
+    dx.hypot(dy)
+}
+
+fn point_segment_distance(point: &Coord, segment: &Segment) -> f64 {
+    let (start, end) = (segment.start(), segment.end());
+    let (dx, dy) = (end.lng() - start.lng(), end.lat() - start.lat());
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((point.lng() - start.lng()) * dx + (point.lat() - start.lat()) * dy) / length)
+            .clamp(0.0, 1.0)
+    };
+    (start.lng() + t * dx - point.lng()).hypot(start.lat() + t * dy - point.lat())
+}
+
+/// Two segments that don't intersect are always closest at one of their ends.
+fn segment_distance(a: &Segment, b: &Segment) -> f64 {
+    if a.intersects(b) {
+        return 0.0;
+    }
+    point_segment_distance(a.start(), b)
+        .min(point_segment_distance(a.end(), b))
+        .min(point_segment_distance(b.start(), a))
+        .min(point_segment_distance(b.end(), a))
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{
+        Geometry, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, polygon,
+    };
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    fn write(geometry: Geometry<f64>) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn closer_than() {
+        let square =
+            write(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into());
+        let square = unsafe { Zerometry::from_bytes(&square).unwrap() };
+        let point = write(Point::new(3.0, 0.5).into());
+        let point = unsafe { Zerometry::from_bytes(&point).unwrap() };
+        // The bounding boxes are close but the line is far from the square corner
+        let line = write(LineString::from(vec![(1.5, 3.0), (3.0, 1.5)]).into());
+        let line = unsafe { Zerometry::from_bytes(&line).unwrap() };
+        let empty = write(MultiPoint::<f64>::new(Vec::new()).into());
+        let empty = unsafe { Zerometry::from_bytes(&empty).unwrap() };
+
+        assert!(!square.closer_than(&point, 2.0));
+        assert!(square.closer_than(&point, 2.1));
+        assert!(point.closer_than(&square, 2.1));
+        assert!(square.closer_than(&square, f64::MIN_POSITIVE));
+        assert!(!square.closer_than(&line, 1.7));
+        assert!(square.closer_than(&line, 1.8));
+        assert!(!square.closer_than(&empty, f64::INFINITY));
+    }
+
+    #[test]
+    fn farthest_point_from() {
+        let square =
+            write(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into());
+        let square = unsafe { Zerometry::from_bytes(&square).unwrap() };
+        let origin = [0.1, 0.2];
+        let origin = unsafe { Coord::from_slice(&origin) };
+        assert_compact_debug_snapshot!(square.farthest_point_from(origin), @"Some(Zoint { lng: 1.0, lat: 1.0 })");
+
+        let empty = write(MultiPoint::<f64>::new(Vec::new()).into());
+        let empty = unsafe { Zerometry::from_bytes(&empty).unwrap() };
+        assert_compact_debug_snapshot!(empty.farthest_point_from(origin), @"None");
+    }
+
+    #[test]
+    fn distance_matrix() {
+        let geometries: Vec<Geometry<f64>> = vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into(),
+            Point::new(3.0, 0.5).into(),
+            Point::new(0.5, 0.5).into(),
+            LineString::from(vec![(1.5, 3.0), (3.0, 1.5)]).into(),
+            MultiPoint::<f64>::new(Vec::new()).into(),
+        ];
+        let buffers: Vec<_> = geometries.iter().cloned().map(write).collect();
+        let shapes: Vec<_> = buffers
+            .iter()
+            .map(|buffer| Zerometry::try_from_bytes(buffer).unwrap())
+            .collect();
+
+        let matrix = super::distance_matrix(&shapes[..2], &shapes);
+        assert_compact_debug_snapshot!(matrix, @"[0.0, 2.0, 0.0, 1.7677669529663687, inf, 2.0, 0.0, 2.5, 1.0, inf]");
+        // Same as geo except for the empty shapes
+        for (i, query) in geometries[..2].iter().enumerate() {
+            for (j, target) in geometries[..4].iter().enumerate() {
+                let expected = Euclidean.distance(query, target);
+                assert!((matrix[i * shapes.len() + j] - expected).abs() < 1e-12);
+            }
+        }
+
+        assert_compact_debug_snapshot!(super::distance_matrix(&shapes, &[]), @"[]");
+    }
+
+    #[test]
+    fn distance_between_shapes() {
+        let geometries: Vec<Geometry<f64>> = vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into(),
+            Point::new(3.0, 0.5).into(),
+            Point::new(0.5, 0.5).into(),
+            LineString::from(vec![(1.5, 3.0), (3.0, 1.5)]).into(),
+            LineString::from(vec![(-1.0, -1.0), (2.0, 2.0)]).into(),
+            MultiPoint::from(vec![(5.0, 5.0), (-2.0, 0.5)]).into(),
+            MultiLineString::new(vec![
+                LineString::from(vec![(4.0, 0.0), (4.0, 4.0)]),
+                LineString::from(vec![(0.0, -3.0), (1.0, -2.0)]),
+            ])
+            .into(),
+            MultiPolygon::new(vec![
+                polygon![(x: 2., y: 2.), (x: 3., y: 2.), (x: 3., y: 3.)],
+                polygon![(x: -3., y: -3.), (x: -2., y: -3.), (x: -2., y: -2.)],
+            ])
+            .into(),
+        ];
+        let buffers: Vec<_> = geometries.iter().cloned().map(write).collect();
+        let shapes: Vec<_> = buffers
+            .iter()
+            .map(|buffer| Zerometry::try_from_bytes(buffer).unwrap())
+            .collect();
+
+        for (shape, geometry) in shapes.iter().zip(&geometries) {
+            for (other, other_geometry) in shapes.iter().zip(&geometries) {
+                let expected = Euclidean.distance(geometry, other_geometry);
+                assert!(
+                    (shape.distance(other) - expected).abs() < 1e-12,
+                    "{geometry:?} {other_geometry:?}"
+                );
+            }
+        }
+
+        let (Zerometry::Polygon(square), Zerometry::Line(line)) = (shapes[0], shapes[3]) else {
+            unreachable!()
+        };
+        assert_compact_debug_snapshot!(square.distance(&line), @"1.7677669529663689");
+        assert_compact_debug_snapshot!(line.distance(&square), @"1.7677669529663689");
+        let empty = write(MultiPoint::<f64>::new(Vec::new()).into());
+        let empty = Zerometry::try_from_bytes(&empty).unwrap();
+        assert_compact_debug_snapshot!(square.distance(&empty), @"inf");
+    }
+}
@@ -0,0 +1,177 @@
+use std::mem;
+
+use geo_types::Geometry;
+
+use crate::{
+    Zerometry, ZerometryError,
+    header::{ELEVATIONS_FLAG, Header},
+};
+
+impl<'a> Zerometry<'a> {
+    /// Same as [`Self::write_from_geometry`] but an elevation, or `z`, is attached to every coordinate of the geometry.
+    /// The elevations are stored in the header and never used by the relations, which keep working in 2D.
+    ///
+    /// There must be one elevation per coordinate, in the order of the coordinates of [`Self::to_geo`].
+    /// For a [`geo_types::GeometryCollection`] it means the points come first, then the lines and the polygons.
+    /// The interiors of the polygons are not stored and must not get an elevation.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, LineString};
+    ///
+    /// let trail = Geometry::LineString(LineString::from(vec![(6.86, 45.83), (6.87, 45.84)]));
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry_with_elevations(&mut buffer, &trail, &[1035.0, 4808.0]).unwrap();
+    ///
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    /// assert_eq!(zerometry.to_geo(), trail);
+    /// assert_eq!(Zerometry::read_alt(&buffer, 1).unwrap(), Some(4808.0));
+    /// ```
+    pub fn write_from_geometry_with_elevations(
+        writer: &mut Vec<u8>,
+        geometry: &Geometry<f64>,
+        elevations: &[f64],
+    ) -> Result<(), ZerometryError> {
+        let coords = stored_coords(geometry);
+        if coords != elevations.len() {
+            return Err(ZerometryError::InvalidElevationsCount {
+                coords,
+                elevations: elevations.len(),
+            });
+        }
+
+        let start = writer.len();
+        Zerometry::write_from_geometry(writer, geometry)?;
+
+        let tag_end = start + mem::size_of::<u64>();
+        let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+        writer[start..tag_end].copy_from_slice(&(tag | ELEVATIONS_FLAG).to_ne_bytes());
+        let header = (elevations.len() as u64).to_ne_bytes().into_iter().chain(
+            elevations
+                .iter()
+                .flat_map(|elevation| elevation.to_ne_bytes()),
+        );
+        writer.splice(tag_end..tag_end, header);
+        Ok(())
+    }
+
+    /// Return the elevation of every coordinate stored in the header of a serialized zerometry, or `None` if it was
+    /// written without elevations, see [`Self::write_from_geometry_with_elevations`].
+    /// Only the header is read, the number of elevations is checked against the shape by [`Self::from_bytes`].
+    pub fn read_elevations(data: &'a [u8]) -> Result<Option<&'a [f64]>, ZerometryError> {
+        Ok(Header::parse(data)?.elevations)
+    }
+
+    /// Return the elevation of the coordinate at the specified index, in the order of the coordinates of
+    /// [`Self::to_geo`], or `None` if the zerometry has no elevations or the index doesn't exist.
+    pub fn read_alt(data: &[u8], index: usize) -> Result<Option<f64>, ZerometryError> {
+        Ok(Zerometry::read_elevations(data)?.and_then(|elevations| elevations.get(index).copied()))
+    }
+
+    /// Check there is one elevation read from the header per coordinate of the zerometry.
+    pub(crate) fn check_elevations(&self, elevations: &[f64]) -> Result<(), ZerometryError> {
+        let coords = self.coords_count();
+        if elevations.len() != coords {
+            return Err(ZerometryError::InvalidElevations {
+                elevations: elevations.len(),
+                coords,
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn coords_count(&self) -> usize {
+        match self {
+            Zerometry::Point(_) => 1,
+            Zerometry::MultiPoints(points) => points.len(),
+            Zerometry::Line(line) => line.len(),
+            Zerometry::MultiLines(lines) => lines.lines().map(|line| line.len()).sum(),
+            Zerometry::Polygon(polygon) => polygon.coords().len(),
+            Zerometry::MultiPolygon(polygons) => polygons
+                .polygons()
+                .map(|polygon| polygon.coords().len())
+                .sum(),
+            Zerometry::Collection(collection) => [
+                Zerometry::MultiPoints(collection.points()),
+                Zerometry::MultiLines(collection.lines()),
+                Zerometry::MultiPolygon(collection.polygons()),
+            ]
+            .iter()
+            .map(Zerometry::coords_count)
+            .sum(),
+        }
+    }
+}
+
+/// Return the number of coordinates stored when writing the geometry, the interiors of the polygons are dropped.
+fn stored_coords(geometry: &Geometry<f64>) -> usize {
+    match geometry {
+        Geometry::Point(_) => 1,
+        Geometry::MultiPoint(points) => points.0.len(),
+        Geometry::Line(_) => 2,
+        Geometry::LineString(line) => line.0.len(),
+        Geometry::MultiLineString(lines) => lines.iter().map(|line| line.0.len()).sum(),
+        Geometry::Polygon(polygon) => polygon.exterior().0.len(),
+        Geometry::MultiPolygon(polygons) => polygons
+            .iter()
+            .map(|polygon| polygon.exterior().0.len())
+            .sum(),
+        Geometry::Rect(rect) => rect.to_polygon().exterior().0.len(),
+        Geometry::Triangle(triangle) => triangle.to_polygon().exterior().0.len(),
+        Geometry::GeometryCollection(collection) => collection.iter().map(stored_coords).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{GeometryCollection, LineString, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn elevations_round_trip() {
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            Point::new(5.0, 5.0).into(),
+        ]));
+        // The point is stored before the polygon
+        let elevations = [50.0, 0.0, 10.0, 20.0, 0.0];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_elevations(&mut buffer, &collection, &elevations)
+            .unwrap();
+        let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(zerometry.to_geo(), @"GEOMETRYCOLLECTION(MULTIPOINT(5.0 5.0),MULTILINESTRING EMPTY,MULTIPOLYGON(((0.0 0.0,1.0 0.0,1.0 1.0,0.0 0.0))))");
+        assert_eq!(
+            Zerometry::read_elevations(&buffer).unwrap(),
+            Some(&elevations[..])
+        );
+        assert_eq!(Zerometry::read_alt(&buffer, 0).unwrap(), Some(50.0));
+        assert_eq!(Zerometry::read_alt(&buffer, 5).unwrap(), None);
+
+        // The shape read is the same as without elevations
+        let mut flat = Vec::new();
+        Zerometry::write_from_geometry(&mut flat, &collection).unwrap();
+        assert_eq!(Zerometry::read_elevations(&flat).unwrap(), None);
+        let flat = Zerometry::try_from_bytes(&flat).unwrap();
+        assert_eq!(format!("{zerometry:?}"), format!("{flat:?}"));
+
+        let error = Zerometry::write_from_geometry_with_elevations(
+            &mut Vec::new(),
+            &collection,
+            &elevations[1..],
+        )
+        .unwrap_err();
+        assert_compact_debug_snapshot!(error, @"InvalidElevationsCount { coords: 5, elevations: 4 }");
+        assert_compact_debug_snapshot!(error.to_string(), @r#""The geometry contains 5 coordinates but 4 elevations were provided""#);
+
+        // The number of elevations is checked when reading
+        let mut buffer = Vec::new();
+        let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]));
+        Zerometry::write_from_geometry_with_elevations(&mut buffer, &line, &[1.0, 2.0]).unwrap();
+        buffer[8..16].copy_from_slice(&1_u64.to_ne_bytes());
+        buffer.drain(16..24);
+        let error = Zerometry::try_from_bytes(&buffer).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"InvalidElevations { elevations: 1, coords: 2 }");
+    }
+}
@@ -0,0 +1,249 @@
+use bytemuck::cast_slice;
+
+use crate::header::{COLLECTION_TAG, tag};
+
+use crate::{Zerometry, Zine, Zollection, Zolygon, ZultiLines, ZultiPoints, ZultiPolygons};
+
+/// The offsets of the lines and polygons in an empty collection, the points and lines are made of
+/// an empty bounding box and the lines also contain their count of zero followed by the padding.
+const EMPTY_COLLECTION_OFFSETS: u64 = {
+    let lines = 32_u32.to_ne_bytes();
+    let polygons = 72_u32.to_ne_bytes();
+    u64::from_ne_bytes([
+        lines[0],
+        lines[1],
+        lines[2],
+        lines[3],
+        polygons[0],
+        polygons[1],
+        polygons[2],
+        polygons[3],
+    ])
+};
+
+/// The corners of an empty bounding box, inverted and infinite, see [`crate::BoundingBox::is_empty`].
+const MIN: u64 = f64::INFINITY.to_bits();
+const MAX: u64 = f64::NEG_INFINITY.to_bits();
+
+/// The serialized empty collection prefixed by its tag, stored in `u64`s to be aligned on 64 bits.
+/// Every shape starts with an empty bounding box and the counts are zero, which means the bounding box of the
+/// lines followed by their count is also a valid empty shape, see [`empty_shape`].
+static EMPTY_COLLECTION: [u64; 20] = [
+    tag(COLLECTION_TAG),
+    // the bounding box
+    MIN,
+    MIN,
+    MAX,
+    MAX,
+    EMPTY_COLLECTION_OFFSETS,
+    // the points
+    MIN,
+    MIN,
+    MAX,
+    MAX,
+    // the lines
+    MIN,
+    MIN,
+    MAX,
+    MAX,
+    0,
+    // the polygons
+    MIN,
+    MIN,
+    MAX,
+    MAX,
+    0,
+];
+
+/// An empty bounding box followed by a count of zero and its padding.
+fn empty_shape() -> &'static [u8] {
+    &cast_slice(&EMPTY_COLLECTION)[80..120]
+}
+
+impl Zerometry<'static> {
+    /// Return the bytes of an empty [`Zerometry::Collection`], without allocating.
+    /// It's a valid minimal zerometry that can be stored as a placeholder, a deleted document tombstone for example.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        cast_slice(&EMPTY_COLLECTION)
+    }
+
+    /// Return an empty [`Zerometry::Collection`].
+    #[inline]
+    pub fn empty() -> Self {
+        Zerometry::Collection(Zollection::empty())
+    }
+}
+
+impl Zollection<'static> {
+    /// Return the bytes of an empty [`Zollection`], without allocating.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        &Zerometry::empty_bytes()[8..]
+    }
+
+    /// Return an empty [`Zollection`].
+    #[inline]
+    pub fn empty() -> Self {
+        unsafe { Zollection::from_bytes(Self::empty_bytes()) }
+    }
+}
+
+impl ZultiPoints<'static> {
+    /// Return the bytes of an empty [`ZultiPoints`], without allocating.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        &empty_shape()[..32]
+    }
+
+    /// Return an empty [`ZultiPoints`].
+    #[inline]
+    pub fn empty() -> Self {
+        unsafe { ZultiPoints::from_bytes(Self::empty_bytes()) }
+    }
+}
+
+impl Zine<'static> {
+    /// Return the bytes of an empty [`Zine`], without allocating.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        &empty_shape()[..32]
+    }
+
+    /// Return an empty [`Zine`].
+    #[inline]
+    pub fn empty() -> Self {
+        unsafe { Zine::from_bytes(Self::empty_bytes()) }
+    }
+}
+
+impl ZultiLines<'static> {
+    /// Return the bytes of an empty [`ZultiLines`], without allocating.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        empty_shape()
+    }
+
+    /// Return an empty [`ZultiLines`].
+    #[inline]
+    pub fn empty() -> Self {
+        unsafe { ZultiLines::from_bytes(Self::empty_bytes()) }
+    }
+}
+
+impl Zolygon<'static> {
+    /// Return the bytes of an empty [`Zolygon`], without allocating.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        &empty_shape()[..32]
+    }
+
+    /// Return an empty [`Zolygon`].
+    #[inline]
+    pub fn empty() -> Self {
+        unsafe { Zolygon::from_bytes(Self::empty_bytes()) }
+    }
+}
+
+impl ZultiPolygons<'static> {
+    /// Return the bytes of an empty [`ZultiPolygons`], without allocating.
+    #[inline]
+    pub fn empty_bytes() -> &'static [u8] {
+        empty_shape()
+    }
+
+    /// Return an empty [`ZultiPolygons`].
+    #[inline]
+    pub fn empty() -> Self {
+        unsafe { ZultiPolygons::from_bytes(Self::empty_bytes()) }
+    }
+}
+
+impl Default for Zerometry<'_> {
+    fn default() -> Self {
+        Zerometry::empty()
+    }
+}
+
+impl Default for Zollection<'_> {
+    fn default() -> Self {
+        Zollection::empty()
+    }
+}
+
+impl Default for ZultiPoints<'_> {
+    fn default() -> Self {
+        ZultiPoints::empty()
+    }
+}
+
+impl Default for Zine<'_> {
+    fn default() -> Self {
+        Zine::empty()
+    }
+}
+
+impl Default for ZultiLines<'_> {
+    fn default() -> Self {
+        ZultiLines::empty()
+    }
+}
+
+impl Default for Zolygon<'_> {
+    fn default() -> Self {
+        Zolygon::empty()
+    }
+}
+
+impl Default for ZultiPolygons<'_> {
+    fn default() -> Self {
+        ZultiPolygons::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+        Polygon,
+    };
+
+    use super::*;
+
+    #[test]
+    fn empty_bytes_match_the_written_ones() {
+        let mut buffer = Vec::new();
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(Vec::new()));
+        Zerometry::write_from_geometry(&mut buffer, &collection).unwrap();
+        assert_eq!(Zerometry::empty_bytes(), buffer);
+        assert!(Zerometry::default().is_empty());
+
+        let mut buffer = Vec::new();
+        ZultiPoints::write_from_geometry(&mut buffer, &MultiPoint::new(Vec::new())).unwrap();
+        assert_eq!(ZultiPoints::empty_bytes(), buffer);
+        assert!(ZultiPoints::default().is_empty());
+
+        let mut buffer = Vec::new();
+        Zine::write_from_geometry(&mut buffer, &LineString::new(Vec::new())).unwrap();
+        assert_eq!(Zine::empty_bytes(), buffer);
+        assert!(Zine::default().is_empty());
+
+        let mut buffer = Vec::new();
+        ZultiLines::write_from_geometry(&mut buffer, &MultiLineString::new(Vec::new())).unwrap();
+        assert_eq!(ZultiLines::empty_bytes(), buffer);
+        assert!(ZultiLines::default().is_empty());
+
+        let mut buffer = Vec::new();
+        let polygon = Polygon::new(LineString::new(Vec::new()), Vec::new());
+        Zolygon::write_from_geometry(&mut buffer, &polygon).unwrap();
+        assert_eq!(Zolygon::empty_bytes(), buffer);
+        assert!(Zolygon::default().is_empty());
+
+        let mut buffer = Vec::new();
+        ZultiPolygons::write_from_geometry(&mut buffer, &MultiPolygon::new(Vec::new())).unwrap();
+        assert_eq!(ZultiPolygons::empty_bytes(), buffer);
+        assert!(ZultiPolygons::default().is_empty());
+
+        assert!(Zollection::default().is_empty());
+    }
+}
@@ -0,0 +1,317 @@
+use std::mem;
+
+use crate::{
+    ZerometryError,
+    checksum::{CHECKSUM_SIZE_IN_BYTES, crc32},
+    coverage_mask::bitmap_words,
+    header::{
+        CHECKSUM_FLAG, COVERAGE_MASK_FLAG, ELEVATIONS_FLAG, GENERATION_FLAG, GROUPS_FLAG, IDS_FLAG,
+        KIND_MASK, PROPERTIES_FLAG,
+    },
+    properties::padded_size,
+};
+
+/// Whether the numbers are converted from the native endianness to the foreign one, or the opposite.
+/// The swap itself is the same in both directions, but the counts and offsets must be read in the native endianness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    FromNative,
+    ToNative,
+}
+
+/// Reverse the bytes of every number of a serialized zerometry, including its tag and header, in place.
+/// The version stamped in the tag is left untouched.
+pub(crate) fn swap_byte_order(data: &mut [u8], direction: Direction) -> Result<(), ZerometryError> {
+    let tag = data
+        .first_chunk::<8>()
+        .map(|tag| u64::from_ne_bytes(*tag))
+        .ok_or(ZerometryError::Truncated {
+            expected: mem::size_of::<u64>(),
+            found: data.len(),
+        })?;
+    let native_tag = match direction {
+        Direction::FromNative => tag,
+        Direction::ToNative => tag.swap_bytes(),
+    };
+    if native_tag & CHECKSUM_FLAG == 0 {
+        return swap_body(data, direction);
+    }
+
+    // The checksum is verified on the bytes as they're stored and computed again once they're swapped
+    let split =
+        data.len()
+            .checked_sub(CHECKSUM_SIZE_IN_BYTES)
+            .ok_or(ZerometryError::Truncated {
+                expected: CHECKSUM_SIZE_IN_BYTES,
+                found: data.len(),
+            })?;
+    let (data, trailer) = data.split_at_mut(split);
+    let stored = u64::from_ne_bytes(trailer.try_into().unwrap());
+    let stored = match direction {
+        Direction::FromNative => stored,
+        Direction::ToNative => stored.swap_bytes(),
+    };
+    let computed = crc32(data);
+    if stored as u32 != computed {
+        return Err(ZerometryError::ChecksumMismatch {
+            stored: stored as u32,
+            computed,
+        });
+    }
+    swap_body(data, direction)?;
+    let computed = crc32(data) as u64;
+    let computed = match direction {
+        Direction::FromNative => computed.swap_bytes(),
+        Direction::ToNative => computed,
+    };
+    trailer.copy_from_slice(&computed.to_ne_bytes());
+    Ok(())
+}
+
+/// Swap the bytes of a zerometry without its checksum.
+fn swap_body(data: &mut [u8], direction: Direction) -> Result<(), ZerometryError> {
+    let mut swapper = Swapper::new(data, direction);
+    let tag = swapper.u64()?;
+    if tag & GENERATION_FLAG != 0 {
+        swapper.u64()?;
+    }
+    // The ids and the groups are both made of the number of lines and polygons followed by their content
+    for (flag, words_per_element) in [(IDS_FLAG, 1), (GROUPS_FLAG, 4)] {
+        if tag & flag != 0 {
+            let lines = swapper.u64()?;
+            let polygons = swapper.u64()?;
+            swapper.words(
+                lines
+                    .saturating_add(polygons)
+                    .saturating_mul(words_per_element),
+            )?;
+        }
+    }
+    if tag & COVERAGE_MASK_FLAG != 0 {
+        let size = swapper.u64()?;
+        let words = usize::try_from(size)
+            .ok()
+            .and_then(bitmap_words)
+            .map_or(u64::MAX, |words| words as u64);
+        swapper.words(words.saturating_mul(2))?;
+    }
+    if tag & ELEVATIONS_FLAG != 0 {
+        let count = swapper.u64()?;
+        swapper.words(count)?;
+    }
+    if tag & PROPERTIES_FLAG != 0 {
+        let count = swapper.u64()?;
+        let size = swapper.u64()?;
+        swapper.words(count)?;
+        // The properties are opaque bytes that are kept as-is
+        swapper.take(padded_size(size))?;
+    }
+
+    match tag & KIND_MASK {
+        // The points, lines and polygons are only made of floats
+        0 | 1 | 2 | 4 => swapper.rest(),
+        3 | 5 => swap_children(swapper),
+        6 => {
+            swapper.words(4)?;
+            let lines_offset = swapper.u32()? as usize;
+            let polygons_offset = swapper.u32()? as usize;
+            let rest = swapper.into_rest();
+            let len = rest.len();
+            if lines_offset > polygons_offset || polygons_offset > len {
+                return Err(ZerometryError::InvalidOffset {
+                    offset: polygons_offset.min(lines_offset),
+                    len,
+                });
+            }
+            let (points, rest) = rest.split_at_mut(lines_offset);
+            let (lines, polygons) = rest.split_at_mut(polygons_offset - lines_offset);
+            Swapper::new(points, direction).rest()?;
+            swap_children(Swapper::new(lines, direction))?;
+            swap_children(Swapper::new(polygons, direction))
+        }
+        7 => {
+            swapper.words(4)?;
+            let len = swapper.u64()?;
+            swapper.words(len.saturating_mul(2))?;
+            // The original indices are padded to stay aligned on 64 bits
+            swapper.u32s(len.saturating_add(len % 2))?;
+            swapper.rest()
+        }
+        kind => Err(ZerometryError::InvalidTag(kind)),
+    }
+}
+
+/// Swap a multi lines or multi polygons: its bounding box, the number of children and their offsets,
+/// the padding and the children that are only made of floats.
+fn swap_children(mut swapper: Swapper) -> Result<(), ZerometryError> {
+    swapper.words(4)?;
+    let count = swapper.u32()? as u64;
+    swapper.u32s(count + (1 - count % 2))?;
+    swapper.rest()
+}
+
+struct Swapper<'a> {
+    data: &'a mut [u8],
+    direction: Direction,
+}
+
+impl<'a> Swapper<'a> {
+    fn new(data: &'a mut [u8], direction: Direction) -> Self {
+        Self { data, direction }
+    }
+
+    /// Split the next `size` bytes from the data.
+    fn take(&mut self, size: u64) -> Result<&'a mut [u8], ZerometryError> {
+        let len = self.data.len();
+        let size = usize::try_from(size)
+            .ok()
+            .filter(|size| *size <= len)
+            .ok_or(ZerometryError::Truncated {
+                expected: usize::try_from(size).unwrap_or(usize::MAX),
+                found: len,
+            })?;
+        let (taken, rest) = mem::take(&mut self.data).split_at_mut(size);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    /// Swap the next `count` numbers of eight bytes.
+    fn words(&mut self, count: u64) -> Result<(), ZerometryError> {
+        let size = count.saturating_mul(mem::size_of::<u64>() as u64);
+        self.take(size)?
+            .chunks_exact_mut(mem::size_of::<u64>())
+            .for_each(<[u8]>::reverse);
+        Ok(())
+    }
+
+    /// Swap the next `count` numbers of four bytes.
+    fn u32s(&mut self, count: u64) -> Result<(), ZerometryError> {
+        let size = count.saturating_mul(mem::size_of::<u32>() as u64);
+        self.take(size)?
+            .chunks_exact_mut(mem::size_of::<u32>())
+            .for_each(<[u8]>::reverse);
+        Ok(())
+    }
+
+    /// Swap the next `u64` and return its value in the native endianness.
+    fn u64(&mut self) -> Result<u64, ZerometryError> {
+        let bytes: &mut [u8; 8] = self.take(8)?.try_into().unwrap();
+        let before = u64::from_ne_bytes(*bytes);
+        bytes.reverse();
+        Ok(match self.direction {
+            Direction::FromNative => before,
+            Direction::ToNative => before.swap_bytes(),
+        })
+    }
+
+    /// Swap the next `u32` and return its value in the native endianness.
+    fn u32(&mut self) -> Result<u32, ZerometryError> {
+        let bytes: &mut [u8; 4] = self.take(4)?.try_into().unwrap();
+        let before = u32::from_ne_bytes(*bytes);
+        bytes.reverse();
+        Ok(match self.direction {
+            Direction::FromNative => before,
+            Direction::ToNative => before.swap_bytes(),
+        })
+    }
+
+    /// Swap all the remaining numbers of eight bytes.
+    fn rest(mut self) -> Result<(), ZerometryError> {
+        let trailing = self.data.len() % mem::size_of::<u64>();
+        if trailing != 0 {
+            return Err(ZerometryError::TrailingBytes(trailing));
+        }
+        self.words((self.data.len() / mem::size_of::<u64>()) as u64)
+    }
+
+    fn into_rest(self) -> &'a mut [u8] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPoint, MultiPolygon, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{WriteOptions, Zerometry};
+
+    #[test]
+    fn swap_round_trip() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let points = MultiPoint::from(vec![(3.0, 1.0), (1.0, 2.0), (2.0, 2.0)]);
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            points.clone().into(),
+            line.into(),
+            square.clone().into(),
+            square.clone().into(),
+        ]));
+        let options = WriteOptions {
+            generation: Some(4),
+            bounding_box_groups: true,
+            ..WriteOptions::default()
+        };
+        let mut samples = Vec::new();
+        for (geometry, options) in [
+            (collection.clone(), options),
+            (
+                square.clone().into(),
+                WriteOptions {
+                    coverage_mask: Some(3),
+                    ..options
+                },
+            ),
+            (
+                points.into(),
+                WriteOptions {
+                    sort_points: true,
+                    ..options
+                },
+            ),
+            (
+                collection.clone(),
+                WriteOptions {
+                    checksum: true,
+                    ..options
+                },
+            ),
+        ] {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+            samples.push(buffer);
+        }
+        let mut buffer = Vec::new();
+        let multi_polygon = MultiPolygon::new(vec![square.clone(), square]).into();
+        Zerometry::write_from_geometry_with_ids(&mut buffer, &multi_polygon, &[1, 2]).unwrap();
+        samples.push(buffer);
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_elevations(&mut buffer, &multi_polygon, &[1.0; 10])
+            .unwrap();
+        samples.push(buffer);
+        let mut buffer = Vec::new();
+        let features = GeometryCollection::new_from(vec![multi_polygon]);
+        Zerometry::write_from_geometry_with_properties(&mut buffer, &features, &[b"properties"])
+            .unwrap();
+        samples.push(buffer);
+
+        for sample in samples {
+            let mut swapped = sample.clone();
+            swap_byte_order(&mut swapped, Direction::FromNative).unwrap();
+            assert_ne!(swapped, sample);
+            assert_eq!(
+                u64::from_ne_bytes(swapped[..8].try_into().unwrap()),
+                u64::from_ne_bytes(sample[..8].try_into().unwrap()).swap_bytes()
+            );
+            swap_byte_order(&mut swapped, Direction::ToNative).unwrap();
+            assert_eq!(swapped, sample);
+        }
+
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &collection).unwrap();
+        let len = buffer.len();
+        let err = swap_byte_order(&mut buffer[..len - 4], Direction::FromNative).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"TrailingBytes(4)");
+    }
+}
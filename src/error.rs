@@ -0,0 +1,536 @@
+use std::{fmt, io, mem};
+
+use crate::{BOUNDING_BOX_SIZE_IN_BYTES, COORD_SIZE_IN_BYTES, bounding_box::is_valid_bounding_box};
+
+/// The errors returned when reading an invalid zerometry, see [`crate::Zerometry::try_from_bytes`],
+/// or when a geometry can't be written, see [`crate::Zerometry::write_from_geometry`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ZerometryError {
+    /// The tag doesn't correspond to any kind of shape or contains unknown flags.
+    InvalidTag(u64),
+    /// The zerometry was written by a more recent version of the format, see [`crate::FORMAT_VERSION`].
+    UnsupportedVersion(u8),
+    /// The data ends before the end of the shape.
+    Truncated {
+        /// The number of bytes required to read the next part of the shape.
+        expected: usize,
+        /// The number of bytes available.
+        found: usize,
+    },
+    /// The data contains bytes after the end of the shape.
+    TrailingBytes(usize),
+    /// The data is not aligned on 64 bits.
+    Misaligned,
+    /// The zerometry is stored in little-endian and must be converted before being read on this big-endian platform,
+    /// see [`crate::AlignedBytes`].
+    ForeignEndianness,
+    /// The padding inserted to stay aligned on 64 bits is not made of zeros.
+    InvalidPadding,
+    /// The bottom left corner of a bounding box is not below and on the left of its top right corner.
+    InvalidBoundingBox,
+    /// An offset points outside of the shape, before the previous one or is not aligned on 64 bits.
+    InvalidOffset {
+        /// The invalid offset.
+        offset: usize,
+        /// The number of bytes the offset points into.
+        len: usize,
+    },
+    /// An original index of sorted multi points doesn't correspond to any point.
+    InvalidIndex {
+        /// The invalid index.
+        index: usize,
+        /// The number of points.
+        len: usize,
+    },
+    /// The number of ids stored in the header doesn't match the number of lines and polygons.
+    InvalidIds {
+        /// The number of lines ids stored.
+        line_ids: usize,
+        /// The number of polygons ids stored.
+        polygon_ids: usize,
+        /// The number of lines in the shape.
+        lines: usize,
+        /// The number of polygons in the shape.
+        polygons: usize,
+    },
+    /// The number of elevations stored in the header doesn't match the number of coordinates of the shape.
+    InvalidElevations {
+        /// The number of elevations stored.
+        elevations: usize,
+        /// The number of coordinates of the shape.
+        coords: usize,
+    },
+    /// A coverage mask is stored with a shape that is not a polygon.
+    UnexpectedCoverageMask,
+    /// Properties are stored with a shape that is not a collection.
+    UnexpectedProperties,
+    /// The convexity stored in the tag doesn't match the polygon, or is stored with a shape that is not a polygon.
+    InvalidConvexity,
+    /// The ends of the properties stored in the header are not sorted or exceed their size.
+    InvalidProperties,
+    /// The subtype stored in the tag doesn't match the kind or the number of coordinates of the shape.
+    UnexpectedSubtype,
+    /// The number of bounding box groups stored in the header doesn't match the number of lines or polygons.
+    InvalidGroups {
+        /// The number of groups stored.
+        groups: usize,
+        /// The number of lines or polygons in the shape.
+        members: usize,
+    },
+    /// Bounding box groups are stored with a shape that is neither a multi lines, a multi polygon or a collection.
+    UnexpectedGroups,
+    /// The first and last points of the ring of a polygon are different.
+    UnclosedRing,
+    /// A line or a polygon ring crosses or touches itself, see [`crate::Zerometry::check_simple`].
+    SelfIntersection {
+        /// The index of the first segment, as yielded by [`crate::Zerometry::segments_enumerated`].
+        first: usize,
+        /// The index of the segment it intersects with.
+        second: usize,
+    },
+    /// The step of a quantized shape is not a positive number, or is too small to store the shape,
+    /// see [`crate::QuantizedZolygon`].
+    InvalidQuantizationStep(f64),
+    /// The checksum stored after the shape doesn't match its bytes, the data is corrupted,
+    /// see [`crate::WriteOptions::checksum`].
+    ChecksumMismatch {
+        /// The checksum stored after the shape.
+        stored: u32,
+        /// The checksum of the bytes of the zerometry.
+        computed: u32,
+    },
+    /// Ids were provided for a geometry that can't store them, see [`crate::Zerometry::write_from_geometry_with_ids`].
+    UnsupportedIds,
+    /// The number of properties provided doesn't match the number of members of the collection,
+    /// see [`crate::Zerometry::write_from_geometry_with_properties`].
+    InvalidPropertiesCount {
+        /// The number of members of the collection.
+        members: usize,
+        /// The number of properties provided.
+        properties: usize,
+    },
+    /// The number of ids provided doesn't match the number of members of the geometry.
+    InvalidIdsCount {
+        /// The number of members of the geometry.
+        members: usize,
+        /// The number of ids provided.
+        ids: usize,
+    },
+    /// The number of elevations provided doesn't match the number of coordinates of the geometry,
+    /// see [`crate::Zerometry::write_from_geometry_with_elevations`].
+    InvalidElevationsCount {
+        /// The number of coordinates of the geometry.
+        coords: usize,
+        /// The number of elevations provided.
+        elevations: usize,
+    },
+    /// The number of timestamps provided doesn't match the number of points of the line,
+    /// see [`crate::TimedZine::write_from_geometry`].
+    InvalidTimestampsCount {
+        /// The number of points of the line.
+        points: usize,
+        /// The number of timestamps provided.
+        timestamps: usize,
+    },
+    /// The timestamps provided are not sorted in increasing order.
+    UnsortedTimestamps,
+    /// A coordinate is out of the `[-180, 180]` longitude or `[-90, 90]` latitude range,
+    /// see [`crate::Zerometry::check_lng_lat`].
+    InvalidLngLat {
+        /// The longitude, or `x`, of the coordinate.
+        lng: f64,
+        /// The latitude, or `y`, of the coordinate.
+        lat: f64,
+    },
+    /// Some coordinates are out of range but all of them would be valid once their axes are swapped: the geometry
+    /// was most likely written in the `lat, lng` order instead of the `lng, lat` order, see [`crate::AxisOrder`].
+    SwappedLngLat {
+        /// The longitude, or `x`, of the first coordinate out of range.
+        lng: f64,
+        /// The latitude, or `y`, of the first coordinate out of range.
+        lat: f64,
+    },
+    /// The underlying writer failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for ZerometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZerometryError::InvalidTag(tag) => write!(f, "Invalid zerometry tag {tag:#x}"),
+            ZerometryError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported zerometry format version {version}, the latest supported version is {}",
+                crate::FORMAT_VERSION
+            ),
+            ZerometryError::Truncated { expected, found } => write!(
+                f,
+                "Was expecting at least {expected} bytes but found {found}"
+            ),
+            ZerometryError::TrailingBytes(len) => {
+                write!(f, "Found {len} unexpected bytes after the end of the shape")
+            }
+            ZerometryError::Misaligned => write!(f, "The data must be aligned on 64 bits"),
+            ZerometryError::ForeignEndianness => write!(
+                f,
+                "The zerometry is stored in little-endian and must be converted to be read on this platform"
+            ),
+            ZerometryError::InvalidPadding => write!(f, "The padding must be made of zeros"),
+            ZerometryError::InvalidBoundingBox => write!(
+                f,
+                "The bottom left corner of the bounding box must be below and on the left of its top right corner"
+            ),
+            ZerometryError::InvalidOffset { offset, len } => {
+                write!(f, "Invalid offset {offset} in a shape of {len} bytes")
+            }
+            ZerometryError::InvalidIndex { index, len } => {
+                write!(f, "Invalid original index {index} for {len} points")
+            }
+            ZerometryError::InvalidIds {
+                line_ids,
+                polygon_ids,
+                lines,
+                polygons,
+            } => write!(
+                f,
+                "Found {line_ids} lines ids and {polygon_ids} polygons ids for {lines} lines and {polygons} polygons"
+            ),
+            ZerometryError::InvalidElevations { elevations, coords } => {
+                write!(f, "Found {elevations} elevations for {coords} coordinates")
+            }
+            ZerometryError::UnexpectedCoverageMask => {
+                write!(f, "A coverage mask can only be attached to a polygon")
+            }
+            ZerometryError::InvalidConvexity => {
+                write!(f, "The convexity stored in the tag doesn't match the shape")
+            }
+            ZerometryError::UnexpectedProperties => {
+                write!(f, "Properties can only be attached to a collection")
+            }
+            ZerometryError::InvalidProperties => {
+                write!(
+                    f,
+                    "The ends of the properties are not sorted or exceed their size"
+                )
+            }
+            ZerometryError::InvalidPropertiesCount {
+                members,
+                properties,
+            } => write!(
+                f,
+                "The collection contains {members} members but {properties} properties were provided"
+            ),
+            ZerometryError::UnexpectedSubtype => write!(
+                f,
+                "The subtype of the tag doesn't match the shape, a line must contain 2 coordinates, a triangle 4 and a rectangle 5"
+            ),
+            ZerometryError::InvalidGroups { groups, members } => write!(
+                f,
+                "Found {groups} bounding box groups for {members} members, there must be one group per {} members",
+                crate::groups::GROUP_SIZE
+            ),
+            ZerometryError::UnexpectedGroups => write!(
+                f,
+                "Bounding box groups can only be attached to multi lines, multi polygons and collections"
+            ),
+            ZerometryError::UnclosedRing => {
+                write!(f, "The first and last points of a ring must be equal")
+            }
+            ZerometryError::InvalidQuantizationStep(step) => write!(
+                f,
+                "Invalid quantization step {step}, it must be positive and small enough to store the shape"
+            ),
+            ZerometryError::SelfIntersection { first, second } => write!(
+                f,
+                "Self-intersection between the segment {first} and the segment {second}"
+            ),
+            ZerometryError::ChecksumMismatch { stored, computed } => write!(
+                f,
+                "The zerometry is corrupted, its checksum is {computed:#010x} but {stored:#010x} was stored"
+            ),
+            ZerometryError::UnsupportedIds => write!(
+                f,
+                "Ids can only be attached to multi lines, multi polygons and collections"
+            ),
+            ZerometryError::InvalidIdsCount { members, ids } => write!(
+                f,
+                "The geometry contains {members} members but {ids} ids were provided"
+            ),
+            ZerometryError::InvalidElevationsCount { coords, elevations } => write!(
+                f,
+                "The geometry contains {coords} coordinates but {elevations} elevations were provided"
+            ),
+            ZerometryError::InvalidTimestampsCount { points, timestamps } => write!(
+                f,
+                "The line contains {points} points but {timestamps} timestamps were provided"
+            ),
+            ZerometryError::UnsortedTimestamps => {
+                write!(f, "The timestamps must be sorted in increasing order")
+            }
+            ZerometryError::InvalidLngLat { lng, lat } => write!(
+                f,
+                "The coordinate [{lng}, {lat}] is out of the valid longitude and latitude ranges"
+            ),
+            ZerometryError::SwappedLngLat { lng, lat } => write!(
+                f,
+                "The coordinate [{lng}, {lat}] is out of range, the geometry seems to be written in the lat, lng order instead of lng, lat"
+            ),
+            ZerometryError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ZerometryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZerometryError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ZerometryError {
+    fn from(error: io::Error) -> Self {
+        ZerometryError::Io(error)
+    }
+}
+
+impl From<ZerometryError> for io::Error {
+    fn from(error: ZerometryError) -> Self {
+        let kind = match error {
+            ZerometryError::Io(error) => return error,
+            ZerometryError::Truncated { .. } => io::ErrorKind::UnexpectedEof,
+            ZerometryError::UnsupportedIds
+            | ZerometryError::InvalidIdsCount { .. }
+            | ZerometryError::InvalidPropertiesCount { .. }
+            | ZerometryError::InvalidElevationsCount { .. }
+            | ZerometryError::InvalidTimestampsCount { .. }
+            | ZerometryError::UnsortedTimestamps
+            | ZerometryError::InvalidLngLat { .. }
+            | ZerometryError::SwappedLngLat { .. } => io::ErrorKind::InvalidInput,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, error)
+    }
+}
+
+/// Return an error if the data is not aligned on 64 bits.
+pub(crate) fn check_aligned(data: &[u8]) -> Result<(), ZerometryError> {
+    if data.as_ptr() as usize % mem::align_of::<u64>() != 0 {
+        return Err(ZerometryError::Misaligned);
+    }
+    Ok(())
+}
+
+/// Split the data at `size`, or return an error if it's too short.
+pub(crate) fn split_checked(data: &[u8], size: usize) -> Result<(&[u8], &[u8]), ZerometryError> {
+    data.split_at_checked(size)
+        .ok_or(ZerometryError::Truncated {
+            expected: size,
+            found: data.len(),
+        })
+}
+
+/// Read a native-endian `u32` at the start of the data.
+pub(crate) fn split_u32(data: &[u8]) -> Result<(u32, &[u8]), ZerometryError> {
+    let (value, data) = split_checked(data, mem::size_of::<u32>())?;
+    Ok((u32::from_ne_bytes(value.try_into().unwrap()), data))
+}
+
+/// Read a native-endian `u64` at the start of the data.
+pub(crate) fn split_u64(data: &[u8]) -> Result<(u64, &[u8]), ZerometryError> {
+    let (value, data) = split_checked(data, mem::size_of::<u64>())?;
+    Ok((u64::from_ne_bytes(value.try_into().unwrap()), data))
+}
+
+/// Return the number of bytes used by `count` elements of `size` bytes, or an error if it can't fit in the data.
+pub(crate) fn checked_size(count: u64, size: usize, data: &[u8]) -> Result<usize, ZerometryError> {
+    usize::try_from(count)
+        .ok()
+        .and_then(|count| count.checked_mul(size))
+        .ok_or(ZerometryError::Truncated {
+            expected: usize::MAX,
+            found: data.len(),
+        })
+}
+
+/// Return an error if some bytes remain after the end of the shape.
+pub(crate) fn check_empty(data: &[u8]) -> Result<(), ZerometryError> {
+    if !data.is_empty() {
+        return Err(ZerometryError::TrailingBytes(data.len()));
+    }
+    Ok(())
+}
+
+/// Validate the bounding box at the start of the data and return the data following it.
+pub(crate) fn split_bounding_box(data: &[u8]) -> Result<&[u8], ZerometryError> {
+    let (bounding_box, data) = split_checked(data, BOUNDING_BOX_SIZE_IN_BYTES)?;
+    let bounding_box = [0, 1, 2, 3].map(|index| {
+        let start = index * mem::size_of::<f64>();
+        f64::from_ne_bytes(
+            bounding_box[start..start + mem::size_of::<f64>()]
+                .try_into()
+                .unwrap(),
+        )
+    });
+    if !is_valid_bounding_box(bounding_box) {
+        return Err(ZerometryError::InvalidBoundingBox);
+    }
+    Ok(data)
+}
+
+/// Validate the layout shared by the points, lines and polygons: a bounding box followed by the coords.
+pub(crate) fn check_bounding_box_and_coords(data: &[u8]) -> Result<(), ZerometryError> {
+    check_aligned(data)?;
+    let coords = split_bounding_box(data)?;
+    check_coords(coords)
+}
+
+/// Return an error if the data doesn't contain a whole number of coords.
+pub(crate) fn check_coords(data: &[u8]) -> Result<(), ZerometryError> {
+    match data.len() % COORD_SIZE_IN_BYTES {
+        0 => Ok(()),
+        trailing => Err(ZerometryError::TrailingBytes(trailing)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::cast_slice;
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+        polygon,
+    };
+    use insta::assert_compact_debug_snapshot;
+    use proptest::prelude::*;
+
+    use crate::{WriteOptions, Zerometry};
+
+    use super::*;
+
+    fn aligned(bytes: &[u8]) -> Vec<u64> {
+        let mut words = vec![0_u64; bytes.len().div_ceil(mem::size_of::<u64>())];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..bytes.len()].copy_from_slice(bytes);
+        words
+    }
+
+    fn samples() -> Vec<Vec<u8>> {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let points = MultiPoint::from(vec![Point::new(3.0, 1.0), Point::new(1.0, 2.0)]);
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            points.clone().into(),
+            line.clone().into(),
+            MultiLineString::new(vec![line.clone(), line.clone()]).into(),
+            square.clone().into(),
+            MultiPolygon::new(vec![square.clone(), square.clone()]).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                points.clone().into(),
+                line.into(),
+                square.clone().into(),
+            ])),
+        ];
+        let mut samples: Vec<Vec<u8>> = geometries
+            .iter()
+            .map(|geometry| {
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, geometry).unwrap();
+                buffer
+            })
+            .collect();
+        let options = WriteOptions {
+            sort_points: true,
+            coverage_mask: Some(4),
+            generation: Some(3),
+            ..WriteOptions::default()
+        };
+        for geometry in [points.into(), square.into()] {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+            samples.push(buffer);
+        }
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_ids(&mut buffer, &geometries[5], &[1, 2]).unwrap();
+        samples.push(buffer);
+        let mut buffer = Vec::new();
+        let options = WriteOptions {
+            bounding_box_groups: true,
+            ..WriteOptions::default()
+        };
+        Zerometry::write_from_geometry_with_options(&mut buffer, &geometries[6], options).unwrap();
+        samples.push(buffer);
+        samples
+    }
+
+    #[test]
+    fn valid_zerometries() {
+        for sample in samples() {
+            let words = aligned(&sample);
+            let data = &cast_slice(&words)[..sample.len()];
+            let expected = unsafe { Zerometry::from_bytes(data).unwrap() };
+            let zerometry = Zerometry::try_from_bytes(data).unwrap();
+            assert_eq!(zerometry.to_geo(), expected.to_geo());
+
+            let mut unaligned = vec![0; sample.len() + 1];
+            unaligned[1..].copy_from_slice(&sample);
+            let geometry = Zerometry::decode_unaligned_bytes(&unaligned[1..]).unwrap();
+            assert_eq!(geometry, expected.to_geo());
+        }
+    }
+
+    #[test]
+    fn invalid_zerometries() {
+        let words = aligned(&samples()[5]);
+        let data: &[u8] = cast_slice(&words);
+
+        let err = Zerometry::try_from_bytes(&data[1..]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"Misaligned");
+        let err = Zerometry::try_from_bytes(&data[..4]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"Truncated { expected: 8, found: 4 }");
+        let err = Zerometry::try_from_bytes(&data[..data.len() - 8]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"TrailingBytes(8)");
+
+        let mut corrupted = words.clone();
+        corrupted[0] = 12;
+        let err = Zerometry::try_from_bytes(cast_slice(&corrupted)).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidTag(12)");
+
+        // The offset of the second polygon
+        let mut corrupted = words.clone();
+        let offsets: &mut [u32] = bytemuck::cast_slice_mut(&mut corrupted[5..7]);
+        offsets[2] = 1000;
+        let err = Zerometry::try_from_bytes(cast_slice(&corrupted)).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidOffset { offset: 1000, len: 224 }");
+        assert_compact_debug_snapshot!(io::Error::from(err), @"Custom { kind: InvalidData, error: InvalidOffset { offset: 1000, len: 224 } }");
+
+        // The last point of the square
+        let mut corrupted = aligned(&samples()[4]);
+        corrupted[13] = 0.5_f64.to_bits();
+        let err = Zerometry::try_from_bytes(cast_slice(&corrupted)).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"UnclosedRing");
+    }
+
+    proptest! {
+        #[test]
+        fn corrupted_zerometries_never_panic(
+            sample in 0..11_usize,
+            corruptions in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 0..4),
+            truncate in any::<prop::sample::Index>(),
+        ) {
+            let mut sample = samples()[sample].clone();
+            for (index, byte) in corruptions {
+                let index = index.index(sample.len());
+                sample[index] = byte;
+            }
+            let len = truncate.index(sample.len() + 1);
+            let words = aligned(&sample);
+            let data = &cast_slice(&words)[..len];
+            let unaligned = Zerometry::decode_unaligned_bytes(&sample[..len]);
+            if let Ok(zerometry) = Zerometry::try_from_bytes(data) {
+                prop_assert_eq!(unaligned.unwrap(), zerometry.to_geo());
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    BoundingBox, Coord, Coords, LINE_TAG, POINT_TAG, POLYGON_TAG, Zerometry, Zollection,
+    ZultiLines, ZultiPoints, ZultiPolygons, header::tag,
+};
+
+impl Zerometry<'_> {
+    /// Split the shape into one standalone zerometry per member, each of them can be read back with
+    /// [`Zerometry::from_bytes`] and stored on its own, for example as one entry per shape in an index.
+    ///
+    /// - The multi points produce one point per point, in their original order.
+    /// - The multi lines and multi polygons produce one line or polygon per member, copied as-is.
+    /// - The collections produce their points, then their lines and finally their polygons.
+    /// - The points, lines and polygons produce a copy of themselves.
+    ///
+    /// The optional fields of the header, like the ids or the generation, are not copied.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, MultiPolygon, polygon};
+    ///
+    /// let multi_polygon = MultiPolygon::new(vec![
+    ///     polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+    ///     polygon![(x: 5., y: 5.), (x: 6., y: 5.), (x: 6., y: 6.)],
+    /// ]);
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &multi_polygon.clone().into()).unwrap();
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    ///
+    /// let members = zerometry.explode();
+    /// assert_eq!(members.len(), 2);
+    /// let second = Zerometry::try_from_bytes(&members[1]).unwrap();
+    /// assert_eq!(second.to_geo(), Geometry::Polygon(multi_polygon.0[1].clone()));
+    /// ```
+    pub fn explode(&self) -> Vec<Vec<u8>> {
+        match self {
+            Zerometry::Point(point) => vec![standalone_point(point.coord())],
+            Zerometry::MultiPoints(points) => points.explode(),
+            Zerometry::Line(line) => vec![standalone(LINE_TAG, line.bounding_box(), line.coords())],
+            Zerometry::MultiLines(lines) => lines.explode(),
+            Zerometry::Polygon(polygon) => {
+                vec![standalone(
+                    POLYGON_TAG,
+                    polygon.bounding_box(),
+                    polygon.coords(),
+                )]
+            }
+            Zerometry::MultiPolygon(polygons) => polygons.explode(),
+            Zerometry::Collection(collection) => collection.explode(),
+        }
+    }
+}
+
+impl ZultiPoints<'_> {
+    /// Return one standalone [`Zerometry::Point`] per point, in their original order.
+    /// See [`Zerometry::explode`].
+    pub fn explode(&self) -> Vec<Vec<u8>> {
+        self.points_in_original_order()
+            .map(|point| standalone_point(point.coord()))
+            .collect()
+    }
+}
+
+impl ZultiLines<'_> {
+    /// Return one standalone [`Zerometry::Line`] per line, the lines are copied as-is.
+    /// See [`Zerometry::explode`].
+    pub fn explode(&self) -> Vec<Vec<u8>> {
+        (0..self.len())
+            .map(|index| with_tag(LINE_TAG, self.get_bytes(index).unwrap()))
+            .collect()
+    }
+}
+
+impl ZultiPolygons<'_> {
+    /// Return one standalone [`Zerometry::Polygon`] per polygon, the polygons are copied as-is.
+    /// See [`Zerometry::explode`].
+    pub fn explode(&self) -> Vec<Vec<u8>> {
+        (0..self.len())
+            .map(|index| with_tag(POLYGON_TAG, self.get_bytes(index).unwrap()))
+            .collect()
+    }
+}
+
+impl Zollection<'_> {
+    /// Return one standalone zerometry per point, line and polygon of the collection, in this order.
+    /// See [`Zerometry::explode`].
+    pub fn explode(&self) -> Vec<Vec<u8>> {
+        let mut members = self.points().explode();
+        members.extend(self.lines().explode());
+        members.extend(self.polygons().explode());
+        members
+    }
+}
+
+fn with_tag(kind: u64, bytes: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(size_of::<u64>() + bytes.len());
+    buffer.extend_from_slice(&tag(kind).to_ne_bytes());
+    buffer.extend_from_slice(bytes);
+    buffer
+}
+
+fn standalone_point(coord: &Coord) -> Vec<u8> {
+    let mut buffer = tag(POINT_TAG).to_ne_bytes().to_vec();
+    push_coord(&mut buffer, coord);
+    buffer
+}
+
+/// Write the layout shared by the lines and the polygons: the bounding box followed by the coords.
+fn standalone(kind: u64, bounding_box: &BoundingBox, coords: &Coords) -> Vec<u8> {
+    let mut buffer = tag(kind).to_ne_bytes().to_vec();
+    for coord in bounding_box.coords().iter().chain(coords.iter()) {
+        push_coord(&mut buffer, coord);
+    }
+    buffer
+}
+
+fn push_coord(buffer: &mut Vec<u8>, coord: &Coord) {
+    buffer.extend_from_slice(&coord.lng().to_ne_bytes());
+    buffer.extend_from_slice(&coord.lat().to_ne_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+        polygon,
+    };
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::WriteOptions;
+
+    fn explode(geometry: &Geometry<f64>, options: WriteOptions) -> Vec<Geometry<f64>> {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, geometry, options).unwrap();
+        let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+        zerometry
+            .explode()
+            .iter()
+            .map(|member| Zerometry::try_from_bytes(member).unwrap().to_geo())
+            .collect()
+    }
+
+    #[test]
+    fn test_explode() {
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0)]);
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let points = MultiPoint::from(vec![Point::new(3.0, 1.0), Point::new(1.0, 2.0)]);
+
+        let exploded = explode(&Point::new(1.0, 2.0).into(), WriteOptions::default());
+        assert_compact_debug_snapshot!(exploded, @"[POINT(1.0 2.0)]");
+        let exploded = explode(&line.clone().into(), WriteOptions::default());
+        assert_compact_debug_snapshot!(exploded, @"[LINESTRING(0.0 0.0,1.0 2.0)]");
+        let exploded = explode(&square.clone().into(), WriteOptions::default());
+        assert_compact_debug_snapshot!(exploded, @"[POLYGON((0.0 0.0,1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0))]");
+
+        let sorted = WriteOptions {
+            sort_points: true,
+            ..WriteOptions::default()
+        };
+        let exploded = explode(&points.clone().into(), sorted);
+        assert_compact_debug_snapshot!(exploded, @"[POINT(3.0 1.0), POINT(1.0 2.0)]");
+
+        let multi_line = MultiLineString::new(vec![line.clone(), LineString::new(Vec::new())]);
+        let exploded = explode(&multi_line.into(), WriteOptions::default());
+        assert_compact_debug_snapshot!(exploded, @"[LINESTRING(0.0 0.0,1.0 2.0), LINESTRING EMPTY]");
+
+        let multi_polygon = MultiPolygon::new(vec![square.clone(), square.clone()]);
+        let with_generation = WriteOptions {
+            generation: Some(12),
+            ..WriteOptions::default()
+        };
+        let exploded = explode(&multi_polygon.into(), with_generation);
+        assert_compact_debug_snapshot!(exploded, @"[POLYGON((0.0 0.0,1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0)), POLYGON((0.0 0.0,1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0))]");
+
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            square.into(),
+            line.into(),
+            points.into(),
+        ]));
+        let exploded = explode(&collection, WriteOptions::default());
+        assert_compact_debug_snapshot!(exploded, @"[POINT(3.0 1.0), POINT(1.0 2.0), LINESTRING(0.0 0.0,1.0 2.0), POLYGON((0.0 0.0,1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0))]");
+
+        let empty = MultiPolygon::new(Vec::new());
+        assert!(explode(&empty.into(), WriteOptions::default()).is_empty());
+    }
+}
@@ -0,0 +1,264 @@
+use std::io;
+
+use geo_types::{
+    Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
+
+use crate::{BoundingBox, Coords, Zerometry, Zine, Zolygon, ZultiLines, ZultiPolygons, header};
+
+impl Zerometry<'_> {
+    /// Serialize the [`Zerometry`] in its canonical form: every integer and float is written in
+    /// little-endian and all the NaN are replaced by [`f64::NAN`].
+    ///
+    /// The layout is exactly the same as the one produced by [`Self::write_from_geometry`], which
+    /// means that on little-endian platforms it only differs by the NaN payloads. It's meant to be
+    /// used in tests and snapshots that must stay the same regardless of the platform.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut writer = Vec::new();
+        let tag: u64 = match self {
+            Zerometry::Point(_) => 0,
+            Zerometry::MultiPoints(zulti_points) if zulti_points.is_sorted() => 7,
+            Zerometry::MultiPoints(_) => 1,
+            Zerometry::Polygon(_) => 2,
+            Zerometry::MultiPolygon(_) => 3,
+            Zerometry::Line(_) => 4,
+            Zerometry::MultiLines(_) => 5,
+            Zerometry::Collection(_) => 6,
+        };
+        writer.extend_from_slice(&header::tag(tag).to_le_bytes());
+
+        match self {
+            Zerometry::Point(zoint) => write_floats(&mut writer, [zoint.lng(), zoint.lat()]),
+            Zerometry::MultiPoints(zulti_points) if zulti_points.is_sorted() => {
+                write_coords(&mut writer, zulti_points.bounding_box().coords());
+                writer.extend_from_slice(&(zulti_points.len() as u64).to_le_bytes());
+                write_coords(&mut writer, zulti_points.coords());
+                for index in zulti_points.original_indices() {
+                    writer.extend_from_slice(&index.to_le_bytes());
+                }
+                if zulti_points.len() % 2 == 1 {
+                    writer.extend_from_slice(&0_u32.to_le_bytes());
+                }
+            }
+            Zerometry::MultiPoints(zulti_points) => write_bbox_and_coords(
+                &mut writer,
+                zulti_points.bounding_box(),
+                zulti_points.coords(),
+            ),
+            Zerometry::Line(zine) => write_zine(&mut writer, zine),
+            Zerometry::MultiLines(zulti_lines) => write_zulti_lines(&mut writer, zulti_lines),
+            Zerometry::Polygon(zolygon) => write_zolygon(&mut writer, zolygon),
+            Zerometry::MultiPolygon(zulti_polygons) => {
+                write_zulti_polygons(&mut writer, zulti_polygons)
+            }
+            Zerometry::Collection(zollection) => {
+                write_coords(&mut writer, zollection.bounding_box().coords());
+                let header = writer.len();
+                writer.extend_from_slice(&[0; 8]);
+                // The offsets are relative to the end of the header
+                let base = writer.len();
+                let points = zollection.points();
+                write_bbox_and_coords(&mut writer, points.bounding_box(), points.coords());
+                let lines_offset = (writer.len() - base) as u32;
+                write_zulti_lines(&mut writer, &zollection.lines());
+                let polygons_offset = (writer.len() - base) as u32;
+                write_zulti_polygons(&mut writer, &zollection.polygons());
+                writer[header..header + 4].copy_from_slice(&lines_offset.to_le_bytes());
+                writer[header + 4..header + 8].copy_from_slice(&polygons_offset.to_le_bytes());
+            }
+        }
+
+        writer
+    }
+
+    /// Self-test ensuring the format written and read on this platform is the one expected by zerometry.
+    ///
+    /// A sample of every kind of shape is written, checked against a reference encoding written by hand and read back.
+    /// It doesn't depend on anything and is cheap enough to be called by a database on startup before
+    /// trusting the zerometries it stored.
+    pub fn verify_format() -> Result<(), io::Error> {
+        let point = Point::new(1.0, 2.0);
+        let line = LineString::from(vec![(0.0, 0.0), (2.0, 1.0)]);
+        let polygon = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+            Vec::new(),
+        );
+        // The collection is already flattened to be able to compare it after the round trip
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            MultiPoint::new(vec![point]).into(),
+            MultiLineString::new(vec![line]).into(),
+            MultiPolygon::new(vec![polygon]).into(),
+        ]));
+
+        // The reference encoding of the collection written by hand
+        let mut expected = Vec::new();
+        // the kind of the collection followed by the version 2 of the format in the most significant byte
+        expected.extend_from_slice(&(6_u64 | 2 << 56).to_le_bytes());
+        // bounding box of the collection
+        write_floats(&mut expected, [0.0, 0.0, 2.0, 2.0]);
+        // offsets of the lines and polygons
+        expected.extend_from_slice(&48_u32.to_le_bytes());
+        expected.extend_from_slice(&152_u32.to_le_bytes());
+        // the multi points with their bounding box
+        write_floats(&mut expected, [1.0, 2.0, 1.0, 2.0, 1.0, 2.0]);
+        // the multi lines with their bounding box, 1 line at the offset 0 then the line with its own bounding box
+        write_floats(&mut expected, [0.0, 0.0, 2.0, 1.0]);
+        expected.extend_from_slice(&1_u32.to_le_bytes());
+        expected.extend_from_slice(&0_u32.to_le_bytes());
+        write_floats(&mut expected, [0.0, 0.0, 2.0, 1.0, 0.0, 0.0, 2.0, 1.0]);
+        // the multi polygons with their bounding box, 1 polygon at the offset 0 then the closed polygon
+        write_floats(&mut expected, [0.0, 0.0, 1.0, 1.0]);
+        expected.extend_from_slice(&1_u32.to_le_bytes());
+        expected.extend_from_slice(&0_u32.to_le_bytes());
+        write_floats(
+            &mut expected,
+            [0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+        );
+
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &collection)?;
+        // Copy the buffer to a Vec<f64> to ensure it's aligned on 64 bits
+        let mut aligned = vec![0.0_f64; buffer.len().div_ceil(8)];
+        bytemuck::cast_slice_mut::<f64, u8>(&mut aligned)[..buffer.len()].copy_from_slice(&buffer);
+        let bytes = &bytemuck::cast_slice::<f64, u8>(&aligned)[..buffer.len()];
+        // SAFETY: The buffer was generated by `write_from_geometry` and is aligned
+        let zerometry = unsafe { Zerometry::from_bytes(bytes)? };
+
+        if zerometry.to_canonical_bytes() != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The zerometry format written on this platform doesn't match the reference encoding",
+            ));
+        }
+        if zerometry.to_geo() != collection {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The zerometry read on this platform doesn't match the geometry that was written",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Write the floats in little-endian, with all the NaN replaced by [`f64::NAN`].
+fn write_floats(writer: &mut Vec<u8>, values: impl IntoIterator<Item = f64>) {
+    for value in values {
+        let value = if value.is_nan() { f64::NAN } else { value };
+        writer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn write_coords(writer: &mut Vec<u8>, coords: &Coords) {
+    write_floats(
+        writer,
+        coords.iter().flat_map(|coord| [coord.lng(), coord.lat()]),
+    );
+}
+
+fn write_bbox_and_coords(writer: &mut Vec<u8>, bounding_box: &BoundingBox, coords: &Coords) {
+    write_coords(writer, bounding_box.coords());
+    write_coords(writer, coords);
+}
+
+fn write_zine(writer: &mut Vec<u8>, zine: &Zine) {
+    write_bbox_and_coords(writer, zine.bounding_box(), zine.coords());
+}
+
+fn write_zolygon(writer: &mut Vec<u8>, zolygon: &Zolygon) {
+    write_bbox_and_coords(writer, zolygon.bounding_box(), zolygon.coords());
+}
+
+fn write_zulti_lines(writer: &mut Vec<u8>, zulti_lines: &ZultiLines) {
+    let children: Vec<_> = zulti_lines.lines().collect();
+    write_offsets_and_children(writer, zulti_lines.bounding_box(), &children, write_zine);
+}
+
+fn write_zulti_polygons(writer: &mut Vec<u8>, zulti_polygons: &ZultiPolygons) {
+    let children: Vec<_> = zulti_polygons.polygons().collect();
+    write_offsets_and_children(
+        writer,
+        zulti_polygons.bounding_box(),
+        &children,
+        write_zolygon,
+    );
+}
+
+/// Write the bounding box, the number of children, their offsets, the padding and finally the children.
+fn write_offsets_and_children<T>(
+    writer: &mut Vec<u8>,
+    bounding_box: &BoundingBox,
+    children: &[T],
+    write_child: impl Fn(&mut Vec<u8>, &T),
+) {
+    write_coords(writer, bounding_box.coords());
+    writer.extend_from_slice(&(children.len() as u32).to_le_bytes());
+    let offsets_start = writer.len();
+    writer.extend(std::iter::repeat_n(0, children.len() * 4));
+    if children.len() % 2 == 0 {
+        writer.extend_from_slice(&0_u32.to_le_bytes());
+    }
+    let children_start = writer.len();
+    for (index, child) in children.iter().enumerate() {
+        let offset = (writer.len() - children_start) as u32;
+        writer[offsets_start + index * 4..offsets_start + (index + 1) * 4]
+            .copy_from_slice(&offset.to_le_bytes());
+        write_child(writer, child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn verify_format() {
+        Zerometry::verify_format().unwrap();
+    }
+
+    #[test]
+    fn canonical_bytes_match_native_bytes_on_little_endian() {
+        let geometries: [Geometry<f64>; 3] = [
+            Point::new(1.0, 2.0).into(),
+            MultiPolygon::new(vec![
+                Polygon::new(
+                    LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+                    Vec::new(),
+                ),
+                Polygon::new(
+                    LineString::from(vec![(5.0, 5.0), (6.0, 5.0), (5.0, 6.0)]),
+                    Vec::new(),
+                ),
+            ])
+            .into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(Vec::new())),
+        ];
+        for geometry in geometries {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+            let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+            if cfg!(target_endian = "little") {
+                assert_eq!(zerometry.to_canonical_bytes(), buffer);
+            } else {
+                assert_eq!(zerometry.to_canonical_bytes().len(), buffer.len());
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_nan() {
+        let mut buffer = Vec::new();
+        let nan = f64::from_bits(f64::NAN.to_bits() | 0xdead);
+        buffer.extend_from_slice(&header::tag(header::POINT_TAG).to_ne_bytes());
+        crate::Zoint::write_from_geometry(&mut buffer, &Point::new(nan, -nan)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        let canonical = zerometry.to_canonical_bytes();
+        let floats: Vec<u64> = canonical
+            .chunks(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_compact_debug_snapshot!(floats, @"[144115188075855872, 9221120237041090560, 9221120237041090560]");
+    }
+}
@@ -0,0 +1,93 @@
+use std::mem;
+
+use geo_types::Geometry;
+
+use crate::{
+    Zerometry, ZerometryError,
+    error::{split_checked, split_u64},
+};
+
+impl<'a> Zerometry<'a> {
+    /// Same as [`Self::write_from_geometry`] but the zerometry is prefixed by its length in bytes stored as a `u64`.
+    /// Since the zerometry length is always a multiple of 8, everything written after it stays aligned on 64 bits.
+    ///
+    /// It's meant to be used when other data must be stored after the zerometry, see [`Self::from_bytes_prefix`] to read it back.
+    pub fn write_from_geometry_framed(
+        writer: &mut Vec<u8>,
+        geometry: &Geometry<f64>,
+    ) -> Result<(), ZerometryError> {
+        let start = writer.len();
+        writer.extend_from_slice(&0_u64.to_ne_bytes());
+        Zerometry::write_from_geometry(writer, geometry)?;
+        let len = (writer.len() - start - mem::size_of::<u64>()) as u64;
+        writer[start..start + mem::size_of::<u64>()].copy_from_slice(&len.to_ne_bytes());
+        Ok(())
+    }
+
+    /// Create a `Zerometry` from the front of a slice of bytes that may contain anything after it,
+    /// and return the number of bytes consumed, including the length prefix.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry_framed(&mut buffer, &Geometry::Point(Point::new(1.0, 2.0))).unwrap();
+    /// buffer.extend_from_slice(b"some metadata");
+    ///
+    /// let (zerometry, consumed) = unsafe { Zerometry::from_bytes_prefix(&buffer).unwrap() };
+    /// assert_eq!(zerometry.to_geo(), Geometry::Point(Point::new(1.0, 2.0)));
+    /// assert_eq!(&buffer[consumed..], b"some metadata");
+    /// ```
+    ///
+    /// # Safety
+    /// The data must start with a zerometry generated from the [`Self::write_from_geometry_framed`] method and be aligned on 64 bits
+    pub unsafe fn from_bytes_prefix(data: &'a [u8]) -> Result<(Self, usize), ZerometryError> {
+        let (len, data) = split_u64(data)?;
+        let len = len as usize;
+        let (data, _) = split_checked(data, len)?;
+        let zerometry = unsafe { Zerometry::from_bytes(data)? };
+        Ok((zerometry, mem::size_of::<u64>() + len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, MultiPoint, Point};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn read_consecutive_framed_zerometries() {
+        let geometries: [Geometry<f64>; 3] = [
+            Point::new(1.0, 2.0).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+            MultiPoint::<f64>::new(Vec::new()).into(),
+        ];
+        let mut buffer = Vec::new();
+        for geometry in &geometries {
+            Zerometry::write_from_geometry_framed(&mut buffer, geometry).unwrap();
+        }
+
+        let mut data = &buffer[..];
+        for geometry in &geometries {
+            let (zerometry, consumed) = unsafe { Zerometry::from_bytes_prefix(data).unwrap() };
+            assert_eq!(&zerometry.to_geo(), geometry);
+            assert_eq!(consumed % mem::size_of::<u64>(), 0);
+            data = &data[consumed..];
+        }
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_framed(&mut buffer, &Point::new(1.0, 2.0).into()).unwrap();
+
+        let err = unsafe { Zerometry::from_bytes_prefix(&buffer[..4]).unwrap_err() };
+        assert_compact_debug_snapshot!(err, @"Truncated { expected: 8, found: 4 }");
+        let err = unsafe { Zerometry::from_bytes_prefix(&buffer[..16]).unwrap_err() };
+        assert_compact_debug_snapshot!(err, @"Truncated { expected: 24, found: 8 }");
+    }
+}
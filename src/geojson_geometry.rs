@@ -0,0 +1,96 @@
+use geojson::{Position, Value};
+
+use crate::{Coords, Zerometry, Zolygon};
+
+impl Zerometry<'_> {
+    /// Convert the [`Zerometry`] to a [`geojson::Geometry`], straight from its coordinates without going through
+    /// [`Self::to_geo`].
+    /// It's the same geometry as [`Self::to_geo`] except for the lines, rectangles and triangles that are converted
+    /// to line strings and polygons since they don't exist in GeoJSON.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(2.35, 48.85))).unwrap();
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    /// assert_eq!(zerometry.to_geojson().value, geojson::Value::Point(vec![2.35, 48.85]));
+    /// ```
+    pub fn to_geojson(&self) -> geojson::Geometry {
+        geojson::Geometry::new(self.geojson_value())
+    }
+
+    fn geojson_value(&self) -> Value {
+        match self {
+            Zerometry::Point(a) => Value::Point(vec![a.lng(), a.lat()]),
+            Zerometry::MultiPoints(a) => Value::MultiPoint(positions(a.coords())),
+            Zerometry::Line(a) => Value::LineString(positions(a.coords())),
+            Zerometry::MultiLines(a) => {
+                Value::MultiLineString(a.lines().map(|line| positions(line.coords())).collect())
+            }
+            Zerometry::Polygon(a) => Value::Polygon(rings(a)),
+            Zerometry::MultiPolygon(a) => {
+                Value::MultiPolygon(a.polygons().map(|polygon| rings(&polygon)).collect())
+            }
+            // Same as `to_geo` the collection contains its points, lines and polygons
+            Zerometry::Collection(a) => Value::GeometryCollection(vec![
+                Zerometry::MultiPoints(a.points()).to_geojson(),
+                Zerometry::MultiLines(a.lines()).to_geojson(),
+                Zerometry::MultiPolygon(a.polygons()).to_geojson(),
+            ]),
+        }
+    }
+}
+
+fn positions(coords: &Coords) -> Vec<Position> {
+    coords
+        .iter()
+        .map(|coord| vec![coord.lng(), coord.lat()])
+        .collect()
+}
+
+/// The polygons only have an exterior ring.
+fn rings(zolygon: &Zolygon) -> Vec<Vec<Position>> {
+    vec![positions(zolygon.coords())]
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+        Polygon, polygon,
+    };
+
+    use crate::Zerometry;
+
+    #[test]
+    fn same_as_geo() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let points = MultiPoint::from(vec![(3.0, 1.0), (1.0, 2.0)]);
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            points.clone().into(),
+            line.clone().into(),
+            MultiLineString::new(vec![line.clone(), line.clone()]).into(),
+            square.clone().into(),
+            Polygon::new(LineString::new(Vec::new()), Vec::new()).into(),
+            MultiPolygon::new(vec![square.clone(), square.clone()]).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                points.into(),
+                line.into(),
+                square.into(),
+            ])),
+        ];
+        for geometry in geometries {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+            let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+            assert_eq!(
+                zerometry.to_geojson(),
+                geojson::Geometry::from(&zerometry.to_geo())
+            );
+        }
+    }
+}
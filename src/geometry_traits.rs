@@ -0,0 +1,267 @@
+use geo_traits::{
+    CoordTrait, Dimensions, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait,
+    MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait,
+    UnimplementedLine, UnimplementedRect, UnimplementedTriangle,
+};
+
+use crate::{
+    Coord, Zerometry, Zine, Zoint, Zollection, Zolygon, ZultiLines, ZultiPoints, ZultiPolygons,
+};
+
+/// The [`GeometryType`] returned by all the zerometry types, the lines, rects and triangles are stored as
+/// line strings and polygons and never returned.
+type ZerometryType<'b, 'a> = GeometryType<
+    'b,
+    Zoint<'a>,
+    Zine<'a>,
+    Zolygon<'a>,
+    ZultiPoints<'a>,
+    ZultiLines<'a>,
+    ZultiPolygons<'a>,
+    Zollection<'a>,
+    UnimplementedRect<f64>,
+    UnimplementedTriangle<f64>,
+    UnimplementedLine<f64>,
+>;
+
+impl CoordTrait for &Coord {
+    type T = f64;
+
+    fn dim(&self) -> Dimensions {
+        Dimensions::Xy
+    }
+
+    fn x(&self) -> f64 {
+        self.lng()
+    }
+
+    fn y(&self) -> f64 {
+        self.lat()
+    }
+
+    fn nth_or_panic(&self, n: usize) -> f64 {
+        match n {
+            0 => self.lng(),
+            1 => self.lat(),
+            _ => panic!("Coord only has 2 dimensions, can't access dimension {n}"),
+        }
+    }
+}
+
+/// Implement [`GeometryTrait`] for a zerometry type, the variant is the one its [`GeometryTrait::as_type`] returns.
+macro_rules! impl_geometry_trait {
+    ($geometry:ident, |$this:ident| $as_type:expr) => {
+        impl<'a> GeometryTrait for $geometry<'a> {
+            type T = f64;
+            type PointType<'b>
+                = Zoint<'a>
+            where
+                Self: 'b;
+            type LineStringType<'b>
+                = Zine<'a>
+            where
+                Self: 'b;
+            type PolygonType<'b>
+                = Zolygon<'a>
+            where
+                Self: 'b;
+            type MultiPointType<'b>
+                = ZultiPoints<'a>
+            where
+                Self: 'b;
+            type MultiLineStringType<'b>
+                = ZultiLines<'a>
+            where
+                Self: 'b;
+            type MultiPolygonType<'b>
+                = ZultiPolygons<'a>
+            where
+                Self: 'b;
+            type GeometryCollectionType<'b>
+                = Zollection<'a>
+            where
+                Self: 'b;
+            type RectType<'b>
+                = UnimplementedRect<f64>
+            where
+                Self: 'b;
+            type TriangleType<'b>
+                = UnimplementedTriangle<f64>
+            where
+                Self: 'b;
+            type LineType<'b>
+                = UnimplementedLine<f64>
+            where
+                Self: 'b;
+
+            fn dim(&self) -> Dimensions {
+                Dimensions::Xy
+            }
+
+            fn as_type(&self) -> ZerometryType<'_, 'a> {
+                let $this = self;
+                $as_type
+            }
+        }
+    };
+}
+
+impl_geometry_trait!(Zoint, |this| GeometryType::Point(this));
+impl_geometry_trait!(Zine, |this| GeometryType::LineString(this));
+impl_geometry_trait!(Zolygon, |this| GeometryType::Polygon(this));
+impl_geometry_trait!(ZultiPoints, |this| GeometryType::MultiPoint(this));
+impl_geometry_trait!(ZultiLines, |this| GeometryType::MultiLineString(this));
+impl_geometry_trait!(ZultiPolygons, |this| GeometryType::MultiPolygon(this));
+impl_geometry_trait!(Zollection, |this| GeometryType::GeometryCollection(this));
+impl_geometry_trait!(Zerometry, |this| match this {
+    Zerometry::Point(point) => GeometryType::Point(point),
+    Zerometry::MultiPoints(points) => GeometryType::MultiPoint(points),
+    Zerometry::Line(line) => GeometryType::LineString(line),
+    Zerometry::MultiLines(lines) => GeometryType::MultiLineString(lines),
+    Zerometry::Polygon(polygon) => GeometryType::Polygon(polygon),
+    Zerometry::MultiPolygon(polygons) => GeometryType::MultiPolygon(polygons),
+    Zerometry::Collection(collection) => GeometryType::GeometryCollection(collection),
+});
+
+impl<'a> PointTrait for Zoint<'a> {
+    type CoordType<'b>
+        = &'a Coord
+    where
+        Self: 'b;
+
+    fn coord(&self) -> Option<&'a Coord> {
+        Some(self.coord())
+    }
+}
+
+impl<'a> LineStringTrait for Zine<'a> {
+    type CoordType<'b>
+        = &'a Coord
+    where
+        Self: 'b;
+
+    fn num_coords(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn coord_unchecked(&self, i: usize) -> &'a Coord {
+        &self.coords()[i]
+    }
+}
+
+/// Only the exterior of the polygons is stored, they never have any interior.
+impl<'a> PolygonTrait for Zolygon<'a> {
+    type RingType<'b>
+        = Zine<'a>
+    where
+        Self: 'b;
+
+    fn exterior(&self) -> Option<Zine<'a>> {
+        Some(self.exterior_line())
+    }
+
+    fn num_interiors(&self) -> usize {
+        0
+    }
+
+    unsafe fn interior_unchecked(&self, i: usize) -> Zine<'a> {
+        unreachable!("A zolygon has no interior, can't access the interior {i}")
+    }
+}
+
+/// The points are returned in the order they're stored, see [`ZultiPoints::points_in_original_order`].
+impl<'a> MultiPointTrait for ZultiPoints<'a> {
+    type InnerPointType<'b>
+        = Zoint<'a>
+    where
+        Self: 'b;
+
+    fn num_points(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn point_unchecked(&self, i: usize) -> Zoint<'a> {
+        self.get(i).unwrap()
+    }
+}
+
+impl<'a> MultiLineStringTrait for ZultiLines<'a> {
+    type InnerLineStringType<'b>
+        = Zine<'a>
+    where
+        Self: 'b;
+
+    fn num_line_strings(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn line_string_unchecked(&self, i: usize) -> Zine<'a> {
+        self.get(i).unwrap()
+    }
+}
+
+impl<'a> MultiPolygonTrait for ZultiPolygons<'a> {
+    type InnerPolygonType<'b>
+        = Zolygon<'a>
+    where
+        Self: 'b;
+
+    fn num_polygons(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn polygon_unchecked(&self, i: usize) -> Zolygon<'a> {
+        self.get(i).unwrap()
+    }
+}
+
+/// As with [`Zollection::to_geo`], the collection is made of its points, its lines and its polygons.
+impl<'a> GeometryCollectionTrait for Zollection<'a> {
+    type GeometryType<'b>
+        = Zerometry<'a>
+    where
+        Self: 'b;
+
+    fn num_geometries(&self) -> usize {
+        3
+    }
+
+    unsafe fn geometry_unchecked(&self, i: usize) -> Zerometry<'a> {
+        match i {
+            0 => Zerometry::MultiPoints(self.points()),
+            1 => Zerometry::MultiLines(self.lines()),
+            2 => Zerometry::MultiPolygon(self.polygons()),
+            _ => unreachable!("A zollection has 3 geometries, can't access the geometry {i}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_traits::to_geo::ToGeoGeometry;
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPoint, Point, polygon};
+
+    use super::*;
+
+    #[test]
+    fn same_as_to_geo() {
+        let collection = GeometryCollection::new_from(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            Point::new(5.0, 5.0).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+        ]);
+        let geometries: [Geometry<f64>; 5] = [
+            Point::new(1.0, 2.0).into(),
+            MultiPoint::from(vec![(1.0, 2.0), (3.0, 4.0)]).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            Geometry::GeometryCollection(collection),
+        ];
+        for geometry in geometries {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+            let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+            assert_eq!(zerometry.to_geometry(), zerometry.to_geo());
+        }
+    }
+}
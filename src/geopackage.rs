@@ -0,0 +1,355 @@
+//! Export and import of a set of zerometries to a GeoPackage file, the SQLite based format read by QGIS, GDAL and
+//! most GIS tools, to back up or inspect a spatial index.
+//!
+//! Every zerometry is stored as a feature associated with its key, in a table whose geometries are
+//! in the WGS 84 coordinate system.
+
+use std::{io, path::Path};
+
+use geo::BoundingRect;
+use geo_types::{Geometry, Rect};
+use rusqlite::{Connection, params};
+
+use crate::{
+    Zerometry, ZerometryBuf,
+    wkb::{read_wkb, write_wkb},
+};
+
+/// The `GPKG` application id stored in the header of the SQLite database.
+const APPLICATION_ID: i32 = 0x4750_4B47;
+/// The version 1.3 of the GeoPackage specification.
+const USER_VERSION: i32 = 10300;
+/// The id of the WGS 84 coordinate system.
+const WGS84: i32 = 4326;
+
+/// Flags of the header of the geometries: little-endian with an `[min_x, max_x, min_y, max_y]` envelope.
+const FLAGS_WITH_ENVELOPE: u8 = 0b0000_0011;
+/// Flags of the header of the empty geometries: little-endian without envelope.
+const FLAGS_EMPTY: u8 = 0b0001_0001;
+
+/// Write a set of zerometries in a new GeoPackage file, in a single transaction.
+///
+/// ```
+/// use zerometry::{GeoPackageReader, GeoPackageWriter, Zerometry, ZerometryBuf};
+/// use geo_types::{Geometry, Point};
+///
+/// let path = std::env::temp_dir().join(format!("zerometry-doctest-{}.gpkg", std::process::id()));
+/// let shape = ZerometryBuf::from(Geometry::Point(Point::new(2.35, 48.85)));
+///
+/// let mut writer = GeoPackageWriter::create(&path, "places").unwrap();
+/// writer.write(b"paris", &shape.as_zerometry()).unwrap();
+/// writer.finish().unwrap();
+///
+/// let reader = GeoPackageReader::open(&path, "places").unwrap();
+/// let mut shapes = Vec::new();
+/// reader.for_each(|key, shape| Ok(shapes.push((key, shape)))).unwrap();
+/// assert_eq!(shapes, vec![(b"paris".to_vec(), shape)]);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub struct GeoPackageWriter {
+    connection: Connection,
+    table: String,
+    extent: Option<Rect<f64>>,
+}
+
+impl GeoPackageWriter {
+    /// Create the GeoPackage file and the table containing the zerometries.
+    /// If the file already exists, it must be a GeoPackage that doesn't contain the table yet.
+    pub fn create(path: impl AsRef<Path>, table: &str) -> io::Result<Self> {
+        let connection = Connection::open(path).map_err(io::Error::other)?;
+        connection
+            .execute_batch(&format!(
+                "PRAGMA application_id = {APPLICATION_ID};
+                PRAGMA user_version = {USER_VERSION};
+                BEGIN;
+                CREATE TABLE IF NOT EXISTS gpkg_spatial_ref_sys (
+                    srs_name TEXT NOT NULL,
+                    srs_id INTEGER PRIMARY KEY,
+                    organization TEXT NOT NULL,
+                    organization_coordsys_id INTEGER NOT NULL,
+                    definition TEXT NOT NULL,
+                    description TEXT
+                );
+                INSERT OR IGNORE INTO gpkg_spatial_ref_sys VALUES
+                    ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', NULL),
+                    ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', NULL),
+                    ('WGS 84 geodetic', {WGS84}, 'EPSG', {WGS84}, '{WGS84_DEFINITION}', NULL);
+                CREATE TABLE IF NOT EXISTS gpkg_contents (
+                    table_name TEXT NOT NULL PRIMARY KEY,
+                    data_type TEXT NOT NULL,
+                    identifier TEXT UNIQUE,
+                    description TEXT DEFAULT '',
+                    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+                    min_x DOUBLE,
+                    min_y DOUBLE,
+                    max_x DOUBLE,
+                    max_y DOUBLE,
+                    srs_id INTEGER REFERENCES gpkg_spatial_ref_sys(srs_id)
+                );
+                CREATE TABLE IF NOT EXISTS gpkg_geometry_columns (
+                    table_name TEXT NOT NULL,
+                    column_name TEXT NOT NULL,
+                    geometry_type_name TEXT NOT NULL,
+                    srs_id INTEGER NOT NULL REFERENCES gpkg_spatial_ref_sys(srs_id),
+                    z TINYINT NOT NULL,
+                    m TINYINT NOT NULL,
+                    PRIMARY KEY (table_name, column_name)
+                );
+                CREATE TABLE {quoted} (fid INTEGER PRIMARY KEY AUTOINCREMENT, geom GEOMETRY, key BLOB NOT NULL);",
+                quoted = quote(table),
+            ))
+            .map_err(io::Error::other)?;
+        connection
+            .execute(
+                "INSERT INTO gpkg_contents (table_name, data_type, identifier, srs_id) VALUES (?1, 'features', ?1, ?2)",
+                params![table, WGS84],
+            )
+            .map_err(io::Error::other)?;
+        connection
+            .execute(
+                "INSERT INTO gpkg_geometry_columns VALUES (?1, 'geom', 'GEOMETRY', ?2, 0, 0)",
+                params![table, WGS84],
+            )
+            .map_err(io::Error::other)?;
+
+        Ok(Self {
+            connection,
+            table: table.to_string(),
+            extent: None,
+        })
+    }
+
+    /// Write a zerometry associated with its key.
+    pub fn write(&mut self, key: &[u8], zerometry: &Zerometry) -> io::Result<()> {
+        let geometry = zerometry.to_geo();
+        let envelope = geometry.bounding_rect();
+        if let Some(envelope) = envelope {
+            self.extent = Some(match self.extent {
+                Some(extent) => Rect::new(
+                    (
+                        extent.min().x.min(envelope.min().x),
+                        extent.min().y.min(envelope.min().y),
+                    ),
+                    (
+                        extent.max().x.max(envelope.max().x),
+                        extent.max().y.max(envelope.max().y),
+                    ),
+                ),
+                None => envelope,
+            });
+        }
+
+        let mut blob = Vec::new();
+        write_geometry_header(&mut blob, envelope);
+        write_wkb(&mut blob, &geometry)?;
+        self.connection
+            .prepare_cached(&format!(
+                "INSERT INTO {} (geom, key) VALUES (?1, ?2)",
+                quote(&self.table)
+            ))
+            .and_then(|mut statement| statement.execute(params![blob, key]))
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    /// Store the extent of the zerometries written and commit the transaction.
+    /// Nothing is written in the file if this method is not called.
+    pub fn finish(self) -> io::Result<()> {
+        if let Some(extent) = self.extent {
+            self.connection
+                .execute(
+                    "UPDATE gpkg_contents SET min_x = ?1, min_y = ?2, max_x = ?3, max_y = ?4 WHERE table_name = ?5",
+                    params![
+                        extent.min().x,
+                        extent.min().y,
+                        extent.max().x,
+                        extent.max().y,
+                        self.table
+                    ],
+                )
+                .map_err(io::Error::other)?;
+        }
+        self.connection
+            .execute_batch("COMMIT")
+            .map_err(io::Error::other)
+    }
+}
+
+/// Read the zerometries stored in a GeoPackage file by a [`GeoPackageWriter`].
+pub struct GeoPackageReader {
+    connection: Connection,
+    table: String,
+}
+
+impl GeoPackageReader {
+    /// Open an existing GeoPackage file in read-only mode.
+    pub fn open(path: impl AsRef<Path>, table: &str) -> io::Result<Self> {
+        let connection =
+            Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+                .map_err(io::Error::other)?;
+        Ok(Self {
+            connection,
+            table: table.to_string(),
+        })
+    }
+
+    /// Call the function on every key and zerometry of the table, in the order they were written.
+    /// The iteration stops at the first error returned by the function.
+    pub fn for_each(
+        &self,
+        mut f: impl FnMut(Vec<u8>, ZerometryBuf) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut statement = self
+            .connection
+            .prepare(&format!(
+                "SELECT key, geom FROM {} ORDER BY fid",
+                quote(&self.table)
+            ))
+            .map_err(io::Error::other)?;
+        let mut rows = statement.query([]).map_err(io::Error::other)?;
+        while let Some(row) = rows.next().map_err(io::Error::other)? {
+            let key: Vec<u8> = row.get(0).map_err(io::Error::other)?;
+            let blob = row.get_ref(1).map_err(io::Error::other)?;
+            let blob = blob.as_blob().map_err(io::Error::other)?;
+            let geometry = read_geometry(blob)?;
+            let shape = ZerometryBuf::from_geometry(&geometry)?;
+            f(key, shape)?;
+        }
+        Ok(())
+    }
+}
+
+const WGS84_DEFINITION: &str = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]"#;
+
+/// Quote an SQL identifier.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Write the header of a GeoPackage geometry: the magic, the version, the flags, the coordinate system and the envelope.
+fn write_geometry_header(blob: &mut Vec<u8>, envelope: Option<Rect<f64>>) {
+    blob.extend_from_slice(b"GP");
+    blob.push(0);
+    match envelope {
+        Some(envelope) => {
+            blob.push(FLAGS_WITH_ENVELOPE);
+            blob.extend(WGS84.to_le_bytes());
+            for value in [
+                envelope.min().x,
+                envelope.max().x,
+                envelope.min().y,
+                envelope.max().y,
+            ] {
+                blob.extend(value.to_le_bytes());
+            }
+        }
+        None => {
+            blob.push(FLAGS_EMPTY);
+            blob.extend(WGS84.to_le_bytes());
+        }
+    }
+}
+
+/// Skip the header of a GeoPackage geometry and decode its WKB.
+fn read_geometry(blob: &[u8]) -> io::Result<Geometry<f64>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let Some((b"GP", rest)) = blob.split_first_chunk::<2>() else {
+        return Err(invalid("Not a GeoPackage geometry"));
+    };
+    let [_version, flags, ..] = *rest else {
+        return Err(invalid("The GeoPackage geometry is truncated"));
+    };
+    if flags & 0b0010_0000 != 0 {
+        return Err(invalid(
+            "The extended GeoPackage geometries are not supported",
+        ));
+    }
+    let envelope = match (flags >> 1) & 0b111 {
+        0 => 0,
+        1 => 4,
+        2 | 3 => 6,
+        4 => 8,
+        _ => return Err(invalid("Invalid GeoPackage envelope")),
+    } * size_of::<f64>();
+    // The magic, the version, the flags and the coordinate system
+    let header = 2 + 1 + 1 + size_of::<i32>() + envelope;
+    let wkb = blob
+        .get(header..)
+        .ok_or_else(|| invalid("The GeoPackage geometry is truncated"))?;
+    read_wkb(wkb)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{GeometryCollection, LineString, MultiPoint, MultiPolygon, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn geopackage_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "zerometry-geopackage-round-trip-{}.gpkg",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let shapes: Vec<ZerometryBuf> = [
+            Geometry::Point(Point::new(1.0, 2.0)),
+            LineString::from(vec![(0.0, 0.0), (-3.0, 5.0)]).into(),
+            MultiPolygon::new(vec![square.clone(), square]).into(),
+            MultiPoint::<f64>::new(Vec::new()).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Point::new(4.0, 4.0).into(),
+            ])),
+        ]
+        .iter()
+        .map(ZerometryBuf::from)
+        .collect();
+
+        let mut writer = GeoPackageWriter::create(&path, "shapes \"v1\"").unwrap();
+        for (key, shape) in shapes.iter().enumerate() {
+            writer
+                .write(&(key as u32).to_be_bytes(), &shape.as_zerometry())
+                .unwrap();
+        }
+        writer.finish().unwrap();
+
+        let reader = GeoPackageReader::open(&path, "shapes \"v1\"").unwrap();
+        let mut read = Vec::new();
+        reader
+            .for_each(|key, shape| {
+                read.push((key, shape));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(read.len(), shapes.len());
+        for (key, (read_key, shape)) in read.into_iter().enumerate() {
+            assert_eq!(read_key, (key as u32).to_be_bytes());
+            assert_eq!(shape, shapes[key]);
+        }
+
+        let extent: (f64, f64, f64, f64) = reader
+            .connection
+            .query_row(
+                "SELECT min_x, min_y, max_x, max_y FROM gpkg_contents",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_compact_debug_snapshot!(extent, @"(-3.0, 0.0, 4.0, 5.0)");
+        let application_id: i32 = reader
+            .connection
+            .query_row("PRAGMA application_id", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(application_id, APPLICATION_ID);
+
+        // The table already exists
+        let err = GeoPackageWriter::create(&path, "shapes \"v1\"")
+            .err()
+            .unwrap();
+        assert_compact_debug_snapshot!(err.kind(), @"Other");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
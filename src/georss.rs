@@ -0,0 +1,314 @@
+//! Tolerant parser for the small subset of GeoRSS and GML used by legacy feeds.
+//!
+//! Only the following elements are understood, everything else is skipped:
+//! - GeoRSS Simple: `georss:point`, `georss:line`, `georss:polygon` and `georss:box`
+//! - GML: `gml:Point`, `gml:LineString` and `gml:Polygon` with `gml:pos` or `gml:posList`
+//!
+//! Namespace prefixes are ignored, and as specified by GeoRSS the coordinates are read
+//! in the `lat lng` order.
+
+use std::io;
+
+use geo_types::{Coord, Geometry, GeometryCollection, LineString, Point, Polygon, Rect};
+
+use crate::{AxisOrder, Zerometry};
+
+impl Zerometry<'_> {
+    /// Parse the GeoRSS or GML markup and write the geometries it contains as a valid [`Zerometry`]
+    /// slice of bytes in the input buffer.
+    /// If the markup contains a single geometry it's written as-is, otherwise all the geometries are
+    /// written in a [`crate::Zollection`].
+    pub fn write_from_georss(writer: &mut Vec<u8>, input: &str) -> Result<(), io::Error> {
+        let geometry = parse_georss(input)?;
+        Ok(Zerometry::write_from_geometry(writer, &geometry)?)
+    }
+}
+
+/// Extract all the geometries contained in the GeoRSS or GML markup.
+pub(crate) fn parse_georss(input: &str) -> Result<Geometry<f64>, io::Error> {
+    let mut geometries = Vec::new();
+    let mut events = Tokenizer { input };
+
+    while let Some(event) = events.next() {
+        let Event::Start(name) = event else { continue };
+        let geometry = match name {
+            "point" => Geometry::Point(single_point(&parse_coords(&text_until(
+                &mut events,
+                name,
+            ))?)?),
+            "line" => Geometry::LineString(LineString::new(parse_coords(&text_until(
+                &mut events,
+                name,
+            ))?)),
+            "polygon" => Geometry::Polygon(Polygon::new(
+                LineString::new(parse_coords(&text_until(&mut events, name))?),
+                Vec::new(),
+            )),
+            "box" => {
+                let coords = parse_coords(&text_until(&mut events, name))?;
+                let [min, max] = coords[..] else {
+                    return Err(invalid_data(format!(
+                        "A box must contain exactly two positions but found {}",
+                        coords.len()
+                    )));
+                };
+                Geometry::Polygon(Rect::new(min, max).to_polygon())
+            }
+            "Point" => Geometry::Point(single_point(&parse_coords(&positions_until(
+                &mut events,
+                name,
+            ))?)?),
+            "LineString" => Geometry::LineString(LineString::new(parse_coords(&positions_until(
+                &mut events,
+                name,
+            ))?)),
+            "Polygon" => Geometry::Polygon(Polygon::new(
+                LineString::new(parse_coords(&positions_until(&mut events, name))?),
+                Vec::new(),
+            )),
+            _ => continue,
+        };
+        geometries.push(geometry);
+    }
+
+    match geometries.len() {
+        0 => Err(invalid_data("No GeoRSS or GML geometry found")),
+        1 => Ok(geometries.pop().unwrap()),
+        _ => Ok(Geometry::GeometryCollection(GeometryCollection::new_from(
+            geometries,
+        ))),
+    }
+}
+
+/// Concatenate all the text found before the closing tag.
+fn text_until(events: &mut Tokenizer, name: &str) -> String {
+    let mut text = String::new();
+    for event in events {
+        match event {
+            Event::End(end) if end == name => break,
+            Event::Text(s) => {
+                text.push(' ');
+                text.push_str(s);
+            }
+            _ => (),
+        }
+    }
+    text
+}
+
+/// Concatenate the text of all the `pos` and `posList` found before the closing tag.
+/// The interior rings of the polygons are skipped since we don't support holes.
+fn positions_until(events: &mut Tokenizer, name: &str) -> String {
+    let mut text = String::new();
+    let mut in_position = false;
+    let mut in_interior = false;
+    for event in events {
+        match event {
+            Event::End(end) if end == name => break,
+            Event::Start("interior" | "innerBoundaryIs") => in_interior = true,
+            Event::End("interior" | "innerBoundaryIs") => in_interior = false,
+            Event::Start("pos" | "posList") => in_position = !in_interior,
+            Event::End("pos" | "posList") => in_position = false,
+            Event::Text(s) if in_position => {
+                text.push(' ');
+                text.push_str(s);
+            }
+            _ => (),
+        }
+    }
+    text
+}
+
+/// Parse a list of `lat lng` pairs, separated by spaces or commas.
+fn parse_coords(text: &str) -> Result<Vec<Coord<f64>>, io::Error> {
+    let numbers = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<f64>()
+                .map_err(|e| invalid_data(format!("Invalid coordinate `{s}`: {e}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if numbers.len() % 2 != 0 {
+        return Err(invalid_data(format!(
+            "Was expecting pairs of coordinates but found {} numbers",
+            numbers.len()
+        )));
+    }
+
+    Ok(numbers
+        .chunks_exact(2)
+        .map(|pair| AxisOrder::LatLng.to_geo((pair[0], pair[1])))
+        .collect())
+}
+
+fn single_point(coords: &[Coord<f64>]) -> Result<Point<f64>, io::Error> {
+    match coords {
+        [coord] => Ok(Point::from(*coord)),
+        _ => Err(invalid_data(format!(
+            "A point must contain exactly one position but found {}",
+            coords.len()
+        ))),
+    }
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event<'a> {
+    /// The local name of an opening tag, without its namespace prefix.
+    Start(&'a str),
+    /// The local name of a closing tag, without its namespace prefix.
+    End(&'a str),
+    Text(&'a str),
+}
+
+/// A very naive tokenizer that doesn't validate anything. Self-closing tags are skipped
+/// since they can't hold any coordinates.
+struct Tokenizer<'a> {
+    input: &'a str,
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.input.is_empty() {
+                return None;
+            }
+
+            if let Some(rest) = self.input.strip_prefix('<') {
+                // Skip the comments, cdata, doctype and processing instructions entirely
+                if let Some(rest) = rest.strip_prefix("!--") {
+                    self.input = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+                    continue;
+                }
+                let end = rest.find('>').unwrap_or(rest.len());
+                let tag = &rest[..end];
+                self.input = rest.get(end + 1..).unwrap_or("");
+                if tag.starts_with(['!', '?']) {
+                    continue;
+                }
+
+                let (closing, tag) = match tag.strip_prefix('/') {
+                    Some(tag) => (true, tag),
+                    None => (false, tag),
+                };
+                let self_closing = tag.ends_with('/');
+                let name = tag
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or_default();
+                let name = name.rsplit(':').next().unwrap_or_default();
+
+                if closing {
+                    return Some(Event::End(name));
+                } else if self_closing {
+                    // There is no content in a self closing tag, we can skip it
+                    continue;
+                } else {
+                    return Some(Event::Start(name));
+                }
+            }
+
+            let end = self.input.find('<').unwrap_or(self.input.len());
+            let text = &self.input[..end];
+            self.input = &self.input[end..];
+            if !text.trim().is_empty() {
+                return Some(Event::Text(text));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::{assert_compact_debug_snapshot, assert_snapshot};
+
+    use super::*;
+
+    #[test]
+    fn georss_simple_point() {
+        let geometry = parse_georss("<georss:point>45.256 -71.92</georss:point>").unwrap();
+        assert_compact_debug_snapshot!(geometry, @"POINT(-71.92 45.256)");
+    }
+
+    #[test]
+    fn georss_simple_line_and_polygon() {
+        let geometry = parse_georss(
+            r#"<entry>
+                <georss:line>45.256 -110.45 46.46 -109.48 43.84 -109.86</georss:line>
+                <georss:polygon>
+                    45.256 -110.45 46.46 -109.48 43.84 -109.86 45.256 -110.45
+                </georss:polygon>
+            </entry>"#,
+        )
+        .unwrap();
+        assert_compact_debug_snapshot!(geometry, @"GEOMETRYCOLLECTION(LINESTRING(-110.45 45.256,-109.48 46.46,-109.86 43.84),POLYGON((-110.45 45.256,-109.48 46.46,-109.86 43.84,-110.45 45.256)))");
+    }
+
+    #[test]
+    fn georss_box() {
+        let geometry =
+            parse_georss("<georss:box>42.943 -71.032 43.039 -69.856</georss:box>").unwrap();
+        assert_compact_debug_snapshot!(geometry, @"POLYGON((-69.856 42.943,-69.856 43.039,-71.032 43.039,-71.032 42.943,-69.856 42.943))");
+    }
+
+    #[test]
+    fn gml_point_and_polygon() {
+        let geometry = parse_georss(
+            r#"<?xml version="1.0"?>
+            <!-- A legacy feed -->
+            <georss:where>
+                <gml:Point srsName="EPSG:4326"><gml:pos>45.256 -71.92</gml:pos></gml:Point>
+            </georss:where>"#,
+        )
+        .unwrap();
+        assert_compact_debug_snapshot!(geometry, @"POINT(-71.92 45.256)");
+
+        let geometry = parse_georss(
+            r#"<georss:where>
+                <gml:Polygon>
+                    <gml:exterior><gml:LinearRing>
+                        <gml:posList>45.256 -110.45 46.46 -109.48 43.84 -109.86 45.256 -110.45</gml:posList>
+                    </gml:LinearRing></gml:exterior>
+                    <gml:interior><gml:LinearRing>
+                        <gml:posList>45.0 -110.0 45.1 -110.0 45.1 -109.9 45.0 -110.0</gml:posList>
+                    </gml:LinearRing></gml:interior>
+                </gml:Polygon>
+            </georss:where>"#,
+        )
+        .unwrap();
+        assert_compact_debug_snapshot!(geometry, @"POLYGON((-110.45 45.256,-109.48 46.46,-109.86 43.84,-110.45 45.256))");
+    }
+
+    #[test]
+    fn gml_line_string_with_multiple_pos() {
+        let geometry = parse_georss(
+            "<gml:LineString><gml:pos>1 2</gml:pos><gml:pos>3 4</gml:pos></gml:LineString>",
+        )
+        .unwrap();
+        assert_compact_debug_snapshot!(geometry, @"LINESTRING(2.0 1.0,4.0 3.0)");
+    }
+
+    #[test]
+    fn write_from_georss() {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_georss(&mut buffer, "<georss:point>45.256 -71.92</georss:point>")
+            .unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert_compact_debug_snapshot!(zerometry, @"Point(Zoint { lng: -71.92, lat: 45.256 })");
+    }
+
+    #[test]
+    fn invalid_georss() {
+        assert_snapshot!(parse_georss("<rss></rss>").unwrap_err(), @"No GeoRSS or GML geometry found");
+        assert_snapshot!(parse_georss("<georss:point>45.256</georss:point>").unwrap_err(), @"Was expecting pairs of coordinates but found 1 numbers");
+        assert_snapshot!(parse_georss("<georss:point>45.256 a</georss:point>").unwrap_err(), @"Invalid coordinate `a`: invalid float literal");
+        assert_snapshot!(parse_georss("<georss:point>1 2 3 4</georss:point>").unwrap_err(), @"A point must contain exactly one position but found 2");
+    }
+}
@@ -0,0 +1,257 @@
+use std::mem;
+
+use crate::{
+    BOUNDING_BOX_SIZE_IN_BYTES, BoundingBox, Zerometry, ZerometryError,
+    bounding_box::is_valid_bounding_box,
+};
+
+/// The number of consecutive lines or polygons summarized by each bounding box of [`Groups`].
+pub(crate) const GROUP_SIZE: usize = 16;
+/// The number of floats used to store a bounding box.
+const BOUNDING_BOX_SIZE_IN_FLOATS: usize = BOUNDING_BOX_SIZE_IN_BYTES / mem::size_of::<f64>();
+
+/// The bounding boxes of every group of [`GROUP_SIZE`] consecutive lines or polygons of a multi shape.
+/// When comparing the multi shape to another shape, all the members of a group whose bounding box doesn't
+/// match are skipped at once, which gives most of the benefit of an R-tree while the members stay stored
+/// in their original order.
+///
+/// The binary format is the four floats of each bounding box, one after the other.
+#[derive(Clone, Copy)]
+pub(crate) struct Groups<'a> {
+    bounding_boxes: &'a [f64],
+}
+
+impl<'a> Groups<'a> {
+    /// Create the groups of a multi shape containing `members` lines or polygons.
+    pub fn new(bounding_boxes: &'a [f64], members: usize) -> Result<Self, ZerometryError> {
+        if bounding_boxes.len() != members.div_ceil(GROUP_SIZE) * BOUNDING_BOX_SIZE_IN_FLOATS {
+            return Err(ZerometryError::InvalidGroups {
+                groups: bounding_boxes.len() / BOUNDING_BOX_SIZE_IN_FLOATS,
+                members,
+            });
+        }
+        for bounding_box in bounding_boxes.chunks_exact(BOUNDING_BOX_SIZE_IN_FLOATS) {
+            if !is_valid_bounding_box(bounding_box.try_into().unwrap()) {
+                return Err(ZerometryError::InvalidBoundingBox);
+            }
+        }
+        Ok(Self { bounding_boxes })
+    }
+
+    /// Compute the bounding box of every group of members and write them in the buffer.
+    pub fn write_from_bounding_boxes<'b>(
+        writer: &mut Vec<u8>,
+        bounding_boxes: impl Iterator<Item = &'b BoundingBox>,
+    ) {
+        let bounding_boxes: Vec<_> = bounding_boxes.collect();
+        for group in bounding_boxes.chunks(GROUP_SIZE) {
+            let (first, rest) = group.split_first().unwrap();
+            let [mut left, mut bottom, mut right, mut top] =
+                [first.left(), first.bottom(), first.right(), first.top()];
+            for bounding_box in rest {
+                left = left.min(bounding_box.left());
+                bottom = bottom.min(bounding_box.bottom());
+                right = right.max(bounding_box.right());
+                top = top.max(bounding_box.top());
+            }
+            for float in [left, bottom, right, top] {
+                writer.extend_from_slice(&float.to_ne_bytes());
+            }
+        }
+    }
+
+    /// Return the number of groups.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bounding_boxes.len() / BOUNDING_BOX_SIZE_IN_FLOATS
+    }
+
+    /// Return the bounding box of a group.
+    #[inline]
+    pub fn get(&self, group: usize) -> &'a BoundingBox {
+        let start = group * BOUNDING_BOX_SIZE_IN_FLOATS;
+        unsafe {
+            BoundingBox::from_slice(
+                &self.bounding_boxes[start..start + BOUNDING_BOX_SIZE_IN_FLOATS],
+            )
+        }
+    }
+}
+
+/// Return the indexes of the `len` members whose group bounding box matches the predicate.
+/// Without groups every member is returned.
+pub(crate) fn candidates<'a>(
+    groups: Option<Groups<'a>>,
+    len: usize,
+    keep: impl Fn(&BoundingBox) -> bool + 'a,
+) -> impl Iterator<Item = usize> + 'a {
+    let (count, size) = match groups {
+        Some(groups) => (groups.len(), GROUP_SIZE),
+        None => (1, len),
+    };
+    (0..count)
+        .filter(move |group| groups.is_none_or(|groups| keep(groups.get(*group))))
+        .flat_map(move |group| group * size..((group + 1) * size).min(len))
+}
+
+impl<'a> Zerometry<'a> {
+    /// Write the groups of the lines and polygons of the zerometry in the buffer, as stored in the header:
+    /// The number of groups of lines and of polygons as `u64`, followed by their bounding boxes.
+    pub(crate) fn write_groups(&self, writer: &mut Vec<u8>) {
+        let (lines, polygons) = match self {
+            Zerometry::MultiLines(lines) => (Some(*lines), None),
+            Zerometry::MultiPolygon(polygons) => (None, Some(*polygons)),
+            Zerometry::Collection(collection) => {
+                (Some(collection.lines()), Some(collection.polygons()))
+            }
+            _ => (None, None),
+        };
+        let lines_count = lines.map_or(0, |lines| lines.len().div_ceil(GROUP_SIZE));
+        let polygons_count = polygons.map_or(0, |polygons| polygons.len().div_ceil(GROUP_SIZE));
+        writer.extend_from_slice(&(lines_count as u64).to_ne_bytes());
+        writer.extend_from_slice(&(polygons_count as u64).to_ne_bytes());
+        if let Some(lines) = lines {
+            let bounding_boxes = lines.lines().map(|line| line.bounding_box());
+            Groups::write_from_bounding_boxes(writer, bounding_boxes);
+        }
+        if let Some(polygons) = polygons {
+            let bounding_boxes = polygons.polygons().map(|polygon| polygon.bounding_box());
+            Groups::write_from_bounding_boxes(writer, bounding_boxes);
+        }
+    }
+
+    /// Attach the groups read from the header to the lines and polygons of the zerometry.
+    pub(crate) fn attach_groups(
+        self,
+        line_groups: &'a [f64],
+        polygon_groups: &'a [f64],
+    ) -> Result<Self, ZerometryError> {
+        Ok(match self {
+            Zerometry::MultiLines(lines) => {
+                Groups::new(polygon_groups, 0)?;
+                Zerometry::MultiLines(lines.with_groups(Groups::new(line_groups, lines.len())?))
+            }
+            Zerometry::MultiPolygon(polygons) => {
+                Groups::new(line_groups, 0)?;
+                Zerometry::MultiPolygon(
+                    polygons.with_groups(Groups::new(polygon_groups, polygons.len())?),
+                )
+            }
+            Zerometry::Collection(collection) => Zerometry::Collection(collection.with_groups(
+                Groups::new(line_groups, collection.lines().len())?,
+                Groups::new(polygon_groups, collection.polygons().len())?,
+            )),
+            _ => return Err(ZerometryError::UnexpectedGroups),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPolygon, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{InputRelation, RelationBetweenShapes, WriteOptions};
+
+    fn collection() -> Geometry<f64> {
+        // A row of 40 small squares followed by a row of 20 lines
+        let squares = (0..40).map(|i| {
+            let x = i as f64 * 2.0;
+            Geometry::Polygon(
+                polygon![(x: x, y: 0.), (x: x + 1., y: 0.), (x: x + 1., y: 1.), (x: x, y: 1.)],
+            )
+        });
+        let lines = (0..20).map(|i| {
+            let x = i as f64 * 2.0;
+            Geometry::LineString(LineString::from(vec![(x, 5.0), (x + 1.0, 6.0)]))
+        });
+        Geometry::GeometryCollection(GeometryCollection::new_from(squares.chain(lines).collect()))
+    }
+
+    #[test]
+    fn groups_skip_the_disjoint_members() {
+        let geometry = collection();
+        let mut buffer = Vec::new();
+        let options = WriteOptions {
+            bounding_box_groups: true,
+            ..WriteOptions::default()
+        };
+        Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+        let grouped = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        let Zerometry::Collection(zollection) = grouped else {
+            panic!("{grouped:?}")
+        };
+        assert!(zollection.polygons().has_bounding_box_groups());
+        assert!(zollection.lines().has_bounding_box_groups());
+        let mut plain_buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut plain_buffer, &geometry).unwrap();
+        let plain = unsafe { Zerometry::from_bytes(&plain_buffer).unwrap() };
+        assert_eq!(grouped.to_geo(), plain.to_geo());
+
+        // Only the second group of squares can be near x = 40
+        let probe = [40.5, 0.5];
+        let probe = unsafe { crate::Coord::from_slice(&probe) };
+        let candidates: Vec<_> = zollection
+            .polygons()
+            .candidate_polygons(|bounding_box| bounding_box.contains_coord(probe))
+            .map(|zolygon| zolygon.bounding_box().left())
+            .collect();
+        assert_compact_debug_snapshot!((candidates.len(), candidates.first(), candidates.last()), @"(16, Some(32.0), Some(62.0))");
+
+        let probes: [Geometry<f64>; 4] = [
+            Point::new(40.5, 0.5).into(),
+            Point::new(41.5, 0.5).into(),
+            LineString::from(vec![(30.5, -1.0), (30.5, 7.0)]).into(),
+            MultiPolygon::new(vec![
+                polygon![(x: 70., y: -1.), (x: 90., y: -1.), (x: 90., y: 2.), (x: 70., y: 2.)],
+            ])
+            .into(),
+        ];
+        for probe in probes {
+            let mut probe_buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut probe_buffer, &probe).unwrap();
+            let probe = unsafe { Zerometry::from_bytes(&probe_buffer).unwrap() };
+            assert_eq!(
+                grouped.relation(&probe, InputRelation::all()),
+                plain.relation(&probe, InputRelation::all()),
+                "{probe:?}"
+            );
+        }
+    }
+
+    fn to_words(bytes: &[u8]) -> Vec<u64> {
+        bytes
+            .chunks_exact(mem::size_of::<u64>())
+            .map(|word| u64::from_ne_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn invalid_groups() {
+        let mut buffer = Vec::new();
+        let options = WriteOptions {
+            bounding_box_groups: true,
+            ..WriteOptions::default()
+        };
+        Zerometry::write_from_geometry_with_options(&mut buffer, &collection(), options).unwrap();
+        let mut words = to_words(&buffer);
+        // Swap the number of groups of lines and polygons
+        words.swap(1, 2);
+        let err = Zerometry::try_from_bytes(bytemuck::cast_slice(&words)).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidGroups { groups: 3, members: 20 }");
+
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(
+            &mut buffer,
+            &Point::new(1., 2.).into(),
+            options,
+        )
+        .unwrap();
+        let mut words = to_words(&buffer);
+        words[0] |= crate::header::GROUPS_FLAG;
+        words.splice(1..1, [0, 0]);
+        let err = Zerometry::try_from_bytes(bytemuck::cast_slice(&words)).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"UnexpectedGroups");
+    }
+}
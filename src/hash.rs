@@ -0,0 +1,245 @@
+use crate::{
+    Coord, Coords, Zerometry, Zine, Zoint, Zollection, Zolygon, ZultiLines, ZultiPoints,
+    ZultiPolygons,
+};
+
+/// The offset basis and prime of the 64 bits FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Zerometry<'_> {
+    /// Return a hash of the shape that only depends on its kind and its coords, to find the identical shapes
+    /// across documents without comparing their bytes.
+    ///
+    /// The hash is stable: it doesn't depend on the platform, the version of the crate or the [`crate::WriteOptions`],
+    /// which means the ids, the generation, the bounding box groups, the coverage mask and the order of the sorted
+    /// multi points are ignored. `0.0` and `-0.0` are considered equal.
+    ///
+    /// Two different shapes may collide, the hash must only be used to skip the comparison of the shapes that differ.
+    ///
+    /// ```
+    /// use zerometry::{WriteOptions, Zerometry};
+    /// use geo_types::{Geometry, MultiPoint, Point};
+    ///
+    /// let points = Geometry::MultiPoint(MultiPoint::from(vec![Point::new(3.0, 1.0), Point::new(1.0, 2.0)]));
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &points).unwrap();
+    /// let mut sorted = Vec::new();
+    /// let options = WriteOptions { sort_points: true, ..WriteOptions::default() };
+    /// Zerometry::write_from_geometry_with_options(&mut sorted, &points, options).unwrap();
+    ///
+    /// let hash = Zerometry::try_from_bytes(&buffer).unwrap().content_hash();
+    /// assert_eq!(hash, Zerometry::try_from_bytes(&sorted).unwrap().content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = ContentHasher::new();
+        match self {
+            Zerometry::Point(a) => {
+                hasher.write_kind(0);
+                a.hash_content(&mut hasher);
+            }
+            Zerometry::MultiPoints(a) => {
+                hasher.write_kind(1);
+                a.hash_content(&mut hasher);
+            }
+            Zerometry::Polygon(a) => {
+                hasher.write_kind(2);
+                a.hash_content(&mut hasher);
+            }
+            Zerometry::MultiPolygon(a) => {
+                hasher.write_kind(3);
+                a.hash_content(&mut hasher);
+            }
+            Zerometry::Line(a) => {
+                hasher.write_kind(4);
+                a.hash_content(&mut hasher);
+            }
+            Zerometry::MultiLines(a) => {
+                hasher.write_kind(5);
+                a.hash_content(&mut hasher);
+            }
+            Zerometry::Collection(a) => {
+                hasher.write_kind(6);
+                a.hash_content(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+impl Zoint<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        hasher.write_coord(self.coord());
+    }
+}
+
+impl ZultiPoints<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        hasher.write_len(self.len());
+        for point in self.points_in_original_order() {
+            hasher.write_coord(point.coord());
+        }
+    }
+}
+
+impl Zine<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        hasher.write_coords(self.coords());
+    }
+}
+
+impl ZultiLines<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        hasher.write_len(self.len());
+        for line in self.lines() {
+            line.hash_content(hasher);
+        }
+    }
+}
+
+impl Zolygon<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        hasher.write_coords(self.coords());
+    }
+}
+
+impl ZultiPolygons<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        hasher.write_len(self.len());
+        for polygon in self.polygons() {
+            polygon.hash_content(hasher);
+        }
+    }
+}
+
+impl Zollection<'_> {
+    fn hash_content(&self, hasher: &mut ContentHasher) {
+        self.points().hash_content(hasher);
+        self.lines().hash_content(hasher);
+        self.polygons().hash_content(hasher);
+    }
+}
+
+/// A 64 bits FNV-1a hasher fed with the numbers in little-endian, contrary to the [`std::hash::Hasher`]
+/// of the standard library its output is guaranteed to never change.
+struct ContentHasher {
+    state: u64,
+}
+
+impl ContentHasher {
+    fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_kind(&mut self, kind: u8) {
+        self.write(&[kind]);
+    }
+
+    /// The lengths are hashed before the lists so the same coords split differently don't collide.
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    fn write_coord(&mut self, coord: &Coord) {
+        // Adding `0.0` turns `-0.0` into `0.0`
+        self.write(&(coord.lng() + 0.0).to_le_bytes());
+        self.write(&(coord.lat() + 0.0).to_le_bytes());
+    }
+
+    fn write_coords(&mut self, coords: &Coords) {
+        self.write_len(coords.len());
+        for coord in coords.iter() {
+            self.write_coord(coord);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+        polygon,
+    };
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::WriteOptions;
+
+    fn hash(geometry: &Geometry<f64>, options: WriteOptions) -> u64 {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, geometry, options).unwrap();
+        Zerometry::try_from_bytes(&buffer).unwrap().content_hash()
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0)]);
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let default = WriteOptions::default();
+
+        // The hash must never change, whatever the platform
+        let hashes = [
+            hash(&Point::new(1.0, 2.0).into(), default),
+            hash(&line.clone().into(), default),
+            hash(&square.clone().into(), default),
+            hash(
+                &MultiPolygon::new(vec![square.clone(), square.clone()]).into(),
+                default,
+            ),
+        ];
+        assert_compact_debug_snapshot!(hashes, @"[12833355219415039262, 15315364315906946700, 7397068198973747200, 5429908970750492336]");
+
+        // The options don't change the hash
+        let options = WriteOptions {
+            generation: Some(42),
+            bounding_box_groups: true,
+            ..WriteOptions::default()
+        };
+        let multi_line: Geometry<f64> =
+            MultiLineString::new(vec![line.clone(), line.clone()]).into();
+        assert_eq!(hash(&multi_line, default), hash(&multi_line, options));
+
+        // Neither does the sign of zero
+        assert_eq!(
+            hash(&Point::new(0.0, 1.0).into(), default),
+            hash(&Point::new(-0.0, 1.0).into(), default)
+        );
+
+        // But the kind and the way the coords are split does
+        let points = MultiPoint::from(vec![Point::new(0.0, 0.0), Point::new(1.0, 2.0)]);
+        assert_ne!(
+            hash(&line.clone().into(), default),
+            hash(&points.into(), default)
+        );
+        let split = MultiLineString::new(vec![
+            LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 3.0)]),
+            LineString::from(vec![(4.0, 4.0), (5.0, 5.0)]),
+        ]);
+        let split_differently = MultiLineString::new(vec![
+            LineString::from(vec![(0.0, 0.0), (1.0, 2.0)]),
+            LineString::from(vec![(3.0, 3.0), (4.0, 4.0), (5.0, 5.0)]),
+        ]);
+        assert_ne!(
+            hash(&split.into(), default),
+            hash(&split_differently.into(), default)
+        );
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            line.into(),
+            square.into(),
+        ]));
+        assert_ne!(hash(&collection, default), hash(&multi_line, default));
+    }
+}
@@ -0,0 +1,626 @@
+use std::mem;
+
+use bytemuck::{Pod, try_cast_slice};
+
+use crate::{
+    COORD_SIZE_IN_BYTES, Zerometry, ZerometryError,
+    checksum::{CHECKSUM_SIZE_IN_BYTES, verify_checksum},
+    coverage_mask::{CoverageMask, bitmap_words},
+    error::{checked_size, split_checked, split_u64},
+    properties::{Properties, padded_size},
+    subtype::Subtype,
+};
+
+/// The number of bytes used by the tag starting every zerometry, it tells which kind of shape follows.
+/// When no [`crate::WriteOptions`] is used the shape starts right after it, otherwise see [`Zerometry::header_size`].
+pub const TAG_SIZE_IN_BYTES: usize = mem::size_of::<u64>();
+/// The kind stored in the tag of a [`crate::Zoint`].
+pub const POINT_TAG: u64 = 0;
+/// The kind stored in the tag of a [`crate::ZultiPoints`].
+pub const MULTI_POINTS_TAG: u64 = 1;
+/// The kind stored in the tag of a [`crate::Zolygon`].
+pub const POLYGON_TAG: u64 = 2;
+/// The kind stored in the tag of a [`crate::ZultiPolygons`].
+pub const MULTI_POLYGON_TAG: u64 = 3;
+/// The kind stored in the tag of a [`crate::Zine`].
+pub const LINE_TAG: u64 = 4;
+/// The kind stored in the tag of a [`crate::ZultiLines`].
+pub const MULTI_LINES_TAG: u64 = 5;
+/// The kind stored in the tag of a [`crate::Zollection`].
+pub const COLLECTION_TAG: u64 = 6;
+/// The kind stored in the tag of a [`crate::ZultiPoints`] sorted by Morton code,
+/// see [`crate::WriteOptions::sort_points`].
+pub const SORTED_MULTI_POINTS_TAG: u64 = 7;
+/// The kind stored in the tag of the nested collections of a [`crate::StructuredZollection`].
+/// It's not a [`Zerometry`] and can't be read by [`Zerometry::from_bytes`].
+pub const STRUCTURED_COLLECTION_TAG: u64 = 8;
+
+/// Bit set in the tag of a zerometry when it's directly followed by its generation stored as a `u64`.
+pub(crate) const GENERATION_FLAG: u64 = 1 << 32;
+/// Bit set in the tag of a zerometry when the ids of its lines and polygons follow the tag and generation.
+/// They're stored as the number of lines ids and polygons ids as `u64`, followed by the ids themselves.
+pub(crate) const IDS_FLAG: u64 = 1 << 33;
+/// Bit set in the tag of a multi lines, multi polygons or collection when the bounding boxes of the groups of
+/// its lines and polygons follow the ids. They're stored as the number of groups of lines and of polygons
+/// as `u64`, followed by the four floats of every bounding box, see [`crate::groups::Groups`].
+pub(crate) const GROUPS_FLAG: u64 = 1 << 35;
+/// Bit set in the tag of a polygon when its coverage mask follows the other optional fields, see [`CoverageMask`].
+pub(crate) const COVERAGE_MASK_FLAG: u64 = 1 << 34;
+/// Bit set in the tag when the zerometry is followed by the CRC32 of all its bytes, stored as a `u64`,
+/// see [`crate::WriteOptions::checksum`].
+pub(crate) const CHECKSUM_FLAG: u64 = 1 << 36;
+/// Bit set in the tag when the elevation of every coordinate follows the other optional fields.
+/// They're stored as their number as `u64`, followed by one `f64` per coordinate in the order the coordinates
+/// are stored, see [`Zerometry::write_from_geometry_with_elevations`].
+pub(crate) const ELEVATIONS_FLAG: u64 = 1 << 37;
+/// Bit set in the tag of a collection when the properties of its members follow the elevations.
+/// They're stored as the number of members and the total size of the properties as `u64`, followed by the end of
+/// the properties of every member as `u64` and all the properties padded to 64 bits, see [`Properties`].
+pub(crate) const PROPERTIES_FLAG: u64 = 1 << 38;
+/// Bit set in the tag of a polygon when its convexity was computed while writing it, the polygon is convex if
+/// the [`CONVEX_FLAG`] is also set, see [`crate::WriteOptions::convexity`].
+pub(crate) const CONVEXITY_FLAG: u64 = 1 << 48;
+/// Bit set in the tag of a polygon along with the [`CONVEXITY_FLAG`] when the polygon is convex.
+pub(crate) const CONVEX_FLAG: u64 = 1 << 49;
+/// The byte of the tag storing the original variant of a line or polygon, see [`crate::subtype::Subtype`].
+pub(crate) const SUBTYPE_SHIFT: u32 = 40;
+const SUBTYPE_MASK: u64 = 0xff << SUBTYPE_SHIFT;
+/// The bits of the tag identifying the kind of shape stored.
+pub(crate) const KIND_MASK: u64 = u32::MAX as u64;
+/// The format version is stored in the most significant byte of the tag.
+const VERSION_SHIFT: u32 = 56;
+const VERSION_MASK: u64 = 0xff << VERSION_SHIFT;
+
+/// The most recent version of the binary format read by this crate.
+/// It's stored in the most significant byte of the tag, which means the zerometries written before
+/// the version was introduced are read as the version 0.
+/// When the layout changes, the version is increased and [`Zerometry::from_bytes`] keeps reading the older versions.
+///
+/// - Version 0: every number is stored in the native endianness of the platform that wrote the zerometry.
+/// - Version 1: same layout but every number is stored in little-endian, which makes the zerometry portable
+///   across platforms.
+/// - Version 2: the shapes without any coordinate are written with an empty bounding box whose corners are
+///   infinite and inverted, see [`crate::BoundingBox::is_empty`], instead of a bounding box made of zeros.
+///   The endianness isn't part of the version anymore, the little-endian zerometries are marked by a flag
+///   of the tag instead, see [`crate::WriteOptions::little_endian`].
+///   It's what [`Zerometry::write_from_geometry`] writes.
+///
+/// The empty shapes of the versions 0 and 1 keep their bounding box made of zeros when they're read,
+/// thus they're not [empty](crate::BoundingBox::is_empty).
+pub const FORMAT_VERSION: u8 = 2;
+/// The version of the zerometries stored in the native endianness of the platform, before the version 2.
+pub(crate) const LEGACY_NATIVE_VERSION: u8 = 0;
+/// The version of the zerometries stored in little-endian, before the version 2.
+pub(crate) const LEGACY_LITTLE_ENDIAN_VERSION: u8 = 1;
+/// Bit set in the tag of the zerometries stored in little-endian since the version 2.
+/// It's out of the kind, which means a little-endian tag read with its bytes swapped is never a known tag.
+pub(crate) const LITTLE_ENDIAN_FLAG: u64 = 1 << 39;
+
+/// Return the tag of the specified kind of shape, stamped with the [`FORMAT_VERSION`].
+pub(crate) const fn tag(kind: u64) -> u64 {
+    with_version(kind, FORMAT_VERSION)
+}
+
+/// Replace the version stamped in the tag.
+pub(crate) const fn with_version(tag: u64, version: u8) -> u64 {
+    tag & !VERSION_MASK | (version as u64) << VERSION_SHIFT
+}
+
+/// Return the version stamped in the tag.
+const fn version(tag: u64) -> u8 {
+    (tag >> VERSION_SHIFT) as u8
+}
+
+/// Return `true` if the tag is the one of a zerometry stored in little-endian.
+fn is_little_endian_tag(tag: u64) -> bool {
+    version(tag) == LEGACY_LITTLE_ENDIAN_VERSION || tag & LITTLE_ENDIAN_FLAG != 0
+}
+
+/// Return the tag of a little-endian zerometry once its bytes were converted to the native endianness.
+pub(crate) fn to_native_tag(tag: u64) -> u64 {
+    if version(tag) == LEGACY_LITTLE_ENDIAN_VERSION {
+        with_version(tag, LEGACY_NATIVE_VERSION)
+    } else {
+        tag & !LITTLE_ENDIAN_FLAG
+    }
+}
+
+/// Return `true` if the tag is made of a known kind and known flags only and its version is supported.
+fn is_known_tag(tag: u64) -> bool {
+    tag & !(KIND_MASK
+        | GENERATION_FLAG
+        | IDS_FLAG
+        | GROUPS_FLAG
+        | COVERAGE_MASK_FLAG
+        | CHECKSUM_FLAG
+        | ELEVATIONS_FLAG
+        | PROPERTIES_FLAG
+        | CONVEXITY_FLAG
+        | CONVEX_FLAG
+        | SUBTYPE_MASK
+        | LITTLE_ENDIAN_FLAG
+        | VERSION_MASK)
+        == 0
+        // The polygon can't be convex if its convexity is unknown
+        && (tag & CONVEX_FLAG == 0 || tag & CONVEXITY_FLAG != 0)
+        && tag & KIND_MASK <= STRUCTURED_COLLECTION_TAG
+        // The endianness was stored in the version before the version 2
+        && (tag & LITTLE_ENDIAN_FLAG == 0 || version(tag) > LEGACY_LITTLE_ENDIAN_VERSION)
+        && version(tag) <= FORMAT_VERSION
+}
+
+/// Return `true` if the zerometry was written in little-endian and must be converted before being read
+/// on this platform, which can only happen on big-endian platforms.
+pub(crate) fn is_foreign(data: &[u8]) -> bool {
+    if cfg!(target_endian = "little") {
+        return false;
+    }
+    let Ok((tag, _)) = split_u64(data) else {
+        return false;
+    };
+    is_swapped_little_endian(tag, data.len())
+}
+
+/// Return `true` if the tag, read in the native endianness of a big-endian platform, is the one of a
+/// little-endian zerometry of `len` bytes.
+fn is_swapped_little_endian(tag: u64, len: usize) -> bool {
+    let little_endian = tag.swap_bytes();
+    if !is_known_tag(little_endian) || !is_little_endian_tag(little_endian) {
+        return false;
+    }
+    // The zerometries written in the native endianness of a big-endian platform are never marked as little-endian,
+    // thus a tag read as little-endian has been swapped. It happens when the little-endian tag is a palindrome,
+    // like the one of a multi points without flags of the version 1.
+    if !is_known_tag(tag) || is_little_endian_tag(tag) {
+        return true;
+    }
+    // The little-endian flag becomes a part of the kind once swapped, which means the only swapped tags left are
+    // the ones of the points and polygons without flags of the version 1: read natively they're the tag of a
+    // multi points without flags of the version 0 and 2 respectively.
+    // The multi points contains at least a bounding box while the point is only made of its coord. But the
+    // polygon has the same layout as the multi points, it's read as the native multi points written by this crate.
+    version(tag) == LEGACY_NATIVE_VERSION && len == mem::size_of::<u64>() + COORD_SIZE_IN_BYTES
+}
+
+/// The parsed header of a zerometry.
+pub(crate) struct Header<'a> {
+    /// The version of the format the zerometry was written with.
+    pub version: u8,
+    /// The kind of shape stored, it's the tag without the flags.
+    pub kind: u64,
+    /// The original variant of the geometry if it was converted to a line or a polygon.
+    pub subtype: Subtype,
+    pub generation: Option<u64>,
+    /// The ids of the lines and of the polygons.
+    pub ids: Option<(&'a [u64], &'a [u64])>,
+    /// The bounding boxes of the groups of lines and of polygons.
+    pub groups: Option<(&'a [f64], &'a [f64])>,
+    pub coverage_mask: Option<CoverageMask<'a>>,
+    /// The elevation of every coordinate of the shape.
+    pub elevations: Option<&'a [f64]>,
+    /// The properties of the members of a collection.
+    pub properties: Option<Properties<'a>>,
+    /// Whether the polygon is convex, if it was computed while writing it.
+    pub convex: Option<bool>,
+    /// The checksum stored after the shape, it's not verified by the parsing.
+    pub checksum: Option<u32>,
+    /// The bytes of the shape following the header.
+    pub body: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    /// Parse the tag of a zerometry and the optional fields following it.
+    pub fn parse(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        let (tag, version, body) = parse_tag(data)?;
+        let (body, checksum) = split_checksum(tag, body)?;
+        let (generation, body) = if tag & GENERATION_FLAG != 0 {
+            let (generation, body) = split_u64(body)?;
+            (Some(generation), body)
+        } else {
+            (None, body)
+        };
+        let (ids, body) = if tag & IDS_FLAG != 0 {
+            let (lines, body) = split_u64(body)?;
+            let (polygons, body) = split_u64(body)?;
+            let (line_ids, body) = split_slice(body, lines)?;
+            let (polygon_ids, body) = split_slice(body, polygons)?;
+            (Some((line_ids, polygon_ids)), body)
+        } else {
+            (None, body)
+        };
+        let (groups, body) = if tag & GROUPS_FLAG != 0 {
+            let (lines, body) = split_u64(body)?;
+            let (polygons, body) = split_u64(body)?;
+            let (line_groups, body) = split_slice(body, lines.saturating_mul(4))?;
+            let (polygon_groups, body) = split_slice(body, polygons.saturating_mul(4))?;
+            (Some((line_groups, polygon_groups)), body)
+        } else {
+            (None, body)
+        };
+        let (coverage_mask, body) = if tag & COVERAGE_MASK_FLAG != 0 {
+            let (size, body) = split_u64(body)?;
+            let words = usize::try_from(size).ok().and_then(bitmap_words).ok_or(
+                ZerometryError::Truncated {
+                    expected: usize::MAX,
+                    found: body.len(),
+                },
+            )?;
+            let (inside, body) = split_slice(body, words as u64)?;
+            let (outside, body) = split_slice(body, words as u64)?;
+            (
+                Some(CoverageMask::new(size as usize, inside, outside)?),
+                body,
+            )
+        } else {
+            (None, body)
+        };
+        let (elevations, body) = if tag & ELEVATIONS_FLAG != 0 {
+            let (count, body) = split_u64(body)?;
+            let (elevations, body) = split_slice(body, count)?;
+            (Some(elevations), body)
+        } else {
+            (None, body)
+        };
+        let (properties, body) = if tag & PROPERTIES_FLAG != 0 {
+            let (count, body) = split_u64(body)?;
+            let (size, body) = split_u64(body)?;
+            let (ends, body) = split_slice(body, count)?;
+            let (bytes, body) = split_slice::<u8>(body, padded_size(size))?;
+            // The size fits in the bytes that were split
+            let bytes = &bytes[..size as usize];
+            (Some(Properties::new(ends, bytes)?), body)
+        } else {
+            (None, body)
+        };
+        Ok(Header {
+            version,
+            kind: tag & KIND_MASK,
+            subtype: Subtype::from_tag(tag)?,
+            generation,
+            ids,
+            groups,
+            coverage_mask,
+            elevations,
+            properties,
+            convex: (tag & CONVEXITY_FLAG != 0).then_some(tag & CONVEX_FLAG != 0),
+            checksum,
+            body,
+        })
+    }
+
+    /// Skip the optional fields of the header of a zerometry that may not be aligned on 64 bits.
+    /// Return the version, the kind of shape, its subtype and the body, see [`Zerometry::decode_unaligned_bytes`].
+    /// The checksum is verified since it's the only time the data is read.
+    pub fn skip_unaligned(data: &[u8]) -> Result<(u8, u64, Subtype, &[u8]), ZerometryError> {
+        let (tag, version, body) = parse_tag(data)?;
+        let (body, checksum) = split_checksum(tag, body)?;
+        if let Some(checksum) = checksum {
+            verify_checksum(data, checksum)?;
+        }
+        let body = if tag & GENERATION_FLAG != 0 {
+            split_u64(body)?.1
+        } else {
+            body
+        };
+        let skip_counted = |body, words_per_element: u64| {
+            let (lines, body) = split_u64(body)?;
+            let (polygons, body) = split_u64(body)?;
+            let count = lines
+                .saturating_add(polygons)
+                .saturating_mul(words_per_element);
+            let size = checked_size(count, mem::size_of::<u64>(), body)?;
+            Ok::<_, ZerometryError>(split_checked(body, size)?.1)
+        };
+        let body = if tag & IDS_FLAG != 0 {
+            skip_counted(body, 1)?
+        } else {
+            body
+        };
+        let body = if tag & GROUPS_FLAG != 0 {
+            skip_counted(body, 4)?
+        } else {
+            body
+        };
+        let body = if tag & COVERAGE_MASK_FLAG != 0 {
+            let (size, body) = split_u64(body)?;
+            let words = usize::try_from(size)
+                .ok()
+                .and_then(bitmap_words)
+                .map_or(u64::MAX, |words| words as u64);
+            let size = checked_size(words.saturating_mul(2), mem::size_of::<u64>(), body)?;
+            split_checked(body, size)?.1
+        } else {
+            body
+        };
+        let body = if tag & ELEVATIONS_FLAG != 0 {
+            let (count, body) = split_u64(body)?;
+            let size = checked_size(count, mem::size_of::<f64>(), body)?;
+            split_checked(body, size)?.1
+        } else {
+            body
+        };
+        let body = if tag & PROPERTIES_FLAG != 0 {
+            let (count, body) = split_u64(body)?;
+            let (size, body) = split_u64(body)?;
+            let size = count
+                .saturating_mul(mem::size_of::<u64>() as u64)
+                .saturating_add(padded_size(size));
+            let size = checked_size(size, 1, body)?;
+            split_checked(body, size)?.1
+        } else {
+            body
+        };
+        Ok((version, tag & KIND_MASK, Subtype::from_tag(tag)?, body))
+    }
+}
+
+/// Read the tag of a zerometry and check its flags and version.
+/// Return the tag, the version and the data following the tag.
+fn parse_tag(data: &[u8]) -> Result<(u64, u8, &[u8]), ZerometryError> {
+    if is_foreign(data) {
+        return Err(ZerometryError::ForeignEndianness);
+    }
+    let (tag, body) = split_u64(data)?;
+    if !is_known_tag(tag) {
+        // The version is checked separately to return a more precise error
+        if version(tag) > FORMAT_VERSION && is_known_tag(with_version(tag, FORMAT_VERSION)) {
+            return Err(ZerometryError::UnsupportedVersion(version(tag)));
+        }
+        return Err(ZerometryError::InvalidTag(tag));
+    }
+    // The layout of the header itself may change with the version
+    Ok((tag, version(tag), body))
+}
+
+/// Split the checksum stored at the end of the zerometry if its flag is set in the tag.
+fn split_checksum(tag: u64, body: &[u8]) -> Result<(&[u8], Option<u32>), ZerometryError> {
+    if tag & CHECKSUM_FLAG == 0 {
+        return Ok((body, None));
+    }
+    let size = mem::size_of::<u64>();
+    let split = body
+        .len()
+        .checked_sub(size)
+        .ok_or(ZerometryError::Truncated {
+            expected: size,
+            found: body.len(),
+        })?;
+    let (body, checksum) = body.split_at(split);
+    let checksum = u64::from_ne_bytes(checksum.try_into().unwrap());
+    Ok((body, Some(checksum as u32)))
+}
+
+fn split_slice<T: Pod>(data: &[u8], count: u64) -> Result<(&[T], &[u8]), ZerometryError> {
+    let size = checked_size(count, mem::size_of::<T>(), data)?;
+    let (values, data) = split_checked(data, size)?;
+    let values = try_cast_slice(values).map_err(|_| ZerometryError::Misaligned)?;
+    Ok((values, data))
+}
+
+impl Zerometry<'_> {
+    /// Return the generation stored in the header of a serialized zerometry, or `None` if it was written without one.
+    /// Only the header is read, which makes it a cheap way for a cache to detect that a stored shape changed.
+    /// See [`crate::WriteOptions::generation`] to write it.
+    ///
+    /// ```
+    /// use zerometry::{WriteOptions, Zerometry};
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let point = Geometry::Point(Point::new(1.0, 2.0));
+    /// let mut buffer = Vec::new();
+    /// let options = WriteOptions { generation: Some(42), ..WriteOptions::default() };
+    /// Zerometry::write_from_geometry_with_options(&mut buffer, &point, options).unwrap();
+    /// assert_eq!(Zerometry::read_generation(&buffer).unwrap(), Some(42));
+    ///
+    /// let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+    /// assert_eq!(zerometry.to_geo(), point);
+    /// ```
+    pub fn read_generation(data: &[u8]) -> Result<Option<u64>, ZerometryError> {
+        Ok(Header::parse(data)?.generation)
+    }
+
+    /// Return the version of the binary format a serialized zerometry was written with, see [`crate::FORMAT_VERSION`].
+    /// Only the header is read, which makes it possible to find the zerometries that should be rewritten after an upgrade.
+    pub fn read_format_version(data: &[u8]) -> Result<u8, ZerometryError> {
+        Ok(Header::parse(data)?.version)
+    }
+
+    /// Return the number of bytes preceding the shape in a serialized zerometry: the tag followed by the optional
+    /// fields of the [`crate::WriteOptions`]. It's [`TAG_SIZE_IN_BYTES`] when no option was used.
+    /// Only the header is read, which makes it possible to read the bounding box straight from the bytes.
+    ///
+    /// ```
+    /// use zerometry::{BOUNDING_BOX_SIZE_IN_BYTES, WriteOptions, Zerometry};
+    /// use geo_types::{Geometry, polygon};
+    ///
+    /// let square = Geometry::Polygon(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)]);
+    /// let mut buffer = Vec::new();
+    /// let options = WriteOptions { generation: Some(42), ..WriteOptions::default() };
+    /// Zerometry::write_from_geometry_with_options(&mut buffer, &square, options).unwrap();
+    ///
+    /// let start = Zerometry::header_size(&buffer).unwrap();
+    /// let bounding_box = &buffer[start..start + BOUNDING_BOX_SIZE_IN_BYTES];
+    /// assert_eq!(f64::from_ne_bytes(bounding_box[16..24].try_into().unwrap()), 1.0);
+    /// ```
+    pub fn header_size(data: &[u8]) -> Result<usize, ZerometryError> {
+        let header = Header::parse(data)?;
+        let trailer = header.checksum.map_or(0, |_| CHECKSUM_SIZE_IN_BYTES);
+        Ok(data.len() - header.body.len() - trailer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString, Point};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{AlignedBytes, BOUNDING_BOX_SIZE_IN_BYTES, WriteOptions};
+
+    #[test]
+    fn format_version() {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(1.0, 2.0)))
+            .unwrap();
+        assert_eq!(
+            Zerometry::read_format_version(&buffer).unwrap(),
+            FORMAT_VERSION
+        );
+
+        // A zerometry written by a future version of the crate
+        let tag = u64::from_ne_bytes(buffer[..8].try_into().unwrap());
+        let future = tag | (FORMAT_VERSION as u64 + 1) << VERSION_SHIFT;
+        buffer[..8].copy_from_slice(&future.to_ne_bytes());
+        let err = unsafe { Zerometry::from_bytes(&buffer).unwrap_err() };
+        assert_compact_debug_snapshot!(err, @"UnsupportedVersion(3)");
+        let err = Zerometry::read_format_version(&buffer).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"UnsupportedVersion(3)");
+    }
+
+    #[test]
+    fn legacy_versions() {
+        // The zerometries written before the version 2 are still read, with their empty bounding box made of zeros
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::LineString(LineString::new(vec![])))
+            .unwrap();
+        buffer[8..40].fill(0);
+        for version in [LEGACY_NATIVE_VERSION, LEGACY_LITTLE_ENDIAN_VERSION] {
+            let tag = u64::from_ne_bytes(buffer[..8].try_into().unwrap());
+            buffer[..8].copy_from_slice(&with_version(tag, version).to_ne_bytes());
+            assert_eq!(Zerometry::read_format_version(&buffer).unwrap(), version);
+            let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+            assert!(!zerometry.to_line().unwrap().bounding_box().is_empty());
+        }
+
+        // The little-endian flag didn't exist before the version 2
+        let tag = u64::from_ne_bytes(buffer[..8].try_into().unwrap());
+        assert!(!is_known_tag(
+            with_version(tag, LEGACY_NATIVE_VERSION) | LITTLE_ENDIAN_FLAG
+        ));
+        assert!(is_known_tag(
+            with_version(tag, FORMAT_VERSION) | LITTLE_ENDIAN_FLAG
+        ));
+    }
+
+    #[test]
+    fn little_endian_version() {
+        let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 2.0)]));
+        let options = WriteOptions {
+            generation: Some(7),
+            little_endian: true,
+            ..WriteOptions::default()
+        };
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &line, options).unwrap();
+        assert_compact_debug_snapshot!(buffer[..16], @"[4, 0, 0, 0, 129, 0, 0, 2, 7, 0, 0, 0, 0, 0, 0, 0]");
+        assert_eq!(
+            Zerometry::read_format_version(&AlignedBytes::new(&buffer)).unwrap(),
+            FORMAT_VERSION
+        );
+        let aligned = AlignedBytes::new(&buffer);
+        let zerometry = Zerometry::try_from_bytes(&aligned).unwrap();
+        assert_eq!(zerometry.to_geo(), line);
+        assert_eq!(Zerometry::decode_unaligned_bytes(&aligned).unwrap(), line);
+
+        // On little-endian platforms only the flag differs from the native encoding
+        let mut native = Vec::new();
+        let options = WriteOptions {
+            little_endian: false,
+            ..options
+        };
+        Zerometry::write_from_geometry_with_options(&mut native, &line, options).unwrap();
+        if cfg!(target_endian = "little") {
+            assert_eq!(buffer[8..], native[8..]);
+        }
+    }
+
+    #[test]
+    fn swapped_little_endian_tags() {
+        // What a big-endian platform reads natively is the little-endian tag with its bytes swapped
+        let point_len = TAG_SIZE_IN_BYTES + COORD_SIZE_IN_BYTES;
+        let shape_len = TAG_SIZE_IN_BYTES + BOUNDING_BOX_SIZE_IN_BYTES + 2 * COORD_SIZE_IN_BYTES;
+        for kind in POINT_TAG..=STRUCTURED_COLLECTION_TAG {
+            for flags in [
+                0,
+                GENERATION_FLAG,
+                IDS_FLAG | CHECKSUM_FLAG,
+                1 << SUBTYPE_SHIFT,
+            ] {
+                let len = if kind == POINT_TAG {
+                    point_len
+                } else {
+                    shape_len
+                };
+                let little_endian = tag(kind | flags | LITTLE_ENDIAN_FLAG);
+                assert!(
+                    is_swapped_little_endian(little_endian.swap_bytes(), len),
+                    "{little_endian:#018x}"
+                );
+                let legacy = with_version(kind | flags, LEGACY_LITTLE_ENDIAN_VERSION);
+                assert_eq!(
+                    is_swapped_little_endian(legacy.swap_bytes(), len),
+                    legacy != with_version(POLYGON_TAG, LEGACY_LITTLE_ENDIAN_VERSION),
+                    "{legacy:#018x}"
+                );
+                for native in [
+                    tag(kind | flags),
+                    with_version(kind | flags, LEGACY_NATIVE_VERSION),
+                ] {
+                    assert!(
+                        !is_swapped_little_endian(native, shape_len),
+                        "{native:#018x}"
+                    );
+                }
+            }
+        }
+
+        // The little-endian multi points without flags of the version 1 is a palindrome
+        let palindrome = with_version(MULTI_POINTS_TAG, LEGACY_LITTLE_ENDIAN_VERSION);
+        assert_eq!(palindrome, palindrome.swap_bytes());
+        assert!(is_known_tag(palindrome));
+        assert!(is_swapped_little_endian(palindrome, shape_len));
+
+        // A little-endian point with a flag is read as an unknown kind
+        let point =
+            with_version(POINT_TAG | GENERATION_FLAG, LEGACY_LITTLE_ENDIAN_VERSION).swap_bytes();
+        assert_eq!(point & KIND_MASK, 0x0100_0001);
+        assert!(!is_known_tag(point));
+        assert!(is_swapped_little_endian(point, shape_len));
+
+        // Without any flag it can only be distinguished from a native multi points by its size
+        let point = with_version(POINT_TAG, LEGACY_LITTLE_ENDIAN_VERSION).swap_bytes();
+        assert_eq!(point, with_version(MULTI_POINTS_TAG, LEGACY_NATIVE_VERSION));
+        assert!(is_swapped_little_endian(point, point_len));
+        assert!(!is_swapped_little_endian(point, shape_len));
+
+        // And the polygon can't be distinguished at all, it's read as a native multi points
+        let polygon = with_version(POLYGON_TAG, LEGACY_LITTLE_ENDIAN_VERSION).swap_bytes();
+        assert_eq!(polygon, tag(MULTI_POINTS_TAG));
+        assert!(!is_swapped_little_endian(polygon, shape_len));
+    }
+
+    #[test]
+    fn header_size() {
+        let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 2.0)]));
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &line).unwrap();
+        assert_eq!(Zerometry::header_size(&buffer).unwrap(), TAG_SIZE_IN_BYTES);
+
+        // The generation is part of the header but not the checksum stored after the shape
+        let options = WriteOptions {
+            generation: Some(1),
+            checksum: true,
+            ..WriteOptions::default()
+        };
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &line, options).unwrap();
+        let size = Zerometry::header_size(&buffer).unwrap();
+        assert_eq!(size, TAG_SIZE_IN_BYTES + 8);
+        let tag = u64::from_ne_bytes(buffer[..TAG_SIZE_IN_BYTES].try_into().unwrap());
+        assert_eq!(tag & KIND_MASK, LINE_TAG);
+        assert_eq!(
+            buffer.len() - size - CHECKSUM_SIZE_IN_BYTES,
+            BOUNDING_BOX_SIZE_IN_BYTES + 2 * COORD_SIZE_IN_BYTES
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::borrow::Cow;
+
+use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+
+use crate::ZerometryBuf;
+
+/// A codec to store the zerometries in a heed database as a [`ZerometryBuf`].
+///
+/// The values of LMDB are not aligned on 64 bits, they're copied in the aligned buffer of the
+/// [`ZerometryBuf`] and validated when they're decoded. Encoding doesn't copy anything.
+///
+/// ```
+/// use heed_traits::{BytesDecode, BytesEncode};
+/// use zerometry::{ZerometryBuf, ZerometryCodec};
+/// use geo_types::{Geometry, Point};
+///
+/// let point = Geometry::Point(Point::new(2.35, 48.85));
+/// let shape = ZerometryBuf::from(&point);
+/// let bytes = ZerometryCodec::bytes_encode(&shape).unwrap();
+/// let decoded = ZerometryCodec::bytes_decode(&bytes).unwrap();
+/// assert_eq!(decoded.as_zerometry().to_geo(), point);
+/// ```
+pub enum ZerometryCodec {}
+
+impl<'a> BytesEncode<'a> for ZerometryCodec {
+    type EItem = ZerometryBuf;
+
+    fn bytes_encode(item: &'a ZerometryBuf) -> Result<Cow<'a, [u8]>, BoxedError> {
+        Ok(Cow::Borrowed(item.as_bytes()))
+    }
+}
+
+impl<'a> BytesDecode<'a> for ZerometryCodec {
+    type DItem = ZerometryBuf;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<ZerometryBuf, BoxedError> {
+        Ok(ZerometryBuf::try_from_bytes(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn unaligned_values() {
+        let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]));
+        let shape = ZerometryBuf::from(&line);
+        let bytes = ZerometryCodec::bytes_encode(&shape).unwrap();
+
+        // Shift the value by one byte to lose the alignment
+        let mut unaligned = vec![0];
+        unaligned.extend_from_slice(&bytes);
+        let decoded = ZerometryCodec::bytes_decode(&unaligned[1..]).unwrap();
+        assert_eq!(decoded, shape);
+
+        // The last coordinate is truncated
+        let error = ZerometryCodec::bytes_decode(&unaligned[1..bytes.len() - 7]).unwrap_err();
+        assert_compact_debug_snapshot!(error.to_string(), @r#""Found 8 unexpected bytes after the end of the shape""#);
+    }
+}
@@ -0,0 +1,215 @@
+use std::{borrow::Cow, mem};
+
+use geo_types::{Geometry, GeometryCollection, MultiLineString, MultiPoint, MultiPolygon};
+
+use crate::{Zerometry, ZerometryError, header::IDS_FLAG, zollection::flatten_geometry_collection};
+
+impl<'a> Zerometry<'a> {
+    /// Same as [`Self::write_from_geometry`] but an id is attached to every member of the geometry,
+    /// they can be retrieved with [`crate::ZultiLines::get_with_id`] and [`crate::ZultiPolygons::get_with_id`]
+    /// to trace a match back to the original feature.
+    ///
+    /// - For a [`geo_types::MultiLineString`] or a [`geo_types::MultiPolygon`] there must be one id per member.
+    /// - For a [`geo_types::GeometryCollection`] there must be one id per geometry of the collection, and
+    ///   every line and polygon it's flattened to gets its id. The points don't keep any id.
+    ///
+    /// ```
+    /// use zerometry::{InputRelation, Zerometry};
+    /// use geo_types::{Geometry, MultiPolygon, Point, polygon};
+    ///
+    /// let districts = Geometry::MultiPolygon(MultiPolygon::new(vec![
+    ///     polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)],
+    ///     polygon![(x: 1., y: 0.), (x: 2., y: 0.), (x: 2., y: 1.), (x: 1., y: 1.)],
+    /// ]));
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry_with_ids(&mut buffer, &districts, &[75001, 75002]).unwrap();
+    /// let Zerometry::MultiPolygon(districts) = (unsafe { Zerometry::from_bytes(&buffer).unwrap() }) else { unreachable!() };
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(1.5, 0.5))).unwrap();
+    /// let point = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+    /// assert_eq!(districts.matching_ids(&point, InputRelation::any()), [75002]);
+    /// ```
+    pub fn write_from_geometry_with_ids(
+        writer: &mut Vec<u8>,
+        geometry: &Geometry<f64>,
+        ids: &[u64],
+    ) -> Result<(), ZerometryError> {
+        let (geometry, line_ids, polygon_ids) = match geometry {
+            Geometry::MultiLineString(multi_line_string) => {
+                check_ids_count(multi_line_string.0.len(), ids)?;
+                (Cow::Borrowed(geometry), ids.to_vec(), Vec::new())
+            }
+            Geometry::MultiPolygon(multi_polygon) => {
+                check_ids_count(multi_polygon.0.len(), ids)?;
+                (Cow::Borrowed(geometry), Vec::new(), ids.to_vec())
+            }
+            Geometry::GeometryCollection(collection) => {
+                check_ids_count(collection.0.len(), ids)?;
+                // Each geometry is flattened separately to know which lines and polygons it produces
+                let mut points = MultiPoint::new(Vec::new());
+                let mut lines = MultiLineString::new(Vec::new());
+                let mut polygons = MultiPolygon::new(Vec::new());
+                let (mut line_ids, mut polygon_ids) = (Vec::new(), Vec::new());
+                for (geometry, id) in collection.iter().zip(ids) {
+                    let (p, l, pl) =
+                        flatten_geometry_collection(&GeometryCollection::new_from(vec![
+                            geometry.clone(),
+                        ]));
+                    line_ids.extend(std::iter::repeat_n(*id, l.0.len()));
+                    polygon_ids.extend(std::iter::repeat_n(*id, pl.0.len()));
+                    points.0.extend(p.0);
+                    lines.0.extend(l.0);
+                    polygons.0.extend(pl.0);
+                }
+                let geometry = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                    points.into(),
+                    lines.into(),
+                    polygons.into(),
+                ]));
+                (Cow::Owned(geometry), line_ids, polygon_ids)
+            }
+            _ => {
+                return Err(ZerometryError::UnsupportedIds);
+            }
+        };
+
+        let start = writer.len();
+        Zerometry::write_from_geometry(writer, &geometry)?;
+
+        let tag_end = start + mem::size_of::<u64>();
+        let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+        writer[start..tag_end].copy_from_slice(&(tag | IDS_FLAG).to_ne_bytes());
+        let header = [line_ids.len() as u64, polygon_ids.len() as u64]
+            .into_iter()
+            .chain(line_ids)
+            .chain(polygon_ids)
+            .flat_map(u64::to_ne_bytes);
+        writer.splice(tag_end..tag_end, header);
+        Ok(())
+    }
+
+    /// Attach the ids read from the header to the lines and polygons of the zerometry.
+    pub(crate) fn attach_ids(
+        self,
+        line_ids: &'a [u64],
+        polygon_ids: &'a [u64],
+    ) -> Result<Self, ZerometryError> {
+        let (lines, polygons) = match self {
+            Zerometry::MultiLines(lines) => (lines.len(), 0),
+            Zerometry::MultiPolygon(polygons) => (0, polygons.len()),
+            Zerometry::Collection(collection) => {
+                (collection.lines().len(), collection.polygons().len())
+            }
+            _ => (0, 0),
+        };
+        if (line_ids.len(), polygon_ids.len()) != (lines, polygons) {
+            return Err(ZerometryError::InvalidIds {
+                line_ids: line_ids.len(),
+                polygon_ids: polygon_ids.len(),
+                lines,
+                polygons,
+            });
+        }
+        Ok(match self {
+            Zerometry::MultiLines(lines) => Zerometry::MultiLines(lines.with_ids(line_ids)),
+            Zerometry::MultiPolygon(polygons) => {
+                Zerometry::MultiPolygon(polygons.with_ids(polygon_ids))
+            }
+            Zerometry::Collection(collection) => {
+                Zerometry::Collection(collection.with_ids(line_ids, polygon_ids))
+            }
+            zerometry => zerometry,
+        })
+    }
+}
+
+fn check_ids_count(members: usize, ids: &[u64]) -> Result<(), ZerometryError> {
+    if members != ids.len() {
+        return Err(ZerometryError::InvalidIdsCount {
+            members,
+            ids: ids.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::InputRelation;
+
+    #[test]
+    fn ids_of_a_collection() {
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+                polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            ])),
+            Point::new(5.0, 5.0).into(),
+            MultiLineString::new(vec![
+                LineString::from(vec![(2.0, 2.0), (3.0, 3.0)]),
+                LineString::from(vec![(4.0, 4.0), (5.0, 5.0)]),
+            ])
+            .into(),
+        ]));
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_ids(&mut buffer, &collection, &[10, 20, 30]).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        let Zerometry::Collection(zollection) = zerometry else {
+            panic!("{zerometry:?}")
+        };
+        assert_compact_debug_snapshot!(zollection.lines().ids(), @"Some([10, 30, 30])");
+        assert_compact_debug_snapshot!(zollection.polygons().ids(), @"Some([10])");
+        assert_compact_debug_snapshot!(zollection.points().len(), @"1");
+        let (line, id) = zollection.lines().get_with_id(2).unwrap();
+        assert_compact_debug_snapshot!((line.to_geo(), id), @"(LINESTRING(4.0 4.0,5.0 5.0), Some(30))");
+
+        let mut buffer = Vec::new();
+        let polygon = polygon![(x: 3.5, y: 0.), (x: 6., y: 0.), (x: 6., y: 6.), (x: 3.5, y: 6.)];
+        Zerometry::write_from_geometry(&mut buffer, &polygon.into()).unwrap();
+        let polygon = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert_compact_debug_snapshot!(zollection.lines().matching_ids(&polygon, InputRelation::all()), @"[30]");
+    }
+
+    #[test]
+    fn ids_are_optional() {
+        let multi_polygon = MultiPolygon::new(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+        ]);
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &multi_polygon.clone().into()).unwrap();
+        let Zerometry::MultiPolygon(polygons) =
+            (unsafe { Zerometry::from_bytes(&buffer).unwrap() })
+        else {
+            unreachable!()
+        };
+        assert_compact_debug_snapshot!(polygons.get_with_id(0).map(|(_, id)| id), @"Some(None)");
+
+        buffer.clear();
+        Zerometry::write_from_geometry_with_ids(&mut buffer, &multi_polygon.into(), &[42]).unwrap();
+        let Zerometry::MultiPolygon(polygons) =
+            (unsafe { Zerometry::from_bytes(&buffer).unwrap() })
+        else {
+            unreachable!()
+        };
+        assert_compact_debug_snapshot!(polygons.get_with_id(0).map(|(_, id)| id), @"Some(Some(42))");
+        assert_compact_debug_snapshot!(polygons.id(1), @"None");
+    }
+
+    #[test]
+    fn invalid_ids() {
+        let point = Geometry::Point(Point::new(0.0, 0.0));
+        let err =
+            Zerometry::write_from_geometry_with_ids(&mut Vec::new(), &point, &[1]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"UnsupportedIds");
+
+        let lines = Geometry::MultiLineString(MultiLineString::new(Vec::new()));
+        let err =
+            Zerometry::write_from_geometry_with_ids(&mut Vec::new(), &lines, &[1]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidIdsCount { members: 0, ids: 1 }");
+    }
+}
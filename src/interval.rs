@@ -0,0 +1,131 @@
+/// A closed range of floats `[start, end]`, used for the longitude and latitude ranges of a [`crate::BoundingBox`].
+///
+/// Both bounds are included: two intervals sharing only a bound overlap and touch.
+///
+/// ```
+/// use zerometry::Interval;
+///
+/// let a = Interval::new(0.0, 10.0);
+/// let b = Interval::new(10.0, 20.0);
+/// assert!(a.overlaps(&b));
+/// assert!(a.touches(&b));
+/// assert_eq!(a.gap(&Interval::new(12.0, 15.0)), 2.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    start: f64,
+    end: f64,
+}
+
+impl Interval {
+    /// Create the interval going from `start` to `end`, both included.
+    ///
+    /// # Panics
+    /// In debug mode, if `start` is greater than `end`.
+    #[inline]
+    pub fn new(start: f64, end: f64) -> Self {
+        debug_assert!(
+            start <= end,
+            "The start of an interval ({start}) must be lower than its end ({end})"
+        );
+        Self { start, end }
+    }
+
+    /// Return the lower bound.
+    #[inline]
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Return the upper bound.
+    #[inline]
+    pub fn end(&self) -> f64 {
+        self.end
+    }
+
+    /// Return the distance between the bounds.
+    #[inline]
+    pub fn length(&self) -> f64 {
+        self.end - self.start
+    }
+
+    /// Return `true` if the interval is reduced to a single value.
+    #[inline]
+    pub fn is_degenerate(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Return `true` if the value is between the bounds, included.
+    #[inline]
+    pub fn contains_value(&self, value: f64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Return `true` if every value of `other` is also in `self`.
+    #[inline]
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Return `true` if the intervals share at least one value, which includes the intervals sharing only a bound.
+    #[inline]
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Return `true` if the intervals share exactly one of their bounds: the end of one is the start of the other.
+    #[inline]
+    pub fn touches(&self, other: &Interval) -> bool {
+        self.end == other.start || other.end == self.start
+    }
+
+    /// Return the distance between the intervals, `0.0` if they overlap.
+    #[inline]
+    pub fn gap(&self, other: &Interval) -> f64 {
+        (other.start - self.end)
+            .max(self.start - other.end)
+            .max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(0.0, 10.0);
+        assert_eq!(a.length(), 10.0);
+        assert!(!a.is_degenerate());
+        assert!(a.contains_value(0.0));
+        assert!(a.contains_value(10.0));
+        assert!(!a.contains_value(10.5));
+
+        let inner = Interval::new(2.0, 3.0);
+        assert!(a.contains(&inner));
+        assert!(!inner.contains(&a));
+        assert!(a.contains(&a));
+        assert!(a.overlaps(&inner));
+        assert!(!a.touches(&inner));
+        assert_eq!(a.gap(&inner), 0.0);
+
+        let next = Interval::new(10.0, 20.0);
+        assert!(a.overlaps(&next));
+        assert!(a.touches(&next));
+        assert!(next.touches(&a));
+        assert_eq!(a.gap(&next), 0.0);
+
+        let far = Interval::new(12.0, 15.0);
+        assert!(!a.overlaps(&far));
+        assert!(!a.touches(&far));
+        assert_eq!(a.gap(&far), 2.0);
+        assert_eq!(far.gap(&a), 2.0);
+
+        let point = Interval::new(5.0, 5.0);
+        assert!(point.is_degenerate());
+        assert!(a.contains(&point));
+        assert!(point.overlaps(&a));
+        assert!(point.touches(&Interval::new(-1.0, 5.0)));
+        assert!(point.touches(&point));
+    }
+}
@@ -0,0 +1,387 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use geo_types::Coord;
+
+use crate::{Zerometry, Zine, Zoint, Zollection, Zolygon, ZultiLines, ZultiPoints, ZultiPolygons};
+
+/// The label point is searched until it can't be improved by more than this fraction of the largest side of
+/// the bounding box of the polygon.
+const RELATIVE_PRECISION: f64 = 1e-4;
+
+impl Zolygon<'_> {
+    /// Return the point of the polygon that is the farthest from its boundary, also called the pole of inaccessibility,
+    /// which is the best place to put a label or a pin.
+    /// Contrary to the centroid, it's always inside the polygon, even when the polygon is concave.
+    ///
+    /// It's computed with the polylabel algorithm, to a precision of a ten-thousandth of the size of the polygon.
+    /// Return `None` if the polygon is empty, and one of its coords if it's flat.
+    ///
+    /// ```
+    /// use zerometry::Zolygon;
+    /// use geo_types::polygon;
+    ///
+    /// // A U shape, its centroid falls in the gap between the two branches
+    /// let u = polygon![
+    ///     (x: 0., y: 0.), (x: 3., y: 0.), (x: 3., y: 3.), (x: 2., y: 3.),
+    ///     (x: 2., y: 1.), (x: 1., y: 1.), (x: 1., y: 3.), (x: 0., y: 3.),
+    /// ];
+    /// let mut buffer = Vec::new();
+    /// Zolygon::write_from_geometry(&mut buffer, &u).unwrap();
+    /// let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
+    ///
+    /// let label = zolygon.label_point().unwrap();
+    /// assert!(label.y < 1.0 || label.x < 1.0 || label.x > 2.0);
+    /// ```
+    pub fn label_point(&self) -> Option<Coord<f64>> {
+        self.pole_of_inaccessibility().map(|cell| cell.center)
+    }
+
+    fn pole_of_inaccessibility(&self) -> Option<Cell> {
+        let first = self.coords().iter().next()?.to_geo();
+        let bounding_box = self.bounding_box();
+        let width = bounding_box.right() - bounding_box.left();
+        let height = bounding_box.top() - bounding_box.bottom();
+        let cell_size = width.min(height);
+        if !cell_size.is_finite() || cell_size <= 0.0 {
+            return Some(Cell::new(first, 0.0, self));
+        }
+        let precision = width.max(height) * RELATIVE_PRECISION;
+
+        // Cover the polygon with square cells
+        let half = cell_size / 2.0;
+        let mut cells = BinaryHeap::new();
+        let mut x = bounding_box.left();
+        while x < bounding_box.right() {
+            let mut y = bounding_box.bottom();
+            while y < bounding_box.top() {
+                cells.push(Cell::new(
+                    Coord {
+                        x: x + half,
+                        y: y + half,
+                    },
+                    half,
+                    self,
+                ));
+                y += cell_size;
+            }
+            x += cell_size;
+        }
+
+        let center = Coord {
+            x: bounding_box.left() + width / 2.0,
+            y: bounding_box.bottom() + height / 2.0,
+        };
+        let mut best = [self.centroid().unwrap_or(first), center, first]
+            .into_iter()
+            .map(|coord| Cell::new(coord, 0.0, self))
+            .max_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap();
+
+        // Only split the cells that may contain a better point than the best one found so far
+        while let Some(cell) = cells.pop() {
+            if cell.distance > best.distance {
+                best = cell;
+            }
+            if cell.max_distance - best.distance <= precision {
+                continue;
+            }
+            let half = cell.half_size / 2.0;
+            for (dx, dy) in [(-half, -half), (half, -half), (-half, half), (half, half)] {
+                let center = Coord {
+                    x: cell.center.x + dx,
+                    y: cell.center.y + dy,
+                };
+                cells.push(Cell::new(center, half, self));
+            }
+        }
+        Some(best)
+    }
+
+    /// The centroid of the area of the polygon, or `None` if it's flat.
+    fn centroid(&self) -> Option<Coord<f64>> {
+        let (mut area, mut x, mut y) = (0.0, 0.0, 0.0);
+        for segment in self.segments() {
+            let (a, b) = (segment.start(), segment.end());
+            let cross = a.lng() * b.lat() - b.lng() * a.lat();
+            area += cross;
+            x += (a.lng() + b.lng()) * cross;
+            y += (a.lat() + b.lat()) * cross;
+        }
+        (area != 0.0).then(|| Coord {
+            x: x / (3.0 * area),
+            y: y / (3.0 * area),
+        })
+    }
+
+    /// Return the distance between the coord and the boundary of the polygon, negative if it's outside of the polygon.
+    fn signed_distance(&self, coord: Coord<f64>) -> f64 {
+        let mut inside = false;
+        let mut squared_distance = f64::INFINITY;
+        for segment in self.segments() {
+            let (a, b) = (segment.start().to_geo(), segment.end().to_geo());
+            if (a.y > coord.y) != (b.y > coord.y)
+                && coord.x < (b.x - a.x) * (coord.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+            squared_distance = squared_distance.min(squared_distance_to_segment(coord, a, b));
+        }
+        let distance = squared_distance.sqrt();
+        if inside { distance } else { -distance }
+    }
+}
+
+impl ZultiPolygons<'_> {
+    /// Return the point of the multi polygons that is the farthest from its boundary, see [`Zolygon::label_point`].
+    /// It's the label point of the polygon that can hold the largest circle.
+    /// Return `None` if there is no polygon.
+    pub fn label_point(&self) -> Option<Coord<f64>> {
+        self.polygons()
+            .filter_map(|polygon| polygon.pole_of_inaccessibility())
+            .max_by(|a, b| a.distance.total_cmp(&b.distance))
+            .map(|cell| cell.center)
+    }
+}
+
+impl Zerometry<'_> {
+    /// Return a single coordinate standing for the whole shape, for example to sort or facet the documents by
+    /// their position. The shape always contains the point:
+    /// - The first point of the points and lines.
+    /// - The label point of the polygons, see [`Zolygon::label_point`], which is inside of them.
+    /// - The representative point of the polygons of a collection if it contains any, then of its lines and finally
+    ///   of its points.
+    ///
+    /// Return `None` if the shape is empty.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, LineString, coord};
+    ///
+    /// let line = Geometry::LineString(LineString::from(vec![(1.0, 2.0), (3.0, 4.0)]));
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &line).unwrap();
+    /// let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+    /// assert_eq!(zerometry.representative_point(), Some(coord! { x: 1.0, y: 2.0 }));
+    /// ```
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        match self {
+            Zerometry::Point(zoint) => zoint.representative_point(),
+            Zerometry::MultiPoints(zulti_points) => zulti_points.representative_point(),
+            Zerometry::Line(zine) => zine.representative_point(),
+            Zerometry::MultiLines(zulti_lines) => zulti_lines.representative_point(),
+            Zerometry::Polygon(zolygon) => zolygon.representative_point(),
+            Zerometry::MultiPolygon(zulti_polygons) => zulti_polygons.representative_point(),
+            Zerometry::Collection(zollection) => zollection.representative_point(),
+        }
+    }
+}
+
+impl Zoint<'_> {
+    /// Return the coordinate of the point, see [`Zerometry::representative_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        Some(self.coord().to_geo())
+    }
+}
+
+impl ZultiPoints<'_> {
+    /// Return the first point, in the original order if the points are sorted, see [`Zerometry::representative_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        self.points_in_original_order()
+            .next()
+            .map(|zoint| zoint.coord().to_geo())
+    }
+}
+
+impl Zine<'_> {
+    /// Return the first point of the line, see [`Zerometry::representative_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        self.coords().iter().next().map(|coord| coord.to_geo())
+    }
+}
+
+impl ZultiLines<'_> {
+    /// Return the first point of the first non-empty line, see [`Zerometry::representative_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        (0..self.len()).find_map(|index| self.get(index)?.representative_point())
+    }
+}
+
+impl Zolygon<'_> {
+    /// Return the label point of the polygon, see [`Zerometry::representative_point`] and [`Self::label_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        self.label_point()
+    }
+}
+
+impl ZultiPolygons<'_> {
+    /// Return the label point of the multi polygons, see [`Zerometry::representative_point`] and [`Self::label_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        self.label_point()
+    }
+}
+
+impl Zollection<'_> {
+    /// Return the representative point of the polygons, lines or points of the collection, in this order of preference,
+    /// see [`Zerometry::representative_point`].
+    pub fn representative_point(&self) -> Option<Coord<f64>> {
+        self.polygons()
+            .representative_point()
+            .or_else(|| self.lines().representative_point())
+            .or_else(|| self.points().representative_point())
+    }
+}
+
+fn squared_distance_to_segment(coord: Coord<f64>, a: Coord<f64>, b: Coord<f64>) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((coord.x - a.x) * dx + (coord.y - a.y) * dy) / length).clamp(0.0, 1.0)
+    };
+    let (x, y) = (a.x + t * dx - coord.x, a.y + t * dy - coord.y);
+    x * x + y * y
+}
+
+/// A square cell explored by the polylabel algorithm.
+#[derive(Clone, Copy)]
+struct Cell {
+    center: Coord<f64>,
+    half_size: f64,
+    /// The distance between the center and the boundary of the polygon
+    distance: f64,
+    /// The maximum distance a point of the cell can have to the boundary of the polygon
+    max_distance: f64,
+}
+
+impl Cell {
+    fn new(center: Coord<f64>, half_size: f64, polygon: &Zolygon) -> Self {
+        let distance = polygon.signed_distance(center);
+        Self {
+            center,
+            half_size,
+            distance,
+            max_distance: distance + half_size * std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+// The cells are explored from the most promising one
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max_distance.total_cmp(&other.max_distance)
+    }
+}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cell {}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Contains, Intersects};
+    use geo_types::{
+        Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+        polygon,
+    };
+    use insta::assert_compact_debug_snapshot;
+
+    use crate::{WriteOptions, Zerometry, Zolygon, ZultiPolygons};
+
+    #[test]
+    fn label_point() {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, &square).unwrap();
+        let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(zolygon.label_point(), @"Some(COORD(2.0 2.0))");
+
+        // A C shape whose centroid is outside
+        let c = polygon![
+            (x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 2.), (x: 2., y: 2.),
+            (x: 2., y: 8.), (x: 10., y: 8.), (x: 10., y: 10.), (x: 0., y: 10.),
+        ];
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, &c).unwrap();
+        let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
+        let label = zolygon.label_point().unwrap();
+        assert_compact_debug_snapshot!(label, @"COORD(1.1712646484375 8.8287353515625)");
+        assert!(zolygon.to_geo().contains(&label));
+
+        let flat = polygon![(x: 0., y: 0.), (x: 1., y: 1.), (x: 2., y: 2.)];
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, &flat).unwrap();
+        let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(zolygon.label_point(), @"Some(COORD(0.0 0.0))");
+
+        let mut buffer = Vec::new();
+        ZultiPolygons::write_from_geometry(&mut buffer, &MultiPolygon::new(vec![c, square]))
+            .unwrap();
+        let zulti_polygons = unsafe { ZultiPolygons::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(zulti_polygons.label_point(), @"Some(COORD(2.0 2.0))");
+        let mut buffer = Vec::new();
+        ZultiPolygons::write_from_geometry(&mut buffer, &MultiPolygon::new(Vec::new())).unwrap();
+        let zulti_polygons = unsafe { ZultiPolygons::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(zulti_polygons.label_point(), @"None");
+    }
+
+    #[test]
+    fn representative_point() {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let line = LineString::from(vec![(5.0, 5.0), (6.0, 6.0)]);
+        let points = MultiPoint::from(vec![(3.0, 1.0), (1.0, 2.0)]);
+        let options = WriteOptions {
+            sort_points: true,
+            ..WriteOptions::default()
+        };
+        let shapes: Vec<(Geometry<f64>, WriteOptions)> = vec![
+            (Point::new(1.0, 2.0).into(), WriteOptions::default()),
+            (points.clone().into(), options),
+            (MultiPoint::<f64>::new(Vec::new()).into(), options),
+            (line.clone().into(), options),
+            (
+                MultiLineString::new(vec![LineString::new(Vec::new()), line.clone()]).into(),
+                options,
+            ),
+            (square.clone().into(), options),
+            (MultiPolygon::new(vec![square.clone()]).into(), options),
+            (
+                Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                    points.clone().into(),
+                    line.clone().into(),
+                ])),
+                options,
+            ),
+            (
+                Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                    points.into(),
+                    line.into(),
+                    square.into(),
+                ])),
+                options,
+            ),
+        ];
+        let mut representative_points = Vec::new();
+        for (shape, options) in shapes {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, &shape, options).unwrap();
+            let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+            let point = zerometry.representative_point();
+            if let Some(point) = point {
+                assert!(shape.intersects(&point), "{shape:?} {point:?}");
+            }
+            representative_points.push(point);
+        }
+        assert_compact_debug_snapshot!(representative_points, @"[Some(COORD(1.0 2.0)), Some(COORD(3.0 1.0)), None, Some(COORD(5.0 5.0)), Some(COORD(5.0 5.0)), Some(COORD(2.0 2.0)), Some(COORD(2.0 2.0)), Some(COORD(5.0 5.0)), Some(COORD(2.0 2.0))]");
+    }
+}
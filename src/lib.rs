@@ -1,35 +1,140 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
+#[cfg(all(unix, feature = "madvise"))]
+mod advise;
+#[cfg(feature = "geo-algorithms")]
+mod algorithms;
+mod aligned_bytes;
+mod axis;
 mod bounding_box;
+mod buffer_pool;
+mod changed_region;
+mod checksum;
+mod complement;
+mod complexity;
 mod coord;
 mod coords;
+mod coverage_mask;
+mod crossing;
+mod distance;
+mod elevation;
+mod empty;
+mod endianness;
+mod error;
+mod explode;
+mod format;
+mod framing;
+#[cfg(feature = "geojson")]
+mod geojson_geometry;
+#[cfg(feature = "geo-traits")]
+mod geometry_traits;
+#[cfg(feature = "geopackage")]
+mod geopackage;
+#[cfg(feature = "georss")]
+mod georss;
+mod groups;
+mod hash;
+mod header;
+#[cfg(feature = "heed")]
+mod heed_codec;
+mod ids;
+mod interval;
+mod label;
+mod macros;
+mod offset;
+mod properties;
+mod quantized;
 mod relation;
+mod rings;
 mod segment;
+#[cfg(feature = "serde")]
+mod serialization;
+mod simple;
+mod size;
+mod spatial_join;
+#[cfg(feature = "test-utils")]
+pub mod strategies;
+mod strategy;
+mod streaming;
+mod structured_zollection;
+mod subtype;
+mod task;
 #[cfg(test)]
 mod test;
+mod text;
+mod timed_zine;
+mod topology;
+mod unaligned;
+mod visibility;
+mod wkb;
+mod write_options;
+mod zerometry_buf;
+mod zerometry_set;
 mod zine;
 mod zoint;
+mod zoint32;
 mod zollection;
 mod zolygon;
+mod zolygon32;
+mod zolygon_mut;
 mod zulti_lines;
 mod zulti_points;
 mod zulti_polygons;
 
-use std::{io, mem};
+use error::check_aligned;
+use header::{Header, tag};
+use subtype::Subtype;
 
-pub use bounding_box::BoundingBox;
-pub use coord::Coord;
-pub(crate) use coord::{COORD_SIZE_IN_BYTES, COORD_SIZE_IN_FLOATS};
+pub use aligned_bytes::AlignedBytes;
+pub use axis::AxisOrder;
+pub use bounding_box::{BOUNDING_BOX_SIZE_IN_BYTES, BoundingBox};
+pub use buffer_pool::BufferPool;
+pub use changed_region::changed_region;
+pub use complement::Complement;
+pub use complexity::Complexity;
+pub(crate) use coord::COORD_SIZE_IN_FLOATS;
+pub use coord::{COORD_SIZE_IN_BYTES, Coord};
 pub use coords::Coords;
+pub use crossing::{Crossing, CrossingDirection};
+pub use distance::{DistanceBetweenShapes, distance_matrix};
+pub use error::ZerometryError;
 use geo::LineString;
 use geo_types::{Geometry, MultiPolygon, Polygon};
-pub use relation::{InputRelation, OutputRelation, RelationBetweenShapes};
+#[cfg(feature = "geopackage")]
+pub use geopackage::{GeoPackageReader, GeoPackageWriter};
+pub use header::{
+    COLLECTION_TAG, FORMAT_VERSION, LINE_TAG, MULTI_LINES_TAG, MULTI_POINTS_TAG, MULTI_POLYGON_TAG,
+    POINT_TAG, POLYGON_TAG, SORTED_MULTI_POINTS_TAG, STRUCTURED_COLLECTION_TAG, TAG_SIZE_IN_BYTES,
+};
+#[cfg(feature = "heed")]
+pub use heed_codec::ZerometryCodec;
+pub use interval::Interval;
+#[doc(hidden)]
+pub use macros::{__zolygon_from_words, __zolygon_words, __zolygon_words_len};
+pub use quantized::{DEFAULT_QUANTIZATION_STEP, QuantizedZine, QuantizedZolygon};
+pub use relation::{
+    Certainty, FieldsCombination, InputRelation, OutputRelation, RelationBetweenShapes,
+    RelationStats,
+};
 pub use segment::Segment;
+pub use spatial_join::spatial_join;
+pub use strategy::{BoundingBoxes, GeoRelate, Naive, RelationStrategy};
+pub use streaming::StreamedShape;
+pub use structured_zollection::{StructuredMember, StructuredZollection};
+pub use task::RelationTask;
+pub use timed_zine::TimedZine;
+pub use visibility::is_visible;
+pub use write_options::WriteOptions;
+pub use zerometry_buf::ZerometryBuf;
+pub use zerometry_set::ZerometrySet;
 pub use zine::Zine;
 pub use zoint::Zoint;
+pub use zoint32::Zoint32;
 pub use zollection::Zollection;
 pub use zolygon::Zolygon;
+pub use zolygon_mut::{BoundingBoxUpdate, CoordsMut, ZolygonMut};
+pub use zolygon32::Zolygon32;
 pub use zulti_lines::ZultiLines;
 pub use zulti_points::ZultiPoints;
 pub use zulti_polygons::ZultiPolygons;
@@ -58,97 +163,214 @@ impl<'a> Zerometry<'a> {
     /// See [`Self::write_from_geometry`] to create the slice of bytes.
     ///
     /// # Safety
-    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits,
+    /// see [`AlignedBytes`] if the alignment is not guaranteed.
     #[inline]
-    pub unsafe fn from_bytes(data: &'a [u8]) -> Result<Self, std::io::Error> {
-        if data.len() < mem::size_of::<u64>() {
-            return Err(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                format!(
-                    "Was expecting at least {} bytes but found {}",
-                    mem::size_of::<u64>(),
-                    data.len()
-                ),
-            ));
-        }
-        let tag = u64::from_ne_bytes(
-            data[..mem::size_of::<u64>()]
-                .try_into()
-                .map_err(io::Error::other)?,
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        debug_assert!(
+            check_aligned(data).is_ok(),
+            "The data must be aligned on 64 bits, use `AlignedBytes` or `Zerometry::from_bytes_copy_if_unaligned`"
         );
-        let data = &data[mem::size_of::<u64>()..];
-        match tag {
-            0 => Ok(Zerometry::Point(unsafe { Zoint::from_bytes(data) })),
-            1 => Ok(Zerometry::MultiPoints(unsafe {
-                ZultiPoints::from_bytes(data)
-            })),
-            2 => Ok(Zerometry::Polygon(unsafe { Zolygon::from_bytes(data) })),
-            3 => Ok(Zerometry::MultiPolygon(unsafe {
-                ZultiPolygons::from_bytes(data)
-            })),
-            // They're located after because it would be a db-breaking to edit the already existing tags
-            4 => Ok(Zerometry::Line(unsafe { Zine::from_bytes(data) })),
-            5 => Ok(Zerometry::MultiLines(unsafe {
-                ZultiLines::from_bytes(data)
-            })),
-            6 => Ok(Zerometry::Collection(unsafe {
-                Zollection::from_bytes(data)
-            })),
-            _ => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid zerometry tag",
-            )),
+        unsafe { Self::read(data, false) }
+    }
+
+    /// Create a `Zerometry` from a slice of bytes that may be invalid.
+    /// Contrary to [`Self::from_bytes`], the alignment, every count and every offset are checked
+    /// before being used, which makes it safe to call on corrupted or untrusted data.
+    ///
+    /// ```
+    /// use zerometry::{Zerometry, ZerometryError};
+    /// use geo_types::{Geometry, LineString};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]));
+    /// Zerometry::write_from_geometry(&mut buffer, &line).unwrap();
+    /// assert_eq!(Zerometry::try_from_bytes(&buffer).unwrap().to_geo(), line);
+    ///
+    /// let err = Zerometry::try_from_bytes(&buffer[..buffer.len() - 8]).unwrap_err();
+    /// assert!(matches!(err, ZerometryError::TrailingBytes(8)));
+    /// ```
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        // SAFETY: Everything is validated before being read
+        unsafe { Self::read(data, true) }
+    }
+
+    /// # Safety
+    /// If `validate` is false, the data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    unsafe fn read(data: &'a [u8], validate: bool) -> Result<Self, ZerometryError> {
+        let Header {
+            version,
+            kind,
+            ids,
+            groups,
+            coverage_mask,
+            elevations,
+            properties,
+            convex,
+            subtype,
+            checksum,
+            body,
+            ..
+        } = Header::parse(data)?;
+        if let Some(checksum) = checksum {
+            checksum::verify_checksum(data, checksum)?;
+        }
+        let zerometry = match version {
+            // The version 1 only differs by its endianness, it's only read as-is on little-endian platforms,
+            // and the version 2 by the bounding box of the empty shapes
+            0..=2 => unsafe { Self::from_body_v0(kind, body, validate) },
+            version => Err(ZerometryError::UnsupportedVersion(version)),
+        }?;
+        let zerometry = zerometry.attach_subtype(subtype)?;
+        let zerometry = match ids {
+            Some((line_ids, polygon_ids)) => zerometry.attach_ids(line_ids, polygon_ids)?,
+            None => zerometry,
+        };
+        let zerometry = match groups {
+            Some((line_groups, polygon_groups)) => {
+                zerometry.attach_groups(line_groups, polygon_groups)?
+            }
+            None => zerometry,
+        };
+        if let Some(elevations) = elevations {
+            zerometry.check_elevations(elevations)?;
         }
+        let zerometry = match properties {
+            Some(properties) => zerometry.attach_properties(properties)?,
+            None => zerometry,
+        };
+        let zerometry = match (zerometry, convex) {
+            (zerometry, None) => zerometry,
+            // Trusting a wrong convexity would make the relations wrong
+            (Zerometry::Polygon(zolygon), Some(convex))
+                if !validate || zolygon.compute_convexity() == convex =>
+            {
+                Zerometry::Polygon(zolygon.with_convexity(convex))
+            }
+            (_, Some(_)) => return Err(ZerometryError::InvalidConvexity),
+        };
+        match (zerometry, coverage_mask) {
+            (zerometry, None) => Ok(zerometry),
+            (Zerometry::Polygon(zolygon), Some(mask)) => {
+                Ok(Zerometry::Polygon(zolygon.with_coverage_mask(mask)))
+            }
+            (_, Some(_)) => Err(ZerometryError::UnexpectedCoverageMask),
+        }
+    }
+
+    /// Read the body of a zerometry following the layout of the version 0 of the format.
+    ///
+    /// # Safety
+    /// If `validate` is false, the data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    unsafe fn from_body_v0(
+        kind: u64,
+        data: &'a [u8],
+        validate: bool,
+    ) -> Result<Self, ZerometryError> {
+        Ok(match kind {
+            POINT_TAG if validate => Zerometry::Point(Zoint::try_from_bytes(data)?),
+            POINT_TAG => Zerometry::Point(unsafe { Zoint::from_bytes(data) }),
+            MULTI_POINTS_TAG if validate => {
+                Zerometry::MultiPoints(ZultiPoints::try_from_bytes(data)?)
+            }
+            MULTI_POINTS_TAG => Zerometry::MultiPoints(unsafe { ZultiPoints::from_bytes(data) }),
+            POLYGON_TAG if validate => Zerometry::Polygon(Zolygon::try_from_bytes(data)?),
+            POLYGON_TAG => Zerometry::Polygon(unsafe { Zolygon::from_bytes(data) }),
+            MULTI_POLYGON_TAG if validate => {
+                Zerometry::MultiPolygon(ZultiPolygons::try_from_bytes(data)?)
+            }
+            MULTI_POLYGON_TAG => {
+                Zerometry::MultiPolygon(unsafe { ZultiPolygons::from_bytes(data) })
+            }
+            // They're located after because it would be a db-breaking to edit the already existing tags
+            LINE_TAG if validate => Zerometry::Line(Zine::try_from_bytes(data)?),
+            LINE_TAG => Zerometry::Line(unsafe { Zine::from_bytes(data) }),
+            MULTI_LINES_TAG if validate => Zerometry::MultiLines(ZultiLines::try_from_bytes(data)?),
+            MULTI_LINES_TAG => Zerometry::MultiLines(unsafe { ZultiLines::from_bytes(data) }),
+            COLLECTION_TAG if validate => Zerometry::Collection(Zollection::try_from_bytes(data)?),
+            COLLECTION_TAG => Zerometry::Collection(unsafe { Zollection::from_bytes(data) }),
+            // The multi points sorted by Morton code, written with `WriteOptions::sort_points`
+            SORTED_MULTI_POINTS_TAG if validate => {
+                Zerometry::MultiPoints(ZultiPoints::try_from_sorted_bytes(data)?)
+            }
+            SORTED_MULTI_POINTS_TAG => {
+                Zerometry::MultiPoints(unsafe { ZultiPoints::from_sorted_bytes(data) })
+            }
+            kind => return Err(ZerometryError::InvalidTag(kind)),
+        })
     }
 
     /// Convert the specified [`geo_types::Geometry`] to a valid [`Zerometry`] slice of bytes in the input buffer.
     /// This is a destructive operation, the original geometry cannot be recreated as-is from the outputted zerometry:
-    /// - The Line, Triangle and Rectangle gets converted respectively to Zine and Zolygon
+    /// - The Line, Triangle and Rectangle gets converted respectively to Zine and Zolygon, but [`Self::to_geo`] still
+    ///   returns the original variant
     /// - The collections are flattened to a collection containing one multipoints, one multipolygons and one multilines.
+    ///
+    /// The coordinates are not validated, see [`crate::WriteOptions::check_lng_lat`] to check them first.
+    ///
+    /// Everything is written in a single pass, so the writer can be any [`std::io::Write`]. For example a slice of
+    /// pre-allocated memory of the size returned by [`Self::serialized_size`]:
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, MultiPolygon, polygon};
+    ///
+    /// let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+    /// let shape = Geometry::MultiPolygon(MultiPolygon::new(vec![square.clone(), square]));
+    /// let mut arena = vec![0_u64; Zerometry::serialized_size(&shape) / 8];
+    /// Zerometry::write_from_geometry(&mut bytemuck::cast_slice_mut(&mut arena), &shape).unwrap();
+    ///
+    /// let zerometry = unsafe { Zerometry::from_bytes(bytemuck::cast_slice(&arena)).unwrap() };
+    /// assert_eq!(zerometry.to_geo(), shape);
+    /// ```
     pub fn write_from_geometry(
-        writer: &mut Vec<u8>,
+        writer: &mut impl std::io::Write,
         geometry: &Geometry<f64>,
-    ) -> Result<(), std::io::Error> {
+    ) -> Result<(), ZerometryError> {
         // to stay aligned on 64 bits we must add the tag as a u64
         match geometry {
             Geometry::Point(point) => {
-                writer.extend_from_slice(&0_u64.to_ne_bytes());
+                writer.write_all(&tag(POINT_TAG).to_ne_bytes())?;
                 Zoint::write_from_geometry(writer, point)?;
             }
             Geometry::MultiPoint(multi_point) => {
-                writer.extend_from_slice(&1_u64.to_ne_bytes());
+                writer.write_all(&tag(MULTI_POINTS_TAG).to_ne_bytes())?;
                 ZultiPoints::write_from_geometry(writer, multi_point)?;
             }
             Geometry::Polygon(polygon) => {
-                writer.extend_from_slice(&2_u64.to_ne_bytes());
+                writer.write_all(&tag(POLYGON_TAG).to_ne_bytes())?;
                 Zolygon::write_from_geometry(writer, polygon)?;
             }
             Geometry::MultiPolygon(multi_polygon) => {
-                writer.extend_from_slice(&3_u64.to_ne_bytes());
+                writer.write_all(&tag(MULTI_POLYGON_TAG).to_ne_bytes())?;
                 ZultiPolygons::write_from_geometry(writer, multi_polygon)?;
             }
             Geometry::LineString(line_string) => {
-                writer.extend_from_slice(&4_u64.to_ne_bytes());
+                writer.write_all(&tag(LINE_TAG).to_ne_bytes())?;
                 Zine::write_from_geometry(writer, line_string)?;
             }
             Geometry::MultiLineString(multi_line_string) => {
-                writer.extend_from_slice(&5_u64.to_ne_bytes());
+                writer.write_all(&tag(MULTI_LINES_TAG).to_ne_bytes())?;
                 ZultiLines::write_from_geometry(writer, multi_line_string)?;
             }
             Geometry::GeometryCollection(collection) => {
-                writer.extend_from_slice(&6_u64.to_ne_bytes());
+                writer.write_all(&tag(COLLECTION_TAG).to_ne_bytes())?;
                 Zollection::write_from_geometry(writer, collection)?;
             }
-            // Should never happens since we're working with geogson in meilisearch
+            // They're stored as a line or a polygon, the subtype is only used to convert them back
             Geometry::Line(line) => {
-                let line = LineString::new(vec![line.start, line.end]);
-                Self::write_from_geometry(writer, &line.into())?;
+                writer.write_all(&(tag(LINE_TAG) | Subtype::Line.tag_bits()).to_ne_bytes())?;
+                Zine::write_from_geometry(writer, &LineString::new(vec![line.start, line.end]))?;
             }
             Geometry::Rect(rect) => {
-                Self::write_from_geometry(writer, &rect.to_polygon().into())?;
+                writer.write_all(&(tag(POLYGON_TAG) | Subtype::Rect.tag_bits()).to_ne_bytes())?;
+                Zolygon::write_from_geometry(writer, &rect.to_polygon())?;
             }
             Geometry::Triangle(triangle) => {
-                Self::write_from_geometry(writer, &triangle.to_polygon().into())?;
+                writer
+                    .write_all(&(tag(POLYGON_TAG) | Subtype::Triangle.tag_bits()).to_ne_bytes())?;
+                Zolygon::write_from_geometry(writer, &triangle.to_polygon())?;
             }
         }
         Ok(())
@@ -217,6 +439,96 @@ impl<'a> Zerometry<'a> {
         }
     }
 
+    /// Return an estimation of the work required to compute the relation with the other shape.
+    /// It's an upper bound computed from the number of points and segments of the shapes, without reading their
+    /// coordinates. See [`InputRelation::budget`].
+    pub fn relation_stats(&self, other: &Zerometry) -> RelationStats {
+        let (points, segments) = self.points_and_segments();
+        let (other_points, other_segments) = other.points_and_segments();
+        // Every segment may be tested against every segment, and every point may be tested against all the
+        // segments of the other shape to know if it's contained.
+        let segment_tests = segments
+            .saturating_mul(other_segments)
+            .saturating_add(points.saturating_mul(other_segments))
+            .saturating_add(other_points.saturating_mul(segments));
+
+        RelationStats {
+            parts: points + segments,
+            other_parts: other_points + other_segments,
+            segment_tests,
+        }
+    }
+
+    /// Return the number of points and segments the shape is made of.
+    fn points_and_segments(&self) -> (usize, usize) {
+        let segments = |coords: &Coords| coords.len().saturating_sub(1);
+        match self {
+            Zerometry::Point(_) => (1, 0),
+            Zerometry::MultiPoints(a) => (a.len(), 0),
+            Zerometry::Line(a) => (0, segments(a.coords())),
+            Zerometry::MultiLines(a) => (0, a.lines().map(|line| segments(line.coords())).sum()),
+            Zerometry::Polygon(a) => (0, segments(a.coords())),
+            Zerometry::MultiPolygon(a) => (
+                0,
+                a.polygons().map(|polygon| segments(polygon.coords())).sum(),
+            ),
+            Zerometry::Collection(a) => {
+                let (_, lines) = Zerometry::MultiLines(a.lines()).points_and_segments();
+                let (_, polygons) = Zerometry::MultiPolygon(a.polygons()).points_and_segments();
+                (a.points().len(), lines + polygons)
+            }
+        }
+    }
+
+    /// Resolve the relation using only the bounding boxes, see [`InputRelation::approximate`].
+    /// A bounding box containing the other one is considered as a strict containment, and overlapping
+    /// bounding boxes as an intersection. The other relations can't be guessed from the bounding boxes and
+    /// are left to `None`.
+    fn approximate_relation(&self, other: &Zerometry, relation: InputRelation) -> OutputRelation {
+        let output = relation.to_false();
+        let (left, bottom, right, top) = self.bounds();
+        let (other_left, other_bottom, other_right, other_top) = other.bounds();
+        let tolerance = relation.tolerance;
+        if self.is_empty()
+            || other.is_empty()
+            || right + tolerance < other_left
+            || other_right + tolerance < left
+            || top + tolerance < other_bottom
+            || other_top + tolerance < bottom
+        {
+            return output.make_disjoint_if_set();
+        }
+
+        let contains = left <= other_left
+            && bottom <= other_bottom
+            && other_right <= right
+            && other_top <= top;
+        let contained = other_left <= left
+            && other_bottom <= bottom
+            && right <= other_right
+            && top <= other_top;
+        let mut output = if contains || contained {
+            output
+        } else {
+            output.make_intersect_if_set()
+        };
+        if contains {
+            output = output.make_strict_contains_if_set();
+        }
+        if contained {
+            output = output.make_strict_contained_if_set();
+        }
+        OutputRelation {
+            overlaps: None,
+            touches: None,
+            crosses: None,
+            covers: None,
+            covered_by: None,
+            approximate: true,
+            ..output
+        }
+    }
+
     /// Convert the [`Zerometry`] back to a [`geo_types::Geometry`].
     /// Don't forget that converting the geometry to a zerometry was a destructive operation.
     /// This means the geometry you'll get back won't necessarily correspond to your initial geometry.
@@ -224,13 +536,44 @@ impl<'a> Zerometry<'a> {
         match self {
             Zerometry::Point(a) => Geometry::Point(a.to_geo()),
             Zerometry::MultiPoints(a) => Geometry::MultiPoint(a.to_geo()),
-            Zerometry::Line(a) => Geometry::LineString(a.to_geo()),
+            Zerometry::Line(a) => a.subtype().restore(Geometry::LineString(a.to_geo())),
             Zerometry::MultiLines(a) => Geometry::MultiLineString(a.to_geo()),
-            Zerometry::Polygon(a) => Geometry::Polygon(a.to_geo()),
+            Zerometry::Polygon(a) => a.subtype().restore(Geometry::Polygon(a.to_geo())),
             Zerometry::MultiPolygon(a) => Geometry::MultiPolygon(a.to_geo()),
             Zerometry::Collection(zollection) => Geometry::GeometryCollection(zollection.to_geo()),
         }
     }
+
+    /// Return the bytes the shape was read from, without the tag and the header of the zerometry.
+    /// They can be read back with the `from_bytes` method of the shape, see [`Zine::as_bytes`] for example.
+    ///
+    /// # Panics
+    /// If the shape was created with one of the `new` constructors from parts that don't follow each other in memory.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            Zerometry::Point(a) => a.as_bytes(),
+            Zerometry::MultiPoints(a) => a.as_bytes(),
+            Zerometry::Line(a) => a.as_bytes(),
+            Zerometry::MultiLines(a) => a.as_bytes(),
+            Zerometry::Polygon(a) => a.as_bytes(),
+            Zerometry::MultiPolygon(a) => a.as_bytes(),
+            Zerometry::Collection(a) => a.as_bytes(),
+        }
+    }
+
+    /// Return `true` if the shape doesn't contain any point.
+    /// A [`Zerometry::Point`] is never empty.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Zerometry::Point(_) => false,
+            Zerometry::MultiPoints(a) => a.is_empty(),
+            Zerometry::Line(a) => a.is_empty(),
+            Zerometry::MultiLines(a) => a.is_empty(),
+            Zerometry::Polygon(a) => a.is_empty(),
+            Zerometry::MultiPolygon(a) => a.is_empty(),
+            Zerometry::Collection(a) => a.is_empty(),
+        }
+    }
 }
 
 impl<'a> From<Zoint<'a>> for Zerometry<'a> {
@@ -247,6 +590,20 @@ impl<'a> From<ZultiPoints<'a>> for Zerometry<'a> {
     }
 }
 
+impl<'a> From<Zine<'a>> for Zerometry<'a> {
+    #[inline]
+    fn from(line: Zine<'a>) -> Self {
+        Zerometry::Line(line)
+    }
+}
+
+impl<'a> From<ZultiLines<'a>> for Zerometry<'a> {
+    #[inline]
+    fn from(lines: ZultiLines<'a>) -> Self {
+        Zerometry::MultiLines(lines)
+    }
+}
+
 impl<'a> From<Zolygon<'a>> for Zerometry<'a> {
     #[inline]
     fn from(polygon: Zolygon<'a>) -> Self {
@@ -261,6 +618,13 @@ impl<'a> From<ZultiPolygons<'a>> for Zerometry<'a> {
     }
 }
 
+impl<'a> From<Zollection<'a>> for Zerometry<'a> {
+    #[inline]
+    fn from(collection: Zollection<'a>) -> Self {
+        Zerometry::Collection(collection)
+    }
+}
+
 impl<'a> RelationBetweenShapes<Zoint<'a>> for Zerometry<'a> {
     fn relation(&self, other: &Zoint, relation: InputRelation) -> OutputRelation {
         match self {
@@ -361,10 +725,19 @@ impl<'a> RelationBetweenShapes<Zollection<'a>> for Zerometry<'a> {
 
 impl<'a> RelationBetweenShapes<Zerometry<'a>> for Zerometry<'a> {
     fn relation(&self, other: &Zerometry, relation: InputRelation) -> OutputRelation {
+        if let Some(budget) = relation.budget
+            && self.relation_stats(other).segment_tests > budget
+        {
+            return OutputRelation::default();
+        }
+        if relation.approximate {
+            return self.approximate_relation(other, relation);
+        }
+
         match other {
             Zerometry::Point(a) => self.relation(a, relation),
             Zerometry::MultiPoints(a) => self.relation(a, relation),
-            Zerometry::Line(a) => a.relation(other, relation),
+            Zerometry::Line(a) => self.relation(a, relation),
             Zerometry::MultiLines(a) => self.relation(a, relation),
             Zerometry::Polygon(a) => self.relation(a, relation),
             Zerometry::MultiPolygon(a) => self.relation(a, relation),
@@ -375,41 +748,60 @@ impl<'a> RelationBetweenShapes<Zerometry<'a>> for Zerometry<'a> {
 
 impl<'a> RelationBetweenShapes<Geometry<f64>> for Zerometry<'a> {
     fn relation(&self, other: &Geometry<f64>, relation: InputRelation) -> OutputRelation {
-        let mut buffer = Vec::new();
-        Zerometry::write_from_geometry(&mut buffer, other).unwrap();
-        let other = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
-        self.relation(&other, relation)
+        with_serialized(
+            |buffer| Zerometry::write_from_geometry(buffer, other),
+            |other| self.relation(&other, relation),
+        )
     }
 }
 
 impl<'a> RelationBetweenShapes<Zerometry<'a>> for Geometry<f64> {
     fn relation(&self, other: &Zerometry<'a>, relation: InputRelation) -> OutputRelation {
-        let mut buffer = Vec::new();
-        Zerometry::write_from_geometry(&mut buffer, self).unwrap();
-        let this = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
-        this.relation(other, relation)
+        with_serialized(
+            |buffer| Zerometry::write_from_geometry(buffer, self),
+            |this| this.relation(other, relation),
+        )
     }
 }
 
 impl<'a> RelationBetweenShapes<Polygon<f64>> for Zerometry<'a> {
     fn relation(&self, other: &Polygon<f64>, relation: InputRelation) -> OutputRelation {
-        let mut buffer = Vec::new();
-        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(other.clone())).unwrap();
-        let other = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
-        self.relation(&other, relation)
+        with_serialized(
+            |buffer| {
+                buffer.extend_from_slice(&tag(POLYGON_TAG).to_ne_bytes());
+                Ok(Zolygon::write_from_geometry(buffer, other)?)
+            },
+            |other| self.relation(&other, relation),
+        )
     }
 }
 
 impl<'a> RelationBetweenShapes<MultiPolygon<f64>> for Zerometry<'a> {
     fn relation(&self, other: &MultiPolygon<f64>, relation: InputRelation) -> OutputRelation {
-        let mut buffer = Vec::new();
-        Zerometry::write_from_geometry(&mut buffer, &Geometry::MultiPolygon(other.clone()))
-            .unwrap();
-        let other = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
-        self.relation(&other, relation)
+        with_serialized(
+            |buffer| {
+                buffer.extend_from_slice(&tag(MULTI_POLYGON_TAG).to_ne_bytes());
+                Ok(ZultiPolygons::write_from_geometry(buffer, other)?)
+            },
+            |other| self.relation(&other, relation),
+        )
     }
 }
 
+/// Serialize a shape in a buffer of the thread-local [`BufferPool`] and compute a relation with it.
+/// If the shape can't be serialized nothing is computed and all the fields of the [`OutputRelation`] are `None`.
+pub(crate) fn with_serialized(
+    write: impl FnOnce(&mut Vec<u8>) -> Result<(), ZerometryError>,
+    relation: impl FnOnce(Zerometry) -> OutputRelation,
+) -> OutputRelation {
+    BufferPool::with_thread_local_buffer(|buffer| {
+        match write(buffer).and_then(|()| unsafe { Zerometry::from_bytes(buffer) }) {
+            Ok(zerometry) => relation(zerometry),
+            Err(_) => OutputRelation::default(),
+        }
+    })
+}
+
 impl PartialEq<Geometry> for Zerometry<'_> {
     fn eq(&self, other: &Geometry) -> bool {
         match (self, other) {
@@ -425,6 +817,10 @@ impl PartialEq<Geometry> for Zerometry<'_> {
             (Zerometry::MultiPolygon(zulti_polygon), Geometry::MultiPolygon(multi_polygon)) => {
                 zulti_polygon.eq(multi_polygon)
             }
+            (
+                Zerometry::Line(_) | Zerometry::Polygon(_),
+                Geometry::Line(_) | Geometry::Rect(_) | Geometry::Triangle(_),
+            ) => self.to_geo() == *other,
             _ => false,
         }
     }
@@ -432,9 +828,10 @@ impl PartialEq<Geometry> for Zerometry<'_> {
 
 #[cfg(test)]
 mod zerometry_test {
+    use geo::polygon;
     use geo_types::geometry;
 
-    use crate::Zerometry;
+    use crate::{Certainty, InputRelation, RelationBetweenShapes, Zerometry};
 
     #[test]
     fn naive_point_roundtrip() {
@@ -604,4 +1001,108 @@ mod zerometry_test {
         assert_eq!(zeometry_collection, geometry_collection);
         */
     }
+
+    #[test]
+    fn relation_budget() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let mut buf = Vec::new();
+        Zerometry::write_from_geometry(&mut buf, &polygon.into()).unwrap();
+        let polygon = unsafe { Zerometry::from_bytes(&buf).unwrap() };
+        let collection =
+            geometry::Geometry::GeometryCollection(geometry::GeometryCollection::new_from(vec![
+                geometry::Geometry::Point(geometry::Point::new(1.0, 1.0)),
+                geometry::Geometry::LineString(geometry::LineString::from(vec![
+                    (1.0, 1.0),
+                    (2.0, 2.0),
+                    (5.0, 5.0),
+                ])),
+            ]));
+        let mut buf = Vec::new();
+        Zerometry::write_from_geometry(&mut buf, &collection).unwrap();
+        let collection = unsafe { Zerometry::from_bytes(&buf).unwrap() };
+
+        insta::assert_compact_debug_snapshot!(polygon.relation_stats(&collection), @"RelationStats { parts: 4, other_parts: 3, segment_tests: 12 }");
+
+        let relation = InputRelation {
+            budget: Some(12),
+            ..InputRelation::all()
+        };
+        insta::assert_compact_debug_snapshot!(polygon.relation(&collection, relation), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let relation = InputRelation {
+            budget: Some(11),
+            ..InputRelation::all()
+        };
+        insta::assert_compact_debug_snapshot!(polygon.relation(&collection, relation), @"OutputRelation { contains: None, strict_contains: None, contained: None, strict_contained: None, intersect: None, disjoint: None, overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        // The relations between the specific shapes ignore the budget
+        let (Zerometry::Polygon(zolygon), Zerometry::Collection(zollection)) =
+            (polygon, collection)
+        else {
+            unreachable!()
+        };
+        assert_eq!(zolygon.relation(&zollection, relation).contains, Some(true));
+    }
+
+    #[test]
+    fn relation_with_planar_geometries() {
+        // The coordinates are not checked when relating a zerometry with a geo shape
+        let point = geometry::Geometry::Point(geometry::Point::new(250000.0, 10.0));
+        let mut buf = Vec::new();
+        Zerometry::write_from_geometry(&mut buf, &point).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buf).unwrap() };
+        let square =
+            polygon![(x: 0., y: 0.), (x: 500000., y: 0.), (x: 500000., y: 20.), (x: 0., y: 20.)];
+
+        assert_eq!(
+            zerometry.relation(&point, InputRelation::all()).disjoint,
+            Some(false)
+        );
+        assert_eq!(
+            point.relation(&zerometry, InputRelation::all()).disjoint,
+            Some(false)
+        );
+        assert!(zerometry.contained(&square.clone()));
+        assert!(zerometry.contained(&geometry::MultiPolygon::new(vec![square])));
+    }
+
+    #[test]
+    fn approximate_relation() {
+        let relation = |left: geometry::Geometry, right: geometry::Geometry| {
+            let mut left_buf = Vec::new();
+            Zerometry::write_from_geometry(&mut left_buf, &left).unwrap();
+            let left = Zerometry::try_from_bytes(&left_buf).unwrap();
+            let mut right_buf = Vec::new();
+            Zerometry::write_from_geometry(&mut right_buf, &right).unwrap();
+            let right = Zerometry::try_from_bytes(&right_buf).unwrap();
+            let exact = left.all_relation(&right);
+            let approximate = left.relation(
+                &right,
+                InputRelation {
+                    approximate: true,
+                    ..InputRelation::all()
+                },
+            );
+            (exact, approximate)
+        };
+
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        // The point is in the bounding box of the triangle but outside of it
+        let triangle = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.)];
+        let (exact, approximate) = relation(
+            triangle.into(),
+            geometry::Geometry::Point(geometry::Point::new(1.0, 3.0)),
+        );
+        insta::assert_compact_debug_snapshot!(exact, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_eq!(approximate.certainty(), Certainty::BboxOnly);
+        insta::assert_compact_debug_snapshot!(approximate, @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: true }");
+
+        let overlapping = polygon![(x: 3., y: 3.), (x: 6., y: 3.), (x: 6., y: 6.)];
+        let (_, approximate) = relation(square.clone().into(), overlapping.into());
+        insta::assert_compact_debug_snapshot!(approximate, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: true }");
+
+        // Disjoint bounding boxes are exact
+        let far = polygon![(x: 10., y: 10.), (x: 11., y: 10.), (x: 11., y: 11.)];
+        let (exact, approximate) = relation(square.into(), far.into());
+        assert_eq!(exact, approximate);
+        assert_eq!(approximate.certainty(), Certainty::Exact);
+    }
 }
@@ -0,0 +1,109 @@
+use bytemuck::cast_slice;
+
+use crate::{Zolygon, bounding_box::EMPTY_BOUNDING_BOX};
+
+/// Create a [`Zolygon`] from a list of `(x, y)` coordinates, like [`geo_types::polygon`] does for a polygon.
+/// The bytes are laid out at compile time in a static, nothing is serialized or allocated at runtime,
+/// which makes it useful for fixed query shapes or test fixtures.
+///
+/// As with [`geo_types::Polygon::new`], the ring is closed if the last coordinate isn't the first one.
+///
+/// ```
+/// use zerometry::{RelationBetweenShapes, Zerometry, zolygon};
+/// use geo_types::{Point, polygon};
+///
+/// let northern_hemisphere = zolygon![(-180., 0.), (180., 0.), (180., 90.), (-180., 90.)];
+/// assert_eq!(
+///     northern_hemisphere,
+///     polygon![(x: -180., y: 0.), (x: 180., y: 0.), (x: 180., y: 90.), (x: -180., y: 90.)],
+/// );
+///
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &Point::new(2.35, 48.85).into()).unwrap();
+/// let paris = Zerometry::try_from_bytes(&buffer).unwrap();
+/// assert!(northern_hemisphere.contains(&paris));
+/// ```
+#[macro_export]
+macro_rules! zolygon {
+    ($(($x:expr, $y:expr)),* $(,)?) => {{
+        #[allow(clippy::unnecessary_cast)]
+        const COORDS: &[[f64; 2]] = &[$([$x as f64, $y as f64]),*];
+        static WORDS: [u64; $crate::__zolygon_words_len(COORDS)] = $crate::__zolygon_words(COORDS);
+        $crate::__zolygon_from_words(&WORDS)
+    }};
+}
+
+/// Return the number of `u64` used by the bounding box and the closed ring of the coordinates.
+#[doc(hidden)]
+pub const fn __zolygon_words_len(coords: &[[f64; 2]]) -> usize {
+    EMPTY_BOUNDING_BOX.len() + closed_len(coords) * 2
+}
+
+/// Lay out the bounding box followed by the closed ring of the coordinates, as [`Zolygon::write_from_geometry`] does.
+#[doc(hidden)]
+pub const fn __zolygon_words<const N: usize>(coords: &[[f64; 2]]) -> [u64; N] {
+    assert!(N == __zolygon_words_len(coords));
+    let [mut left, mut bottom, mut right, mut top] = EMPTY_BOUNDING_BOX;
+    let mut words = [0; N];
+    let mut i = 0;
+    while i < closed_len(coords) {
+        // The closing coordinate is the first one
+        let [x, y] = coords[i % coords.len()];
+        left = left.min(x);
+        bottom = bottom.min(y);
+        right = right.max(x);
+        top = top.max(y);
+        words[EMPTY_BOUNDING_BOX.len() + i * 2] = x.to_bits();
+        words[EMPTY_BOUNDING_BOX.len() + i * 2 + 1] = y.to_bits();
+        i += 1;
+    }
+    words[0] = left.to_bits();
+    words[1] = bottom.to_bits();
+    words[2] = right.to_bits();
+    words[3] = top.to_bits();
+    words
+}
+
+/// Read the words generated by [`__zolygon_words`], they're always aligned since they're stored in `u64`s.
+#[doc(hidden)]
+pub fn __zolygon_from_words(words: &'static [u64]) -> Zolygon<'static> {
+    // SAFETY: The words were laid out by `__zolygon_words` in the `zolygon!` macro
+    unsafe { Zolygon::from_bytes(cast_slice(words)) }
+}
+
+const fn closed_len(coords: &[[f64; 2]]) -> usize {
+    match coords {
+        [] => 0,
+        [first, .., last] if first[0] != last[0] || first[1] != last[1] => coords.len() + 1,
+        _ => coords.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, Polygon, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use crate::{Zolygon, zolygon};
+
+    #[test]
+    fn same_bytes_as_written() {
+        let mut buffer = Vec::new();
+        let triangle: Polygon<f64> = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 1., y: 3.)];
+        Zolygon::write_from_geometry(&mut buffer, &triangle).unwrap();
+        let zolygon = zolygon![(0, 0), (2, 0), (1, 3)];
+        assert_eq!(zolygon.as_bytes(), &buffer[..]);
+        assert_compact_debug_snapshot!(zolygon, @"Zolygon { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 2.0, lat: 3.0 } }, coords: [Coord { lng: 0.0, lat: 0.0 }, Coord { lng: 2.0, lat: 0.0 }, Coord { lng: 1.0, lat: 3.0 }, Coord { lng: 0.0, lat: 0.0 }] }");
+
+        // Already closed
+        let zolygon = zolygon![(0., 0.), (2., 0.), (1., 3.), (0., 0.),];
+        assert_eq!(zolygon.as_bytes(), &buffer[..]);
+
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, &Polygon::new(LineString::new(vec![]), vec![]))
+            .unwrap();
+        let zolygon = zolygon![];
+        assert_eq!(zolygon.as_bytes(), &buffer[..]);
+        assert_compact_debug_snapshot!(zolygon, @"Zolygon { bounding_box: BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }, coords: [] }");
+    }
+}
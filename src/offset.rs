@@ -0,0 +1,157 @@
+use geo_types::{Coord, LineString, Polygon};
+
+use crate::Zolygon;
+
+/// A corner is cut once its offset vertex would be farther than this many times the distance from the original vertex.
+const MITER_LIMIT: f64 = 2.0;
+
+impl Zolygon<'_> {
+    /// Return an approximation of the polygon grown by `distance` in every direction, to add a safety margin
+    /// around a query shape without computing a real buffer.
+    ///
+    /// Every vertex is moved along the bisector of its corner so the edges end up at `distance` from the original ones.
+    /// The sharp corners whose vertex would be moved by more than twice the distance away from the polygon
+    /// are cut by two vertices instead.
+    /// The distance is in the same unit as the coords, which means degrees.
+    ///
+    /// ```
+    /// use zerometry::Zolygon;
+    /// use geo_types::polygon;
+    ///
+    /// let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+    /// let mut buffer = Vec::new();
+    /// Zolygon::write_from_geometry(&mut buffer, &square).unwrap();
+    /// let zolygon = Zolygon::try_from_bytes(&buffer).unwrap();
+    ///
+    /// let expected = polygon![(x: -1., y: -1.), (x: 2., y: -1.), (x: 2., y: 2.), (x: -1., y: 2.)];
+    /// assert_eq!(zolygon.dilate(1.0), expected);
+    /// ```
+    pub fn dilate(&self, distance: f64) -> Polygon<f64> {
+        self.offset(distance)
+    }
+
+    /// Return an approximation of the polygon shrunk by `distance` in every direction, see [`Self::dilate`].
+    /// When the distance is larger than the polygon the result turns itself inside out instead of disappearing,
+    /// it's up to the caller to keep the distance small compared to the shape.
+    pub fn erode(&self, distance: f64) -> Polygon<f64> {
+        self.offset(-distance)
+    }
+
+    /// Move every edge of the polygon by `distance` to the outside, or to the inside if it's negative.
+    fn offset(&self, distance: f64) -> Polygon<f64> {
+        let mut ring: Vec<Coord<f64>> = Vec::with_capacity(self.coords().len());
+        for coord in self.coords().iter().map(|coord| coord.to_geo()) {
+            if ring.last() != Some(&coord) {
+                ring.push(coord);
+            }
+        }
+        // The ring is closed by repeating its first coord
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        if ring.len() < 3 || distance == 0.0 {
+            return self.to_geo();
+        }
+
+        // The outward normal of an edge depends on the winding of the ring
+        let doubled_area: f64 = (0..ring.len())
+            .map(|i| {
+                let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        let winding = if doubled_area < 0.0 { -1.0 } else { 1.0 };
+
+        let mut offset = Vec::with_capacity(ring.len() + 1);
+        for i in 0..ring.len() {
+            let previous = ring[(i + ring.len() - 1) % ring.len()];
+            let current = ring[i];
+            let next = ring[(i + 1) % ring.len()];
+            let before = outward_normal(previous, current, winding);
+            let after = outward_normal(current, next, winding);
+
+            // The length of the bisector going to the intersection of both offset edges is `distance / cos(angle / 2)`
+            let cos = 1.0 + before.x * after.x + before.y * after.y;
+            let bisector = Coord {
+                x: (before.x + after.x) / cos,
+                y: (before.y + after.y) / cos,
+            };
+            let length = bisector.x.hypot(bisector.y);
+            // The corners pointing in the direction of the offset are the ones growing with the distance
+            let turn = (current.x - previous.x) * (next.y - current.y)
+                - (current.y - previous.y) * (next.x - current.x);
+            let outer = turn * winding * distance > 0.0;
+            if cos > f64::EPSILON && (length <= MITER_LIMIT || !outer) {
+                offset.push(current + bisector * distance);
+            } else if outer {
+                // The corner is cut by the two offset edges
+                offset.push(current + before * distance);
+                offset.push(current + after * distance);
+            } else {
+                // The ring goes back on itself, the vertex is moved backward
+                let backward = current - previous;
+                let backward = backward / backward.x.hypot(backward.y);
+                offset.push(current - backward * (distance.abs() * MITER_LIMIT));
+            }
+        }
+        offset.push(offset[0]);
+        Polygon::new(LineString::new(offset), Vec::new())
+    }
+}
+
+/// Return the unit vector orthogonal to the edge going from `a` to `b`, pointing outside of the ring.
+fn outward_normal(a: Coord<f64>, b: Coord<f64>, winding: f64) -> Coord<f64> {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length = dx.hypot(dy);
+    Coord {
+        x: dy / length * winding,
+        y: -dx / length * winding,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::polygon;
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    fn zolygon(polygon: &Polygon<f64>) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, polygon).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_offset() {
+        let square =
+            zolygon(&polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)]);
+        let square = Zolygon::try_from_bytes(&square).unwrap();
+        assert_compact_debug_snapshot!(square.dilate(0.5), @"POLYGON((-0.5 -0.5,1.5 -0.5,1.5 1.5,-0.5 1.5,-0.5 -0.5))");
+        assert_compact_debug_snapshot!(square.erode(0.25), @"POLYGON((0.25 0.25,0.75 0.25,0.75 0.75,0.25 0.75,0.25 0.25))");
+        assert_compact_debug_snapshot!(square.dilate(0.0), @"POLYGON((0.0 0.0,1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0))");
+
+        // Same square but clockwise
+        let clockwise =
+            zolygon(&polygon![(x: 0., y: 0.), (x: 0., y: 1.), (x: 1., y: 1.), (x: 1., y: 0.)]);
+        let clockwise = Zolygon::try_from_bytes(&clockwise).unwrap();
+        assert_compact_debug_snapshot!(clockwise.dilate(0.5), @"POLYGON((-0.5 -0.5,-0.5 1.5,1.5 1.5,1.5 -0.5,-0.5 -0.5))");
+
+        // The sharp corner at (10, 0.5) is cut when dilating but moved along its bisector when eroding
+        let spike = zolygon(&polygon![(x: 0., y: 0.), (x: 10., y: 0.5), (x: 0., y: 1.)]);
+        let spike = Zolygon::try_from_bytes(&spike).unwrap();
+        assert_compact_debug_snapshot!(spike.dilate(0.1), @"POLYGON((-0.1 -0.10512492197250392,10.00499376169439 0.40012476611221554,10.00499376169439 0.5998752338877844,-0.1 1.105124921972504,-0.1 -0.10512492197250392))");
+        assert_compact_debug_snapshot!(spike.erode(0.1), @"POLYGON((0.1 0.10512492197250392,7.9975015605500195 0.5,0.1 0.8948750780274961,0.1 0.10512492197250392))");
+
+        // The concave corner at (1, 1) is moved inward when dilating
+        let concave = zolygon(
+            &polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 1., y: 1.), (x: 0., y: 2.)],
+        );
+        let concave = Zolygon::try_from_bytes(&concave).unwrap();
+        assert_compact_debug_snapshot!(concave.dilate(0.1), @"POLYGON((-0.1 -0.1,2.1 -0.1,2.1 2.0,1.9292893218813452 2.0707106781186546,1.0 1.1414213562373094,0.07071067811865475 2.0707106781186546,-0.1 2.0,-0.1 -0.1))");
+
+        let flat = zolygon(&polygon![(x: 0., y: 0.), (x: 1., y: 0.)]);
+        let flat = Zolygon::try_from_bytes(&flat).unwrap();
+        assert_compact_debug_snapshot!(flat.dilate(1.0), @"POLYGON((0.0 0.0,1.0 0.0,0.0 0.0))");
+    }
+}
@@ -0,0 +1,183 @@
+use std::mem;
+
+use geo_types::{Geometry, GeometryCollection};
+
+use crate::{Zerometry, ZerometryError, header::PROPERTIES_FLAG};
+
+/// The opaque properties attached to the members of a collection, read from the header of the zerometry.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Properties<'a> {
+    /// The end of the properties of every member in the bytes, the first ones start at zero.
+    ends: &'a [u64],
+    bytes: &'a [u8],
+}
+
+impl<'a> Properties<'a> {
+    pub fn new(ends: &'a [u64], bytes: &'a [u8]) -> Result<Self, ZerometryError> {
+        if !ends.is_sorted() || ends.last().is_some_and(|end| *end > bytes.len() as u64) {
+            return Err(ZerometryError::InvalidProperties);
+        }
+        Ok(Self { ends, bytes })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a [u8]> {
+        let end = *self.ends.get(index)? as usize;
+        let start = match index.checked_sub(1) {
+            Some(previous) => self.ends[previous] as usize,
+            None => 0,
+        };
+        Some(&self.bytes[start..end])
+    }
+}
+
+/// Return the size of the properties once padded to stay aligned on 64 bits.
+pub(crate) fn padded_size(size: u64) -> u64 {
+    size.div_ceil(mem::size_of::<u64>() as u64)
+        .saturating_mul(mem::size_of::<u64>() as u64)
+}
+
+impl<'a> Zerometry<'a> {
+    /// Same as [`Self::write_from_geometry`] but an opaque blob of properties is attached to every geometry of the
+    /// collection, they can be retrieved with [`crate::Zollection::properties`] by the index of the geometry.
+    /// It lets the application metadata, like the properties of the features of a GeoJSON, be stored along the shape.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{GeometryCollection, Point};
+    ///
+    /// let features = GeometryCollection::new_from(vec![
+    ///     Point::new(2.35, 48.85).into(),
+    ///     Point::new(4.83, 45.76).into(),
+    /// ]);
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry_with_properties(&mut buffer, &features, &[r#"{"name":"Paris"}"#, r#"{"name":"Lyon"}"#]).unwrap();
+    ///
+    /// let Zerometry::Collection(collection) = Zerometry::try_from_bytes(&buffer).unwrap() else { unreachable!() };
+    /// assert_eq!(collection.properties(1), Some(&br#"{"name":"Lyon"}"#[..]));
+    /// ```
+    pub fn write_from_geometry_with_properties(
+        writer: &mut Vec<u8>,
+        collection: &GeometryCollection<f64>,
+        properties: &[impl AsRef<[u8]>],
+    ) -> Result<(), ZerometryError> {
+        if collection.0.len() != properties.len() {
+            return Err(ZerometryError::InvalidPropertiesCount {
+                members: collection.0.len(),
+                properties: properties.len(),
+            });
+        }
+
+        let start = writer.len();
+        Zerometry::write_from_geometry(writer, &Geometry::GeometryCollection(collection.clone()))?;
+
+        let tag_end = start + mem::size_of::<u64>();
+        let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+        writer[start..tag_end].copy_from_slice(&(tag | PROPERTIES_FLAG).to_ne_bytes());
+        let ends: Vec<u64> = properties
+            .iter()
+            .scan(0, |end, properties| {
+                *end += properties.as_ref().len() as u64;
+                Some(*end)
+            })
+            .collect();
+        let size = ends.last().copied().unwrap_or(0);
+        let header = [properties.len() as u64, size]
+            .into_iter()
+            .chain(ends)
+            .flat_map(u64::to_ne_bytes)
+            .chain(
+                properties
+                    .iter()
+                    .flat_map(|properties| properties.as_ref().iter().copied()),
+            )
+            .chain(std::iter::repeat_n(0, (padded_size(size) - size) as usize));
+        writer.splice(tag_end..tag_end, header.collect::<Vec<u8>>());
+        Ok(())
+    }
+
+    /// Attach the properties read from the header to the collection.
+    pub(crate) fn attach_properties(
+        self,
+        properties: Properties<'a>,
+    ) -> Result<Self, ZerometryError> {
+        match self {
+            Zerometry::Collection(collection) => Ok(Zerometry::Collection(
+                collection.with_properties(properties),
+            )),
+            _ => Err(ZerometryError::UnexpectedProperties),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str;
+
+    use geo_types::{LineString, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn properties_round_trip() {
+        let collection = GeometryCollection::new_from(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            Point::new(5.0, 5.0).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+        ]);
+        let properties: [&[u8]; 3] = [b"forest", b"", b"river"];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_properties(&mut buffer, &collection, &properties)
+            .unwrap();
+        assert_eq!(buffer.len() % mem::size_of::<u64>(), 0);
+        let Zerometry::Collection(zollection) = Zerometry::try_from_bytes(&buffer).unwrap() else {
+            unreachable!()
+        };
+        let read: Vec<_> = (0..4)
+            .map(|index| zollection.properties(index).map(str::from_utf8))
+            .collect();
+        assert_compact_debug_snapshot!(read, @r#"[Some(Ok("forest")), Some(Ok("")), Some(Ok("river")), None]"#);
+
+        // The shape is the same as without properties
+        let mut flat = Vec::new();
+        let geometry = Geometry::GeometryCollection(collection.clone());
+        Zerometry::write_from_geometry(&mut flat, &geometry).unwrap();
+        let flat = Zerometry::try_from_bytes(&flat).unwrap();
+        assert_eq!(
+            Zerometry::decode_unaligned_bytes(&buffer).unwrap(),
+            flat.to_geo()
+        );
+        let Zerometry::Collection(flat) = flat else {
+            unreachable!()
+        };
+        assert_eq!(zollection.to_geo(), flat.to_geo());
+        assert_compact_debug_snapshot!(flat.properties(0), @"None");
+
+        let error =
+            Zerometry::write_from_geometry_with_properties(&mut Vec::new(), &collection, &[b"a"])
+                .unwrap_err();
+        assert_compact_debug_snapshot!(error.to_string(), @r#""The collection contains 3 members but 1 properties were provided""#);
+    }
+
+    #[test]
+    fn invalid_properties() {
+        let mut buffer = Vec::new();
+        let point = GeometryCollection::new_from(vec![Point::new(5.0, 5.0).into()]);
+        Zerometry::write_from_geometry_with_properties(&mut buffer, &point, &[b"abc"]).unwrap();
+
+        // The end exceeds the size of the properties
+        let mut corrupted = buffer.clone();
+        corrupted[24..32].copy_from_slice(&4_u64.to_ne_bytes());
+        let error = Zerometry::try_from_bytes(&corrupted).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"InvalidProperties");
+
+        // Only a collection can have properties
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Point::new(5.0, 5.0).into()).unwrap();
+        let tag = u64::from_ne_bytes(buffer[..8].try_into().unwrap());
+        buffer[..8].copy_from_slice(&(tag | PROPERTIES_FLAG).to_ne_bytes());
+        buffer.splice(8..8, [0_u64, 0].iter().flat_map(|word| word.to_ne_bytes()));
+        let error = Zerometry::try_from_bytes(&buffer).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"UnexpectedProperties");
+    }
+}
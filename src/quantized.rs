@@ -0,0 +1,463 @@
+use core::fmt;
+use std::io::Write;
+use std::mem;
+
+use bytemuck::cast_slice;
+use geo_types::{LineString, Polygon};
+
+use crate::{
+    BOUNDING_BOX_SIZE_IN_BYTES, BoundingBox, ZerometryError,
+    aligned_bytes::contiguous,
+    bounding_box::{EMPTY_BOUNDING_BOX, is_valid_bounding_box},
+    error::{check_aligned, split_checked},
+};
+
+/// The default precision of the quantized coordinates, in degrees. It's around one centimeter on the earth.
+pub const DEFAULT_QUANTIZATION_STEP: f64 = 1e-7;
+
+/// The number of bytes used by the header of the quantized shapes: the bounding box followed by the step.
+const HEADER_SIZE_IN_BYTES: usize = BOUNDING_BOX_SIZE_IN_BYTES + mem::size_of::<f64>();
+/// The number of bytes used by a quantized coordinate, two `u32`.
+const QUANTIZED_COORD_SIZE_IN_BYTES: usize = 2 * mem::size_of::<u32>();
+
+/// Equivalent of a [`geo_types::LineString`] storing its coordinates as fixed-point offsets from the bottom left
+/// corner of its bounding box, it takes half the space of a [`crate::Zine`].
+///
+/// Every coordinate is stored as two `u32` counting the number of `step` between the coordinate and the corner,
+/// which means a coordinate is moved by at most half a step when written, see [`DEFAULT_QUANTIZATION_STEP`].
+/// The coordinates are decoded one by one when read, nothing is allocated.
+///
+/// ```
+/// use zerometry::{DEFAULT_QUANTIZATION_STEP, QuantizedZine};
+/// use geo_types::LineString;
+///
+/// let line = LineString::from(vec![(2.3522, 48.8566), (2.2945, 48.8584)]);
+/// let mut buffer = Vec::new();
+/// QuantizedZine::write_from_geometry(&mut buffer, &line, DEFAULT_QUANTIZATION_STEP).unwrap();
+/// assert_eq!(buffer.len(), 40 + 2 * 8);
+///
+/// let zine = QuantizedZine::try_from_bytes(&buffer).unwrap();
+/// let first = zine.coord(0).unwrap();
+/// assert!((first.x - 2.3522).abs() <= DEFAULT_QUANTIZATION_STEP / 2.0);
+/// ```
+#[derive(Clone, Copy)]
+pub struct QuantizedZine<'a> {
+    coords: QuantizedCoords<'a>,
+}
+
+/// Equivalent of a [`geo_types::Polygon`] storing its coordinates as fixed-point offsets from the bottom left
+/// corner of its bounding box, see [`QuantizedZine`].
+///
+/// ```
+/// use zerometry::{DEFAULT_QUANTIZATION_STEP, QuantizedZolygon};
+/// use geo_types::{coord, polygon};
+///
+/// let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+/// let mut buffer = Vec::new();
+/// QuantizedZolygon::write_from_geometry(&mut buffer, &square, DEFAULT_QUANTIZATION_STEP).unwrap();
+///
+/// let zolygon = QuantizedZolygon::try_from_bytes(&buffer).unwrap();
+/// assert!(zolygon.contains_coord(coord! { x: 0.5, y: 0.5 }));
+/// assert_eq!(zolygon.to_geo(), square);
+/// ```
+#[derive(Clone, Copy)]
+pub struct QuantizedZolygon<'a> {
+    coords: QuantizedCoords<'a>,
+}
+
+impl<'a> QuantizedZine<'a> {
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        Self {
+            coords: unsafe { QuantizedCoords::from_bytes(data) },
+        }
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        Ok(Self {
+            coords: QuantizedCoords::try_from_bytes(data)?,
+        })
+    }
+
+    /// Convert the specified [`geo_types::LineString`] to a valid [`QuantizedZine`] slice of bytes in the input buffer.
+    /// The coordinates are rounded to the nearest multiple of `step` from the bottom left corner of the line.
+    pub fn write_from_geometry(
+        writer: &mut impl Write,
+        geometry: &LineString<f64>,
+        step: f64,
+    ) -> Result<(), ZerometryError> {
+        QuantizedCoords::write_from_geometry(writer, geometry, step)
+    }
+
+    /// Return the bytes the line was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.coords.as_bytes()
+    }
+
+    /// Return the bounding box of the line, it bounds the coordinates as they were quantized.
+    #[inline]
+    pub fn bounding_box(&self) -> &'a BoundingBox {
+        self.coords.bounding_box()
+    }
+
+    /// Return the precision the coordinates were stored with, in degrees.
+    #[inline]
+    pub fn step(&self) -> f64 {
+        self.coords.step()
+    }
+
+    /// Return the number of coordinates of the line.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Return `true` if the line has no coordinates.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.coords.len() == 0
+    }
+
+    /// Decode the coordinate at the specified index.
+    #[inline]
+    pub fn coord(&self, index: usize) -> Option<geo_types::Coord<f64>> {
+        self.coords.get(index)
+    }
+
+    /// Iterate over the decoded coordinates of the line.
+    #[inline]
+    pub fn coords(&self) -> impl ExactSizeIterator<Item = geo_types::Coord<f64>> + use<'a> {
+        self.coords.iter()
+    }
+
+    /// Iterate over the segments of the line, each coordinate is decoded when reached.
+    #[inline]
+    pub fn segments(&self) -> impl Iterator<Item = geo_types::Line<f64>> + use<'a> {
+        self.coords.segments()
+    }
+
+    /// Convert the [`QuantizedZine`] back to a [`geo_types::LineString`].
+    pub fn to_geo(&self) -> LineString<f64> {
+        self.coords().collect()
+    }
+}
+
+impl<'a> QuantizedZolygon<'a> {
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        Self {
+            coords: unsafe { QuantizedCoords::from_bytes(data) },
+        }
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        let coords = QuantizedCoords::try_from_bytes(data)?;
+        if coords.len() != 0 && coords.coords[0] != coords.coords[coords.len() - 1] {
+            return Err(ZerometryError::UnclosedRing);
+        }
+        Ok(Self { coords })
+    }
+
+    /// Convert the specified [`geo_types::Polygon`] to a valid [`QuantizedZolygon`] slice of bytes in the input buffer.
+    /// The coordinates are rounded to the nearest multiple of `step` from the bottom left corner of the polygon.
+    /// If the polygon contains an interior, the information will be lost and ignored during operations.
+    pub fn write_from_geometry(
+        writer: &mut impl Write,
+        geometry: &Polygon<f64>,
+        step: f64,
+    ) -> Result<(), ZerometryError> {
+        QuantizedCoords::write_from_geometry(writer, geometry.exterior(), step)
+    }
+
+    /// Return the bytes the polygon was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.coords.as_bytes()
+    }
+
+    /// Return the bounding box of the polygon, it bounds the coordinates as they were quantized.
+    #[inline]
+    pub fn bounding_box(&self) -> &'a BoundingBox {
+        self.coords.bounding_box()
+    }
+
+    /// Return the precision the coordinates were stored with, in degrees.
+    #[inline]
+    pub fn step(&self) -> f64 {
+        self.coords.step()
+    }
+
+    /// Return the number of coordinates of the exterior ring, including the one closing it.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Return `true` if the polygon has no coordinates.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.coords.len() == 0
+    }
+
+    /// Decode the coordinate at the specified index.
+    #[inline]
+    pub fn coord(&self, index: usize) -> Option<geo_types::Coord<f64>> {
+        self.coords.get(index)
+    }
+
+    /// Iterate over the decoded coordinates of the exterior ring.
+    #[inline]
+    pub fn coords(&self) -> impl ExactSizeIterator<Item = geo_types::Coord<f64>> + use<'a> {
+        self.coords.iter()
+    }
+
+    /// Iterate over the segments of the exterior ring, each coordinate is decoded when reached.
+    #[inline]
+    pub fn segments(&self) -> impl Iterator<Item = geo_types::Line<f64>> + use<'a> {
+        self.coords.segments()
+    }
+
+    /// Return `true` if the coordinate is strictly inside the polygon.
+    pub fn contains_coord(&self, coord: geo_types::Coord<f64>) -> bool {
+        let bounding_box = self.bounding_box();
+        if self.is_empty()
+            || !bounding_box.horizontal_range().contains(&coord.x)
+            || !bounding_box.vertical_range().contains(&coord.y)
+        {
+            return false;
+        }
+        // Count the number of segments crossed by an horizontal ray going from the coordinate to the right
+        let mut inside = false;
+        for segment in self.segments() {
+            let (a, b) = (segment.start, segment.end);
+            if (a.y > coord.y) != (b.y > coord.y) {
+                let x = a.x + (coord.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if coord.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Convert the [`QuantizedZolygon`] back to a [`geo_types::Polygon`].
+    pub fn to_geo(&self) -> Polygon<f64> {
+        Polygon::new(self.coords().collect(), Vec::new())
+    }
+}
+
+/// The layout shared by the quantized lines and polygons: the bounding box, the step and the coordinates
+/// stored as the number of steps from the bottom left corner of the bounding box.
+#[derive(Clone, Copy)]
+struct QuantizedCoords<'a> {
+    header: &'a [f64; 5],
+    coords: &'a [[u32; 2]],
+}
+
+impl<'a> QuantizedCoords<'a> {
+    unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        let (header, coords) = data.split_at(HEADER_SIZE_IN_BYTES);
+        let header: &[f64] = cast_slice(header);
+        Self {
+            header: header.try_into().unwrap(),
+            coords: cast_slice(coords),
+        }
+    }
+
+    fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        let (_, coords) = split_checked(data, HEADER_SIZE_IN_BYTES)?;
+        if coords.len() % QUANTIZED_COORD_SIZE_IN_BYTES != 0 {
+            return Err(ZerometryError::TrailingBytes(
+                coords.len() % QUANTIZED_COORD_SIZE_IN_BYTES,
+            ));
+        }
+        let quantized = unsafe { Self::from_bytes(data) };
+        let [left, bottom, right, top, step] = *quantized.header;
+        if !is_valid_bounding_box([left, bottom, right, top]) {
+            return Err(ZerometryError::InvalidBoundingBox);
+        }
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(ZerometryError::InvalidQuantizationStep(step));
+        }
+        Ok(quantized)
+    }
+
+    fn write_from_geometry(
+        writer: &mut impl Write,
+        geometry: &LineString<f64>,
+        step: f64,
+    ) -> Result<(), ZerometryError> {
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(ZerometryError::InvalidQuantizationStep(step));
+        }
+        let (left, bottom) = geometry
+            .coords()
+            .fold(None, |corner: Option<(f64, f64)>, coord| {
+                Some(corner.map_or((coord.x, coord.y), |(left, bottom)| {
+                    (left.min(coord.x), bottom.min(coord.y))
+                }))
+            })
+            // Without coords the origin is never used
+            .unwrap_or_default();
+        let quantize = |value: f64, origin: f64| {
+            let steps = ((value - origin) / step).round();
+            if steps <= u32::MAX as f64 {
+                Ok(steps as u32)
+            } else {
+                Err(ZerometryError::InvalidQuantizationStep(step))
+            }
+        };
+        let coords = geometry
+            .coords()
+            .map(|coord| Ok([quantize(coord.x, left)?, quantize(coord.y, bottom)?]))
+            .collect::<Result<Vec<[u32; 2]>, ZerometryError>>()?;
+
+        // The top right corner is computed like the coordinates to be sure it bounds them once decoded
+        let (right, top) = coords
+            .iter()
+            .fold((0, 0), |(right, top), [x, y]| (right.max(*x), top.max(*y)));
+        let [left, bottom, right, top] = if coords.is_empty() {
+            EMPTY_BOUNDING_BOX
+        } else {
+            [
+                left,
+                bottom,
+                dequantize(right, left, step),
+                dequantize(top, bottom, step),
+            ]
+        };
+        let header = [left, bottom, right, top, step];
+        writer.write_all(cast_slice(&header))?;
+        writer.write_all(cast_slice(&coords))?;
+        Ok(())
+    }
+
+    fn as_bytes(&self) -> &'a [u8] {
+        let len = HEADER_SIZE_IN_BYTES + mem::size_of_val(self.coords);
+        contiguous(self.header, self.coords, len, 0)
+    }
+
+    fn bounding_box(&self) -> &'a BoundingBox {
+        unsafe { BoundingBox::from_slice(&self.header[..4]) }
+    }
+
+    fn step(&self) -> f64 {
+        self.header[4]
+    }
+
+    fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    fn get(&self, index: usize) -> Option<geo_types::Coord<f64>> {
+        self.coords.get(index).map(|coord| self.decode(*coord))
+    }
+
+    fn iter(&self) -> impl ExactSizeIterator<Item = geo_types::Coord<f64>> + use<'a> {
+        let quantized = *self;
+        self.coords
+            .iter()
+            .map(move |coord| quantized.decode(*coord))
+    }
+
+    fn segments(&self) -> impl Iterator<Item = geo_types::Line<f64>> + use<'a> {
+        let quantized = *self;
+        self.coords.windows(2).map(move |pair| {
+            geo_types::Line::new(quantized.decode(pair[0]), quantized.decode(pair[1]))
+        })
+    }
+
+    fn decode(&self, [x, y]: [u32; 2]) -> geo_types::Coord<f64> {
+        let [left, bottom, _, _, step] = *self.header;
+        geo_types::Coord {
+            x: dequantize(x, left, step),
+            y: dequantize(y, bottom, step),
+        }
+    }
+}
+
+fn dequantize(steps: u32, origin: f64, step: f64) -> f64 {
+    origin + steps as f64 * step
+}
+
+impl fmt::Debug for QuantizedZine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuantizedZine")
+            .field("bounding_box", &self.bounding_box())
+            .field("step", &self.step())
+            .field("coords", &self.coords.coords)
+            .finish()
+    }
+}
+
+impl fmt::Debug for QuantizedZolygon<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuantizedZolygon")
+            .field("bounding_box", &self.bounding_box())
+            .field("step", &self.step())
+            .field("coords", &self.coords.coords)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{coord, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_quantized() {
+        let line = LineString::from(vec![(2.35221, 48.85661), (2.29451, 48.85837), (2.3, 48.9)]);
+        let mut buffer = Vec::new();
+        QuantizedZine::write_from_geometry(&mut buffer, &line, 1e-3).unwrap();
+        let zine = QuantizedZine::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(zine, @"QuantizedZine { bounding_box: BoundingBox { bottom_left: Coord { lng: 2.29451, lat: 48.85661 }, top_right: Coord { lng: 2.3525099999999997, lat: 48.89961 } }, step: 0.001, coords: [[58, 0], [0, 2], [5, 43]] }");
+        assert_compact_debug_snapshot!(zine.to_geo(), @"LINESTRING(2.3525099999999997 48.85661,2.29451 48.858610000000006,2.2995099999999997 48.89961)");
+        assert_compact_debug_snapshot!(zine.segments().next(), @"Some(LINE(2.3525099999999997 48.85661,2.29451 48.858610000000006))");
+        assert_eq!(zine.as_bytes(), &buffer[..]);
+        for (quantized, original) in zine.coords().zip(line.coords()) {
+            assert!((quantized.x - original.x).abs() <= 5e-4 + f64::EPSILON * 10.0);
+            assert!((quantized.y - original.y).abs() <= 5e-4 + f64::EPSILON * 10.0);
+        }
+
+        let spike = polygon![(x: 0., y: 0.), (x: 10., y: 0.5), (x: 0., y: 1.)];
+        let mut buffer = Vec::new();
+        QuantizedZolygon::write_from_geometry(&mut buffer, &spike, DEFAULT_QUANTIZATION_STEP)
+            .unwrap();
+        let zolygon = QuantizedZolygon::try_from_bytes(&buffer).unwrap();
+        assert_eq!(zolygon.to_geo(), spike);
+        assert!(zolygon.contains_coord(coord! { x: 5.0, y: 0.5 }));
+        assert!(!zolygon.contains_coord(coord! { x: 9.0, y: 0.1 }));
+        assert!(!zolygon.contains_coord(coord! { x: 11.0, y: 0.5 }));
+
+        let err = QuantizedZine::write_from_geometry(&mut Vec::new(), &line, 0.0).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidQuantizationStep(0.0)");
+        // The line is too long for this precision
+        let err = QuantizedZine::write_from_geometry(&mut Vec::new(), &line, 1e-12).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidQuantizationStep(1e-12)");
+
+        // Remove the coord closing the ring
+        let len = buffer.len() - QUANTIZED_COORD_SIZE_IN_BYTES;
+        let err = QuantizedZolygon::try_from_bytes(&buffer[..len]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"UnclosedRing");
+        let err = QuantizedZolygon::try_from_bytes(&buffer[..len - 4]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"TrailingBytes(4)");
+
+        let empty = LineString::new(Vec::new());
+        let mut buffer = Vec::new();
+        QuantizedZine::write_from_geometry(&mut buffer, &empty, DEFAULT_QUANTIZATION_STEP).unwrap();
+        assert!(QuantizedZine::try_from_bytes(&buffer).unwrap().is_empty());
+    }
+}
@@ -7,7 +7,9 @@ use std::ops;
 /// Contains would return true if only one point of a multi-poins is contained in the first shape.
 /// The strict contains only returns true if all the points of the multi-points are contained in the
 /// first shape. It's also way more expensive to compute.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct InputRelation {
     /// Return true if any part on the first shape contains any part of the second shape.
     pub contains: bool,
@@ -25,15 +27,58 @@ pub struct InputRelation {
     /// Return true if there is no relation between both shapes.
     pub disjoint: bool,
 
+    /// Return true if both shapes run along a common portion of line of positive length,
+    /// like two routes sharing a road. Only the lines can overlap.
+    pub overlaps: bool,
+
+    /// Return true if both shapes meet on their boundaries but don't share any interior point,
+    /// like two adjacent parcels or a line ending on the edge of a polygon. Two points never touch.
+    pub touches: bool,
+
+    /// Return true if the interior of a shape goes through both the interior and the exterior of a shape of higher
+    /// dimension, like a line going through a polygon, or if two lines cross each other at some points without
+    /// running along each other. Two polygons or two points never cross.
+    pub crosses: bool,
+
+    /// Return true if no point of the second shape lies outside of the first shape. Unlike [`Self::contains`],
+    /// the boundary of the first shape counts, a point on the edge of a polygon is covered by it.
+    pub covers: bool,
+    /// Return true if no point of the first shape lies outside of the second shape, see [`Self::covers`].
+    pub covered_by: bool,
+
     /// If set to `true` the relation algorithm will stop as soon as possible after filling any value.
     /// For example if you are asking if a shape contains, is contained or intersect with another but
     /// don't really care about which of these happened you can set `early_exit` to true and the relation
     /// algorithm will be able to exit directly after finding the first intersection for example.
     pub early_exit: bool,
+
+    /// Maximum number of segment tests the relation is allowed to perform.
+    /// It's checked against [`RelationStats::segment_tests`] before starting and if the budget is exceeded
+    /// nothing is computed and all the fields of the [`OutputRelation`] are returned as `None`.
+    /// This is useful to ensure a pathological shape cannot stall a thread.
+    ///
+    /// It's an estimate, the segment tests actually performed are not counted while the relation runs.
+    /// It's only enforced by the relation between two [`crate::Zerometry`], which is also used when relating a
+    /// [`crate::Zerometry`] with a [`geo_types::Geometry`]. The relations between the specific shapes, like a
+    /// [`crate::Zolygon`] and a [`crate::Zine`], ignore it: convert them to [`crate::Zerometry`] to bound their cost.
+    pub budget: Option<usize>,
+
+    /// If set to `true` the relation is resolved using only the bounding boxes of the shapes, which is very cheap
+    /// but possibly wrong, and the [`OutputRelation`] is marked as [`approximate`](OutputRelation::approximate).
+    /// It's useful as a first pass to order the candidates before computing their exact relation.
+    /// Only the disjoint shapes are exactly known since their bounding boxes don't overlap, otherwise the relations
+    /// that can't be guessed from the bounding boxes, like the touches or the covers, are left to `None`.
+    pub approximate: bool,
+
+    /// The distance under which the coordinates of both shapes are considered to be the same point, `0.0` by default.
+    /// It absorbs the floating point noise of the reprojected data: two parcels separated by a gap thinner than
+    /// the tolerance meet on their boundaries, they intersect and touch instead of being disjoint.
+    pub tolerance: f64,
 }
 
 impl InputRelation {
-    /// Set everything to `true` and cannot early exit.
+    /// Set everything to `true` except the relations depending on the locations of the shapes relative to each
+    /// other, and cannot early exit. See [`Self::all_with_topology`] to also compute them.
     pub fn all() -> Self {
         Self {
             contains: true,
@@ -42,11 +87,20 @@ impl InputRelation {
             strict_contained: true,
             intersect: true,
             disjoint: true,
+            overlaps: true,
+            touches: false,
+            crosses: false,
+            covers: false,
+            covered_by: false,
             early_exit: false,
+            budget: None,
+            approximate: false,
+            tolerance: 0.0,
         }
     }
 
-    /// Set everything to `true` but can early exit.
+    /// Set everything to `true` except the relations depending on the locations of the shapes relative to each
+    /// other, but can early exit. See [`Self::any_with_topology`] to also compute them.
     pub fn any() -> Self {
         Self {
             contains: true,
@@ -55,14 +109,46 @@ impl InputRelation {
             strict_contained: true,
             intersect: true,
             disjoint: true,
+            overlaps: true,
+            touches: false,
+            crosses: false,
+            covers: false,
+            covered_by: false,
             early_exit: true,
+            budget: None,
+            approximate: false,
+            tolerance: 0.0,
         }
     }
 
-    /// Swap the contains and contained relation.
+    /// Set everything to `true`, including the [`touches`](Self::touches), [`crosses`](Self::crosses),
+    /// [`covers`](Self::covers) and [`covered_by`](Self::covered_by), and cannot early exit.
+    ///
+    /// These relations depend on the locations of the shapes relative to each other: every segment of a shape
+    /// is cut by all the segments of the other shape, which is way more expensive than the other relations.
+    pub fn all_with_topology() -> Self {
+        Self {
+            touches: true,
+            crosses: true,
+            covers: true,
+            covered_by: true,
+            ..Self::all()
+        }
+    }
+
+    /// Same as [`Self::all_with_topology`] but can early exit.
+    pub fn any_with_topology() -> Self {
+        Self {
+            early_exit: true,
+            ..Self::all_with_topology()
+        }
+    }
+
+    /// Swap the contains and contained relation, along with the covers and covered by.
     pub fn swap_contains_relation(mut self) -> Self {
         std::mem::swap(&mut self.contains, &mut self.contained);
         std::mem::swap(&mut self.strict_contains, &mut self.strict_contained);
+        std::mem::swap(&mut self.covers, &mut self.covered_by);
         self
     }
 
@@ -99,18 +185,28 @@ impl InputRelation {
         self.disjoint = false;
         self
     }
+
+    /// Return `true` if a relation computed from the locations of the shapes relative to each other is requested,
+    /// or if the coordinates must be compared with a tolerance.
+    pub(crate) fn needs_topology(&self) -> bool {
+        self.touches || self.crosses || self.covers || self.covered_by || self.tolerance > 0.0
+    }
 }
 
 /// Returned by the `relation` function.
 /// All fields are made of a `Option<bool>`.
-/// There are two cases for which a field can be None:
+/// There are three cases for which a field can be None:
 /// - If you didn't ask for it when filling the `InputRelation` struct
 /// - If the relation algorithm didn't evaluate this relation because the
 ///   `early_exit` flag was set.
+/// - If the relation between two [`crate::Zerometry`] would have exceeded the `budget` set in the `InputRelation`.
+/// - If the [`geo_types`] shape related with a [`crate::Zerometry`] couldn't be serialized.
+/// - If the relation can't be computed for these shapes, like the touches of a [`crate::Complement`].
 ///
 /// Note that when early exit is set, most fields will be set to `Some(false)` even
 /// though they were not evaluated at all.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutputRelation {
     /// Return true if any part on the first shape contains any part of the second shape.
     pub contains: Option<bool>,
@@ -124,6 +220,19 @@ pub struct OutputRelation {
     pub intersect: Option<bool>,
     /// Return true if there is no relation between both shapes.
     pub disjoint: Option<bool>,
+    /// Return true if both shapes run along a common portion of line of positive length.
+    pub overlaps: Option<bool>,
+    /// Return true if both shapes meet on their boundaries but don't share any interior point.
+    pub touches: Option<bool>,
+    /// Return true if a shape goes through the interior and the exterior of the other, or if two lines cross.
+    pub crosses: Option<bool>,
+    /// Return true if no point of the second shape lies outside of the first shape, its boundary included.
+    pub covers: Option<bool>,
+    /// Return true if no point of the first shape lies outside of the second shape, its boundary included.
+    pub covered_by: Option<bool>,
+    /// `true` if the relation was resolved using only the bounding boxes of the shapes and may be wrong,
+    /// see [`InputRelation::approximate`].
+    pub approximate: bool,
 }
 
 impl OutputRelation {
@@ -135,6 +244,12 @@ impl OutputRelation {
             strict_contained: relation.strict_contained.then_some(false),
             intersect: relation.intersect.then_some(false),
             disjoint: relation.disjoint.then_some(false),
+            overlaps: relation.overlaps.then_some(false),
+            touches: relation.touches.then_some(false),
+            crosses: relation.crosses.then_some(false),
+            covers: relation.covers.then_some(false),
+            covered_by: relation.covered_by.then_some(false),
+            approximate: false,
         }
     }
 
@@ -146,6 +261,12 @@ impl OutputRelation {
             strict_contained: relation.strict_contained.then_some(true),
             intersect: relation.intersect.then_some(true),
             disjoint: relation.disjoint.then_some(true),
+            overlaps: relation.overlaps.then_some(true),
+            touches: relation.touches.then_some(true),
+            crosses: relation.crosses.then_some(true),
+            covers: relation.covers.then_some(true),
+            covered_by: relation.covered_by.then_some(true),
+            approximate: false,
         }
     }
 
@@ -181,12 +302,62 @@ impl OutputRelation {
         self
     }
 
+    pub(crate) fn make_overlaps_if_set(mut self) -> Self {
+        self.overlaps = self.overlaps.map(|_| true);
+        self
+    }
+
+    pub(crate) fn make_touches_if_set(mut self) -> Self {
+        self.touches = self.touches.map(|_| true);
+        self
+    }
+
+    pub(crate) fn make_crosses_if_set(mut self) -> Self {
+        self.crosses = self.crosses.map(|_| true);
+        self
+    }
+
+    pub(crate) fn make_covers_if_set(mut self) -> Self {
+        self.covers = self.covers.map(|_| true);
+        self
+    }
+
+    pub(crate) fn make_covered_by_if_set(mut self) -> Self {
+        self.covered_by = self.covered_by.map(|_| true);
+        self
+    }
+
     pub(crate) fn strip_strict(mut self) -> Self {
         self.strict_contains = None;
         self.strict_contained = None;
         self
     }
 
+    /// Return how much the relation can be trusted, see [`InputRelation::approximate`].
+    ///
+    /// ```
+    /// use zerometry::{Certainty, InputRelation, RelationBetweenShapes, ZerometryBuf};
+    /// use geo_types::{Geometry, Point, polygon};
+    ///
+    /// let triangle = ZerometryBuf::from(Geometry::Polygon(polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.)]));
+    /// let point = Geometry::Point(Point::new(1.0, 3.0));
+    /// let first_pass = InputRelation { contains: true, approximate: true, ..InputRelation::default() };
+    /// let relation = triangle.as_zerometry().relation(&point, first_pass);
+    /// assert_eq!(relation.certainty(), Certainty::BboxOnly);
+    ///
+    /// // The point is only in the bounding box of the triangle, a refinement pass is needed
+    /// let relation = triangle.as_zerometry().relation(&point, InputRelation { approximate: false, ..first_pass });
+    /// assert_eq!(relation.contains, Some(false));
+    /// assert_eq!(relation.certainty(), Certainty::Exact);
+    /// ```
+    pub fn certainty(&self) -> Certainty {
+        if self.approximate {
+            Certainty::BboxOnly
+        } else {
+            Certainty::Exact
+        }
+    }
+
     /// Return true if the output contains anything except disjoint.
     pub fn any_relation(&self) -> bool {
         // If the shape are distinct we don't need to check anything else and can stop early
@@ -196,15 +367,117 @@ impl OutputRelation {
                 || self.strict_contains.unwrap_or_default()
                 || self.contained.unwrap_or_default()
                 || self.strict_contained.unwrap_or_default()
-                || self.intersect.unwrap_or_default())
+                || self.intersect.unwrap_or_default()
+                || self.overlaps.unwrap_or_default()
+                || self.touches.unwrap_or_default()
+                || self.crosses.unwrap_or_default()
+                || self.covers.unwrap_or_default()
+                || self.covered_by.unwrap_or_default())
     }
 
-    /// Swap the contains and contained relation.
+    /// Swap the contains and contained relation, along with the covers and covered by.
     pub fn swap_contains_relation(mut self) -> Self {
         std::mem::swap(&mut self.contains, &mut self.contained);
         std::mem::swap(&mut self.strict_contains, &mut self.strict_contained);
+        std::mem::swap(&mut self.covers, &mut self.covered_by);
         self
     }
+
+    /// Merge the relations computed between a shape and the multiple geo fields of a single document.
+    ///
+    /// Every field of the output is combined independently following the [`FieldsCombination`]:
+    /// - With [`FieldsCombination::Any`] a relation is `true` if it's `true` for at least one field.
+    /// - With [`FieldsCombination::All`] a relation is `true` only if it's `true` for every field.
+    ///
+    /// A relation that was not computed (`None`) for a field is unknown. It's ignored if the result
+    /// is already known thanks to the other fields and makes the result unknown otherwise.
+    /// If there are no fields, everything is unknown.
+    pub fn combine_fields(
+        relations: impl IntoIterator<Item = OutputRelation>,
+        combination: FieldsCombination,
+    ) -> OutputRelation {
+        // The value that decides the result by itself
+        let decisive = combination == FieldsCombination::Any;
+        let combine = |acc: Option<bool>, value: Option<bool>| match (acc, value) {
+            (Some(acc), _) if acc == decisive => Some(acc),
+            (_, Some(value)) if value == decisive => Some(value),
+            (Some(acc), Some(_)) => Some(acc),
+            _ => None,
+        };
+
+        let mut relations = relations.into_iter();
+        let Some(first) = relations.next() else {
+            return OutputRelation::default();
+        };
+        let mut approximate = first.approximate;
+        let [
+            contains,
+            strict_contains,
+            contained,
+            strict_contained,
+            intersect,
+            disjoint,
+            overlaps,
+            touches,
+            crosses,
+            covers,
+            covered_by,
+        ] = relations.fold(first.fields(), |acc, relation| {
+            approximate |= relation.approximate;
+            let fields = relation.fields();
+            std::array::from_fn(|i| combine(acc[i], fields[i]))
+        });
+
+        OutputRelation {
+            contains,
+            strict_contains,
+            contained,
+            strict_contained,
+            intersect,
+            disjoint,
+            overlaps,
+            touches,
+            crosses,
+            covers,
+            covered_by,
+            approximate,
+        }
+    }
+
+    fn fields(&self) -> [Option<bool>; 11] {
+        [
+            self.contains,
+            self.strict_contains,
+            self.contained,
+            self.strict_contained,
+            self.intersect,
+            self.disjoint,
+            self.overlaps,
+            self.touches,
+            self.crosses,
+            self.covers,
+            self.covered_by,
+        ]
+    }
+}
+
+/// How much an [`OutputRelation`] can be trusted, returned by [`OutputRelation::certainty`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Certainty {
+    /// The relation was resolved using only the bounding boxes and may be wrong, it must be refined to be exact.
+    BboxOnly,
+    /// The relation was computed on the shapes themselves.
+    Exact,
+}
+
+/// How [`OutputRelation::combine_fields`] merges the relations of several fields of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldsCombination {
+    /// The relation holds for the document if it holds for any of its fields.
+    Any,
+    /// The relation holds for the document only if it holds for all of its fields.
+    All,
 }
 
 impl ops::BitOr for OutputRelation {
@@ -218,6 +491,12 @@ impl ops::BitOr for OutputRelation {
             mut strict_contained,
             mut intersect,
             mut disjoint,
+            mut overlaps,
+            mut touches,
+            mut crosses,
+            mut covers,
+            mut covered_by,
+            approximate,
         } = self;
 
         if let Some(ref mut s) = contains {
@@ -244,6 +523,26 @@ impl ops::BitOr for OutputRelation {
             *s |= other.disjoint.unwrap_or_default()
         }
 
+        if let Some(ref mut s) = overlaps {
+            *s |= other.overlaps.unwrap_or_default()
+        }
+
+        if let Some(ref mut s) = touches {
+            *s |= other.touches.unwrap_or_default()
+        }
+
+        if let Some(ref mut s) = crosses {
+            *s |= other.crosses.unwrap_or_default()
+        }
+
+        if let Some(ref mut s) = covers {
+            *s |= other.covers.unwrap_or_default()
+        }
+
+        if let Some(ref mut s) = covered_by {
+            *s |= other.covered_by.unwrap_or_default()
+        }
+
         Self {
             contains,
             strict_contains,
@@ -251,6 +550,12 @@ impl ops::BitOr for OutputRelation {
             strict_contained,
             intersect,
             disjoint,
+            overlaps,
+            touches,
+            crosses,
+            covers,
+            covered_by,
+            approximate: approximate || other.approximate,
         }
     }
 }
@@ -261,6 +566,19 @@ impl ops::BitOrAssign for OutputRelation {
     }
 }
 
+/// Estimation of the work required to compute the relation between two shapes.
+/// Retrieved with [`crate::Zerometry::relation_stats`], it can be used to tune the
+/// [`InputRelation::budget`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RelationStats {
+    /// Number of points and segments of the first shape.
+    pub parts: usize,
+    /// Number of points and segments of the second shape.
+    pub other_parts: usize,
+    /// Upper bound of the number of segment tests required to compute the relation.
+    pub segment_tests: usize,
+}
+
 /// Lets you query the relation between two shapes.
 pub trait RelationBetweenShapes<Other: ?Sized> {
     /// Return the relation between two shapes.
@@ -288,7 +606,7 @@ pub trait RelationBetweenShapes<Other: ?Sized> {
     /// ```
     fn relation(&self, other: &Other, relation: InputRelation) -> OutputRelation;
 
-    /// Return all relations with no early return.
+    /// Return all relations with no early return, except the topology ones, see [`InputRelation::all`].
     fn all_relation(&self, other: &Other) -> OutputRelation {
         self.relation(other, InputRelation::all())
     }
@@ -382,3 +700,41 @@ pub trait RelationBetweenShapes<Other: ?Sized> {
         .unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn combine_fields() {
+        let contained = OutputRelation {
+            contained: Some(true),
+            intersect: Some(false),
+            disjoint: Some(false),
+            ..Default::default()
+        };
+        let disjoint = OutputRelation {
+            contained: Some(false),
+            intersect: Some(false),
+            disjoint: Some(true),
+            ..Default::default()
+        };
+        let unknown = OutputRelation {
+            contained: None,
+            intersect: Some(false),
+            disjoint: None,
+            ..Default::default()
+        };
+
+        let fields = [contained, disjoint, unknown];
+        assert_compact_debug_snapshot!(OutputRelation::combine_fields(fields, FieldsCombination::Any), @"OutputRelation { contains: None, strict_contains: None, contained: Some(true), strict_contained: None, intersect: Some(false), disjoint: Some(true), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(OutputRelation::combine_fields(fields, FieldsCombination::All), @"OutputRelation { contains: None, strict_contains: None, contained: Some(false), strict_contained: None, intersect: Some(false), disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(OutputRelation::combine_fields([contained, unknown], FieldsCombination::All), @"OutputRelation { contains: None, strict_contains: None, contained: None, strict_contained: None, intersect: Some(false), disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_eq!(
+            OutputRelation::combine_fields([], FieldsCombination::Any),
+            OutputRelation::default()
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{Coords, Segment, Zerometry, ZultiLines, ZultiPolygons};
+
+impl<'a> Zerometry<'a> {
+    /// Return the closed rings of the shape: the exterior of every polygon, including the polygons of a collection.
+    /// The first and last coords of a ring are the same. The points and lines don't have any ring.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, MultiPolygon, polygon};
+    ///
+    /// let multi_polygon = MultiPolygon::new(vec![
+    ///     polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+    ///     polygon![(x: 5., y: 5.), (x: 6., y: 5.), (x: 6., y: 6.)],
+    /// ]);
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &multi_polygon.into()).unwrap();
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    ///
+    /// let rings: Vec<_> = zerometry.rings().map(|ring| ring.len()).collect();
+    /// assert_eq!(rings, [4, 4]);
+    /// ```
+    pub fn rings(&self) -> impl Iterator<Item = &'a Coords> + use<'a> {
+        let (polygon, polygons) = match self {
+            Zerometry::Polygon(polygon) => (Some(polygon.coords()), None),
+            Zerometry::MultiPolygon(polygons) => (None, Some(*polygons)),
+            Zerometry::Collection(collection) => (None, Some(collection.polygons())),
+            _ => (None, None),
+        };
+        polygon
+            .into_iter()
+            .chain(polygons.into_iter().flat_map(polygons_coords))
+    }
+
+    /// Return the segments between the consecutive coords of every line and ring of the shape, see [`Self::rings`].
+    /// The points don't have any segment.
+    pub fn segments(&self) -> impl Iterator<Item = Segment<'a>> + use<'a> {
+        let (line, lines) = match self {
+            Zerometry::Line(line) => (Some(line.coords()), None),
+            Zerometry::MultiLines(lines) => (None, Some(*lines)),
+            Zerometry::Collection(collection) => (None, Some(collection.lines())),
+            _ => (None, None),
+        };
+        line.into_iter()
+            .chain(lines.into_iter().flat_map(lines_coords))
+            .chain(self.rings())
+            .flat_map(|coords| coords.consecutive_pairs())
+            // SAFETY: The pairs returned by coords are aligned and contains only two points
+            .map(|coords| unsafe { Segment::from_slice(coords) })
+    }
+}
+
+fn lines_coords(lines: ZultiLines<'_>) -> impl Iterator<Item = &Coords> {
+    (0..lines.len()).map(move |index| lines.get(index).unwrap().coords())
+}
+
+fn polygons_coords(polygons: ZultiPolygons<'_>) -> impl Iterator<Item = &Coords> {
+    (0..polygons.len()).map(move |index| polygons.get(index).unwrap().coords())
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPoint, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn rings_and_segments() {
+        let collection = GeometryCollection::new_from(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            Point::new(5.0, 5.0).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)]).into(),
+        ]);
+        let geometries: [Geometry<f64>; 4] = [
+            Point::new(1.0, 2.0).into(),
+            MultiPoint::from(vec![(1.0, 2.0), (3.0, 4.0)]).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+            Geometry::GeometryCollection(collection),
+        ];
+        let counts: Vec<_> = geometries
+            .iter()
+            .map(|geometry| {
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, geometry).unwrap();
+                let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+                (zerometry.rings().count(), zerometry.segments().count())
+            })
+            .collect();
+        assert_compact_debug_snapshot!(counts, @"[(0, 0), (0, 0), (0, 1), (1, 5)]");
+
+        let mut buffer = Vec::new();
+        let triangle = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)];
+        Zerometry::write_from_geometry(&mut buffer, &triangle.into()).unwrap();
+        let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+        let segments: Vec<_> = zerometry
+            .segments()
+            .map(|segment| (segment.start().to_geo(), segment.end().to_geo()))
+            .collect();
+        assert_compact_debug_snapshot!(segments, @"[(COORD(0.0 0.0), COORD(1.0 0.0)), (COORD(1.0 0.0), COORD(1.0 1.0)), (COORD(1.0 1.0), COORD(0.0 0.0))]");
+    }
+}
@@ -1,5 +1,7 @@
 use core::fmt;
 
+use geo::line_intersection::LineIntersection;
+
 use crate::{Coord, Coords};
 
 /// A segment is a line between two points.
@@ -66,6 +68,30 @@ impl<'a> Segment<'a> {
             &geo_types::Line::new(other.start.to_geo(), other.end.to_geo()),
         )
     }
+
+    /// Returns true if both segments are collinear and share a portion of positive length.
+    /// Two segments that only touch at one point don't overlap.
+    #[inline]
+    pub fn overlaps(&self, other: &Segment) -> bool {
+        let intersection = geo::line_intersection::line_intersection(
+            geo_types::Line::new(self.start.to_geo(), self.end.to_geo()),
+            geo_types::Line::new(other.start.to_geo(), other.end.to_geo()),
+        );
+        matches!(
+            intersection,
+            Some(LineIntersection::Collinear { intersection }) if intersection.start != intersection.end
+        )
+    }
+
+    /// Returns true if the coord lies exactly on the segment, including its ends.
+    /// The test relies on robust predicates so it doesn't depend on the rounding errors.
+    #[inline]
+    pub fn contains_coord(&self, coord: &Coord) -> bool {
+        geo::intersects::Intersects::intersects(
+            &geo_types::Line::new(self.start.to_geo(), self.end.to_geo()),
+            &coord.to_geo(),
+        )
+    }
 }
 
 impl<'a> fmt::Debug for Segment<'a> {
@@ -88,16 +114,16 @@ mod tests {
         let data = [1.0, 2.0, 3.0, 4.0];
         let bb = unsafe { Segment::from_bytes(cast_slice(&data)) };
         insta::assert_debug_snapshot!(bb, @r"
-        Segment {
-            start: Coord {
-                x: 1.0,
-                y: 2.0,
-            },
-            end: Coord {
-                x: 3.0,
-                y: 4.0,
-            },
-        }
+            Segment {
+                start: Coord {
+                    lng: 1.0,
+                    lat: 2.0,
+                },
+                end: Coord {
+                    lng: 3.0,
+                    lat: 4.0,
+                },
+            }
         ");
     }
 
@@ -134,16 +160,16 @@ mod tests {
         let data = [1.0, 2.0, 3.0, 4.0];
         let bb = unsafe { Segment::from_slice(&data) };
         insta::assert_debug_snapshot!(bb, @r"
-        Segment {
-            start: Coord {
-                x: 1.0,
-                y: 2.0,
-            },
-            end: Coord {
-                x: 3.0,
-                y: 4.0,
-            },
-        }
+            Segment {
+                start: Coord {
+                    lng: 1.0,
+                    lat: 2.0,
+                },
+                end: Coord {
+                    lng: 3.0,
+                    lat: 4.0,
+                },
+            }
         ");
     }
 
@@ -196,8 +222,8 @@ mod tests {
 
     #[test]
     fn bug_missing_intersection() {
-        // ray: Segment { start: Coord { x: -6.436337296790293, y: 49.63676497357687 }, end: Coord { x: 6.0197316417968105, y: 49.63676497357687 } }
-        // segment: Segment { start: Coord { x: 1.188509553443464, y: 49.47027919866874 }, end: Coord { x: 3.6300086390995316, y: 50.610463312569514 } }
+        // ray: Segment { start: Coord { lng: -6.436337296790293, lat: 49.63676497357687 }, end: Coord { lng: 6.0197316417968105, lat: 49.63676497357687 } }
+        // segment: Segment { start: Coord { lng: 1.188509553443464, lat: 49.47027919866874 }, end: Coord { lng: 3.6300086390995316, lat: 50.610463312569514 } }
         let ray = unsafe {
             Segment::from_slice(&[
                 -6.436337296790293,
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, SeqAccess, Visitor},
+};
+
+use crate::{Zerometry, ZerometryBuf, wkb::write_zerometry};
+
+/// The zerometries are serialized as WKB, which doesn't depend on the endianness of the machine and can be read
+/// by most of the geo libraries, see [`Zerometry::to_wkb`].
+/// A borrowed zerometry can't be deserialized since its bytes must be aligned, deserialize a [`ZerometryBuf`] instead.
+impl Serialize for Zerometry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut wkb = Vec::new();
+        write_zerometry(&mut wkb, self).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(&wkb)
+    }
+}
+
+/// Serialized as WKB, like [`Zerometry`].
+impl Serialize for ZerometryBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_zerometry().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ZerometryBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(WkbVisitor)
+    }
+}
+
+struct WkbVisitor;
+
+impl<'de> Visitor<'de> for WkbVisitor {
+    type Value = ZerometryBuf;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the WKB bytes of a geometry")
+    }
+
+    fn visit_bytes<E: de::Error>(self, wkb: &[u8]) -> Result<ZerometryBuf, E> {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_wkb(&mut buffer, wkb).map_err(E::custom)?;
+        ZerometryBuf::try_from_bytes(&buffer).map_err(E::custom)
+    }
+
+    // The formats without a bytes type, like JSON, store them as a sequence
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ZerometryBuf, A::Error> {
+        let mut wkb = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            wkb.push(byte);
+        }
+        self.visit_bytes(&wkb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, GeometryCollection, LineString, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{InputRelation, OutputRelation, RelationBetweenShapes};
+
+    #[test]
+    fn json_round_trip() {
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            Point::new(5.0, 5.0).into(),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+        ]));
+        let shape = ZerometryBuf::from(&collection);
+        let json = serde_json::to_string(&shape.as_zerometry()).unwrap();
+        let read: ZerometryBuf = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.as_zerometry().to_geo(), shape.as_zerometry().to_geo());
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+
+        let error = serde_json::from_str::<ZerometryBuf>("[1, 2, 3]").unwrap_err();
+        assert_compact_debug_snapshot!(error.to_string(), @r#""The WKB geometry is truncated at line 1 column 9""#);
+
+        // The missing fields of the input are not computed
+        let input: InputRelation = serde_json::from_str(r#"{ "contains": true }"#).unwrap();
+        let triangle = ZerometryBuf::from(Geometry::Polygon(
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)],
+        ));
+        let point = Geometry::Point(Point::new(0.9, 0.1));
+        let output = triangle.as_zerometry().relation(&point, input);
+        let json = serde_json::to_string(&output).unwrap();
+        assert_compact_debug_snapshot!(json, @r#""{\"contains\":true,\"strict_contains\":null,\"contained\":null,\"strict_contained\":null,\"intersect\":null,\"disjoint\":null,\"overlaps\":null,\"touches\":null,\"crosses\":null,\"covers\":null,\"covered_by\":null,\"approximate\":false}""#);
+        assert_eq!(
+            serde_json::from_str::<OutputRelation>(&json).unwrap(),
+            output
+        );
+    }
+}
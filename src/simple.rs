@@ -0,0 +1,264 @@
+use std::iter;
+
+use crate::{
+    Coords, Segment, Zerometry, ZerometryError, Zine, Zollection, Zolygon, ZultiLines,
+    ZultiPolygons,
+};
+
+impl<'a> Zerometry<'a> {
+    /// Iterate over all the segments of the lines and polygon rings of the shape with their index.
+    /// The indices keep increasing from one member to the next, in the order they're stored, which makes them
+    /// usable to point at a segment of the whole shape, see [`Self::check_simple`].
+    /// The points and multi points have no segments.
+    pub fn segments_enumerated(&self) -> impl Iterator<Item = (usize, Segment<'a>)> + use<'a> {
+        let rings: Vec<_> = match self {
+            Zerometry::Point(_) | Zerometry::MultiPoints(_) => Vec::new(),
+            Zerometry::Line(line) => line.rings().collect(),
+            Zerometry::MultiLines(lines) => lines.rings().collect(),
+            Zerometry::Polygon(polygon) => polygon.rings().collect(),
+            Zerometry::MultiPolygon(polygons) => polygons.rings().collect(),
+            Zerometry::Collection(collection) => collection.rings().collect(),
+        };
+        enumerate_segments(rings.into_iter())
+    }
+
+    /// Return an error if a line or a polygon ring of the shape crosses or touches itself.
+    /// The error contains the indices of the first two segments found intersecting, as yielded by
+    /// [`Self::segments_enumerated`], so they can be located in a large shape.
+    ///
+    /// The consecutive segments of a line or ring always share a coord and are never considered intersecting,
+    /// and the different members of a multi shape or collection are allowed to intersect each other.
+    ///
+    /// ```
+    /// use zerometry::{Zerometry, ZerometryError};
+    /// use geo_types::{Geometry, polygon};
+    ///
+    /// let bowtie = Geometry::Polygon(polygon![(x: 0., y: 0.), (x: 2., y: 2.), (x: 2., y: 0.), (x: 0., y: 2.)]);
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &bowtie).unwrap();
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    ///
+    /// let err = zerometry.check_simple().unwrap_err();
+    /// assert!(matches!(err, ZerometryError::SelfIntersection { first: 0, second: 2 }));
+    /// ```
+    pub fn check_simple(&self) -> Result<(), ZerometryError> {
+        match self {
+            Zerometry::Point(_) | Zerometry::MultiPoints(_) => Ok(()),
+            Zerometry::Line(line) => line.check_simple(),
+            Zerometry::MultiLines(lines) => lines.check_simple(),
+            Zerometry::Polygon(polygon) => polygon.check_simple(),
+            Zerometry::MultiPolygon(polygons) => polygons.check_simple(),
+            Zerometry::Collection(collection) => collection.check_simple(),
+        }
+    }
+}
+
+impl<'a> Zine<'a> {
+    /// Iterate over the segments of the line with their index, see [`Zerometry::segments_enumerated`].
+    pub fn segments_enumerated(&self) -> impl Iterator<Item = (usize, Segment<'a>)> + use<'a> {
+        enumerate_segments(self.rings())
+    }
+
+    /// Return an error if the line crosses or touches itself, see [`Zerometry::check_simple`].
+    pub fn check_simple(&self) -> Result<(), ZerometryError> {
+        check_rings(self.rings())
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &'a Coords> + use<'a> {
+        iter::once(self.coords())
+    }
+}
+
+impl<'a> ZultiLines<'a> {
+    /// Iterate over the segments of all the lines with their index, see [`Zerometry::segments_enumerated`].
+    pub fn segments_enumerated(&self) -> impl Iterator<Item = (usize, Segment<'a>)> + use<'a> {
+        enumerate_segments(self.rings())
+    }
+
+    /// Return an error if one of the lines crosses or touches itself, see [`Zerometry::check_simple`].
+    pub fn check_simple(&self) -> Result<(), ZerometryError> {
+        check_rings(self.rings())
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &'a Coords> + use<'a> {
+        let lines = *self;
+        (0..lines.len()).map(move |index| lines.get(index).unwrap().coords())
+    }
+}
+
+impl<'a> Zolygon<'a> {
+    /// Iterate over the segments of the polygon with their index, see [`Zerometry::segments_enumerated`].
+    pub fn segments_enumerated(&self) -> impl Iterator<Item = (usize, Segment<'a>)> + use<'a> {
+        enumerate_segments(self.rings())
+    }
+
+    /// Return an error if the ring of the polygon crosses or touches itself, see [`Zerometry::check_simple`].
+    pub fn check_simple(&self) -> Result<(), ZerometryError> {
+        check_rings(self.rings())
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &'a Coords> + use<'a> {
+        iter::once(self.coords())
+    }
+}
+
+impl<'a> ZultiPolygons<'a> {
+    /// Iterate over the segments of all the polygons with their index, see [`Zerometry::segments_enumerated`].
+    pub fn segments_enumerated(&self) -> impl Iterator<Item = (usize, Segment<'a>)> + use<'a> {
+        enumerate_segments(self.rings())
+    }
+
+    /// Return an error if the ring of one of the polygons crosses or touches itself,
+    /// see [`Zerometry::check_simple`].
+    pub fn check_simple(&self) -> Result<(), ZerometryError> {
+        check_rings(self.rings())
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &'a Coords> + use<'a> {
+        let polygons = *self;
+        (0..polygons.len()).map(move |index| polygons.get(index).unwrap().coords())
+    }
+}
+
+impl<'a> Zollection<'a> {
+    /// Iterate over the segments of the lines and then of the polygons with their index,
+    /// see [`Zerometry::segments_enumerated`].
+    pub fn segments_enumerated(&self) -> impl Iterator<Item = (usize, Segment<'a>)> + use<'a> {
+        enumerate_segments(self.rings())
+    }
+
+    /// Return an error if one of the lines or polygon rings crosses or touches itself,
+    /// see [`Zerometry::check_simple`].
+    pub fn check_simple(&self) -> Result<(), ZerometryError> {
+        check_rings(self.rings())
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &'a Coords> + use<'a> {
+        self.lines().rings().chain(self.polygons().rings())
+    }
+}
+
+fn enumerate_segments<'a>(
+    rings: impl Iterator<Item = &'a Coords>,
+) -> impl Iterator<Item = (usize, Segment<'a>)> {
+    rings
+        .flat_map(|coords| {
+            coords
+                .consecutive_pairs()
+                // SAFETY: The pairs returned by coords are aligned and contains only two points
+                .map(|pair| unsafe { Segment::from_slice(pair) })
+        })
+        .enumerate()
+}
+
+/// Check every ring on its own, the indices of the segments keep increasing from one ring to the next.
+fn check_rings<'a>(rings: impl Iterator<Item = &'a Coords>) -> Result<(), ZerometryError> {
+    let mut offset = 0;
+    for coords in rings {
+        let segments: Vec<Segment> = enumerate_segments(iter::once(coords))
+            .map(|(_, segment)| segment)
+            .collect();
+        if let Some((first, second)) = first_self_intersection(&segments) {
+            return Err(ZerometryError::SelfIntersection {
+                first: offset + first,
+                second: offset + second,
+            });
+        }
+        offset += segments.len();
+    }
+    Ok(())
+}
+
+/// Return the lowest pair of indices of non-consecutive segments intersecting each other.
+/// The segments are swept from left to right to only compare the ones whose longitudes overlap.
+fn first_self_intersection(segments: &[Segment]) -> Option<(usize, usize)> {
+    let closed = segments.len() > 2
+        && segments[0].start().lng() == segments[segments.len() - 1].end().lng()
+        && segments[0].start().lat() == segments[segments.len() - 1].end().lat();
+    let consecutive = |a: usize, b: usize| {
+        a.abs_diff(b) == 1 || (closed && a.min(b) == 0 && a.max(b) == segments.len() - 1)
+    };
+    let left = |segment: &Segment| segment.start().lng().min(segment.end().lng());
+    let right = |segment: &Segment| segment.start().lng().max(segment.end().lng());
+
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|a, b| left(&segments[*a]).total_cmp(&left(&segments[*b])));
+
+    let mut found: Option<(usize, usize)> = None;
+    for (position, &a) in order.iter().enumerate() {
+        let end = right(&segments[a]);
+        for &b in order[position + 1..]
+            .iter()
+            .take_while(|b| left(&segments[**b]) <= end)
+        {
+            if !consecutive(a, b) && segments[a].intersects(&segments[b]) {
+                let pair = (a.min(b), a.max(b));
+                found = Some(found.map_or(pair, |found| found.min(pair)));
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString, MultiLineString, MultiPolygon, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    fn check(geometry: Geometry<f64>) -> Result<(), crate::ZerometryError> {
+        let mut buffer = Vec::new();
+        crate::Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+        crate::Zerometry::try_from_bytes(&buffer)
+            .unwrap()
+            .check_simple()
+    }
+
+    #[test]
+    fn test_segments_enumerated() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(5.0, 5.0), (6.0, 6.0)]);
+        let collection =
+            Geometry::GeometryCollection(geo_types::GeometryCollection::new_from(vec![
+                square.into(),
+                line.into(),
+            ]));
+        let mut buffer = Vec::new();
+        crate::Zerometry::write_from_geometry(&mut buffer, &collection).unwrap();
+        let zerometry = crate::Zerometry::try_from_bytes(&buffer).unwrap();
+        let segments: Vec<_> = zerometry
+            .segments_enumerated()
+            .map(|(index, segment)| (index, segment.start().to_geo(), segment.end().to_geo()))
+            .collect();
+        assert_compact_debug_snapshot!(segments, @"[(0, COORD(5.0 5.0), COORD(6.0 6.0)), (1, COORD(0.0 0.0), COORD(1.0 0.0)), (2, COORD(1.0 0.0), COORD(1.0 1.0)), (3, COORD(1.0 1.0), COORD(0.0 1.0)), (4, COORD(0.0 1.0), COORD(0.0 0.0))]");
+    }
+
+    #[test]
+    fn test_check_simple() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        assert!(check(square.clone().into()).is_ok());
+        let bowtie = polygon![(x: 0., y: 0.), (x: 2., y: 2.), (x: 2., y: 0.), (x: 0., y: 2.)];
+        assert_compact_debug_snapshot!(check(bowtie.clone().into()), @"Err(SelfIntersection { first: 0, second: 2 })");
+
+        // The indices are counted across the members
+        let multi_polygon = MultiPolygon::new(vec![square.clone(), bowtie]);
+        let err = check(multi_polygon.into()).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"SelfIntersection { first: 4, second: 6 }");
+        assert_compact_debug_snapshot!(err.to_string(), @r#""Self-intersection between the segment 4 and the segment 6""#);
+
+        // The members can overlap each other
+        let overlapping = MultiPolygon::new(vec![square.clone(), square]);
+        assert!(check(overlapping.into()).is_ok());
+
+        // A line going back on one of its previous segments
+        let line = LineString::from(vec![
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 1.0),
+            (2.0, 1.0),
+            (2.0, -1.0),
+        ]);
+        assert_compact_debug_snapshot!(check(line.clone().into()), @"Err(SelfIntersection { first: 0, second: 3 })");
+        let open = LineString::from(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (0.0, 0.0)]);
+        assert!(check(MultiLineString::new(vec![open]).into()).is_ok());
+    }
+}
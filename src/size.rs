@@ -0,0 +1,187 @@
+use std::mem;
+
+use geo_types::{Geometry, GeometryCollection};
+
+use crate::{BOUNDING_BOX_SIZE_IN_BYTES, COORD_SIZE_IN_BYTES, Zerometry};
+
+impl Zerometry<'_> {
+    /// Return the exact number of bytes [`Self::write_from_geometry`] would write for the specified geometry,
+    /// without writing or allocating anything.
+    /// It lets you allocate the output buffer once before writing many geometries.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, LineString};
+    ///
+    /// let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]));
+    /// let mut buffer = Vec::with_capacity(Zerometry::serialized_size(&line));
+    /// Zerometry::write_from_geometry(&mut buffer, &line).unwrap();
+    /// assert_eq!(buffer.len(), buffer.capacity());
+    /// ```
+    pub fn serialized_size(geometry: &Geometry<f64>) -> usize {
+        // The tag is stored as a u64
+        mem::size_of::<u64>() + body_size(geometry)
+    }
+}
+
+fn body_size(geometry: &Geometry<f64>) -> usize {
+    match geometry {
+        Geometry::Point(_) => COORD_SIZE_IN_BYTES,
+        Geometry::MultiPoint(multi_point) => coords_size(multi_point.0.len()),
+        Geometry::LineString(line) => coords_size(line.0.len()),
+        Geometry::Polygon(polygon) => coords_size(polygon.exterior().0.len()),
+        Geometry::MultiLineString(multi_line) => children_size(
+            multi_line.0.len(),
+            multi_line
+                .iter()
+                .map(|line| coords_size(line.0.len()))
+                .sum(),
+        ),
+        Geometry::MultiPolygon(multi_polygon) => children_size(
+            multi_polygon.0.len(),
+            multi_polygon
+                .iter()
+                .map(|polygon| coords_size(polygon.exterior().0.len()))
+                .sum(),
+        ),
+        Geometry::GeometryCollection(collection) => {
+            let mut flattened = FlattenedSize::default();
+            flattened.add_collection(collection);
+            flattened.size()
+        }
+        // They're converted to a line and polygons made of 2, 5 and 4 coords
+        Geometry::Line(_) => coords_size(2),
+        Geometry::Rect(_) => coords_size(5),
+        Geometry::Triangle(_) => coords_size(4),
+    }
+}
+
+/// The size of a bounding box followed by the coords, shared by the multi points, the lines and the polygons.
+pub(crate) fn coords_size(coords: usize) -> usize {
+    BOUNDING_BOX_SIZE_IN_BYTES + coords * COORD_SIZE_IN_BYTES
+}
+
+/// The size of a multi lines or multi polygons: the bounding box, the number of children followed by their offsets
+/// padded to stay aligned on 64 bits, and the children.
+pub(crate) fn children_size(count: usize, children_size: usize) -> usize {
+    BOUNDING_BOX_SIZE_IN_BYTES
+        + (count + 1).next_multiple_of(2) * mem::size_of::<u32>()
+        + children_size
+}
+
+/// The sizes of the sections of a collection once flattened, see [`crate::Zollection::write_from_geometry`].
+#[derive(Default)]
+struct FlattenedSize {
+    points: usize,
+    lines: usize,
+    lines_size: usize,
+    polygons: usize,
+    polygons_size: usize,
+}
+
+impl FlattenedSize {
+    fn add_collection(&mut self, collection: &GeometryCollection<f64>) {
+        for geometry in collection {
+            match geometry {
+                Geometry::Point(_) => self.points += 1,
+                Geometry::MultiPoint(multi_point) => self.points += multi_point.0.len(),
+                Geometry::LineString(line) => self.add_line(line.0.len()),
+                Geometry::MultiLineString(multi_line) => multi_line
+                    .iter()
+                    .for_each(|line| self.add_line(line.0.len())),
+                Geometry::Polygon(polygon) => self.add_polygon(polygon.exterior().0.len()),
+                Geometry::MultiPolygon(multi_polygon) => multi_polygon
+                    .iter()
+                    .for_each(|polygon| self.add_polygon(polygon.exterior().0.len())),
+                Geometry::GeometryCollection(collection) => self.add_collection(collection),
+                Geometry::Line(_) => self.add_line(2),
+                Geometry::Rect(_) => self.add_polygon(5),
+                Geometry::Triangle(_) => self.add_polygon(4),
+            }
+        }
+    }
+
+    fn add_line(&mut self, coords: usize) {
+        self.lines += 1;
+        self.lines_size += coords_size(coords);
+    }
+
+    fn add_polygon(&mut self, coords: usize) {
+        self.polygons += 1;
+        self.polygons_size += coords_size(coords);
+    }
+
+    fn size(&self) -> usize {
+        // The bounding box and the offsets of the lines and polygons sections
+        BOUNDING_BOX_SIZE_IN_BYTES
+            + mem::size_of::<u32>() * 2
+            + coords_size(self.points)
+            + children_size(self.lines, self.lines_size)
+            + children_size(self.polygons, self.polygons_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{
+        Line, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Rect, Triangle, coord,
+        polygon,
+    };
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn serialized_size_matches_written_size() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let points = MultiPoint::from(vec![Point::new(3.0, 1.0), Point::new(1.0, 2.0)]);
+        let rect = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 1. });
+        let triangle = Triangle::new(
+            coord! { x: 0., y: 0. },
+            coord! { x: 1., y: 0. },
+            coord! { x: 0., y: 1. },
+        );
+        let segment = Line::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 1. });
+        let nested = GeometryCollection::new_from(vec![
+            line.clone().into(),
+            Point::new(1.0, 1.0).into(),
+            rect.into(),
+        ]);
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            points.clone().into(),
+            MultiPoint::new(Vec::new()).into(),
+            line.clone().into(),
+            MultiLineString::new(vec![line.clone(), line.clone()]).into(),
+            MultiLineString::new(vec![line.clone()]).into(),
+            square.clone().into(),
+            MultiPolygon::new(vec![square.clone(), square.clone(), square.clone()]).into(),
+            MultiPolygon::new(Vec::new()).into(),
+            segment.into(),
+            rect.into(),
+            triangle.into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                points.into(),
+                segment.into(),
+                triangle.into(),
+                square.into(),
+                Geometry::GeometryCollection(nested),
+            ])),
+            Geometry::GeometryCollection(GeometryCollection::new_from(Vec::new())),
+        ];
+
+        let mut sizes = Vec::new();
+        for geometry in &geometries {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, geometry).unwrap();
+            assert_eq!(
+                Zerometry::serialized_size(geometry),
+                buffer.len(),
+                "{geometry:?}"
+            );
+            sizes.push(buffer.len());
+        }
+        assert_compact_debug_snapshot!(sizes, @"[24, 72, 40, 88, 216, 128, 120, 392, 48, 72, 120, 104, 688, 160]");
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{InputRelation, RelationBetweenShapes, Zerometry, ZerometrySet};
+
+/// Return the pairs of indices `(index in a, index in b)` of the shapes that have any of the requested relations,
+/// see [`crate::OutputRelation::any_relation`]. The pairs are sorted.
+///
+/// Instead of relating every shape of `a` with every shape of `b`, the bounding boxes of the shapes are sorted
+/// by their left side and swept from left to right, only the shapes whose bounding boxes overlap are related.
+/// For this reason the disjoint relation is never requested, the disjoint shapes are never returned.
+///
+/// ```
+/// use zerometry::{InputRelation, ZerometrySet, spatial_join};
+/// use geo_types::{Geometry, Point, polygon};
+///
+/// let mut areas = Vec::new();
+/// ZerometrySet::write_from_geometries(&mut areas, &[
+///     Geometry::Polygon(polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)]),
+///     Geometry::Polygon(polygon![(x: 10., y: 0.), (x: 14., y: 0.), (x: 14., y: 4.), (x: 10., y: 4.)]),
+/// ]).unwrap();
+/// let mut points = Vec::new();
+/// ZerometrySet::write_from_geometries(&mut points, &[
+///     Geometry::Point(Point::new(12., 2.)),
+///     Geometry::Point(Point::new(6., 2.)),
+///     Geometry::Point(Point::new(1., 1.)),
+/// ]).unwrap();
+///
+/// let areas = ZerometrySet::try_from_bytes(&areas).unwrap();
+/// let points = ZerometrySet::try_from_bytes(&points).unwrap();
+/// let contains = InputRelation { contains: true, ..InputRelation::none() };
+/// assert_eq!(spatial_join(&areas, &points, contains), [(0, 2), (1, 0)]);
+/// ```
+pub fn spatial_join(
+    a: &ZerometrySet,
+    b: &ZerometrySet,
+    relation: InputRelation,
+) -> Vec<(usize, usize)> {
+    let relation = relation.strip_disjoint();
+    let mut events: Vec<_> = entries(a, Side::A).chain(entries(b, Side::B)).collect();
+    events.sort_by(|left, right| left.left.total_cmp(&right.left));
+
+    // The shapes of each set whose bounding box may still overlap the next ones
+    let mut active_a: Vec<Entry> = Vec::new();
+    let mut active_b: Vec<Entry> = Vec::new();
+    let mut pairs = Vec::new();
+    for entry in events {
+        let (same, other) = match entry.side {
+            Side::A => (&mut active_a, &mut active_b),
+            Side::B => (&mut active_b, &mut active_a),
+        };
+        other.retain(|active| active.right >= entry.left);
+        for active in other.iter() {
+            if active.bottom > entry.top || entry.bottom > active.top {
+                continue;
+            }
+            let (shape_a, shape_b) = match entry.side {
+                Side::A => (&entry, active),
+                Side::B => (active, &entry),
+            };
+            if shape_a
+                .shape
+                .relation(&shape_b.shape, relation)
+                .any_relation()
+            {
+                pairs.push((shape_a.index, shape_b.index));
+            }
+        }
+        same.push(entry);
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+    A,
+    B,
+}
+
+/// A non-empty shape of one of the sets, with its bounds.
+struct Entry<'a> {
+    side: Side,
+    index: usize,
+    shape: Zerometry<'a>,
+    left: f64,
+    bottom: f64,
+    right: f64,
+    top: f64,
+}
+
+fn entries<'a>(set: &ZerometrySet<'a>, side: Side) -> impl Iterator<Item = Entry<'a>> + use<'a> {
+    set.iter()
+        .enumerate()
+        .filter(|(_, shape)| !shape.is_empty())
+        .map(move |(index, shape)| {
+            let (left, bottom, right, top) = shape.bounds();
+            Entry {
+                side,
+                index,
+                shape,
+                left,
+                bottom,
+                right,
+                top,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, MultiPoint, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn same_pairs_as_the_nested_loop() {
+        let mut a = Vec::new();
+        ZerometrySet::write_from_geometries(
+            &mut a,
+            &[
+                Geometry::Polygon(
+                    polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)],
+                ),
+                Geometry::MultiPoint(MultiPoint::from(Vec::<(f64, f64)>::new())),
+                Geometry::Polygon(polygon![(x: 3., y: 3.), (x: 8., y: 3.), (x: 8., y: 8.)]),
+                Geometry::Polygon(polygon![(x: 0., y: 10.), (x: 4., y: 10.), (x: 4., y: 14.)]),
+            ],
+        )
+        .unwrap();
+        let mut b = Vec::new();
+        ZerometrySet::write_from_geometries(
+            &mut b,
+            &[
+                Geometry::Point(Point::new(1.0, 1.0)),
+                Geometry::Point(Point::new(3.5, 3.2)),
+                Geometry::Polygon(polygon![(x: -1., y: 12.), (x: 5., y: 12.), (x: 5., y: 13.)]),
+                Geometry::Point(Point::new(1.0, 6.0)),
+                Geometry::Polygon(polygon![(x: 7., y: 0.), (x: 9., y: 0.), (x: 9., y: 4.)]),
+            ],
+        )
+        .unwrap();
+        let a = ZerometrySet::try_from_bytes(&a).unwrap();
+        let b = ZerometrySet::try_from_bytes(&b).unwrap();
+
+        for relation in [InputRelation::all(), InputRelation::any()] {
+            let mut expected = Vec::new();
+            for (i, shape_a) in a.iter().enumerate() {
+                for (j, shape_b) in b.iter().enumerate() {
+                    let output = shape_a.relation(&shape_b, relation.strip_disjoint());
+                    if output.any_relation() {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            assert_eq!(spatial_join(&a, &b, relation), expected);
+        }
+        assert_compact_debug_snapshot!(spatial_join(&a, &b, InputRelation::all()), @"[(0, 0), (0, 1), (2, 1), (3, 2)]");
+
+        let contained = InputRelation {
+            strict_contained: true,
+            ..InputRelation::none()
+        };
+        assert_compact_debug_snapshot!(spatial_join(&b, &a, contained), @"[(0, 0), (1, 0), (1, 2)]");
+    }
+}
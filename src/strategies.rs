@@ -0,0 +1,146 @@
+//! [`proptest`](mod@proptest) strategies generating valid zerometries, to fuzz the code built on top of zerometry
+//! without writing the generators again. Only available with the `test-utils` feature.
+//!
+//! Every strategy takes the maximum number of coords of the generated shape, to keep the cases small
+//! enough to run hundreds of them. The coords are always in the `lng, lat` order and in the valid ranges.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use zerometry::{ZerometryBuf, strategies};
+//!
+//! proptest!(|(shape in strategies::zerometry(16))| {
+//!     let geometry = shape.as_zerometry().to_geo();
+//!     prop_assert_eq!(ZerometryBuf::from(&geometry), shape);
+//! });
+//! ```
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+use proptest::{collection::vec, prelude::*};
+
+use crate::{WriteOptions, Zerometry, ZerometryBuf};
+
+/// Generate a coord in the valid ranges of the longitude and latitude.
+pub fn coord() -> impl Strategy<Value = Coord<f64>> {
+    (-180.0..=180.0, -90.0..=90.0).prop_map(|(x, y)| Coord { x, y })
+}
+
+/// Generate a [`geo_types::Point`].
+pub fn point() -> impl Strategy<Value = Point<f64>> {
+    coord().prop_map(Point)
+}
+
+/// Generate a [`geo_types::MultiPoint`] of up to `max_coords` points.
+pub fn multi_point(max_coords: usize) -> impl Strategy<Value = MultiPoint<f64>> {
+    vec(point(), 0..=max_coords).prop_map(MultiPoint::new)
+}
+
+/// Generate a [`geo_types::LineString`] of at least two coords and up to `max_coords` coords.
+pub fn line(max_coords: usize) -> impl Strategy<Value = LineString<f64>> {
+    vec(coord(), 2..=max_coords.max(2)).prop_map(LineString::new)
+}
+
+/// Generate a [`geo_types::MultiLineString`] of up to `max_coords` coords in total.
+pub fn multi_line(max_coords: usize) -> impl Strategy<Value = MultiLineString<f64>> {
+    let max_lines = max_coords / 2;
+    (0..=max_lines)
+        .prop_flat_map(move |lines| vec(line(max_coords / lines.max(1)), lines))
+        .prop_map(MultiLineString::new)
+}
+
+/// Generate a [`geo_types::Polygon`] without holes, whose ring is closed and made of at least three distinct coords
+/// and up to `max_coords` coords. The ring may cross itself.
+pub fn polygon(max_coords: usize) -> impl Strategy<Value = Polygon<f64>> {
+    // The ring is closed by repeating the first coord
+    vec(coord(), 3..=max_coords.saturating_sub(1).max(3))
+        .prop_map(|coords| Polygon::new(LineString::new(coords), Vec::new()))
+}
+
+/// Generate a [`geo_types::MultiPolygon`] of up to `max_coords` coords in total.
+pub fn multi_polygon(max_coords: usize) -> impl Strategy<Value = MultiPolygon<f64>> {
+    let max_polygons = max_coords / 4;
+    (0..=max_polygons)
+        .prop_flat_map(move |polygons| vec(polygon(max_coords / polygons.max(1)), polygons))
+        .prop_map(MultiPolygon::new)
+}
+
+/// Generate a [`geo_types::GeometryCollection`] made of a multi points, a multi lines and a multi polygons,
+/// of up to `max_coords` coords in total.
+pub fn collection(max_coords: usize) -> impl Strategy<Value = GeometryCollection<f64>> {
+    let max_coords = max_coords / 3;
+    (
+        multi_point(max_coords),
+        multi_line(max_coords),
+        multi_polygon(max_coords),
+    )
+        .prop_map(|(points, lines, polygons)| {
+            GeometryCollection::new_from(vec![points.into(), lines.into(), polygons.into()])
+        })
+}
+
+/// Generate a [`geo_types::Geometry`] of any kind supported by zerometry, of up to `max_coords` coords.
+pub fn geometry(max_coords: usize) -> impl Strategy<Value = Geometry<f64>> {
+    prop_oneof![
+        point().prop_map(Geometry::Point),
+        multi_point(max_coords).prop_map(Geometry::MultiPoint),
+        line(max_coords).prop_map(Geometry::LineString),
+        multi_line(max_coords).prop_map(Geometry::MultiLineString),
+        polygon(max_coords).prop_map(Geometry::Polygon),
+        multi_polygon(max_coords).prop_map(Geometry::MultiPolygon),
+        collection(max_coords).prop_map(Geometry::GeometryCollection),
+    ]
+}
+
+/// Generate the [`WriteOptions`] changing the layout of the zerometries: the sorted multi points,
+/// the generation, the coverage mask and the bounding box groups.
+pub fn write_options() -> impl Strategy<Value = WriteOptions> {
+    (
+        any::<bool>(),
+        proptest::option::of(any::<u64>()),
+        proptest::option::of(1..=16_u32),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(sort_points, generation, coverage_mask, bounding_box_groups)| WriteOptions {
+                sort_points,
+                generation,
+                coverage_mask,
+                bounding_box_groups,
+                ..WriteOptions::default()
+            },
+        )
+}
+
+/// Generate a valid zerometry of any kind, of up to `max_coords` coords, written with the default options.
+pub fn zerometry(max_coords: usize) -> impl Strategy<Value = ZerometryBuf> {
+    geometry(max_coords).prop_map(ZerometryBuf::from)
+}
+
+/// Generate a valid zerometry of any kind, of up to `max_coords` coords, written with any [`write_options`].
+/// It covers every layout of the format, including the sorted multi points and the optional headers.
+pub fn zerometry_with_options(max_coords: usize) -> impl Strategy<Value = ZerometryBuf> {
+    (geometry(max_coords), write_options()).prop_map(|(geometry, options)| {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+        ZerometryBuf::try_from_bytes(&buffer).unwrap()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::CoordsIter;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn generated_zerometries_are_valid(shape in zerometry_with_options(12)) {
+            let zerometry = Zerometry::try_from_bytes(shape.as_bytes()).unwrap();
+            let geometry = zerometry.to_geo();
+            prop_assert!(geometry.coords_count() <= 12);
+            prop_assert!(Zerometry::check_lng_lat(&geometry).is_ok());
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use geo::Relate;
+
+use crate::{InputRelation, OutputRelation, RelationBetweenShapes, Zerometry};
+
+/// An algorithm computing the relation between two zerometries.
+///
+/// [`RelationBetweenShapes`] always uses the [`Naive`] strategy, the other strategies are picked per call
+/// so an alternative algorithm can be benchmarked against it, or rolled out on a subset of the queries,
+/// without changing the results of the existing code.
+///
+/// ```
+/// use zerometry::{BoundingBoxes, InputRelation, Naive, RelationStrategy, ZerometryBuf};
+/// use geo_types::{Geometry, Point, polygon};
+///
+/// let triangle = ZerometryBuf::from(Geometry::Polygon(polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.)]));
+/// let point = ZerometryBuf::from(Geometry::Point(Point::new(1.0, 3.0)));
+/// let (triangle, point) = (triangle.as_zerometry(), point.as_zerometry());
+///
+/// // The point is outside of the triangle but in its bounding box
+/// let strategies: [&dyn RelationStrategy; 2] = [&Naive, &BoundingBoxes];
+/// let contains: Vec<_> = strategies
+///     .iter()
+///     .map(|strategy| strategy.relation(&triangle, &point, InputRelation::all()).contains)
+///     .collect();
+/// assert_eq!(contains, [Some(false), Some(true)]);
+/// ```
+pub trait RelationStrategy {
+    /// Compute the relation between `shape` and `other`, see [`RelationBetweenShapes::relation`].
+    fn relation(
+        &self,
+        shape: &Zerometry,
+        other: &Zerometry,
+        relation: InputRelation,
+    ) -> OutputRelation;
+}
+
+/// The algorithm of [`RelationBetweenShapes`], which works directly on the serialized coords.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Naive;
+
+impl RelationStrategy for Naive {
+    fn relation(
+        &self,
+        shape: &Zerometry,
+        other: &Zerometry,
+        relation: InputRelation,
+    ) -> OutputRelation {
+        shape.relation(other, relation)
+    }
+}
+
+/// Only compare the bounding boxes of the shapes, as if [`InputRelation::approximate`] was set.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BoundingBoxes;
+
+impl RelationStrategy for BoundingBoxes {
+    fn relation(
+        &self,
+        shape: &Zerometry,
+        other: &Zerometry,
+        relation: InputRelation,
+    ) -> OutputRelation {
+        shape.approximate_relation(other, relation)
+    }
+}
+
+/// Convert the shapes back to geo and compute their DE-9IM matrix with [`geo::Relate`], which relies on robust
+/// predicates. It's much slower but gives a reference to check the other strategies against.
+///
+/// The shapes are related as a whole, so for the multi shapes the contains and contained are the same as
+/// their strict version. The [`InputRelation::budget`] and [`InputRelation::approximate`] are ignored.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GeoRelate;
+
+impl RelationStrategy for GeoRelate {
+    fn relation(
+        &self,
+        shape: &Zerometry,
+        other: &Zerometry,
+        relation: InputRelation,
+    ) -> OutputRelation {
+        let output = relation.to_false();
+        let matrix = shape.to_geo().relate(&other.to_geo());
+        if matrix.is_disjoint() {
+            return output.make_disjoint_if_set();
+        }
+
+        let (contains, within) = (matrix.is_contains(), matrix.is_within());
+        let mut output = if contains || within {
+            output
+        } else {
+            output.make_intersect_if_set()
+        };
+        if contains {
+            output = output.make_strict_contains_if_set();
+        }
+        if within {
+            output = output.make_strict_contained_if_set();
+        }
+        if matrix.is_touches() {
+            output = output.make_touches_if_set();
+        }
+        if matrix.is_crosses() {
+            output = output.make_crosses_if_set();
+        }
+        if matrix.is_covers() {
+            output = output.make_covers_if_set();
+        }
+        if matrix.is_coveredby() {
+            output = output.make_covered_by_if_set();
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::ZerometryBuf;
+
+    #[test]
+    fn same_relations_as_naive() {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let shapes = [
+            Geometry::Point(Point::new(1.0, 3.0)),
+            Geometry::Point(Point::new(10.0, 10.0)),
+            Geometry::MultiPoint(vec![(1.0, 1.0), (2.0, 2.0)].into()),
+            Geometry::Polygon(polygon![(x: 1., y: 1.), (x: 2., y: 1.), (x: 2., y: 2.)]),
+            Geometry::Polygon(polygon![(x: 3., y: 3.), (x: 6., y: 3.), (x: 6., y: 6.)]),
+            // Touching the square
+            Geometry::Polygon(polygon![(x: 4., y: 1.), (x: 6., y: 1.), (x: 6., y: 3.)]),
+            Geometry::LineString(vec![(6.0, 2.0), (4.0, 2.0)].into()),
+            // Crossing the square
+            Geometry::LineString(vec![(-2.0, 2.0), (6.0, 2.0)].into()),
+        ];
+        let square = ZerometryBuf::from(Geometry::Polygon(square));
+        let square = square.as_zerometry();
+        for shape in shapes {
+            let shape = ZerometryBuf::from(&shape);
+            let shape = shape.as_zerometry();
+            let naive = Naive.relation(&square, &shape, InputRelation::all());
+            let geo = GeoRelate.relation(&square, &shape, InputRelation::all());
+            assert_eq!(naive, geo, "{:?}", shape.to_geo());
+        }
+
+        // The bounding boxes only know the point is in the bounding box of the triangle
+        let triangle = ZerometryBuf::from(Geometry::Polygon(
+            polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.)],
+        ));
+        let point = ZerometryBuf::from(Geometry::Point(Point::new(1.0, 3.0)));
+        let input = InputRelation {
+            contains: true,
+            disjoint: true,
+            ..InputRelation::none()
+        };
+        let relations =
+            [&Naive as &dyn RelationStrategy, &BoundingBoxes, &GeoRelate].map(|strategy| {
+                strategy.relation(&triangle.as_zerometry(), &point.as_zerometry(), input)
+            });
+        assert_compact_debug_snapshot!(relations, @"[OutputRelation { contains: Some(false), strict_contains: None, contained: None, strict_contained: None, intersect: None, disjoint: Some(true), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }, OutputRelation { contains: Some(true), strict_contains: None, contained: None, strict_contained: None, intersect: None, disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: true }, OutputRelation { contains: Some(false), strict_contains: None, contained: None, strict_contained: None, intersect: None, disjoint: Some(true), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }]");
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    BoundingBox, Coord, InputRelation, OutputRelation, RelationBetweenShapes, Segment, Zerometry,
+    Zine, Zolygon, topology::without_topology,
+};
+
+/// The kind of shape described by the coordinates given to [`Zerometry::relation_streaming`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamedShape {
+    /// The coordinates describe a line string.
+    Line,
+    /// The coordinates describe the exterior ring of a polygon.
+    /// If the ring is not closed it'll be closed automatically.
+    Polygon,
+}
+
+/// One of the shapes composing the stored zerometry, plus everything we learnt about it while
+/// streaming the coordinates of the other shape.
+struct Part<'a> {
+    shape: PartShape<'a>,
+    /// Set to `true` if any streamed segment intersects with the shape, or goes through the point.
+    intersects: bool,
+    /// Crossing number of the first coordinate of the shape against the streamed polygon.
+    inside: bool,
+}
+
+enum PartShape<'a> {
+    Point(&'a Coord),
+    Line(Zine<'a>),
+    Polygon(Zolygon<'a>),
+}
+
+impl<'a> PartShape<'a> {
+    /// The coordinate we use to know if the shape is inside the streamed polygon.
+    fn probe(&self) -> &'a Coord {
+        match self {
+            PartShape::Point(coord) => coord,
+            PartShape::Line(zine) => &zine.coords()[0],
+            PartShape::Polygon(zolygon) => &zolygon.coords()[0],
+        }
+    }
+
+    fn segment_intersects(&self, segment: &Segment) -> bool {
+        match self {
+            PartShape::Point(coord) => segment.contains_coord(coord),
+            PartShape::Line(zine) => {
+                may_intersect(zine.bounding_box(), segment)
+                    && zine.segments().any(|other| other.intersects(segment))
+            }
+            PartShape::Polygon(zolygon) => {
+                may_intersect(zolygon.bounding_box(), segment)
+                    && zolygon.segments().any(|other| other.intersects(segment))
+            }
+        }
+    }
+}
+
+/// Return `false` if the bounding box of the segment is disjoint from the bounding box.
+fn may_intersect(bounding_box: &BoundingBox, segment: &Segment) -> bool {
+    let (start, end) = (segment.start(), segment.end());
+    start.lng().max(end.lng()) >= bounding_box.left()
+        && start.lng().min(end.lng()) <= bounding_box.right()
+        && start.lat().max(end.lat()) >= bounding_box.bottom()
+        && start.lat().min(end.lat()) <= bounding_box.top()
+}
+
+impl<'a> Zerometry<'a> {
+    /// Return the relation between `self` and a shape described by a stream of `(x, y)` coordinates.
+    ///
+    /// This lets you compare a huge query shape (e.g. a 1M points isochrone) against stored shapes
+    /// without serializing it into a buffer first. The coordinates are consumed exactly once.
+    /// As with [`RelationBetweenShapes::relation`], `contains` means that `self` contains the streamed shape.
+    ///
+    /// The relations depending on the locations of the shapes relative to each other, like
+    /// [`OutputRelation::touches`], require the whole streamed shape: they're left to `None` and the
+    /// [`InputRelation::tolerance`] is ignored.
+    pub fn relation_streaming(
+        &self,
+        coords: impl IntoIterator<Item = (f64, f64)>,
+        kind: StreamedShape,
+        relation: InputRelation,
+    ) -> OutputRelation {
+        let relation = without_topology(relation);
+        let mut parts = Vec::new();
+        self.collect_parts(&mut parts);
+
+        let mut coords = coords.into_iter();
+        let Some(first) = coords.next() else {
+            return relation.to_false().make_disjoint_if_set();
+        };
+
+        let mut previous = first;
+        let mut process_segment = |start: (f64, f64), end: (f64, f64)| {
+            let buffer = [start.0, start.1, end.0, end.1];
+            // SAFETY: The buffer contains exactly 4 f64
+            let segment = unsafe { Segment::from_slice(&buffer) };
+
+            for part in parts.iter_mut() {
+                if !part.intersects && part.shape.segment_intersects(&segment) {
+                    part.intersects = true;
+                }
+                if kind == StreamedShape::Polygon {
+                    let probe = part.shape.probe();
+                    if (start.1 > probe.lat()) != (end.1 > probe.lat())
+                        && probe.lng()
+                            < (end.0 - start.0) * (probe.lat() - start.1) / (end.1 - start.1)
+                                + start.0
+                    {
+                        part.inside = !part.inside;
+                    }
+                }
+            }
+        };
+
+        for coord in coords {
+            process_segment(previous, coord);
+            previous = coord;
+        }
+        if kind == StreamedShape::Polygon && previous != first {
+            process_segment(previous, first);
+        }
+
+        let buffer = [first.0, first.1];
+        // SAFETY: The buffer contains exactly 2 f64
+        let first = unsafe { Coord::from_slice(&buffer) };
+
+        let mut output = relation.to_false();
+        let mut contained = 0;
+        for part in parts.iter() {
+            match part.shape {
+                // Like with a stored line, a point is contained in the streamed line when it lies on one of its segments
+                PartShape::Point(_) if kind == StreamedShape::Line => {
+                    if part.intersects {
+                        output = output.make_contained_if_set();
+                        contained += 1;
+                    }
+                }
+                PartShape::Point(_) => {
+                    if part.inside {
+                        output = output.make_contained_if_set();
+                        contained += 1;
+                    }
+                }
+                PartShape::Line(_) | PartShape::Polygon(_) if part.intersects => {
+                    output = output.make_intersect_if_set();
+                }
+                PartShape::Line(_) | PartShape::Polygon(_) if part.inside => {
+                    output = output.make_contained_if_set();
+                    contained += 1;
+                }
+                PartShape::Polygon(zolygon) if zolygon.contains(first) => {
+                    output = output.make_strict_contains_if_set();
+                }
+                _ => (),
+            }
+        }
+
+        if !parts.is_empty() && contained == parts.len() {
+            output = output.make_strict_contained_if_set();
+        }
+
+        if output.any_relation() {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
+    }
+
+    fn collect_parts(&self, parts: &mut Vec<Part<'a>>) {
+        let mut push = |shape| {
+            parts.push(Part {
+                shape,
+                intersects: false,
+                inside: false,
+            })
+        };
+        match *self {
+            Zerometry::Point(zoint) => push(PartShape::Point(zoint.coord())),
+            Zerometry::MultiPoints(zulti_points) => zulti_points
+                .coords()
+                .iter()
+                .for_each(|coord| push(PartShape::Point(coord))),
+            Zerometry::Line(zine) if !zine.is_empty() => push(PartShape::Line(zine)),
+            Zerometry::MultiLines(zulti_lines) => (0..zulti_lines.len())
+                .filter_map(|index| zulti_lines.get(index))
+                .filter(|zine| !zine.is_empty())
+                .for_each(|zine| push(PartShape::Line(zine))),
+            Zerometry::Polygon(zolygon) if !zolygon.is_empty() => push(PartShape::Polygon(zolygon)),
+            Zerometry::MultiPolygon(zulti_polygons) => (0..zulti_polygons.len())
+                .filter_map(|index| zulti_polygons.get(index))
+                .filter(|zolygon| !zolygon.is_empty())
+                .for_each(|zolygon| push(PartShape::Polygon(zolygon))),
+            Zerometry::Collection(zollection) => {
+                Zerometry::MultiPoints(zollection.points()).collect_parts(parts);
+                Zerometry::MultiLines(zollection.lines()).collect_parts(parts);
+                Zerometry::MultiPolygon(zollection.polygons()).collect_parts(parts);
+            }
+            Zerometry::Line(_) | Zerometry::Polygon(_) => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString, MultiPoint, Point, Polygon, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    fn square(min: f64, max: f64) -> Vec<(f64, f64)> {
+        vec![(min, min), (max, min), (max, max), (min, max)]
+    }
+
+    #[test]
+    fn streamed_polygon_against_polygon() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(polygon)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+        let inside = zerometry.relation_streaming(
+            square(2.0, 3.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(inside, @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let outside = zerometry.relation_streaming(
+            square(-5.0, 15.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(outside, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let intersect = zerometry.relation_streaming(
+            square(5.0, 15.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(intersect, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let disjoint = zerometry.relation_streaming(
+            square(20.0, 25.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(disjoint, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let empty = zerometry.relation_streaming([], StreamedShape::Polygon, InputRelation::all());
+        assert_compact_debug_snapshot!(empty, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn streamed_line_against_polygon() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(polygon)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+        let inside = zerometry.relation_streaming(
+            [(1.0, 1.0), (2.0, 2.0)],
+            StreamedShape::Line,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(inside, @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let intersect = zerometry.relation_streaming(
+            [(1.0, 1.0), (20.0, 2.0)],
+            StreamedShape::Line,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(intersect, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn streamed_polygon_against_multi_points_and_lines() {
+        let points = MultiPoint::from(vec![Point::new(1.0, 1.0), Point::new(20.0, 20.0)]);
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::MultiPoint(points)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+        let some = zerometry.relation_streaming(
+            square(0.0, 10.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(some, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let all = zerometry.relation_streaming(
+            square(0.0, 30.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(all, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+
+        let line = LineString::from(vec![(1.0, 1.0), (2.0, 2.0)]);
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::LineString(line)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        let contained = zerometry.relation_streaming(
+            square(0.0, 10.0),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(contained, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let crossing = zerometry.relation_streaming(
+            [(0.0, 2.0), (2.0, 0.0)],
+            StreamedShape::Line,
+            InputRelation::all(),
+        );
+        assert_compact_debug_snapshot!(crossing, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn streamed_line_against_points() {
+        let points = MultiPoint::from(vec![Point::new(1.0, 1.0), Point::new(3.0, 1.0)]);
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::MultiPoint(points)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        let relation = InputRelation {
+            contains: true,
+            strict_contains: true,
+            contained: true,
+            strict_contained: true,
+            intersect: true,
+            disjoint: true,
+            ..InputRelation::default()
+        };
+
+        let line = [(0.0, 0.0), (2.0, 2.0), (4.0, 0.0)];
+        let on_the_line = zerometry.relation_streaming(line, StreamedShape::Line, relation);
+        assert_compact_debug_snapshot!(on_the_line, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &LineString::from(line.to_vec()).into())
+            .unwrap();
+        let line = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert_eq!(on_the_line, zerometry.relation(&line, relation));
+
+        let through_one = [(0.0, 0.0), (2.0, 2.0)];
+        let through_one = zerometry.relation_streaming(through_one, StreamedShape::Line, relation);
+        assert_compact_debug_snapshot!(through_one, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: None, touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn streamed_topology() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(polygon)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+        // The topology requires the whole streamed shape, it's never computed and the tolerance is ignored
+        let relation = InputRelation {
+            tolerance: 1.0,
+            ..InputRelation::all_with_topology()
+        };
+        let through = [(-5.0, 5.0), (15.0, 5.0)];
+        let streamed = zerometry.relation_streaming(through, StreamedShape::Line, relation);
+        let line = Geometry::LineString(LineString::from(through.to_vec()));
+        assert_eq!(streamed, zerometry.all_relation(&line));
+        let close = [(10.5, 5.0), (15.0, 5.0)];
+        let streamed = zerometry.relation_streaming(close, StreamedShape::Line, relation);
+        assert_compact_debug_snapshot!((streamed.disjoint, streamed.touches, streamed.crosses, streamed.covers, streamed.covered_by), @"(Some(true), None, None, None, None)");
+    }
+
+    #[test]
+    fn streaming_matches_regular_relation() {
+        let polygon: Polygon =
+            polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)];
+        let query: Polygon =
+            polygon![(x: 5., y: 5.), (x: 15., y: 5.), (x: 15., y: 15.), (x: 5., y: 15.)];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(polygon)).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+
+        let streamed = zerometry.relation_streaming(
+            query.exterior().coords().map(|coord| (coord.x, coord.y)),
+            StreamedShape::Polygon,
+            InputRelation::all(),
+        );
+        assert_eq!(streamed, zerometry.all_relation(&query));
+    }
+}
@@ -0,0 +1,294 @@
+use core::fmt;
+use std::{io, mem};
+
+use bytemuck::cast_slice;
+use geo::CoordsIter;
+use geo_types::{Geometry, GeometryCollection, Point};
+
+use crate::{
+    BoundingBox, Zerometry, ZerometryError,
+    aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    error::split_u64,
+    header::{KIND_MASK, STRUCTURED_COLLECTION_TAG, tag},
+    zulti_lines::{check_children, write_children},
+};
+
+/// Equivalent of a [`geo_types::GeometryCollection`] that keeps the type and the order of its members,
+/// unlike a [`crate::Zollection`] which flattens them into one multi points, one multi lines and one multi polygon.
+///
+/// Its layout is the same as a [`crate::ZultiLines`]: the bounding box of all the members, their offsets and then
+/// the members. Every member is stored as a whole [`Zerometry`], starting with its tag, and the nested collections
+/// are stored as structured collections as well, with the [`crate::STRUCTURED_COLLECTION_TAG`].
+///
+/// Like [`Zerometry::write_from_geometry`], the [`geo_types::Line`], [`geo_types::Rect`] and [`geo_types::Triangle`]
+/// are stored as lines and polygons, and the interiors of the polygons are dropped.
+///
+/// ```
+/// use zerometry::{StructuredMember, StructuredZollection};
+/// use geo_types::{Geometry, GeometryCollection, LineString, Point};
+///
+/// let collection = GeometryCollection::new_from(vec![
+///     Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)])),
+///     Geometry::Point(Point::new(2.0, 3.0)),
+/// ]);
+/// let mut buffer = Vec::new();
+/// StructuredZollection::write_from_geometry(&mut buffer, &collection).unwrap();
+///
+/// let structured = StructuredZollection::try_from_bytes(&buffer).unwrap();
+/// assert_eq!(structured.len(), 2);
+/// assert!(matches!(structured.get(1), Some(StructuredMember::Shape(shape)) if shape.to_point().is_some()));
+/// assert_eq!(structured.to_geo(), collection);
+/// ```
+#[derive(Clone, Copy)]
+pub struct StructuredZollection<'a> {
+    bounding_box: &'a BoundingBox,
+    // Same as the multi lines, the number of offsets is stored before them and they're followed by
+    // a padding if there is an even number of offsets
+    offsets: &'a [u32],
+    bytes: &'a [u8],
+}
+
+/// A member of a [`StructuredZollection`].
+// The members are short-lived views on the bytes, as the zerometry itself, boxing the shape would cost an allocation
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Copy)]
+pub enum StructuredMember<'a> {
+    /// Any shape but a collection.
+    Shape(Zerometry<'a>),
+    /// A nested collection, which keeps its structure as well.
+    Collection(StructuredZollection<'a>),
+}
+
+impl<'a> StructuredZollection<'a> {
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        let bounding_box = unsafe { BoundingBox::from_bytes(&data[..BOUNDING_BOX_SIZE_IN_BYTES]) };
+        let data = &data[BOUNDING_BOX_SIZE_IN_BYTES..];
+        let offsets_count = u32::from_ne_bytes(data[..mem::size_of::<u32>()].try_into().unwrap());
+        let data = &data[mem::size_of::<u32>()..];
+        let size_of_offsets = offsets_count as usize * mem::size_of::<u32>();
+        let offsets: &[u32] = cast_slice(&data[..size_of_offsets]);
+        let data = &data[size_of_offsets..];
+        // Skip the padding
+        let bytes = if offsets_count % 2 == 0 {
+            &data[mem::size_of::<u32>()..]
+        } else {
+            data
+        };
+        Self {
+            bounding_box,
+            offsets,
+            bytes,
+        }
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_children(data, |member| {
+            StructuredMember::try_from_bytes(member).map(drop)
+        })?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
+    /// Convert the specified [`geo_types::GeometryCollection`] to a valid [`StructuredZollection`] slice of bytes
+    /// in the input buffer.
+    pub fn write_from_geometry(
+        writer: &mut impl io::Write,
+        geometry: &GeometryCollection<f64>,
+    ) -> Result<(), ZerometryError> {
+        let members = geometry
+            .iter()
+            .map(|member| {
+                let mut buffer = Vec::new();
+                match member {
+                    Geometry::GeometryCollection(collection) => {
+                        buffer.extend_from_slice(&tag(STRUCTURED_COLLECTION_TAG).to_ne_bytes());
+                        Self::write_from_geometry(&mut buffer, collection)?;
+                    }
+                    member => Zerometry::write_from_geometry(&mut buffer, member)?,
+                }
+                Ok(buffer)
+            })
+            .collect::<Result<Vec<_>, ZerometryError>>()?;
+        write_children(
+            writer,
+            geometry
+                .iter()
+                .flat_map(|member| member.exterior_coords_iter())
+                .map(Point::from),
+            members.iter().map(Vec::as_slice),
+        )?;
+        Ok(())
+    }
+
+    /// Return the bytes the collection was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let offsets = (self.offsets.len() + 1).next_multiple_of(2) * mem::size_of::<u32>();
+        let len = BOUNDING_BOX_SIZE_IN_BYTES + offsets + self.bytes.len();
+        contiguous(self.bounding_box, self.bytes, len, 0)
+    }
+
+    /// Return the bounding box of all the members.
+    #[inline]
+    pub fn bounding_box(&self) -> &'a BoundingBox {
+        self.bounding_box
+    }
+
+    /// Return the number of members of the collection, the nested collections count as one member.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Return `true` if the collection doesn't contain any member.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return a member by index, if the index doesn't exists, returns None
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<StructuredMember<'a>> {
+        let offset = *self.offsets.get(index)? as usize;
+        let next_offset = self
+            .offsets
+            .get(index + 1)
+            .map_or(self.bytes.len(), |offset| *offset as usize);
+        // SAFETY: The members were written by `write_from_geometry` or validated by `try_from_bytes`
+        Some(unsafe { StructuredMember::from_bytes(&self.bytes[offset..next_offset]) }.unwrap())
+    }
+
+    /// Iterate over the members in their original order.
+    pub fn members(&self) -> impl Iterator<Item = StructuredMember<'a>> + use<'a> {
+        let collection = *self;
+        (0..collection.len()).map(move |index| collection.get(index).unwrap())
+    }
+
+    /// Convert the [`StructuredZollection`] back to a [`geo_types::GeometryCollection`], the members keep their
+    /// type and their order.
+    pub fn to_geo(&self) -> GeometryCollection<f64> {
+        GeometryCollection::new_from(self.members().map(|member| member.to_geo()).collect())
+    }
+}
+
+impl<'a> StructuredMember<'a> {
+    /// # Safety
+    /// The data must be a member of a [`StructuredZollection`] generated from
+    /// [`StructuredZollection::write_from_geometry`] and be aligned on 64 bits
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        let (tag, body) = split_u64(data)?;
+        if tag & KIND_MASK == STRUCTURED_COLLECTION_TAG {
+            Ok(StructuredMember::Collection(unsafe {
+                StructuredZollection::from_bytes(body)
+            }))
+        } else {
+            Ok(StructuredMember::Shape(unsafe {
+                Zerometry::from_bytes(data)?
+            }))
+        }
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        let (tag, body) = split_u64(data)?;
+        if tag & KIND_MASK == STRUCTURED_COLLECTION_TAG {
+            Ok(StructuredMember::Collection(
+                StructuredZollection::try_from_bytes(body)?,
+            ))
+        } else {
+            Ok(StructuredMember::Shape(Zerometry::try_from_bytes(data)?))
+        }
+    }
+
+    /// Convert the member back to a [`geo_types::Geometry`].
+    pub fn to_geo(&self) -> Geometry<f64> {
+        match self {
+            StructuredMember::Shape(shape) => shape.to_geo(),
+            StructuredMember::Collection(collection) => {
+                Geometry::GeometryCollection(collection.to_geo())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for StructuredZollection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuredZollection")
+            .field("bounding_box", &self.bounding_box)
+            .field("members", &self.members().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, MultiPoint, MultiPolygon, Rect, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn keep_the_structure() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let collection = GeometryCollection::new_from(vec![
+            Geometry::MultiPolygon(MultiPolygon::new(vec![square.clone()])),
+            Geometry::Point(Point::new(5.0, 5.0)),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Geometry::MultiPoint(MultiPoint::from(vec![(2.0, 2.0)])),
+                Geometry::GeometryCollection(GeometryCollection::new_from(Vec::new())),
+            ])),
+            Geometry::LineString(LineString::from(vec![(-1.0, 0.0), (0.0, 3.0)])),
+            Geometry::Polygon(square),
+        ]);
+        let mut buffer = Vec::new();
+        StructuredZollection::write_from_geometry(&mut buffer, &collection).unwrap();
+        let structured = StructuredZollection::try_from_bytes(&buffer).unwrap();
+        assert_eq!(structured.to_geo(), collection);
+        assert_eq!(structured.as_bytes(), &buffer[..]);
+        assert_compact_debug_snapshot!(structured.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: -1.0, lat: 0.0 }, top_right: Coord { lng: 5.0, lat: 5.0 } }");
+        assert_compact_debug_snapshot!(structured.get(2), @"Some(Collection(StructuredZollection { bounding_box: BoundingBox { bottom_left: Coord { lng: 2.0, lat: 2.0 }, top_right: Coord { lng: 2.0, lat: 2.0 } }, members: [Shape(MultiPoints(ZultiPoints { bounding_box: BoundingBox { bottom_left: Coord { lng: 2.0, lat: 2.0 }, top_right: Coord { lng: 2.0, lat: 2.0 } }, points: [Zoint { lng: 2.0, lat: 2.0 }] })), Collection(StructuredZollection { bounding_box: BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }, members: [] })] }))");
+        assert!(structured.get(5).is_none());
+
+        // The flattened collection loses the structure
+        let collection = Geometry::GeometryCollection(collection);
+        let mut flattened = Vec::new();
+        Zerometry::write_from_geometry(&mut flattened, &collection).unwrap();
+        let flattened = Zerometry::try_from_bytes(&flattened).unwrap();
+        assert_ne!(flattened.to_geo(), collection);
+
+        // The members are converted like a zerometry
+        let rect = GeometryCollection::new_from(vec![Rect::new((0.0, 0.0), (1.0, 1.0)).into()]);
+        let mut buffer = Vec::new();
+        StructuredZollection::write_from_geometry(&mut buffer, &rect).unwrap();
+        let structured = StructuredZollection::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(structured.to_geo(), @"GEOMETRYCOLLECTION(RECT(0.0 0.0,1.0 1.0))");
+    }
+
+    #[test]
+    fn invalid_structured_collection() {
+        let collection = GeometryCollection::new_from(vec![
+            Geometry::Point(Point::new(5.0, 5.0)),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![Geometry::Point(
+                Point::new(1.0, 1.0),
+            )])),
+        ]);
+        let mut buffer = Vec::new();
+        StructuredZollection::write_from_geometry(&mut buffer, &collection).unwrap();
+        // The nested collection is made of its bounding box, one offset and a point
+        let nested = buffer.len() - 64;
+        // Break the tag of the first member
+        let first = BOUNDING_BOX_SIZE_IN_BYTES + 4 * mem::size_of::<u32>();
+        let mut corrupted = buffer.clone();
+        corrupted[first..first + 8].copy_from_slice(&42_u64.to_ne_bytes());
+        assert_compact_debug_snapshot!(StructuredZollection::try_from_bytes(&corrupted).unwrap_err(), @"InvalidTag(42)");
+        // Break the bounding box of the nested collection
+        let mut corrupted = buffer.clone();
+        corrupted[nested..nested + 8].copy_from_slice(&10.0_f64.to_ne_bytes());
+        assert_compact_debug_snapshot!(StructuredZollection::try_from_bytes(&corrupted).unwrap_err(), @"InvalidBoundingBox");
+    }
+}
@@ -0,0 +1,178 @@
+use geo_types::{Geometry, Line, Rect, Triangle};
+
+use crate::{
+    Zerometry, ZerometryError,
+    header::{LINE_TAG, POLYGON_TAG, SUBTYPE_SHIFT},
+};
+
+/// The original variant of a geometry that has no equivalent in zerometry and is stored as a line or a polygon.
+/// It's stored in a byte of the tag to let [`Zerometry::to_geo`] return the geometry that was written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Subtype {
+    /// The shape was written as-is.
+    #[default]
+    None,
+    /// A [`geo_types::Line`] stored as a line of two coords.
+    Line,
+    /// A [`geo_types::Rect`] stored as a polygon of five coords.
+    Rect,
+    /// A [`geo_types::Triangle`] stored as a polygon of four coords.
+    Triangle,
+}
+
+impl Subtype {
+    /// Read the subtype stored in the tag.
+    pub fn from_tag(tag: u64) -> Result<Self, ZerometryError> {
+        match (tag >> SUBTYPE_SHIFT) as u8 {
+            0 => Ok(Subtype::None),
+            1 => Ok(Subtype::Line),
+            2 => Ok(Subtype::Rect),
+            3 => Ok(Subtype::Triangle),
+            _ => Err(ZerometryError::InvalidTag(tag)),
+        }
+    }
+
+    /// Return the bits to set in the tag to store the subtype.
+    pub const fn tag_bits(self) -> u64 {
+        let subtype: u64 = match self {
+            Subtype::None => 0,
+            Subtype::Line => 1,
+            Subtype::Rect => 2,
+            Subtype::Triangle => 3,
+        };
+        subtype << SUBTYPE_SHIFT
+    }
+
+    /// Return an error if the subtype can't be stored in a shape of this kind and number of coords.
+    pub fn check(self, kind: u64, coords: usize) -> Result<(), ZerometryError> {
+        let valid = match self {
+            Subtype::None => true,
+            Subtype::Line => kind == LINE_TAG && coords == 2,
+            Subtype::Rect => kind == POLYGON_TAG && coords == 5,
+            Subtype::Triangle => kind == POLYGON_TAG && coords == 4,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ZerometryError::UnexpectedSubtype)
+        }
+    }
+
+    /// Convert the line or the polygon back to its original variant, it must have been [checked](Self::check) first.
+    pub fn restore(self, geometry: Geometry<f64>) -> Geometry<f64> {
+        match (self, geometry) {
+            (Subtype::Line, Geometry::LineString(line)) => {
+                Geometry::Line(Line::new(line[0], line[1]))
+            }
+            (Subtype::Rect, Geometry::Polygon(polygon)) => {
+                // The opposite corners of the rectangle
+                let exterior = polygon.exterior();
+                Geometry::Rect(Rect::new(exterior[0], exterior[2]))
+            }
+            (Subtype::Triangle, Geometry::Polygon(polygon)) => {
+                let exterior = polygon.exterior();
+                Geometry::Triangle(Triangle::new(exterior[0], exterior[1], exterior[2]))
+            }
+            (_, geometry) => geometry,
+        }
+    }
+}
+
+impl Zerometry<'_> {
+    /// Attach the subtype read from the tag to the line or the polygon.
+    pub(crate) fn attach_subtype(self, subtype: Subtype) -> Result<Self, ZerometryError> {
+        Ok(match self {
+            Zerometry::Line(line) => {
+                subtype.check(LINE_TAG, line.len())?;
+                Zerometry::Line(line.with_subtype(subtype))
+            }
+            Zerometry::Polygon(polygon) => {
+                subtype.check(POLYGON_TAG, polygon.coords().len())?;
+                Zerometry::Polygon(polygon.with_subtype(subtype))
+            }
+            zerometry => {
+                // Only the lines and polygons can have a subtype
+                subtype.check(u64::MAX, 0)?;
+                zerometry
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Line, Rect, Triangle, coord};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{WriteOptions, header::TAG_SIZE_IN_BYTES};
+
+    #[test]
+    fn lossless_round_trip() {
+        let geometries: [Geometry<f64>; 3] = [
+            Line::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 2.0 }).into(),
+            Rect::new(coord! { x: 3.0, y: 1.0 }, coord! { x: 0.0, y: 2.0 }).into(),
+            Triangle::new(
+                coord! { x: 0.0, y: 0.0 },
+                coord! { x: 2.0, y: 0.0 },
+                coord! { x: 1.0, y: 1.0 },
+            )
+            .into(),
+        ];
+        for geometry in geometries {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+            assert_eq!(Zerometry::serialized_size(&geometry), buffer.len());
+            let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+            assert_eq!(zerometry.to_geo(), geometry);
+            assert_eq!(zerometry, geometry);
+            assert_eq!(
+                Zerometry::decode_unaligned_bytes(&buffer).unwrap(),
+                geometry
+            );
+
+            // The subtype is kept along with the options
+            let options = WriteOptions {
+                generation: Some(1),
+                checksum: true,
+                little_endian: true,
+                ..WriteOptions::default()
+            };
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+            let zerometry = crate::ZerometryBuf::try_from_bytes(&buffer).unwrap();
+            assert_eq!(zerometry.as_zerometry().to_geo(), geometry);
+        }
+
+        // The relations only see a line and a polygon
+        let mut buffer = Vec::new();
+        let rect = Rect::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 1.0 });
+        Zerometry::write_from_geometry(&mut buffer, &rect.into()).unwrap();
+        let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(zerometry.to_polygon().unwrap().to_geo(), @"POLYGON((1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0,1.0 0.0))");
+    }
+
+    #[test]
+    fn invalid_subtype() {
+        let line = Line::new(coord! { x: 0.0, y: 0.0 }, coord! { x: 1.0, y: 2.0 });
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &line.into()).unwrap();
+        let tag = u64::from_ne_bytes(buffer[..TAG_SIZE_IN_BYTES].try_into().unwrap());
+
+        // A rect can't be stored as a line
+        let mut corrupted = buffer.clone();
+        let rect = tag & !Subtype::Line.tag_bits() | Subtype::Rect.tag_bits();
+        corrupted[..TAG_SIZE_IN_BYTES].copy_from_slice(&rect.to_ne_bytes());
+        let error = Zerometry::try_from_bytes(&corrupted).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"UnexpectedSubtype");
+        let error = Zerometry::decode_unaligned_bytes(&corrupted).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"UnexpectedSubtype");
+
+        // Unknown subtype
+        let mut corrupted = buffer.clone();
+        corrupted[..TAG_SIZE_IN_BYTES]
+            .copy_from_slice(&(tag | 0x42 << SUBTYPE_SHIFT).to_ne_bytes());
+        let error = Zerometry::try_from_bytes(&corrupted).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"InvalidTag(144188855354916868)");
+    }
+}
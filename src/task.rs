@@ -0,0 +1,552 @@
+use std::task::Poll;
+use std::time::Instant;
+
+use crate::{
+    Coords, InputRelation, OutputRelation, RelationBetweenShapes, Segment, Zerometry,
+    topology::without_topology,
+};
+
+/// The maximum number of segment tests performed by a single step of a [`RelationTask`].
+const SEGMENT_TESTS_PER_STEP: usize = 1024;
+
+/// A resumable evaluation of the relation between two shapes.
+///
+/// Instead of computing the whole relation in one call like [`RelationBetweenShapes::relation`],
+/// the task evaluates every part of the first shape (its points, lines and polygons) against every part
+/// of the second shape, a few segment tests at a time. It can be polled with a number of steps to evaluate
+/// or given a deadline, which lets a cooperative scheduler yield between two polls, even on a single
+/// polygon made of millions of segments.
+///
+/// Every step performs at most 1024 segment tests, except when a point is involved: relating a point
+/// with a line or a polygon is done in a single step. The relation between two shapes only made of points
+/// is also computed in a single step. The relations depending on the locations of the shapes relative to each
+/// other, like [`OutputRelation::touches`], require the whole shapes: they're left to `None` and the
+/// [`InputRelation::tolerance`] is ignored, use [`RelationBetweenShapes::relation`] to compute them.
+///
+/// ```
+/// use std::task::Poll;
+/// use geo_types::polygon;
+/// use zerometry::{InputRelation, RelationTask, Zerometry};
+///
+/// let points = geo_types::MultiPoint::from(vec![(0.0, 0.0), (10.0, 10.0)]);
+/// let polygon = polygon![(x: -1.0, y: -1.0), (x: 1.0, y: -1.0), (x: 1.0, y: 1.0), (x: -1.0, y: 1.0)];
+///
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &points.into()).unwrap();
+/// let points = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &polygon.into()).unwrap();
+/// let polygon = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+///
+/// let mut task = RelationTask::new(points, polygon, InputRelation::all());
+/// // Only the first point has been evaluated
+/// assert!(task.poll(1).is_pending());
+/// let Poll::Ready(relation) = task.poll(1) else { panic!() };
+/// // One of the points is contained in the polygon, but not all of them
+/// assert_eq!(relation.contained, Some(true));
+/// assert_eq!(relation.strict_contained, Some(false));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RelationTask<'a> {
+    shape: Zerometry<'a>,
+    other: Zerometry<'a>,
+    relation: InputRelation,
+    /// The relations found between any part of `shape` and any part of `other`.
+    found: OutputRelation,
+    /// Index of the part of `shape` being evaluated.
+    part: usize,
+    /// Index of the part of `other` being evaluated against it.
+    other_part: usize,
+    /// Index of the next segment test between both parts.
+    cursor: usize,
+    /// Set once a segment of the part intersects with a segment of the other part.
+    intersects: bool,
+    /// Set once a segment of the line overlaps with a segment of the other line.
+    overlaps: bool,
+    /// Set if the part of `shape` is contained in any of the parts of `other` evaluated so far.
+    part_contained: bool,
+    /// Number of parts of `shape` contained in a part of `other`.
+    contained: usize,
+    /// For every part of `other`, set if it's contained in a part of `shape`.
+    other_contained: Vec<bool>,
+    finished: bool,
+}
+
+impl<'a> RelationTask<'a> {
+    /// Create a new task computing the relation between `shape` and `other`.
+    /// Nothing is evaluated before the first poll.
+    pub fn new(shape: Zerometry<'a>, other: Zerometry<'a>, relation: InputRelation) -> Self {
+        let pair_relation = InputRelation {
+            contains: true,
+            contained: true,
+            intersect: true,
+            overlaps: true,
+            ..InputRelation::none()
+        };
+        Self {
+            shape,
+            other,
+            relation,
+            found: pair_relation.to_false(),
+            part: 0,
+            other_part: 0,
+            cursor: 0,
+            intersects: false,
+            overlaps: false,
+            part_contained: false,
+            contained: 0,
+            other_contained: vec![false; other.parts_len()],
+            finished: false,
+        }
+    }
+
+    /// Evaluate at most `steps` steps.
+    /// Return [`Poll::Ready`] with the relation once everything has been evaluated.
+    pub fn poll(&mut self, steps: usize) -> Poll<OutputRelation> {
+        for _ in 0..steps {
+            if self.step() {
+                return Poll::Ready(self.finish());
+            }
+        }
+        Poll::Pending
+    }
+
+    /// Evaluate the steps until the deadline is reached.
+    /// At least one step is evaluated on every call so the task always makes progress.
+    /// Return [`Poll::Ready`] with the relation once everything has been evaluated.
+    pub fn poll_until(&mut self, deadline: Instant) -> Poll<OutputRelation> {
+        loop {
+            if self.step() {
+                return Poll::Ready(self.finish());
+            }
+            if Instant::now() >= deadline {
+                return Poll::Pending;
+            }
+        }
+    }
+
+    /// Evaluate the whole relation without yielding.
+    pub fn run(mut self) -> OutputRelation {
+        while !self.step() {}
+        self.finish()
+    }
+
+    /// Return `true` once the relation has been fully evaluated.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Evaluate the next step, return `true` if there is nothing left to evaluate.
+    fn step(&mut self) -> bool {
+        if self.finished {
+            return true;
+        }
+        if self.shape.parts_len() == 0 || self.other.parts_len() == 0 || self.points_only() {
+            self.finished = true;
+            return true;
+        }
+
+        let part = self.shape.part(self.part).unwrap();
+        let other_part = self.other.part(self.other_part).unwrap();
+        if !self.step_pair(&part, &other_part) {
+            return false;
+        }
+
+        // Both parts have been fully evaluated, move to the next pair
+        self.other_part += 1;
+        self.cursor = 0;
+        self.intersects = false;
+        self.overlaps = false;
+        if self.other_part == self.other.parts_len() {
+            self.contained += self.part_contained as usize;
+            self.part_contained = false;
+            self.other_part = 0;
+            self.part += 1;
+        }
+        if self.part == self.shape.parts_len()
+            || (self.relation.early_exit && self.found.any_relation())
+        {
+            self.finished = true;
+        }
+        self.finished
+    }
+
+    /// Evaluate the next segment tests between both parts, return `true` once their relation is known.
+    fn step_pair(&mut self, part: &Zerometry, other_part: &Zerometry) -> bool {
+        let (Some(coords), Some(other_coords)) = (chain(part), chain(other_part)) else {
+            // Relating a point is cheap enough to be done at once
+            let relation = part.relation(other_part, pair_relation(self.relation));
+            self.record(relation);
+            return true;
+        };
+        let (left, bottom, right, top) = part.bounds();
+        let other_bounds = other_part.bounds();
+        let (other_left, other_bottom, other_right, other_top) = other_bounds;
+        if part.is_empty()
+            || other_part.is_empty()
+            || right < other_left
+            || other_right < left
+            || top < other_bottom
+            || other_top < bottom
+        {
+            return true;
+        }
+
+        // Once the parts intersect, only the overlapping segments of two lines can tell us more
+        let lines = matches!((part, other_part), (Zerometry::Line(_), Zerometry::Line(_)));
+        let done =
+            |task: &Self| task.intersects && !(lines && task.relation.overlaps && !task.overlaps);
+        let segments = coords.len().saturating_sub(1);
+        let other_segments = other_coords.len().saturating_sub(1);
+        let tests = segments * other_segments;
+        let end = tests.min(self.cursor + SEGMENT_TESTS_PER_STEP);
+        while self.cursor < end && !done(self) {
+            let (index, other_index) = (self.cursor / other_segments, self.cursor % other_segments);
+            let segment = Segment::from_coord_pair(&coords[index], &coords[index + 1]);
+            if other_index == 0 && !segment_meets(&segment, other_bounds) {
+                // None of the tests of this segment can succeed
+                self.cursor += other_segments;
+                continue;
+            }
+            let other_segment = Segment::from_coord_pair(
+                &other_coords[other_index],
+                &other_coords[other_index + 1],
+            );
+            if segment.intersects(&other_segment) {
+                self.intersects = true;
+                self.overlaps |= lines && segment.overlaps(&other_segment);
+            }
+            self.cursor += 1;
+        }
+        if self.cursor < tests && !done(self) {
+            return false;
+        }
+
+        let relation = pair_relation(self.relation).to_false();
+        let relation = if self.intersects {
+            let relation = relation.make_intersect_if_set();
+            if self.overlaps {
+                relation.make_overlaps_if_set()
+            } else {
+                relation
+            }
+        } else {
+            // The parts don't intersect, one is contained in the other if any of its coords is in the other
+            match (part, other_part) {
+                (_, Zerometry::Polygon(polygon)) if polygon.contains(&coords[0]) => {
+                    relation.make_contained_if_set()
+                }
+                (Zerometry::Polygon(polygon), _) if polygon.contains(&other_coords[0]) => {
+                    relation.make_contains_if_set()
+                }
+                _ => relation,
+            }
+        };
+        self.record(relation);
+        true
+    }
+
+    /// Record the relation between the current pair of parts.
+    fn record(&mut self, relation: OutputRelation) {
+        self.found |= OutputRelation {
+            disjoint: None,
+            ..relation
+        };
+        self.part_contained |= relation.contained.unwrap_or_default();
+        if relation.contains.unwrap_or_default() {
+            self.other_contained[self.other_part] = true;
+        }
+    }
+
+    /// Return `true` if both shapes are only made of points, see [`Zerometry::part`].
+    fn points_only(&self) -> bool {
+        let points_only = |zerometry: &Zerometry| {
+            let len = zerometry.parts_len();
+            len > 0 && matches!(zerometry.part(len - 1), Some(Zerometry::Point(_)))
+        };
+        points_only(&self.shape) && points_only(&self.other)
+    }
+
+    fn finish(&self) -> OutputRelation {
+        let exact = InputRelation {
+            budget: None,
+            ..without_topology(self.relation)
+        };
+        if self.points_only() {
+            // The multi points have their own way of being related, see `ZultiPoints`
+            return self.shape.relation(&self.other, exact);
+        }
+
+        let found = self.found;
+        let strict_contains = !self.other_contained.is_empty()
+            && self.other_contained.iter().all(|contained| *contained);
+        let strict_contained = self.part > 0 && self.contained == self.shape.parts_len();
+        let any = found.any_relation();
+        let output = exact.to_false();
+        OutputRelation {
+            contains: output.contains.map(|_| found.contains.unwrap_or_default()),
+            strict_contains: output.strict_contains.map(|_| strict_contains),
+            contained: output
+                .contained
+                .map(|_| found.contained.unwrap_or_default()),
+            strict_contained: output.strict_contained.map(|_| strict_contained),
+            intersect: output
+                .intersect
+                .map(|_| found.intersect.unwrap_or_default()),
+            overlaps: output.overlaps.map(|_| found.overlaps.unwrap_or_default()),
+            disjoint: output.disjoint.map(|_| !any),
+            ..output
+        }
+    }
+}
+
+/// The relation computed between every pair of parts, it's combined in [`RelationTask::finish`].
+fn pair_relation(relation: InputRelation) -> InputRelation {
+    InputRelation {
+        contains: true,
+        contained: true,
+        intersect: true,
+        overlaps: relation.overlaps,
+        ..InputRelation::none()
+    }
+}
+
+/// Return the coords of a line or a polygon, and `None` for a point.
+fn chain<'a>(part: &Zerometry<'a>) -> Option<&'a Coords> {
+    match part {
+        Zerometry::Line(line) => Some(line.coords()),
+        Zerometry::Polygon(polygon) => Some(polygon.coords()),
+        _ => None,
+    }
+}
+
+/// Return `false` if the bounding box of the segment is disjoint from the `(left, bottom, right, top)` bounds.
+fn segment_meets(segment: &Segment, (left, bottom, right, top): (f64, f64, f64, f64)) -> bool {
+    let (start, end) = (segment.start(), segment.end());
+    start.lng().max(end.lng()) >= left
+        && start.lng().min(end.lng()) <= right
+        && start.lat().max(end.lat()) >= bottom
+        && start.lat().min(end.lat()) <= top
+}
+
+impl<'a> Zerometry<'a> {
+    /// Return the number of parts composing the shape, see [`Self::part`].
+    fn parts_len(&self) -> usize {
+        match self {
+            Zerometry::Point(_) | Zerometry::Line(_) | Zerometry::Polygon(_) => 1,
+            Zerometry::MultiPoints(a) => a.len(),
+            Zerometry::MultiLines(a) => a.len(),
+            Zerometry::MultiPolygon(a) => a.len(),
+            Zerometry::Collection(a) => a.points().len() + a.lines().len() + a.polygons().len(),
+        }
+    }
+
+    /// Return the point, line or polygon at the specified index in the list of all the parts
+    /// composing the shape. The points are returned first, then the lines and finally the polygons.
+    fn part(&self, index: usize) -> Option<Zerometry<'a>> {
+        match self {
+            Zerometry::Point(_) | Zerometry::Line(_) | Zerometry::Polygon(_) => {
+                (index == 0).then_some(*self)
+            }
+            Zerometry::MultiPoints(a) => a.get(index).map(Zerometry::Point),
+            Zerometry::MultiLines(a) => a.get(index).map(Zerometry::Line),
+            Zerometry::MultiPolygon(a) => a.get(index).map(Zerometry::Polygon),
+            Zerometry::Collection(a) => {
+                let points = a.points();
+                let lines = a.lines();
+                if index < points.len() {
+                    points.get(index).map(Zerometry::Point)
+                } else if index < points.len() + lines.len() {
+                    lines.get(index - points.len()).map(Zerometry::Line)
+                } else {
+                    a.polygons()
+                        .get(index - points.len() - lines.len())
+                        .map(Zerometry::Polygon)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{MultiPoint, polygon};
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPolygon, Point, Polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    fn write(geometry: Geometry<f64>) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+        buffer
+    }
+
+    /// A regular polygon of the specified number of vertices.
+    fn circle(center: (f64, f64), radius: f64, vertices: usize) -> Polygon<f64> {
+        let exterior: Vec<_> = (0..vertices)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / vertices as f64;
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            })
+            .collect();
+        Polygon::new(LineString::from(exterior), Vec::new())
+    }
+
+    #[test]
+    fn poll_step_by_step() {
+        let collection = write(Geometry::GeometryCollection(GeometryCollection::new_from(
+            vec![
+                Geometry::Point(Point::new(0.5, 0.5)),
+                Geometry::LineString(LineString::from(vec![(0.2, 0.2), (0.8, 0.8)])),
+                polygon![(x: 0.1, y: 0.1), (x: 0.3, y: 0.1), (x: 0.3, y: 0.3)].into(),
+            ],
+        )));
+        let collection = unsafe { Zerometry::from_bytes(&collection).unwrap() };
+        let square =
+            write(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into());
+        let square = unsafe { Zerometry::from_bytes(&square).unwrap() };
+
+        let mut task = RelationTask::new(collection, square, InputRelation::all());
+        assert!(task.poll(2).is_pending());
+        assert!(!task.is_finished());
+        let output = task.poll(2);
+        assert_compact_debug_snapshot!(output, @"Ready(OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false })");
+        assert!(task.is_finished());
+        // Polling a finished task returns the same result
+        assert_eq!(task.poll(1), output);
+    }
+
+    #[test]
+    fn large_polygons_yield() {
+        let large = write(circle((0.0, 0.0), 10.0, 2000).into());
+        let large = unsafe { Zerometry::from_bytes(&large).unwrap() };
+        let inside = write(circle((1.0, 0.0), 5.0, 100).into());
+        let inside = unsafe { Zerometry::from_bytes(&inside).unwrap() };
+        let crossing = write(circle((10.0, 0.0), 5.0, 100).into());
+        let crossing = unsafe { Zerometry::from_bytes(&crossing).unwrap() };
+
+        for (shape, other) in [(large, inside), (inside, large), (large, crossing)] {
+            let mut task = RelationTask::new(shape, other, InputRelation::all());
+            let mut steps = 1;
+            let output = loop {
+                match task.poll(1) {
+                    Poll::Ready(output) => break output,
+                    Poll::Pending => steps += 1,
+                }
+            };
+            assert!(steps > 1);
+            assert_eq!(output, shape.relation(&other, InputRelation::all()));
+        }
+        let output = RelationTask::new(large, crossing, InputRelation::all()).run();
+        assert_compact_debug_snapshot!((output.intersect, output.contains), @"(Some(true), Some(false))");
+        let output = RelationTask::new(inside, large, InputRelation::all()).run();
+        assert_compact_debug_snapshot!((output.contained, output.strict_contained), @"(Some(true), Some(true))");
+    }
+
+    #[test]
+    fn every_part_must_be_contained() {
+        let points = write(MultiPoint::from(vec![(5., 5.), (0.5, 0.5), (6., 6.)]).into());
+        let points = unsafe { Zerometry::from_bytes(&points).unwrap() };
+        let squares = write(
+            MultiPolygon::new(vec![
+                polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)],
+                polygon![(x: 3., y: 3.), (x: 4., y: 3.), (x: 4., y: 4.), (x: 3., y: 4.)],
+            ])
+            .into(),
+        );
+        let squares = unsafe { Zerometry::from_bytes(&squares).unwrap() };
+        let inner = write(polygon![(x: 0.2, y: 0.2), (x: 0.5, y: 0.2), (x: 0.5, y: 0.5)].into());
+        let inner = unsafe { Zerometry::from_bytes(&inner).unwrap() };
+        let line = write(LineString::from(vec![(0.2, 0.8), (3.5, 3.5)]).into());
+        let line = unsafe { Zerometry::from_bytes(&line).unwrap() };
+
+        for (shape, other) in [
+            (points, squares),
+            (squares, points),
+            (inner, squares),
+            (squares, inner),
+            (line, squares),
+            (squares, line),
+        ] {
+            let output = RelationTask::new(shape, other, InputRelation::all()).run();
+            assert_eq!(
+                output,
+                shape.relation(&other, InputRelation::all()),
+                "{shape:?} {other:?}"
+            );
+        }
+        // Only one of the points is contained in the squares, and the squares contains only one of them
+        let output = RelationTask::new(points, squares, InputRelation::all()).run();
+        assert_compact_debug_snapshot!((output.contained, output.strict_contained), @"(Some(true), Some(false))");
+        let output = RelationTask::new(squares, points, InputRelation::all()).run();
+        assert_compact_debug_snapshot!((output.contains, output.strict_contains), @"(Some(true), Some(false))");
+    }
+
+    #[test]
+    fn topology() {
+        let square = write(
+            polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)].into(),
+        );
+        let square = unsafe { Zerometry::from_bytes(&square).unwrap() };
+        let through = write(LineString::from(vec![(-5., 5.), (15., 5.)]).into());
+        let through = unsafe { Zerometry::from_bytes(&through).unwrap() };
+        let close = write(Point::new(10.5, 5.).into());
+        let close = unsafe { Zerometry::from_bytes(&close).unwrap() };
+
+        // The topology requires the whole shapes, it's never computed by the task and the tolerance is ignored
+        let relation = InputRelation {
+            tolerance: 1.0,
+            ..InputRelation::all_with_topology()
+        };
+        for (shape, other) in [(square, through), (through, square), (square, close)] {
+            let output = RelationTask::new(shape, other, relation).run();
+            assert_eq!(output, shape.all_relation(&other), "{shape:?} {other:?}");
+        }
+        let output = RelationTask::new(square, close, relation).run();
+        assert_compact_debug_snapshot!((output.disjoint, output.touches, output.crosses, output.covers, output.covered_by), @"(Some(true), None, None, None, None)");
+    }
+
+    #[test]
+    fn early_exit_and_deadline() {
+        let points = write(MultiPoint::from(vec![(5., 5.), (0.5, 0.5), (6., 6.)]).into());
+        let points = unsafe { Zerometry::from_bytes(&points).unwrap() };
+        let square =
+            write(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into());
+        let square = unsafe { Zerometry::from_bytes(&square).unwrap() };
+
+        let relation = InputRelation {
+            early_exit: true,
+            ..InputRelation::all()
+        };
+        let mut task = RelationTask::new(points, square, relation);
+        assert!(task.poll(1).is_pending());
+        assert_compact_debug_snapshot!(task.poll(1), @"Ready(OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false })");
+
+        // A deadline in the past still evaluates one step per call
+        let mut task = RelationTask::new(points, square, InputRelation::all());
+        let now = Instant::now();
+        assert!(task.poll_until(now).is_pending());
+        assert!(task.poll_until(now).is_pending());
+        assert_compact_debug_snapshot!(task.poll_until(now), @"Ready(OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false })");
+        assert_eq!(
+            RelationTask::new(points, square, InputRelation::all()).run(),
+            points.relation(&square, InputRelation::all())
+        );
+    }
+
+    #[test]
+    fn empty_shape() {
+        let points = write(MultiPoint::<f64>::new(Vec::new()).into());
+        let points = unsafe { Zerometry::from_bytes(&points).unwrap() };
+        let square =
+            write(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)].into());
+        let square = unsafe { Zerometry::from_bytes(&square).unwrap() };
+
+        assert_compact_debug_snapshot!(RelationTask::new(points, square, InputRelation::all()).run(), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(RelationTask::new(square, points, InputRelation::all()).run(), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+}
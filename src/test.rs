@@ -30,7 +30,7 @@ fn test_mono_multipolygon_contains_points() {
     .unwrap();
     let wrong_multipolygon = unsafe { Zerometry::from_bytes(&wrong_buffer).unwrap() };
     let wrong = wrong_multipolygon.all_relation(&point);
-    assert_compact_debug_snapshot!(wrong, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
+    assert_compact_debug_snapshot!(wrong, @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
     let right_multipolygon = geo_types::MultiPolygon::new(vec![geo_types::Polygon::new(
         geo_types::LineString::from(vec![
@@ -56,7 +56,7 @@ fn test_mono_multipolygon_contains_points() {
     println!("right_multipolygon: {}", print_geojson(&right_multipolygon));
 
     let right = right_multipolygon.all_relation(&point);
-    assert_compact_debug_snapshot!(right, @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+    assert_compact_debug_snapshot!(right, @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 }
 
 fn print_geojson(geometry: &Zerometry) -> String {
@@ -0,0 +1,438 @@
+use std::fmt::Write;
+
+use crate::{
+    Coord, Coords, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry, ZultiLines,
+    ZultiPoints, ZultiPolygons,
+};
+
+impl Zerometry<'_> {
+    /// Export the shape as a compact WKT string, with every coordinate rounded to `decimals` digits after the dot.
+    ///
+    /// The output doesn't depend on the locale nor on the platform, the trailing zeros are removed and `-0` is written `0`,
+    /// which makes it suitable as a cache key or to diff exports.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(1.123456, -0.00001))).unwrap();
+    /// let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+    /// assert_eq!(zerometry.to_wkt_with_precision(3), "POINT(1.123 0)");
+    /// ```
+    pub fn to_wkt_with_precision(&self, decimals: usize) -> String {
+        let mut output = String::new();
+        write_wkt(&mut output, self, decimals);
+        output
+    }
+
+    /// Export the shape as a compact GeoJSON geometry, with every coordinate rounded to `decimals` digits after the dot.
+    /// The numbers are written following the same rules as [`Self::to_wkt_with_precision`], and the non-finite
+    /// coordinates are written as `null` since JSON can't represent them.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry(&mut buffer, &Geometry::Point(Point::new(1.123456, 2.5))).unwrap();
+    /// let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+    /// assert_eq!(zerometry.to_geojson_with_precision(2), r#"{"type":"Point","coordinates":[1.12,2.5]}"#);
+    /// ```
+    pub fn to_geojson_with_precision(&self, decimals: usize) -> String {
+        let mut output = String::new();
+        write_geojson(&mut output, self, decimals);
+        output
+    }
+
+    /// Export both shapes as a GeoJSON `FeatureCollection` with their relations as properties, ready to be pasted
+    /// in a bug report or a GeoJSON viewer.
+    ///
+    /// The first feature is `a` and contains the relation of `a` with `b` computed with [`InputRelation::all`],
+    /// the second one is `b` with its relation with `a`. The coordinates are written with their full precision
+    /// to reproduce the exact shapes.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let mut a = Vec::new();
+    /// Zerometry::write_from_geometry(&mut a, &Geometry::Point(Point::new(1.0, 2.0))).unwrap();
+    /// let a = unsafe { Zerometry::from_bytes(&a).unwrap() };
+    /// let mut b = Vec::new();
+    /// Zerometry::write_from_geometry(&mut b, &Geometry::Point(Point::new(3.0, 4.0))).unwrap();
+    /// let b = unsafe { Zerometry::from_bytes(&b).unwrap() };
+    ///
+    /// let dump = Zerometry::debug_pair(&a, &b);
+    /// assert!(dump.starts_with(r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"operand":"a","#));
+    /// ```
+    pub fn debug_pair(a: &Zerometry, b: &Zerometry) -> String {
+        let mut output = String::from(r#"{"type":"FeatureCollection","features":["#);
+        write_feature(&mut output, "a", a, a.relation(b, InputRelation::all()));
+        output.push(',');
+        write_feature(&mut output, "b", b, b.relation(a, InputRelation::all()));
+        output.push_str("]}");
+        output
+    }
+}
+
+/// Used in place of a number of decimals to write the shortest representation reading back to the exact same number.
+const FULL_PRECISION: usize = usize::MAX;
+
+fn write_feature(output: &mut String, name: &str, zerometry: &Zerometry, relation: OutputRelation) {
+    write!(
+        output,
+        r#"{{"type":"Feature","properties":{{"operand":"{name}""#
+    )
+    .unwrap();
+    let OutputRelation {
+        contains,
+        strict_contains,
+        contained,
+        strict_contained,
+        intersect,
+        disjoint,
+        overlaps,
+        touches,
+        crosses,
+        covers,
+        covered_by,
+        // The relation is always computed exactly
+        approximate: _,
+    } = relation;
+    for (field, value) in [
+        ("contains", contains),
+        ("strict_contains", strict_contains),
+        ("contained", contained),
+        ("strict_contained", strict_contained),
+        ("intersect", intersect),
+        ("disjoint", disjoint),
+        ("overlaps", overlaps),
+        ("touches", touches),
+        ("crosses", crosses),
+        ("covers", covers),
+        ("covered_by", covered_by),
+    ] {
+        match value {
+            Some(value) => write!(output, r#","{field}":{value}"#).unwrap(),
+            None => write!(output, r#","{field}":null"#).unwrap(),
+        }
+    }
+    output.push_str(r#"},"geometry":"#);
+    write_geojson(output, zerometry, FULL_PRECISION);
+    output.push('}');
+}
+
+/// The points of the multi points in the order they were written, even if they're stored sorted.
+fn original_order<'a>(zulti_points: &ZultiPoints<'a>) -> impl Iterator<Item = &'a Coord> + use<'a> {
+    zulti_points
+        .points_in_original_order()
+        .map(|point| point.coord())
+}
+
+/// Write the number rounded to the specified number of decimals, or [`FULL_PRECISION`], without the trailing zeros.
+fn write_number(output: &mut String, value: f64, decimals: usize) {
+    let start = output.len();
+    if decimals == FULL_PRECISION {
+        write!(output, "{value}").unwrap();
+    } else {
+        write!(output, "{value:.decimals$}").unwrap();
+    }
+    if output[start..].contains('.') {
+        let trimmed = output.trim_end_matches('0').trim_end_matches('.').len();
+        output.truncate(trimmed);
+    }
+    if &output[start..] == "-0" {
+        output.replace_range(start.., "0");
+    }
+}
+
+fn write_wkt(output: &mut String, zerometry: &Zerometry, decimals: usize) {
+    match zerometry {
+        Zerometry::Point(zoint) => {
+            output.push_str("POINT(");
+            write_wkt_coord(output, zoint.coord(), decimals);
+            output.push(')');
+        }
+        Zerometry::MultiPoints(zulti_points) => {
+            output.push_str("MULTIPOINT");
+            write_wkt_coords_iter(output, original_order(zulti_points), decimals);
+        }
+        Zerometry::Line(zine) => {
+            output.push_str("LINESTRING");
+            write_wkt_coords(output, zine.coords(), decimals);
+        }
+        Zerometry::MultiLines(zulti_lines) => {
+            output.push_str("MULTILINESTRING");
+            write_wkt_lines(output, zulti_lines, decimals);
+        }
+        Zerometry::Polygon(zolygon) => {
+            output.push_str("POLYGON");
+            write_wkt_polygon(output, zolygon.coords(), decimals);
+        }
+        Zerometry::MultiPolygon(zulti_polygons) => {
+            output.push_str("MULTIPOLYGON");
+            write_wkt_polygons(output, zulti_polygons, decimals);
+        }
+        Zerometry::Collection(zollection) => {
+            output.push_str("GEOMETRYCOLLECTION(MULTIPOINT");
+            write_wkt_coords(output, zollection.points().coords(), decimals);
+            output.push_str(",MULTILINESTRING");
+            write_wkt_lines(output, &zollection.lines(), decimals);
+            output.push_str(",MULTIPOLYGON");
+            write_wkt_polygons(output, &zollection.polygons(), decimals);
+            output.push(')');
+        }
+    }
+}
+
+fn write_wkt_coord(output: &mut String, coord: &Coord, decimals: usize) {
+    write_number(output, coord.lng(), decimals);
+    output.push(' ');
+    write_number(output, coord.lat(), decimals);
+}
+
+/// Write the coords between parenthesis, or ` EMPTY` if there is none.
+fn write_wkt_coords(output: &mut String, coords: &Coords, decimals: usize) {
+    write_wkt_coords_iter(output, coords.iter(), decimals);
+}
+
+fn write_wkt_coords_iter<'c>(
+    output: &mut String,
+    coords: impl Iterator<Item = &'c Coord>,
+    decimals: usize,
+) {
+    let mut coords = coords.peekable();
+    if coords.peek().is_none() {
+        output.push_str(" EMPTY");
+        return;
+    }
+    output.push('(');
+    for (index, coord) in coords.enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_wkt_coord(output, coord, decimals);
+    }
+    output.push(')');
+}
+
+fn write_wkt_polygon(output: &mut String, coords: &Coords, decimals: usize) {
+    if coords.is_empty() {
+        output.push_str(" EMPTY");
+        return;
+    }
+    output.push('(');
+    write_wkt_coords(output, coords, decimals);
+    output.push(')');
+}
+
+fn write_wkt_lines(output: &mut String, zulti_lines: &ZultiLines, decimals: usize) {
+    if zulti_lines.is_empty() {
+        output.push_str(" EMPTY");
+        return;
+    }
+    output.push('(');
+    for (index, zine) in zulti_lines.lines().enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_wkt_coords(output, zine.coords(), decimals);
+    }
+    output.push(')');
+}
+
+fn write_wkt_polygons(output: &mut String, zulti_polygons: &ZultiPolygons, decimals: usize) {
+    if zulti_polygons.is_empty() {
+        output.push_str(" EMPTY");
+        return;
+    }
+    output.push('(');
+    for (index, zolygon) in zulti_polygons.polygons().enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_wkt_polygon(output, zolygon.coords(), decimals);
+    }
+    output.push(')');
+}
+
+fn write_geojson(output: &mut String, zerometry: &Zerometry, decimals: usize) {
+    match zerometry {
+        Zerometry::Point(zoint) => {
+            output.push_str(r#"{"type":"Point","coordinates":"#);
+            write_geojson_coord(output, zoint.coord(), decimals);
+        }
+        Zerometry::MultiPoints(zulti_points) => {
+            output.push_str(r#"{"type":"MultiPoint","coordinates":"#);
+            write_geojson_coords_iter(output, original_order(zulti_points), decimals);
+        }
+        Zerometry::Line(zine) => {
+            output.push_str(r#"{"type":"LineString","coordinates":"#);
+            write_geojson_coords(output, zine.coords(), decimals);
+        }
+        Zerometry::MultiLines(zulti_lines) => {
+            output.push_str(r#"{"type":"MultiLineString","coordinates":"#);
+            write_geojson_lines(output, zulti_lines, decimals);
+        }
+        Zerometry::Polygon(zolygon) => {
+            output.push_str(r#"{"type":"Polygon","coordinates":"#);
+            write_geojson_polygon(output, zolygon.coords(), decimals);
+        }
+        Zerometry::MultiPolygon(zulti_polygons) => {
+            output.push_str(r#"{"type":"MultiPolygon","coordinates":"#);
+            write_geojson_polygons(output, zulti_polygons, decimals);
+        }
+        Zerometry::Collection(zollection) => {
+            output.push_str(r#"{"type":"GeometryCollection","geometries":["#);
+            output.push_str(r#"{"type":"MultiPoint","coordinates":"#);
+            write_geojson_coords(output, zollection.points().coords(), decimals);
+            output.push_str(r#"},{"type":"MultiLineString","coordinates":"#);
+            write_geojson_lines(output, &zollection.lines(), decimals);
+            output.push_str(r#"},{"type":"MultiPolygon","coordinates":"#);
+            write_geojson_polygons(output, &zollection.polygons(), decimals);
+            output.push_str("}]");
+        }
+    }
+    output.push('}');
+}
+
+fn write_geojson_number(output: &mut String, value: f64, decimals: usize) {
+    if value.is_finite() {
+        write_number(output, value, decimals);
+    } else {
+        output.push_str("null");
+    }
+}
+
+fn write_geojson_coord(output: &mut String, coord: &Coord, decimals: usize) {
+    output.push('[');
+    write_geojson_number(output, coord.lng(), decimals);
+    output.push(',');
+    write_geojson_number(output, coord.lat(), decimals);
+    output.push(']');
+}
+
+fn write_geojson_coords(output: &mut String, coords: &Coords, decimals: usize) {
+    write_geojson_coords_iter(output, coords.iter(), decimals);
+}
+
+fn write_geojson_coords_iter<'c>(
+    output: &mut String,
+    coords: impl Iterator<Item = &'c Coord>,
+    decimals: usize,
+) {
+    output.push('[');
+    for (index, coord) in coords.enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_geojson_coord(output, coord, decimals);
+    }
+    output.push(']');
+}
+
+fn write_geojson_polygon(output: &mut String, coords: &Coords, decimals: usize) {
+    if coords.is_empty() {
+        output.push_str("[]");
+        return;
+    }
+    output.push('[');
+    write_geojson_coords(output, coords, decimals);
+    output.push(']');
+}
+
+fn write_geojson_lines(output: &mut String, zulti_lines: &ZultiLines, decimals: usize) {
+    output.push('[');
+    for (index, zine) in zulti_lines.lines().enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_geojson_coords(output, zine.coords(), decimals);
+    }
+    output.push(']');
+}
+
+fn write_geojson_polygons(output: &mut String, zulti_polygons: &ZultiPolygons, decimals: usize) {
+    output.push('[');
+    for (index, zolygon) in zulti_polygons.polygons().enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_geojson_polygon(output, zolygon.coords(), decimals);
+    }
+    output.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, GeometryCollection, LineString, MultiPoint, Point, polygon};
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        let mut output = String::new();
+        for value in [
+            1.0,
+            1.5,
+            -0.0001,
+            123.456789,
+            1e20,
+            f64::NAN,
+            -f64::INFINITY,
+        ] {
+            write_number(&mut output, value, 3);
+            output.push(' ');
+        }
+        write_number(&mut output, 2.5, 0);
+        assert_snapshot!(output, @"1 1.5 0 123.457 100000000000000000000 NaN -inf 2");
+    }
+
+    #[test]
+    fn export_with_precision() {
+        let geometries: [Geometry<f64>; 5] = [
+            Point::new(1.0 / 3.0, 2.0).into(),
+            MultiPoint::<f64>::new(Vec::new()).into(),
+            LineString::from(vec![(0.123456, 0.0), (1.0, 1.999999)]).into(),
+            polygon![(x: 0., y: 0.), (x: 1.11111, y: 0.), (x: 0., y: 1.)].into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Point::new(1.0, 2.0).into(),
+            ])),
+        ];
+        let mut output = String::new();
+        for geometry in geometries {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+            let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+            writeln!(output, "{}", zerometry.to_wkt_with_precision(2)).unwrap();
+            writeln!(output, "{}", zerometry.to_geojson_with_precision(2)).unwrap();
+        }
+        assert_snapshot!(output, @r#"
+            POINT(0.33 2)
+            {"type":"Point","coordinates":[0.33,2]}
+            MULTIPOINT EMPTY
+            {"type":"MultiPoint","coordinates":[]}
+            LINESTRING(0.12 0,1 2)
+            {"type":"LineString","coordinates":[[0.12,0],[1,2]]}
+            POLYGON((0 0,1.11 0,0 1,0 0))
+            {"type":"Polygon","coordinates":[[[0,0],[1.11,0],[0,1],[0,0]]]}
+            GEOMETRYCOLLECTION(MULTIPOINT(1 2),MULTILINESTRING EMPTY,MULTIPOLYGON EMPTY)
+            {"type":"GeometryCollection","geometries":[{"type":"MultiPoint","coordinates":[[1,2]]},{"type":"MultiLineString","coordinates":[]},{"type":"MultiPolygon","coordinates":[]}]}
+        "#);
+    }
+
+    #[test]
+    fn debug_pair() {
+        let mut a = Vec::new();
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        Zerometry::write_from_geometry(&mut a, &square.into()).unwrap();
+        let a = unsafe { Zerometry::from_bytes(&a).unwrap() };
+        let mut b = Vec::new();
+        Zerometry::write_from_geometry(&mut b, &Point::new(0.1, 0.5).into()).unwrap();
+        let b = unsafe { Zerometry::from_bytes(&b).unwrap() };
+
+        assert_snapshot!(Zerometry::debug_pair(&a, &b), @r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"operand":"a","contains":true,"strict_contains":true,"contained":false,"strict_contained":false,"intersect":false,"disjoint":false,"overlaps":false,"touches":null,"crosses":null,"covers":null,"covered_by":null},"geometry":{"type":"Polygon","coordinates":[[[0,0],[1,0],[1,1],[0,1],[0,0]]]}},{"type":"Feature","properties":{"operand":"b","contains":false,"strict_contains":false,"contained":true,"strict_contained":true,"intersect":false,"disjoint":false,"overlaps":false,"touches":null,"crosses":null,"covers":null,"covered_by":null},"geometry":{"type":"Point","coordinates":[0.1,0.5]}}]}"#);
+    }
+}
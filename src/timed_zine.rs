@@ -0,0 +1,208 @@
+use core::fmt;
+use std::mem;
+
+use bytemuck::cast_slice;
+use geo_types::LineString;
+
+use crate::{
+    BoundingBox, COORD_SIZE_IN_BYTES, Coords, ZerometryError, Zine,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    error::{
+        check_aligned, check_empty, checked_size, split_bounding_box, split_checked, split_u64,
+    },
+};
+
+/// A [`Zine`] carrying a timestamp for each of its points, typically a GPS track.
+///
+/// The timestamps are `u64` in any unit chosen by the caller and must be sorted in increasing order.
+/// The binary format is the bounding box, the number of points as a `u64`, the coords and finally the timestamps.
+///
+/// ```
+/// use zerometry::TimedZine;
+/// use geo_types::{LineString, coord};
+///
+/// let track = LineString::from(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+/// let mut buffer = Vec::new();
+/// TimedZine::write_from_geometry(&mut buffer, &track, &[100, 200, 400]).unwrap();
+/// let timed_zine = unsafe { TimedZine::from_bytes(&buffer) };
+///
+/// assert_eq!(timed_zine.position_at_time(150), Some(coord! { x: 5.0, y: 0.0 }));
+/// assert_eq!(timed_zine.position_at_time(300), Some(coord! { x: 10.0, y: 5.0 }));
+/// assert_eq!(timed_zine.position_at_time(500), None);
+/// ```
+#[derive(Clone, Copy)]
+pub struct TimedZine<'a> {
+    zine: Zine<'a>,
+    timestamps: &'a [u64],
+}
+
+impl<'a> TimedZine<'a> {
+    /// Create a [`TimedZine`] from its line and the timestamps of its points.
+    ///
+    /// # Panics
+    /// If there isn't exactly one timestamp per point.
+    pub fn new(zine: Zine<'a>, timestamps: &'a [u64]) -> Self {
+        assert_eq!(
+            zine.len(),
+            timestamps.len(),
+            "There must be exactly one timestamp per point"
+        );
+        Self { zine, timestamps }
+    }
+
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        let (bounding_box, data) = data.split_at(BOUNDING_BOX_SIZE_IN_BYTES);
+        let bounding_box = unsafe { BoundingBox::from_bytes(bounding_box) };
+        let (len, data) = data.split_at(mem::size_of::<u64>());
+        let len = u64::from_ne_bytes(len.try_into().unwrap()) as usize;
+        let (coords, timestamps) = data.split_at(len * COORD_SIZE_IN_BYTES);
+        let coords = unsafe { Coords::from_bytes(coords) };
+        Self::new(Zine::new(bounding_box, coords), cast_slice(timestamps))
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        let rest = split_bounding_box(data)?;
+        let (len, rest) = split_u64(rest)?;
+        let (_coords, rest) = split_checked(rest, checked_size(len, COORD_SIZE_IN_BYTES, rest)?)?;
+        let (_timestamps, rest) = split_checked(rest, len as usize * mem::size_of::<u64>())?;
+        check_empty(rest)?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
+    /// Convert the specified [`geo_types::LineString`] and the timestamps of its points to a valid
+    /// [`TimedZine`] slice of bytes in the input buffer.
+    pub fn write_from_geometry(
+        writer: &mut Vec<u8>,
+        geometry: &LineString<f64>,
+        timestamps: &[u64],
+    ) -> Result<(), ZerometryError> {
+        if geometry.0.len() != timestamps.len() {
+            return Err(ZerometryError::InvalidTimestampsCount {
+                points: geometry.0.len(),
+                timestamps: timestamps.len(),
+            });
+        }
+        if !timestamps.is_sorted() {
+            return Err(ZerometryError::UnsortedTimestamps);
+        }
+
+        let mut line = Vec::new();
+        Zine::write_from_geometry(&mut line, geometry)?;
+        let (bounding_box, coords) = line.split_at(BOUNDING_BOX_SIZE_IN_BYTES);
+        writer.extend_from_slice(bounding_box);
+        writer.extend_from_slice(&(timestamps.len() as u64).to_ne_bytes());
+        writer.extend_from_slice(coords);
+        for timestamp in timestamps {
+            writer.extend_from_slice(&timestamp.to_ne_bytes());
+        }
+        Ok(())
+    }
+
+    /// Return the line without its timestamps
+    #[inline]
+    pub fn zine(&self) -> Zine<'a> {
+        self.zine
+    }
+
+    /// Return the timestamp of every point of the line
+    #[inline]
+    pub fn timestamps(&self) -> &'a [u64] {
+        self.timestamps
+    }
+
+    /// Return the number of points composing the line
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.zine.len()
+    }
+
+    /// Return true if the line don't contain any point
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.zine.is_empty()
+    }
+
+    /// Return the position at the specified time, linearly interpolated between the two surrounding points.
+    /// Return `None` if the time is before the first point or after the last one.
+    pub fn position_at_time(&self, time: u64) -> Option<geo_types::Coord<f64>> {
+        let (first, last) = (self.timestamps.first()?, self.timestamps.last()?);
+        if time < *first || time > *last {
+            return None;
+        }
+        let coords = self.zine.coords();
+        // The first point whose timestamp is greater or equal to the time
+        let after = self
+            .timestamps
+            .partition_point(|timestamp| *timestamp < time);
+        if self.timestamps[after] == time || after == 0 {
+            return Some(coords[after].to_geo());
+        }
+
+        let before = after - 1;
+        let (start, end) = (&coords[before], &coords[after]);
+        let ratio = (time - self.timestamps[before]) as f64
+            / (self.timestamps[after] - self.timestamps[before]) as f64;
+        Some(geo_types::Coord {
+            x: start.lng() + (end.lng() - start.lng()) * ratio,
+            y: start.lat() + (end.lat() - start.lat()) * ratio,
+        })
+    }
+}
+
+impl<'a> fmt::Debug for TimedZine<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimedZine")
+            .field("zine", &self.zine)
+            .field("timestamps", &self.timestamps)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_timed_zine_binary_format() {
+        let track = LineString::from(vec![(1.0, 2.0), (3.0, 4.0)]);
+        let mut buffer = Vec::new();
+        TimedZine::write_from_geometry(&mut buffer, &track, &[10, 20]).unwrap();
+        let input: &[u64] = cast_slice(&buffer[32..]);
+        assert_compact_debug_snapshot!(input[0], @"2");
+        assert_compact_debug_snapshot!(&input[5..], @"[10, 20]");
+
+        let timed_zine = unsafe { TimedZine::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(timed_zine, @"TimedZine { zine: Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 1.0, lat: 2.0 }, top_right: Coord { lng: 3.0, lat: 4.0 } }, points: [Zoint { lng: 1.0, lat: 2.0 }, Zoint { lng: 3.0, lat: 4.0 }] }, timestamps: [10, 20] }");
+        assert_eq!(timed_zine.zine().to_geo(), track);
+    }
+
+    #[test]
+    fn test_position_at_time() {
+        let track = LineString::from(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 0.0), (4.0, 8.0)]);
+        let mut buffer = Vec::new();
+        TimedZine::write_from_geometry(&mut buffer, &track, &[0, 4, 10, 10]).unwrap();
+        let timed_zine = unsafe { TimedZine::from_bytes(&buffer) };
+
+        let positions: Vec<_> = [0, 1, 4, 7, 10, 11]
+            .into_iter()
+            .map(|time| timed_zine.position_at_time(time))
+            .collect();
+        assert_compact_debug_snapshot!(positions, @"[Some(COORD(0.0 0.0)), Some(COORD(1.0 0.0)), Some(COORD(4.0 0.0)), Some(COORD(4.0 0.0)), Some(COORD(4.0 0.0)), None]");
+    }
+
+    #[test]
+    fn test_invalid_timestamps() {
+        let track = LineString::from(vec![(0.0, 0.0), (4.0, 0.0)]);
+        let err = TimedZine::write_from_geometry(&mut Vec::new(), &track, &[1]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidTimestampsCount { points: 2, timestamps: 1 }");
+        let err = TimedZine::write_from_geometry(&mut Vec::new(), &track, &[2, 1]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"UnsortedTimestamps");
+    }
+}
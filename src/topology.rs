@@ -0,0 +1,697 @@
+use std::cell::OnceCell;
+use std::cmp::Ordering;
+
+use geo::line_intersection::{LineIntersection, line_intersection};
+use geo_types::Line;
+
+use crate::{
+    Coord, InputRelation, OutputRelation, RelationBetweenShapes, Segment, Zerometry, Zine, Zolygon,
+};
+
+/// The coords computed while cutting the segments, like their intersections or the middle of the pieces, are
+/// rounded. They're considered on a segment when they're closer to it than this ratio of their magnitude, or than
+/// the [`InputRelation::tolerance`] if it's larger.
+const TOLERANCE: f64 = 1e-12;
+
+/// Where a coord lies relative to a shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Location {
+    Interior,
+    Boundary,
+    Exterior,
+}
+
+/// How two shapes meet, from the loosest to the closest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Contact {
+    None,
+    Boundaries,
+    Interiors,
+}
+
+/// A relation computed from the locations of the shapes relative to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Predicate {
+    Touches,
+    Crosses,
+    Covers,
+    CoveredBy,
+}
+
+/// Compute the relation exactly, then fill the relations depending on the locations of the shapes relative
+/// to each other and apply the tolerance, see [`fill_topology`].
+pub(crate) fn relation_with_topology<'a, 'b, Shape, Other>(
+    shape: &Shape,
+    other: &Other,
+    relation: InputRelation,
+) -> OutputRelation
+where
+    Shape: RelationBetweenShapes<Other> + Copy + Into<Zerometry<'a>>,
+    Other: Copy + Into<Zerometry<'b>>,
+{
+    let exact = shape.relation(other, exact_relation(relation));
+    complete_topology(exact, relation, &(*shape).into(), &(*other).into())
+}
+
+/// Fill the relation computed with [`exact_relation`] between the shapes with the requested predicates and
+/// apply the tolerance, see [`fill_topology`].
+///
+/// The bounding boxes are checked first and the parts of the shapes are only collected once, when the first
+/// predicate needs them.
+pub(crate) fn complete_topology(
+    exact: OutputRelation,
+    relation: InputRelation,
+    shape: &Zerometry,
+    other: &Zerometry,
+) -> OutputRelation {
+    let tolerance = relation.tolerance;
+    let close =
+        exact.disjoint == Some(true) && tolerance > 0.0 && shape.closer_than(other, tolerance);
+    let parts = OnceCell::new();
+    let parts =
+        || parts.get_or_init(|| (Parts::new(shape, tolerance), Parts::new(other, tolerance)));
+    fill_topology(exact, relation, close, |predicate| {
+        if !bounds_meet(shape, other, tolerance) {
+            return false;
+        }
+        match predicate {
+            Predicate::Touches => parts().0.touches(&parts().1),
+            Predicate::Crosses => parts().0.crosses(&parts().1),
+            Predicate::Covers => {
+                bounds_cover(shape, other, tolerance) && parts().0.covers(&parts().1)
+            }
+            Predicate::CoveredBy => {
+                bounds_cover(other, shape, tolerance) && parts().1.covers(&parts().0)
+            }
+        }
+    })
+}
+
+/// The relation to compute exactly before calling [`fill_topology`]: without the tolerance nor the relations
+/// depending on the locations of the shapes, but with the disjoint.
+pub(crate) fn exact_relation(relation: InputRelation) -> InputRelation {
+    InputRelation {
+        disjoint: true,
+        touches: false,
+        crosses: false,
+        covers: false,
+        covered_by: false,
+        tolerance: 0.0,
+        ..relation
+    }
+}
+
+/// The relation computed by the [`crate::RelationTask`] and [`Zerometry::relation_streaming`], which never see
+/// the whole shapes at once: without the tolerance nor the relations depending on the locations of the shapes,
+/// they're left to `None`.
+pub(crate) fn without_topology(relation: InputRelation) -> InputRelation {
+    InputRelation {
+        disjoint: relation.disjoint,
+        ..exact_relation(relation)
+    }
+}
+
+/// Fill the relation computed with [`exact_relation`] with the requested predicates.
+///
+/// The shapes that are `close`, disjoint but closer than the tolerance, are coincident where they're close:
+/// they meet on their boundaries and intersect instead of being disjoint.
+/// With the [`InputRelation::early_exit`], the predicates aren't computed anymore once a relation was found
+/// and are left to `None`.
+pub(crate) fn fill_topology(
+    exact: OutputRelation,
+    relation: InputRelation,
+    close: bool,
+    predicate: impl Fn(Predicate) -> bool,
+) -> OutputRelation {
+    let disjoint = exact.disjoint == Some(true) && !close;
+    let mut output = OutputRelation {
+        intersect: if close {
+            relation.intersect.then_some(true)
+        } else {
+            exact.intersect
+        },
+        disjoint: relation.disjoint.then_some(disjoint),
+        ..exact
+    };
+    let mut found = output.any_relation();
+    let mut compute = |requested: bool, kind: Predicate| {
+        if !requested || (relation.early_exit && found) {
+            return None;
+        }
+        let value = !disjoint && predicate(kind);
+        found |= value;
+        Some(value)
+    };
+    output.touches = compute(relation.touches, Predicate::Touches);
+    output.crosses = compute(relation.crosses, Predicate::Crosses);
+    output.covers = compute(relation.covers, Predicate::Covers);
+    output.covered_by = compute(relation.covered_by, Predicate::CoveredBy);
+    output
+}
+
+/// Return `true` if both shapes are non-empty and their bounding boxes are closer than the tolerance.
+fn bounds_meet(shape: &Zerometry, other: &Zerometry, tolerance: f64) -> bool {
+    let (left, bottom, right, top) = shape.bounds();
+    let (other_left, other_bottom, other_right, other_top) = other.bounds();
+    !shape.is_empty()
+        && !other.is_empty()
+        && other_left <= right + tolerance
+        && left <= other_right + tolerance
+        && other_bottom <= top + tolerance
+        && bottom <= other_top + tolerance
+}
+
+/// Return `true` if the bounding box of the other shape is in the bounding box of the shape, or closer to
+/// its inside than the tolerance. The shape can't cover the other shape otherwise.
+fn bounds_cover(shape: &Zerometry, other: &Zerometry, tolerance: f64) -> bool {
+    let (left, bottom, right, top) = shape.bounds();
+    let (other_left, other_bottom, other_right, other_top) = other.bounds();
+    left <= other_left + tolerance
+        && bottom <= other_bottom + tolerance
+        && other_right <= right + tolerance
+        && other_top <= top + tolerance
+}
+
+/// The points, lines and polygons making up a shape.
+pub(crate) struct Parts<'a> {
+    points: Vec<&'a Coord>,
+    lines: Vec<Zine<'a>>,
+    polygons: Vec<Zolygon<'a>>,
+    tolerance: f64,
+}
+
+impl<'a> Parts<'a> {
+    pub(crate) fn new(zerometry: &Zerometry<'a>, tolerance: f64) -> Self {
+        let mut parts = Parts {
+            points: Vec::new(),
+            lines: Vec::new(),
+            polygons: Vec::new(),
+            tolerance,
+        };
+        parts.extend(zerometry);
+        parts.lines.retain(|line| !line.is_empty());
+        parts.polygons.retain(|polygon| !polygon.is_empty());
+        parts
+    }
+
+    fn extend(&mut self, zerometry: &Zerometry<'a>) {
+        match zerometry {
+            Zerometry::Point(point) => self.points.push(point.coord()),
+            Zerometry::MultiPoints(points) => self.points.extend(points.coords().iter()),
+            Zerometry::Line(line) => self.lines.push(*line),
+            Zerometry::MultiLines(lines) => self
+                .lines
+                .extend((0..lines.len()).map(|index| lines.get(index).unwrap())),
+            Zerometry::Polygon(polygon) => self.polygons.push(*polygon),
+            Zerometry::MultiPolygon(polygons) => self
+                .polygons
+                .extend((0..polygons.len()).map(|index| polygons.get(index).unwrap())),
+            Zerometry::Collection(collection) => {
+                self.extend(&Zerometry::MultiPoints(collection.points()));
+                self.extend(&Zerometry::MultiLines(collection.lines()));
+                self.extend(&Zerometry::MultiPolygon(collection.polygons()));
+            }
+        }
+    }
+
+    /// Return where the coord lies relative to the shape.
+    pub(crate) fn locate(&self, coord: geo_types::Coord<f64>) -> Location {
+        if self
+            .points
+            .iter()
+            .any(|point| near(point.to_geo(), coord, self.tolerance))
+        {
+            return Location::Interior;
+        }
+        let mut location = Location::Exterior;
+        for line in &self.lines {
+            if line
+                .segments()
+                .any(|segment| near_segment(&segment, coord, self.tolerance))
+            {
+                let coords = line.coords();
+                let (first, last) = (coords[0].to_geo(), coords[coords.len() - 1].to_geo());
+                // A closed line doesn't have any boundary
+                if first != last
+                    && (near(first, coord, self.tolerance) || near(last, coord, self.tolerance))
+                {
+                    location = Location::Boundary;
+                } else {
+                    return Location::Interior;
+                }
+            }
+        }
+        if self.in_area(coord) {
+            return Location::Interior;
+        }
+        if self.on_ring(coord) {
+            location = Location::Boundary;
+        }
+        location
+    }
+
+    /// Return `true` if the coord is in the interior of one of the polygons.
+    pub(crate) fn in_area(&self, coord: geo_types::Coord<f64>) -> bool {
+        let buffer = [coord.x, coord.y];
+        let zcoord = unsafe { Coord::from_slice(&buffer) };
+        self.polygons.iter().any(|polygon| {
+            !on_ring(polygon, coord, self.tolerance)
+                && polygon.ring_winding_contribution(zcoord) != 0
+        })
+    }
+
+    /// Return `true` if the coord is on the boundary of one of the polygons.
+    fn on_ring(&self, coord: geo_types::Coord<f64>) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| on_ring(polygon, coord, self.tolerance))
+    }
+
+    fn segments(&self) -> impl Iterator<Item = Segment<'a>> + '_ {
+        self.lines
+            .iter()
+            .flat_map(|line| line.segments())
+            .chain(self.polygons.iter().flat_map(|polygon| polygon.segments()))
+    }
+
+    /// Return the coords along the segment where its location relative to the shape may change: its ends,
+    /// its intersections with the segments of the shape and the middle of the pieces between them.
+    pub(crate) fn cut(&self, segment: &Segment) -> Vec<geo_types::Coord<f64>> {
+        let line = Line::new(segment.start().to_geo(), segment.end().to_geo());
+        let position = |coord: geo_types::Coord<f64>| {
+            let delta = line.delta();
+            let length = delta.x * delta.x + delta.y * delta.y;
+            if length == 0.0 {
+                0.0
+            } else {
+                ((coord.x - line.start.x) * delta.x + (coord.y - line.start.y) * delta.y) / length
+            }
+        };
+        let mut cuts = vec![(0.0, line.start), (1.0, line.end)];
+        for edge in self.segments() {
+            let edge = Line::new(edge.start().to_geo(), edge.end().to_geo());
+            match line_intersection(line, edge) {
+                Some(LineIntersection::SinglePoint { intersection, .. }) => {
+                    cuts.push((position(intersection), intersection))
+                }
+                Some(LineIntersection::Collinear { intersection }) => {
+                    cuts.push((position(intersection.start), intersection.start));
+                    cuts.push((position(intersection.end), intersection.end));
+                }
+                None => (),
+            }
+        }
+        cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        cuts.dedup_by(|a, b| a.1 == b.1);
+
+        let mut coords = Vec::with_capacity(cuts.len() * 2);
+        for pair in cuts.windows(2) {
+            let (start, end) = (pair[0].1, pair[1].1);
+            coords.push(start);
+            coords.push((start + end) / 2.0);
+        }
+        coords.push(cuts[cuts.len() - 1].1);
+        coords
+    }
+
+    /// Return 0 for the points, 1 for the lines and 2 for the polygons.
+    fn dimension(&self) -> usize {
+        if !self.polygons.is_empty() {
+            2
+        } else if !self.lines.is_empty() {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Return the coords of the points and of the lines and rings cut by the other shape, see [`Self::cut`].
+    /// The coords in the middle of the pieces of the lines and rings are flagged.
+    fn samples(&self, other: &Parts) -> impl Iterator<Item = (geo_types::Coord<f64>, bool)> {
+        let points = self.points.iter().map(|point| (point.to_geo(), false));
+        let lines = self.segments().flat_map(|segment| {
+            other
+                .cut(&segment)
+                .into_iter()
+                .enumerate()
+                .map(|(index, coord)| (coord, index % 2 == 1))
+        });
+        points.chain(lines)
+    }
+
+    /// Return `true` if the interior of the shape goes both in the interior and in the exterior of the other.
+    fn goes_through(&self, other: &Parts) -> bool {
+        let (mut inside, mut outside) = (false, false);
+        for (coord, _) in self.samples(other) {
+            if self.locate(coord) != Location::Interior {
+                continue;
+            }
+            match other.locate(coord) {
+                Location::Interior => inside = true,
+                Location::Exterior => outside = true,
+                Location::Boundary => (),
+            }
+            if inside && outside {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Return `true` if the interiors of the lines meet, but only at some points.
+    fn crosses_lines(&self, other: &Parts) -> bool {
+        let mut crossing = false;
+        for (coord, middle) in self.samples(other) {
+            if self.locate(coord) == Location::Interior && other.locate(coord) == Location::Interior
+            {
+                // Both lines run along each other on this piece
+                if middle {
+                    return false;
+                }
+                crossing = true;
+            }
+        }
+        crossing
+    }
+
+    /// Return how the shape meets the other shape, only looking at the coords of this shape.
+    /// It must be called both ways to know how they meet.
+    fn contact(&self, other: &Parts) -> Contact {
+        let mut contact = Contact::None;
+        let mut locate = |coord: geo_types::Coord<f64>| {
+            let location = other.locate(coord);
+            if location == Location::Interior && self.locate(coord) == Location::Interior {
+                contact = Contact::Interiors;
+            } else if location != Location::Exterior {
+                contact = contact.max(Contact::Boundaries);
+            }
+            contact == Contact::Interiors
+        };
+
+        if self.points.iter().any(|point| locate(point.to_geo())) {
+            return Contact::Interiors;
+        }
+        for line in &self.lines {
+            for segment in line.segments() {
+                if other.cut(&segment).into_iter().any(&mut locate) {
+                    return Contact::Interiors;
+                }
+            }
+        }
+        for polygon in &self.polygons {
+            // The other polygons whose ring goes through all the coords of this ring, they're the same polygon
+            let mut same = vec![true; other.polygons.len()];
+            for segment in polygon.segments() {
+                for coord in other.cut(&segment) {
+                    // The interior of the polygon is on both sides of its boundary
+                    if locate(coord) || other.in_area(coord) {
+                        return Contact::Interiors;
+                    }
+                    for (same, other) in same.iter_mut().zip(&other.polygons) {
+                        *same &= on_ring(other, coord, self.tolerance);
+                    }
+                }
+            }
+            if same.contains(&true) {
+                return Contact::Interiors;
+            }
+        }
+        contact
+    }
+
+    /// Return `true` if the shapes meet on their boundaries but their interiors don't share any point.
+    ///
+    /// The members of the multi shapes and collections keep their own interior and boundary, a point is in the
+    /// interior of the shape if it's in the interior of any of its members.
+    fn touches(&self, other: &Parts) -> bool {
+        self.contact(other).max(other.contact(self)) == Contact::Boundaries
+    }
+
+    /// Return `true` if the interior of the shape of lower dimension goes through both the interior and the
+    /// exterior of the other shape, or if both shapes are lines whose interiors only meet at some points.
+    ///
+    /// The dimension of a multi shape or collection is the highest dimension of its members.
+    fn crosses(&self, other: &Parts) -> bool {
+        match self.dimension().cmp(&other.dimension()) {
+            Ordering::Less => self.goes_through(other),
+            Ordering::Greater => other.goes_through(self),
+            Ordering::Equal => self.dimension() == 1 && self.crosses_lines(other),
+        }
+    }
+
+    /// Return `true` if no point of the other shape lies in the exterior of the shape.
+    fn covers(&self, other: &Parts) -> bool {
+        // The polygons are simple, their interior is covered as soon as their ring is
+        self.dimension() >= other.dimension()
+            && other
+                .samples(self)
+                .all(|(coord, _)| self.locate(coord) != Location::Exterior)
+    }
+}
+
+/// Return `true` if the coord is on the ring of the polygon, or closer to it than the tolerance.
+pub(crate) fn on_ring(polygon: &Zolygon, coord: geo_types::Coord<f64>, tolerance: f64) -> bool {
+    polygon
+        .segments()
+        .any(|segment| near_segment(&segment, coord, tolerance))
+}
+
+fn near(a: geo_types::Coord<f64>, b: geo_types::Coord<f64>, tolerance: f64) -> bool {
+    let scale = 1.0 + a.x.abs().max(a.y.abs());
+    (a.x - b.x).hypot(a.y - b.y) <= (TOLERANCE * scale).max(tolerance)
+}
+
+fn near_segment(segment: &Segment, coord: geo_types::Coord<f64>, tolerance: f64) -> bool {
+    let (start, end) = (segment.start().to_geo(), segment.end().to_geo());
+    let delta = end - start;
+    let length = delta.x * delta.x + delta.y * delta.y;
+    let position = if length == 0.0 {
+        0.0
+    } else {
+        (((coord.x - start.x) * delta.x + (coord.y - start.y) * delta.y) / length).clamp(0.0, 1.0)
+    };
+    near(start + delta * position, coord, tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, LineString, MultiPoint, MultiPolygon, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::Parts;
+    use crate::{InputRelation, RelationBetweenShapes, ZerometryBuf};
+
+    fn touches(left: impl Into<Geometry<f64>>, right: impl Into<Geometry<f64>>) -> (bool, bool) {
+        let (left, right) = (
+            ZerometryBuf::from(left.into()),
+            ZerometryBuf::from(right.into()),
+        );
+        let (left, right) = (left.as_zerometry(), right.as_zerometry());
+        let (left, right) = (Parts::new(&left, 0.0), Parts::new(&right, 0.0));
+        (left.touches(&right), right.touches(&left))
+    }
+
+    #[test]
+    fn touching_shapes() {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        // Two adjacent parcels share an edge
+        let next = polygon![(x: 4., y: 1.), (x: 8., y: 1.), (x: 8., y: 3.), (x: 4., y: 3.)];
+        assert_compact_debug_snapshot!(touches(square.clone(), next), @"(true, true)");
+        // Only a corner
+        let corner = polygon![(x: 4., y: 4.), (x: 6., y: 4.), (x: 6., y: 6.)];
+        assert_compact_debug_snapshot!(touches(square.clone(), corner), @"(true, true)");
+        // Overlapping and identical polygons share their interiors
+        let overlapping = polygon![(x: 3., y: 1.), (x: 8., y: 1.), (x: 8., y: 3.), (x: 3., y: 3.)];
+        assert_compact_debug_snapshot!(touches(square.clone(), overlapping), @"(false, false)");
+        assert_compact_debug_snapshot!(touches(square.clone(), square.clone()), @"(false, false)");
+        // Inside, along an edge
+        let inside = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.)];
+        assert_compact_debug_snapshot!(touches(square.clone(), inside), @"(false, false)");
+        // Diagonal adjacent parcels
+        let left = polygon![(x: 0., y: 0.), (x: 3., y: 1.), (x: 0., y: 1.)];
+        let right = polygon![(x: 0., y: 0.), (x: 3., y: 0.), (x: 3., y: 1.)];
+        assert_compact_debug_snapshot!(touches(left, right), @"(true, true)");
+
+        // A line ending on the edge of the square, going through it, and following it
+        let ending = LineString::from(vec![(6., 2.), (4., 2.)]);
+        assert_compact_debug_snapshot!(touches(square.clone(), ending), @"(true, true)");
+        let through = LineString::from(vec![(6., 2.), (2., 2.)]);
+        assert_compact_debug_snapshot!(touches(square.clone(), through), @"(false, false)");
+        let along = LineString::from(vec![(4., -1.), (4., 6.)]);
+        assert_compact_debug_snapshot!(touches(square.clone(), along), @"(true, true)");
+
+        // Lines touch by their ends, but not when they cross
+        let line = LineString::from(vec![(0., 0.), (2., 2.)]);
+        let from_the_end = LineString::from(vec![(2., 2.), (3., 0.)]);
+        let from_the_middle = LineString::from(vec![(1., 1.), (3., 0.)]);
+        let crossing = LineString::from(vec![(0., 2.), (2., 0.)]);
+        assert_compact_debug_snapshot!(touches(line.clone(), from_the_end), @"(true, true)");
+        assert_compact_debug_snapshot!(touches(line.clone(), from_the_middle), @"(true, true)");
+        assert_compact_debug_snapshot!(touches(line.clone(), crossing), @"(false, false)");
+        // A closed line doesn't have any end
+        let ring = LineString::from(vec![(2., 2.), (3., 0.), (0., 0.), (2., 2.)]);
+        assert_compact_debug_snapshot!(touches(line.clone(), ring), @"(false, false)");
+
+        // A point on the boundary of the square or at the end of the line, but two points never touch
+        assert_compact_debug_snapshot!(touches(square.clone(), Point::new(4., 2.)), @"(true, true)");
+        assert_compact_debug_snapshot!(touches(square.clone(), Point::new(2., 2.)), @"(false, false)");
+        assert_compact_debug_snapshot!(touches(line.clone(), Point::new(0., 0.)), @"(true, true)");
+        assert_compact_debug_snapshot!(touches(line.clone(), Point::new(1., 1.)), @"(false, false)");
+        assert_compact_debug_snapshot!(touches(Point::new(1., 1.), Point::new(1., 1.)), @"(false, false)");
+        let points = MultiPoint::from(vec![(4., 2.), (8., 8.)]);
+        assert_compact_debug_snapshot!(touches(square.clone(), points), @"(true, true)");
+
+        // The members of a multi polygon touch independently
+        let multi = MultiPolygon::new(vec![
+            polygon![(x: 4., y: 0.), (x: 6., y: 0.), (x: 6., y: 2.)],
+            polygon![(x: 10., y: 10.), (x: 12., y: 10.), (x: 12., y: 12.)],
+        ]);
+        assert_compact_debug_snapshot!(touches(square, multi), @"(true, true)");
+    }
+
+    fn crosses(left: impl Into<Geometry<f64>>, right: impl Into<Geometry<f64>>) -> (bool, bool) {
+        let (left, right) = (
+            ZerometryBuf::from(left.into()),
+            ZerometryBuf::from(right.into()),
+        );
+        let (left, right) = (left.as_zerometry(), right.as_zerometry());
+        let (left, right) = (Parts::new(&left, 0.0), Parts::new(&right, 0.0));
+        (left.crosses(&right), right.crosses(&left))
+    }
+
+    #[test]
+    fn crossing_shapes() {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        // A line going through the square, ending in it, staying inside, or only following its edge
+        let through = LineString::from(vec![(-2., 2.), (6., 2.)]);
+        assert_compact_debug_snapshot!(crosses(square.clone(), through), @"(true, true)");
+        let ending = LineString::from(vec![(-2., 2.), (2., 2.)]);
+        assert_compact_debug_snapshot!(crosses(square.clone(), ending), @"(true, true)");
+        let inside = LineString::from(vec![(1., 1.), (3., 3.)]);
+        assert_compact_debug_snapshot!(crosses(square.clone(), inside), @"(false, false)");
+        let along = LineString::from(vec![(4., -1.), (4., 6.)]);
+        assert_compact_debug_snapshot!(crosses(square.clone(), along), @"(false, false)");
+        // Some of the points are inside and the others outside
+        let points = MultiPoint::from(vec![(2., 2.), (8., 8.)]);
+        assert_compact_debug_snapshot!(crosses(square.clone(), points), @"(true, true)");
+        let inside = MultiPoint::from(vec![(2., 2.), (3., 3.)]);
+        assert_compact_debug_snapshot!(crosses(square.clone(), inside), @"(false, false)");
+        // Two polygons never cross
+        let overlapping = polygon![(x: 3., y: 1.), (x: 8., y: 1.), (x: 8., y: 3.), (x: 3., y: 3.)];
+        assert_compact_debug_snapshot!(crosses(square, overlapping), @"(false, false)");
+
+        // Two lines crossing at a point, touching, or running along each other
+        let line = LineString::from(vec![(0., 0.), (2., 2.)]);
+        let crossing = LineString::from(vec![(0., 2.), (2., 0.)]);
+        assert_compact_debug_snapshot!(crosses(line.clone(), crossing), @"(true, true)");
+        let from_the_middle = LineString::from(vec![(1., 1.), (3., 0.)]);
+        assert_compact_debug_snapshot!(crosses(line.clone(), from_the_middle), @"(false, false)");
+        let along = LineString::from(vec![(1., 1.), (3., 3.)]);
+        assert_compact_debug_snapshot!(crosses(line.clone(), along), @"(false, false)");
+        // A point on the line is never outside of it
+        assert_compact_debug_snapshot!(crosses(line, Point::new(1., 1.)), @"(false, false)");
+    }
+
+    fn covers(left: impl Into<Geometry<f64>>, right: impl Into<Geometry<f64>>) -> (bool, bool) {
+        let (left, right) = (
+            ZerometryBuf::from(left.into()),
+            ZerometryBuf::from(right.into()),
+        );
+        let (left, right) = (left.as_zerometry(), right.as_zerometry());
+        let (left, right) = (Parts::new(&left, 0.0), Parts::new(&right, 0.0));
+        (left.covers(&right), right.covers(&left))
+    }
+
+    #[test]
+    fn covering_shapes() {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        // The boundary is covered, inside or not
+        assert_compact_debug_snapshot!(covers(square.clone(), Point::new(4., 2.)), @"(true, false)");
+        assert_compact_debug_snapshot!(covers(square.clone(), Point::new(2., 2.)), @"(true, false)");
+        assert_compact_debug_snapshot!(covers(square.clone(), Point::new(5., 2.)), @"(false, false)");
+        let along = LineString::from(vec![(4., 0.), (4., 4.)]);
+        assert_compact_debug_snapshot!(covers(square.clone(), along), @"(true, false)");
+        let through = LineString::from(vec![(2., 2.), (6., 2.)]);
+        assert_compact_debug_snapshot!(covers(square.clone(), through), @"(false, false)");
+        let inside = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.)];
+        assert_compact_debug_snapshot!(covers(square.clone(), inside), @"(true, false)");
+        assert_compact_debug_snapshot!(covers(square.clone(), square.clone()), @"(true, true)");
+        let overlapping = polygon![(x: 3., y: 1.), (x: 8., y: 1.), (x: 8., y: 3.), (x: 3., y: 3.)];
+        assert_compact_debug_snapshot!(covers(square.clone(), overlapping), @"(false, false)");
+
+        // A closed line doesn't cover the inside of the polygon
+        let ring = LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.)]);
+        assert_compact_debug_snapshot!(covers(square, ring), @"(true, false)");
+
+        let line = LineString::from(vec![(0., 0.), (2., 2.), (4., 0.)]);
+        assert_compact_debug_snapshot!(covers(line.clone(), Point::new(0., 0.)), @"(true, false)");
+        let part = LineString::from(vec![(1., 1.), (2., 2.), (3., 1.)]);
+        assert_compact_debug_snapshot!(covers(line, part), @"(true, false)");
+    }
+
+    #[test]
+    fn tolerant_relations() {
+        let relation = |left: Geometry<f64>, right: Geometry<f64>, tolerance: f64| {
+            let (left, right) = (ZerometryBuf::from(left), ZerometryBuf::from(right));
+            let input = InputRelation {
+                intersect: true,
+                disjoint: true,
+                touches: true,
+                covers: true,
+                tolerance,
+                ..InputRelation::none()
+            };
+            let output = left.as_zerometry().relation(&right.as_zerometry(), input);
+            (
+                output.intersect.unwrap(),
+                output.disjoint.unwrap(),
+                output.touches.unwrap(),
+                output.covers.unwrap(),
+            )
+        };
+
+        // The reprojection left a tiny gap between both parcels
+        let parcel = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let next = polygon![(x: 4.000000001, y: 0.), (x: 8., y: 0.), (x: 8., y: 4.), (x: 4.000000001, y: 4.)];
+        assert_compact_debug_snapshot!(relation(parcel.clone().into(), next.clone().into(), 0.0), @"(false, true, false, false)");
+        assert_compact_debug_snapshot!(relation(parcel.clone().into(), next.into(), 1e-6), @"(true, false, true, false)");
+        // Same for a point right next to the edge, or a line ending right before it
+        let point = Point::new(4.000000001, 2.);
+        assert_compact_debug_snapshot!(relation(parcel.clone().into(), point.into(), 0.0), @"(false, true, false, false)");
+        assert_compact_debug_snapshot!(relation(parcel.clone().into(), point.into(), 1e-6), @"(true, false, true, true)");
+        let line = LineString::from(vec![(6., 2.), (4.000000001, 2.)]);
+        assert_compact_debug_snapshot!(relation(parcel.clone().into(), line.into(), 1e-6), @"(true, false, true, false)");
+        // But the shapes farther than the tolerance stay disjoint
+        assert_compact_debug_snapshot!(relation(parcel.into(), Point::new(4.1, 2.).into(), 1e-6), @"(false, true, false, false)");
+    }
+
+    #[test]
+    fn early_exit_topology() {
+        let square = ZerometryBuf::from(Geometry::from(
+            polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)],
+        ));
+        let edge = ZerometryBuf::from(Geometry::from(LineString::from(vec![(0., 0.), (4., 0.)])));
+        let (square, edge) = (square.as_zerometry(), edge.as_zerometry());
+
+        // The contains relation is found first, the predicates aren't computed anymore
+        let output = square.relation(&edge, InputRelation::any_with_topology());
+        assert_compact_debug_snapshot!((output.any_relation(), output.touches, output.covers), @"(true, None, None)");
+        // Once a predicate holds, the next ones aren't computed either
+        let input = InputRelation {
+            touches: true,
+            crosses: true,
+            covers: true,
+            early_exit: true,
+            ..InputRelation::none()
+        };
+        let output = square.relation(&edge, input);
+        assert_compact_debug_snapshot!((output.touches, output.crosses, output.covers), @"(Some(true), None, None)");
+        // The predicates that don't hold don't stop the next ones
+        let input = InputRelation {
+            touches: false,
+            ..input
+        };
+        let output = square.relation(&edge, input);
+        assert_compact_debug_snapshot!((output.touches, output.crosses, output.covers), @"(None, Some(false), Some(true))");
+    }
+}
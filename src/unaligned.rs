@@ -0,0 +1,149 @@
+use std::mem;
+
+use geo_types::{
+    Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
+
+use crate::{
+    COORD_SIZE_IN_BYTES, Zerometry, ZerometryError,
+    error::{check_coords, check_empty, split_bounding_box, split_checked},
+    header::Header,
+    zollection::split_sections,
+    zulti_lines::split_children,
+    zulti_points::split_sorted,
+};
+
+impl Zerometry<'_> {
+    /// Decode a serialized zerometry that may not be aligned on 64 bits, for example when it's stored in the
+    /// middle of a larger record, directly into a [`geo_types::Geometry`].
+    ///
+    /// Contrary to [`Self::from_bytes`], this is not a view over the bytes: every number is decoded byte by byte
+    /// and copied in a newly allocated [`geo_types::Geometry`], which is validated like [`Self::try_from_bytes`].
+    /// It's a good fit to convert the shapes to geo. To compute relations on them without decoding everything,
+    /// see [`crate::AlignedBytes`] instead, which only copies the data when it's not aligned.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    /// use geo_types::{Geometry, LineString};
+    ///
+    /// let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]));
+    /// let mut record = b"key".to_vec();
+    /// Zerometry::write_from_geometry(&mut record, &line).unwrap();
+    ///
+    /// assert_eq!(Zerometry::decode_unaligned_bytes(&record[3..]).unwrap(), line);
+    /// ```
+    pub fn decode_unaligned_bytes(data: &[u8]) -> Result<Geometry<f64>, ZerometryError> {
+        let (version, kind, subtype, body) = Header::skip_unaligned(data)?;
+        let geometry = match version {
+            0..=2 => decode_body_v0(kind, body),
+            version => Err(ZerometryError::UnsupportedVersion(version)),
+        }?;
+        let coords = match &geometry {
+            Geometry::LineString(line) => line.0.len(),
+            Geometry::Polygon(polygon) => polygon.exterior().0.len(),
+            _ => 0,
+        };
+        subtype.check(kind, coords)?;
+        Ok(subtype.restore(geometry))
+    }
+}
+
+/// Decode the body of a zerometry following the layout of the version 0 of the format.
+fn decode_body_v0(kind: u64, data: &[u8]) -> Result<Geometry<f64>, ZerometryError> {
+    Ok(match kind {
+        0 => {
+            let (coord, rest) = split_checked(data, COORD_SIZE_IN_BYTES)?;
+            check_empty(rest)?;
+            Geometry::Point(Point(decode_coord(coord)))
+        }
+        1 => Geometry::MultiPoint(decode_points(data)?),
+        2 => Geometry::Polygon(decode_polygon(data)?),
+        3 => Geometry::MultiPolygon(MultiPolygon::new(
+            split_children(data)?
+                .into_iter()
+                .map(decode_polygon)
+                .collect::<Result<_, _>>()?,
+        )),
+        4 => Geometry::LineString(decode_line(data)?),
+        5 => Geometry::MultiLineString(decode_lines(data)?),
+        6 => {
+            let (points, lines, polygons) = split_sections(data)?;
+            Geometry::GeometryCollection(GeometryCollection::from_iter([
+                Geometry::MultiPoint(decode_points(points)?),
+                Geometry::MultiLineString(decode_lines(lines)?),
+                Geometry::MultiPolygon(MultiPolygon::new(
+                    split_children(polygons)?
+                        .into_iter()
+                        .map(decode_polygon)
+                        .collect::<Result<_, _>>()?,
+                )),
+            ]))
+        }
+        7 => {
+            let (coords, original_indices) = split_sorted(data)?;
+            let coords = decode_coords(coords);
+            let mut points = vec![Point::new(0.0, 0.0); coords.len()];
+            let original_indices = original_indices
+                .chunks_exact(mem::size_of::<u32>())
+                .map(|index| u32::from_ne_bytes(index.try_into().unwrap()) as usize);
+            for (coord, index) in coords.into_iter().zip(original_indices) {
+                points[index] = Point(coord);
+            }
+            Geometry::MultiPoint(MultiPoint::new(points))
+        }
+        kind => return Err(ZerometryError::InvalidTag(kind)),
+    })
+}
+
+/// Decode the layout shared by the points, lines and polygons: a bounding box followed by the coords.
+fn decode_bounding_box_and_coords(
+    data: &[u8],
+) -> Result<Vec<geo_types::Coord<f64>>, ZerometryError> {
+    let coords = split_bounding_box(data)?;
+    check_coords(coords)?;
+    Ok(decode_coords(coords))
+}
+
+fn decode_points(data: &[u8]) -> Result<MultiPoint<f64>, ZerometryError> {
+    let coords = decode_bounding_box_and_coords(data)?;
+    Ok(MultiPoint::new(coords.into_iter().map(Point).collect()))
+}
+
+fn decode_line(data: &[u8]) -> Result<LineString<f64>, ZerometryError> {
+    Ok(LineString::new(decode_bounding_box_and_coords(data)?))
+}
+
+fn decode_lines(data: &[u8]) -> Result<MultiLineString<f64>, ZerometryError> {
+    Ok(MultiLineString::new(
+        split_children(data)?
+            .into_iter()
+            .map(decode_line)
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+fn decode_polygon(data: &[u8]) -> Result<Polygon<f64>, ZerometryError> {
+    let coords = decode_bounding_box_and_coords(data)?;
+    if let (Some(first), Some(last)) = (coords.first(), coords.last())
+        && (first.x.to_bits(), first.y.to_bits()) != (last.x.to_bits(), last.y.to_bits())
+    {
+        return Err(ZerometryError::UnclosedRing);
+    }
+    Ok(Polygon::new(LineString::new(coords), Vec::new()))
+}
+
+/// Decode the coords, the data must contain a whole number of coords.
+fn decode_coords(data: &[u8]) -> Vec<geo_types::Coord<f64>> {
+    data.chunks_exact(COORD_SIZE_IN_BYTES)
+        .map(decode_coord)
+        .collect()
+}
+
+fn decode_coord(data: &[u8]) -> geo_types::Coord<f64> {
+    let (lng, lat) = data.split_at(mem::size_of::<f64>());
+    geo_types::Coord {
+        x: f64::from_ne_bytes(lng.try_into().unwrap()),
+        y: f64::from_ne_bytes(lat.try_into().unwrap()),
+    }
+}
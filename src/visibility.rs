@@ -0,0 +1,88 @@
+use crate::{Coord, Segment, ZultiPolygons};
+
+/// Return `true` if nothing blocks the sight between `a` and `b`: the segment between them doesn't cross or
+/// touch the boundary of any of the obstacles.
+/// A segment lying entirely inside an obstacle never meets its boundary and is considered visible.
+///
+/// Only the obstacles whose bounding box, or the bounding box of their group, meets the segment are checked,
+/// see [`crate::WriteOptions::bounding_box_groups`].
+///
+/// ```
+/// use zerometry::{Coord, Zerometry, is_visible};
+/// use geo_types::{Geometry, MultiPolygon, polygon};
+///
+/// let wall = polygon![(x: 4., y: 0.), (x: 5., y: 0.), (x: 5., y: 5.), (x: 4., y: 5.)];
+/// let mut buffer = Vec::new();
+/// Zerometry::write_from_geometry(&mut buffer, &Geometry::MultiPolygon(MultiPolygon::new(vec![wall]))).unwrap();
+/// let Zerometry::MultiPolygon(walls) = Zerometry::try_from_bytes(&buffer).unwrap() else { unreachable!() };
+///
+/// let (a, b, c) = ([0.0, 1.0], [10.0, 1.0], [0.0, 9.0]);
+/// let [a, b, c] = [&a, &b, &c].map(|coord| unsafe { Coord::from_slice(coord) });
+/// assert!(!is_visible(a, b, &walls));
+/// assert!(is_visible(a, c, &walls));
+/// ```
+pub fn is_visible(a: &Coord, b: &Coord, obstacles: &ZultiPolygons) -> bool {
+    let sight = Segment::from_coord_pair(a, b);
+    !obstacles
+        .candidate_polygons(|bounding_box| !bounding_box.disjoint_from_segment(&sight))
+        .filter(|obstacle| !obstacle.bounding_box().disjoint_from_segment(&sight))
+        .any(|obstacle| obstacle.segments().any(|edge| edge.intersects(&sight)))
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, MultiPolygon, polygon};
+
+    use super::*;
+    use crate::{WriteOptions, Zerometry};
+
+    #[test]
+    fn is_visible() {
+        let walls = MultiPolygon::new(
+            (0..20)
+                .map(|i| {
+                    let x = i as f64 * 5.0;
+                    polygon![(x: x, y: 0.), (x: x + 1., y: 0.), (x: x + 1., y: 5.), (x: x, y: 5.)]
+                })
+                .collect(),
+        );
+        let geometry = Geometry::MultiPolygon(walls);
+        for options in [
+            WriteOptions::default(),
+            WriteOptions {
+                bounding_box_groups: true,
+                ..WriteOptions::default()
+            },
+        ] {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+            let Zerometry::MultiPolygon(walls) = Zerometry::try_from_bytes(&buffer).unwrap() else {
+                unreachable!()
+            };
+            let coords = [
+                [2.0, 1.0],
+                [4.0, 4.0],
+                [22.5, 1.0],
+                [2.0, 6.0],
+                [97.5, 6.0],
+                [95.5, 2.0],
+                [95.5, 4.0],
+                [6.0, 5.0],
+            ];
+            let [a, b, c, d, e, inside, also_inside, corner] = coords
+                .each_ref()
+                .map(|coord| unsafe { Coord::from_slice(coord) });
+            // Between two walls
+            assert!(super::is_visible(a, b, &walls));
+            assert!(!super::is_visible(a, c, &walls));
+            // Above the walls
+            assert!(super::is_visible(d, e, &walls));
+            // Inside a wall nothing crosses its boundary
+            assert!(super::is_visible(inside, also_inside, &walls));
+            assert!(!super::is_visible(inside, e, &walls));
+            // Touching a corner blocks the sight
+            assert!(!super::is_visible(d, corner, &walls));
+            assert!(!super::is_visible(corner, corner, &walls));
+        }
+    }
+}
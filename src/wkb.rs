@@ -0,0 +1,501 @@
+//! Encoding and decoding of the Well-Known Binary format of the OGC Simple Features specification.
+//!
+//! Only the 2D geometries are supported. The geometries are always written in little-endian
+//! but can be read in both byte orders.
+//! The zerometries are written straight from their coordinates, see [`Zerometry::to_wkb`].
+
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+
+use crate::{Coords, Zerometry, ZerometryError, Zolygon};
+
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+
+const LITTLE_ENDIAN: u8 = 1;
+/// The maximum number of nested collections, to not overflow the stack on malicious inputs.
+const MAX_DEPTH: usize = 64;
+
+impl Zerometry<'_> {
+    /// Convert a WKB geometry, as stored by PostGIS for example, to a valid [`Zerometry`] slice of bytes in the
+    /// input buffer. Only the 2D geometries are supported, in both byte orders.
+    /// The geometry is decoded before being written, see [`Self::write_from_geometry`] for the conversion.
+    ///
+    /// ```
+    /// use zerometry::Zerometry;
+    ///
+    /// // POINT(1 2) in little-endian
+    /// let wkb = [1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 64];
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_wkb(&mut buffer, &wkb).unwrap();
+    ///
+    /// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+    /// assert_eq!(zerometry.to_wkb(), wkb);
+    /// ```
+    pub fn write_from_wkb(writer: &mut impl Write, wkb: &[u8]) -> Result<(), ZerometryError> {
+        Zerometry::write_from_geometry(writer, &read_wkb(wkb)?)
+    }
+
+    /// Convert the [`Zerometry`] to WKB in little-endian, straight from its coordinates.
+    /// It's the same geometry as [`Self::to_geo`] except for the lines, rectangles and triangles that are written
+    /// as line strings and polygons.
+    ///
+    /// # Panics
+    /// If a shape contains more than [`u32::MAX`] members or coordinates, which can't be stored in WKB.
+    pub fn to_wkb(&self) -> Vec<u8> {
+        let mut wkb = Vec::new();
+        write_zerometry(&mut wkb, self).expect("Too many elements for WKB");
+        wkb
+    }
+}
+
+pub(crate) fn write_zerometry(writer: &mut impl Write, zerometry: &Zerometry) -> io::Result<()> {
+    match zerometry {
+        Zerometry::Point(point) => {
+            write_header(writer, POINT)?;
+            write_xy(writer, point.lng(), point.lat())
+        }
+        Zerometry::MultiPoints(points) => {
+            write_header(writer, MULTI_POINT)?;
+            write_count(writer, points.len())?;
+            points.coords().iter().try_for_each(|coord| {
+                write_header(writer, POINT)?;
+                write_xy(writer, coord.lng(), coord.lat())
+            })
+        }
+        Zerometry::Line(line) => {
+            write_header(writer, LINE_STRING)?;
+            write_zerometry_coords(writer, line.coords())
+        }
+        Zerometry::MultiLines(lines) => {
+            write_header(writer, MULTI_LINE_STRING)?;
+            write_count(writer, lines.len())?;
+            lines.lines().try_for_each(|line| {
+                write_header(writer, LINE_STRING)?;
+                write_zerometry_coords(writer, line.coords())
+            })
+        }
+        Zerometry::Polygon(polygon) => {
+            write_header(writer, POLYGON)?;
+            write_zolygon_rings(writer, polygon)
+        }
+        Zerometry::MultiPolygon(polygons) => {
+            write_header(writer, MULTI_POLYGON)?;
+            write_count(writer, polygons.len())?;
+            polygons.polygons().try_for_each(|polygon| {
+                write_header(writer, POLYGON)?;
+                write_zolygon_rings(writer, &polygon)
+            })
+        }
+        // Written as the collection returned by `to_geo`
+        Zerometry::Collection(collection) => {
+            write_header(writer, GEOMETRY_COLLECTION)?;
+            write_count(writer, 3)?;
+            write_zerometry(writer, &Zerometry::MultiPoints(collection.points()))?;
+            write_zerometry(writer, &Zerometry::MultiLines(collection.lines()))?;
+            write_zerometry(writer, &Zerometry::MultiPolygon(collection.polygons()))
+        }
+    }
+}
+
+/// Write the geometry as WKB. The lines, rectangles and triangles are written as line strings and polygons.
+// Only the GeoPackage export writes a geometry that isn't a zerometry
+#[cfg_attr(not(feature = "geopackage"), allow(dead_code))]
+pub(crate) fn write_wkb(writer: &mut impl Write, geometry: &Geometry<f64>) -> io::Result<()> {
+    match geometry {
+        Geometry::Point(point) => {
+            write_header(writer, POINT)?;
+            write_coord(writer, point.0)
+        }
+        Geometry::LineString(line) => {
+            write_header(writer, LINE_STRING)?;
+            write_coords(writer, &line.0)
+        }
+        Geometry::Polygon(polygon) => {
+            write_header(writer, POLYGON)?;
+            write_polygon_rings(writer, polygon)
+        }
+        Geometry::MultiPoint(multi_point) => {
+            write_header(writer, MULTI_POINT)?;
+            write_count(writer, multi_point.0.len())?;
+            for point in multi_point {
+                write_wkb(writer, &Geometry::Point(*point))?;
+            }
+            Ok(())
+        }
+        Geometry::MultiLineString(multi_line) => {
+            write_header(writer, MULTI_LINE_STRING)?;
+            write_count(writer, multi_line.0.len())?;
+            for line in multi_line {
+                write_header(writer, LINE_STRING)?;
+                write_coords(writer, &line.0)?;
+            }
+            Ok(())
+        }
+        Geometry::MultiPolygon(multi_polygon) => {
+            write_header(writer, MULTI_POLYGON)?;
+            write_count(writer, multi_polygon.0.len())?;
+            for polygon in multi_polygon {
+                write_header(writer, POLYGON)?;
+                write_polygon_rings(writer, polygon)?;
+            }
+            Ok(())
+        }
+        Geometry::GeometryCollection(collection) => {
+            write_header(writer, GEOMETRY_COLLECTION)?;
+            write_count(writer, collection.0.len())?;
+            for geometry in collection {
+                write_wkb(writer, geometry)?;
+            }
+            Ok(())
+        }
+        Geometry::Line(line) => write_wkb(
+            writer,
+            &Geometry::LineString(LineString::new(vec![line.start, line.end])),
+        ),
+        Geometry::Rect(rect) => write_wkb(writer, &Geometry::Polygon(rect.to_polygon())),
+        Geometry::Triangle(triangle) => {
+            write_wkb(writer, &Geometry::Polygon(triangle.to_polygon()))
+        }
+    }
+}
+
+fn write_header(writer: &mut impl Write, kind: u32) -> io::Result<()> {
+    writer.write_all(&[LITTLE_ENDIAN])?;
+    writer.write_all(&kind.to_le_bytes())
+}
+
+fn write_count(writer: &mut impl Write, count: usize) -> io::Result<()> {
+    let count = u32::try_from(count)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Too many elements for WKB"))?;
+    writer.write_all(&count.to_le_bytes())
+}
+
+fn write_coord(writer: &mut impl Write, coord: Coord<f64>) -> io::Result<()> {
+    write_xy(writer, coord.x, coord.y)
+}
+
+fn write_xy(writer: &mut impl Write, x: f64, y: f64) -> io::Result<()> {
+    writer.write_all(&x.to_le_bytes())?;
+    writer.write_all(&y.to_le_bytes())
+}
+
+fn write_zerometry_coords(writer: &mut impl Write, coords: &Coords) -> io::Result<()> {
+    write_count(writer, coords.len())?;
+    coords
+        .iter()
+        .try_for_each(|coord| write_xy(writer, coord.lng(), coord.lat()))
+}
+
+fn write_zolygon_rings(writer: &mut impl Write, polygon: &Zolygon) -> io::Result<()> {
+    // Same as the polygons, an empty zolygon doesn't have any ring
+    if polygon.coords().is_empty() {
+        return write_count(writer, 0);
+    }
+    write_count(writer, 1)?;
+    write_zerometry_coords(writer, polygon.coords())
+}
+
+fn write_coords(writer: &mut impl Write, coords: &[Coord<f64>]) -> io::Result<()> {
+    write_count(writer, coords.len())?;
+    coords
+        .iter()
+        .try_for_each(|coord| write_coord(writer, *coord))
+}
+
+fn write_polygon_rings(writer: &mut impl Write, polygon: &Polygon<f64>) -> io::Result<()> {
+    // An empty polygon doesn't have any ring, not even an empty exterior
+    if polygon.exterior().0.is_empty() {
+        return write_count(writer, 0);
+    }
+    write_count(writer, 1 + polygon.interiors().len())?;
+    write_coords(writer, &polygon.exterior().0)?;
+    polygon
+        .interiors()
+        .iter()
+        .try_for_each(|ring| write_coords(writer, &ring.0))
+}
+
+/// Read a single WKB geometry, the data must not contain anything after it.
+pub(crate) fn read_wkb(data: &[u8]) -> io::Result<Geometry<f64>> {
+    let mut reader = Reader {
+        data,
+        little_endian: true,
+        depth: 0,
+    };
+    let geometry = reader.geometry()?;
+    if !reader.data.is_empty() {
+        return Err(invalid_data(format!(
+            "Found {} unexpected bytes after the end of the WKB geometry",
+            reader.data.len()
+        )));
+    }
+    Ok(geometry)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let (bytes, rest) = self
+            .data
+            .split_first_chunk()
+            .ok_or_else(|| invalid_data("The WKB geometry is truncated".to_string()))?;
+        self.data = rest;
+        Ok(*bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let bytes = self.take()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> io::Result<f64> {
+        let bytes = self.take()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Read a number of elements taking at least `min_size` bytes each, to never allocate more than the data.
+    fn count(&mut self, min_size: usize) -> io::Result<usize> {
+        let count = self.u32()? as usize;
+        if count.saturating_mul(min_size) > self.data.len() {
+            return Err(invalid_data(format!(
+                "The WKB geometry announces {count} elements but is too short to contain them"
+            )));
+        }
+        Ok(count)
+    }
+
+    /// Read the byte order and the type of the next geometry.
+    fn header(&mut self) -> io::Result<u32> {
+        let [byte_order] = self.take()?;
+        self.little_endian = match byte_order {
+            0 => false,
+            1 => true,
+            byte_order => return Err(invalid_data(format!("Invalid WKB byte order {byte_order}"))),
+        };
+        self.u32()
+    }
+
+    fn expect_header(&mut self, expected: u32) -> io::Result<()> {
+        match self.header()? {
+            kind if kind == expected => Ok(()),
+            kind => Err(invalid_data(format!(
+                "Expected a WKB geometry of type {expected} but found {kind}"
+            ))),
+        }
+    }
+
+    fn geometry(&mut self) -> io::Result<Geometry<f64>> {
+        // The header of a geometry takes 5 bytes
+        const HEADER: usize = 1 + mem::size_of::<u32>();
+        Ok(match self.header()? {
+            POINT => Geometry::Point(Point(self.coord()?)),
+            LINE_STRING => Geometry::LineString(self.line()?),
+            POLYGON => Geometry::Polygon(self.polygon()?),
+            MULTI_POINT => {
+                let count = self.count(HEADER)?;
+                let points = (0..count)
+                    .map(|_| {
+                        self.expect_header(POINT)?;
+                        self.coord().map(Point)
+                    })
+                    .collect::<io::Result<_>>()?;
+                Geometry::MultiPoint(MultiPoint::new(points))
+            }
+            MULTI_LINE_STRING => {
+                let count = self.count(HEADER)?;
+                let lines = (0..count)
+                    .map(|_| {
+                        self.expect_header(LINE_STRING)?;
+                        self.line()
+                    })
+                    .collect::<io::Result<_>>()?;
+                Geometry::MultiLineString(MultiLineString::new(lines))
+            }
+            MULTI_POLYGON => {
+                let count = self.count(HEADER)?;
+                let polygons = (0..count)
+                    .map(|_| {
+                        self.expect_header(POLYGON)?;
+                        self.polygon()
+                    })
+                    .collect::<io::Result<_>>()?;
+                Geometry::MultiPolygon(MultiPolygon::new(polygons))
+            }
+            GEOMETRY_COLLECTION => {
+                if self.depth == MAX_DEPTH {
+                    return Err(invalid_data(format!(
+                        "The WKB geometry contains more than {MAX_DEPTH} nested collections"
+                    )));
+                }
+                let count = self.count(HEADER)?;
+                self.depth += 1;
+                let geometries = (0..count)
+                    .map(|_| self.geometry())
+                    .collect::<io::Result<_>>()?;
+                self.depth -= 1;
+                Geometry::GeometryCollection(GeometryCollection::new_from(geometries))
+            }
+            kind => {
+                return Err(invalid_data(format!(
+                    "Unsupported WKB geometry type {kind}, only the 2D geometries are supported"
+                )));
+            }
+        })
+    }
+
+    fn coord(&mut self) -> io::Result<Coord<f64>> {
+        Ok(Coord {
+            x: self.f64()?,
+            y: self.f64()?,
+        })
+    }
+
+    fn line(&mut self) -> io::Result<LineString<f64>> {
+        let count = self.count(mem::size_of::<f64>() * 2)?;
+        let coords = (0..count)
+            .map(|_| self.coord())
+            .collect::<io::Result<_>>()?;
+        Ok(LineString::new(coords))
+    }
+
+    fn polygon(&mut self) -> io::Result<Polygon<f64>> {
+        let count = self.count(mem::size_of::<u32>())?;
+        let mut rings = (0..count)
+            .map(|_| self.line())
+            .collect::<io::Result<Vec<_>>>()?;
+        if rings.is_empty() {
+            return Ok(Polygon::new(LineString::new(Vec::new()), Vec::new()));
+        }
+        let exterior = rings.remove(0);
+        Ok(Polygon::new(exterior, rings))
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Rect, coord, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn wkb_round_trip() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let points = MultiPoint::from(vec![(3.0, 1.0), (1.0, 2.0)]);
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            points.clone().into(),
+            line.clone().into(),
+            MultiLineString::new(vec![line.clone(), line.clone()]).into(),
+            square.clone().into(),
+            Polygon::new(LineString::new(Vec::new()), Vec::new()).into(),
+            MultiPolygon::new(vec![square.clone(), square.clone()]).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                points.into(),
+                line.into(),
+                square.into(),
+            ])),
+        ];
+        for geometry in geometries {
+            let mut buffer = Vec::new();
+            write_wkb(&mut buffer, &geometry).unwrap();
+            assert_eq!(read_wkb(&buffer).unwrap(), geometry);
+        }
+
+        let mut buffer = Vec::new();
+        write_wkb(&mut buffer, &Point::new(1.0, 2.0).into()).unwrap();
+        assert_compact_debug_snapshot!(buffer, @"[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 64]");
+        // The same point in big-endian
+        let mut big_endian = vec![0, 0, 0, 0, 1];
+        big_endian.extend(1.0_f64.to_be_bytes());
+        big_endian.extend(2.0_f64.to_be_bytes());
+        assert_compact_debug_snapshot!(read_wkb(&big_endian).unwrap(), @"POINT(1.0 2.0)");
+
+        let rect = Rect::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 1. });
+        let mut buffer = Vec::new();
+        write_wkb(&mut buffer, &rect.into()).unwrap();
+        assert_eq!(read_wkb(&buffer).unwrap(), rect.to_polygon().into());
+
+        let err = read_wkb(&buffer[..buffer.len() - 1]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @r#"Custom { kind: InvalidData, error: "The WKB geometry announces 5 elements but is too short to contain them" }"#);
+        // A multi points announcing a billion points
+        let err = read_wkb(&[1, 4, 0, 0, 0, 0, 202, 154, 59]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @r#"Custom { kind: InvalidData, error: "The WKB geometry announces 1000000000 elements but is too short to contain them" }"#);
+        let nested: Vec<u8> = std::iter::repeat_n([1, 7, 0, 0, 0, 1, 0, 0, 0], 100)
+            .flatten()
+            .collect();
+        let err = read_wkb(&nested).unwrap_err();
+        assert_compact_debug_snapshot!(err, @r#"Custom { kind: InvalidData, error: "The WKB geometry contains more than 64 nested collections" }"#);
+        // A point with a Z coordinate
+        let err = read_wkb(&[1, 0xe9, 3, 0, 0]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @r#"Custom { kind: InvalidData, error: "Unsupported WKB geometry type 1001, only the 2D geometries are supported" }"#);
+    }
+
+    #[test]
+    fn zerometry_wkb() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 2.0), (3.0, 1.0)]);
+        let points = MultiPoint::from(vec![(3.0, 1.0), (1.0, 2.0)]);
+        let geometries: Vec<Geometry<f64>> = vec![
+            Point::new(1.0, 2.0).into(),
+            points.clone().into(),
+            line.clone().into(),
+            MultiLineString::new(vec![line.clone(), line.clone()]).into(),
+            square.clone().into(),
+            Polygon::new(LineString::new(Vec::new()), Vec::new()).into(),
+            MultiPolygon::new(vec![square.clone(), square.clone()]).into(),
+            Rect::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 1. }).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                points.into(),
+                line.into(),
+                square.into(),
+            ])),
+        ];
+        for geometry in geometries {
+            let mut wkb = Vec::new();
+            write_wkb(&mut wkb, &geometry).unwrap();
+            let mut buffer = Vec::new();
+            Zerometry::write_from_wkb(&mut buffer, &wkb).unwrap();
+            let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+
+            // The same WKB is written from the zerometry and from its conversion to geo
+            let mut expected = Vec::new();
+            write_wkb(&mut expected, &zerometry.to_geo()).unwrap();
+            assert_eq!(zerometry.to_wkb(), expected);
+        }
+
+        let err = Zerometry::write_from_wkb(&mut Vec::new(), &[1, 1, 0, 0]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @r#"Io(Custom { kind: InvalidData, error: "The WKB geometry is truncated" })"#);
+    }
+}
@@ -0,0 +1,408 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::mem;
+
+use bytemuck::cast_slice;
+use geo_types::{Geometry, GeometryCollection, LineString, Polygon};
+
+use crate::checksum::crc32;
+use crate::coverage_mask::CoverageMask;
+use crate::endianness::{Direction, swap_byte_order};
+use crate::header::{
+    CHECKSUM_FLAG, CONVEX_FLAG, CONVEXITY_FLAG, COVERAGE_MASK_FLAG, GENERATION_FLAG, GROUPS_FLAG,
+    LITTLE_ENDIAN_FLAG, SORTED_MULTI_POINTS_TAG, tag,
+};
+use crate::zollection::flatten_geometry_collection;
+use crate::{Zerometry, ZerometryError, Zolygon, ZultiPoints};
+
+/// Options used when converting a [`geo_types::Geometry`] to a [`Zerometry`] with
+/// [`Zerometry::write_from_geometry_with_options`].
+/// The default options produce the exact same bytes as [`Zerometry::write_from_geometry`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Remove the exactly duplicated points, lines and polygons inside the multi shapes and collections.
+    /// Only the first occurrence of each member is kept, in its original position.
+    pub dedup: bool,
+    /// Store the points of a [`geo_types::MultiPoint`] sorted by their Morton code,
+    /// see [`ZultiPoints::write_sorted_from_geometry`].
+    /// It only applies to the multi points at the root, not the ones inside a collection.
+    pub sort_points: bool,
+    /// A monotonically increasing number stored in the header of the zerometry that can be read back
+    /// with [`Zerometry::read_generation`] without parsing the shape, to let the caches detect that a shape changed.
+    /// The shapes written without generation stay readable by the previous versions of zerometry.
+    pub generation: Option<u64>,
+    /// Store a grid of the specified number of cells per side over the bounding box of a [`geo_types::Polygon`],
+    /// telling which cells are entirely inside or outside of the polygon.
+    /// Most point-in-polygon tests are then decided with a single bit test instead of a ray casting, which is
+    /// worth it for large polygons that are queried often. A size of 16 or 32 is usually enough.
+    /// It only applies to a polygon at the root, the other shapes are written as-is.
+    pub coverage_mask: Option<u32>,
+    /// Store the bounding box of every group of 16 consecutive lines and polygons of a [`geo_types::MultiLineString`],
+    /// [`geo_types::MultiPolygon`] or [`geo_types::GeometryCollection`] in the header.
+    /// The relations then skip all the members of a group at once when its bounding box is disjoint from the other
+    /// shape, which is worth it for the shapes made of hundreds of members spread over a large area.
+    /// It only applies to the shape at the root.
+    pub bounding_box_groups: bool,
+    /// Return an error if the geometry is not in the `lng, lat` order or is out of the valid ranges,
+    /// see [`Zerometry::check_lng_lat`].
+    pub check_lng_lat: bool,
+    /// Store every number in little-endian instead of the native endianness of the platform, and mark the
+    /// zerometry as little-endian with a flag of its tag, see [`crate::FORMAT_VERSION`].
+    /// The zerometry can then be read on any platform: as-is on the little-endian ones, which means the bytes
+    /// are the same as without the option except for the flag, and after a conversion by [`crate::AlignedBytes`]
+    /// on the big-endian ones.
+    pub little_endian: bool,
+    /// Store the CRC32 of the zerometry after it, it's verified every time the zerometry is read and a
+    /// [`ZerometryError::ChecksumMismatch`] is returned if the bytes were corrupted, at the cost of reading
+    /// every byte of the shape once. It takes eight more bytes to keep the following data aligned on 64 bits.
+    pub checksum: bool,
+    /// Store whether a [`geo_types::Polygon`] is convex in its tag, so [`Zolygon::is_convex`] doesn't need to go
+    /// through all its edges on every relation. It only applies to a polygon at the root.
+    pub convexity: bool,
+}
+
+impl Zerometry<'_> {
+    /// Convert the specified [`geo_types::Geometry`] to a valid [`Zerometry`] slice of bytes in the input buffer
+    /// following the specified [`WriteOptions`].
+    /// See [`Self::write_from_geometry`] for more information.
+    pub fn write_from_geometry_with_options(
+        writer: &mut Vec<u8>,
+        geometry: &Geometry<f64>,
+        options: WriteOptions,
+    ) -> Result<(), ZerometryError> {
+        if options.check_lng_lat {
+            Zerometry::check_lng_lat(geometry)?;
+        }
+        let geometry = if options.dedup {
+            Cow::Owned(dedup_geometry(geometry))
+        } else {
+            Cow::Borrowed(geometry)
+        };
+        let start = writer.len();
+        match &*geometry {
+            Geometry::MultiPoint(multi_point) if options.sort_points => {
+                writer.extend_from_slice(&tag(SORTED_MULTI_POINTS_TAG).to_ne_bytes());
+                ZultiPoints::write_sorted_from_geometry(writer, multi_point)?;
+            }
+            geometry => Zerometry::write_from_geometry(writer, geometry)?,
+        }
+
+        if let (true, Geometry::Polygon(_)) = (options.convexity, &*geometry) {
+            let tag_end = start + mem::size_of::<u64>();
+            // Copy the polygon to be sure it's aligned before reading it
+            let floats: Vec<f64> = writer[tag_end..]
+                .chunks_exact(mem::size_of::<f64>())
+                .map(|float| f64::from_ne_bytes(float.try_into().unwrap()))
+                .collect();
+            let zolygon = unsafe { Zolygon::from_bytes(cast_slice(&floats)) };
+            let flags = if zolygon.is_convex() {
+                CONVEXITY_FLAG | CONVEX_FLAG
+            } else {
+                CONVEXITY_FLAG
+            };
+            let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+            writer[start..tag_end].copy_from_slice(&(tag | flags).to_ne_bytes());
+        }
+
+        if let (Some(size), Geometry::Polygon(_)) = (options.coverage_mask, &*geometry) {
+            let tag_end = start + mem::size_of::<u64>();
+            // Copy the polygon to be sure it's aligned before reading it
+            let floats: Vec<f64> = writer[tag_end..]
+                .chunks_exact(mem::size_of::<f64>())
+                .map(|float| f64::from_ne_bytes(float.try_into().unwrap()))
+                .collect();
+            let zolygon = unsafe { Zolygon::from_bytes(cast_slice(&floats)) };
+            let mut coverage_mask = Vec::new();
+            CoverageMask::write_from_zolygon(&mut coverage_mask, &zolygon, size as usize);
+            let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+            writer[start..tag_end].copy_from_slice(&(tag | COVERAGE_MASK_FLAG).to_ne_bytes());
+            writer.splice(tag_end..tag_end, coverage_mask);
+        }
+
+        if options.bounding_box_groups
+            && matches!(
+                &*geometry,
+                Geometry::MultiLineString(_)
+                    | Geometry::MultiPolygon(_)
+                    | Geometry::GeometryCollection(_)
+            )
+        {
+            let tag_end = start + mem::size_of::<u64>();
+            // Copy the zerometry to be sure it's aligned before reading it
+            let words: Vec<u64> = writer[start..]
+                .chunks_exact(mem::size_of::<u64>())
+                .map(|word| u64::from_ne_bytes(word.try_into().unwrap()))
+                .collect();
+            let zerometry = unsafe { Zerometry::from_bytes(cast_slice(&words))? };
+            let mut groups = Vec::new();
+            zerometry.write_groups(&mut groups);
+            let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+            writer[start..tag_end].copy_from_slice(&(tag | GROUPS_FLAG).to_ne_bytes());
+            writer.splice(tag_end..tag_end, groups);
+        }
+
+        if let Some(generation) = options.generation {
+            let tag_end = start + mem::size_of::<u64>();
+            let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+            writer[start..tag_end].copy_from_slice(&(tag | GENERATION_FLAG).to_ne_bytes());
+            writer.splice(tag_end..tag_end, generation.to_ne_bytes());
+        }
+
+        if options.little_endian {
+            let tag_end = start + mem::size_of::<u64>();
+            if cfg!(target_endian = "big") {
+                swap_byte_order(&mut writer[start..], Direction::FromNative)?;
+            }
+            let tag = u64::from_le_bytes(writer[start..tag_end].try_into().unwrap());
+            writer[start..tag_end].copy_from_slice(&(tag | LITTLE_ENDIAN_FLAG).to_le_bytes());
+        }
+
+        // The checksum covers everything else, it must be computed last
+        if options.checksum {
+            let tag_end = start + mem::size_of::<u64>();
+            let tag = u64::from_ne_bytes(writer[start..tag_end].try_into().unwrap());
+            let to_stored = |n: u64| if options.little_endian { n.to_le() } else { n };
+            writer[start..tag_end].copy_from_slice(&(tag | to_stored(CHECKSUM_FLAG)).to_ne_bytes());
+            let checksum = crc32(&writer[start..]) as u64;
+            writer.extend_from_slice(&to_stored(checksum).to_ne_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Remove the duplicated members of the multi shapes and collections.
+/// Since the collections are flattened once written, their members are flattened and deduplicated
+/// together.
+fn dedup_geometry(geometry: &Geometry<f64>) -> Geometry<f64> {
+    match geometry {
+        Geometry::MultiPoint(multi_point) => {
+            let mut multi_point = multi_point.clone();
+            dedup_by_key(&mut multi_point.0, |point| {
+                [point.x().to_bits(), point.y().to_bits()]
+            });
+            multi_point.into()
+        }
+        Geometry::MultiLineString(multi_line_string) => {
+            let mut multi_line_string = multi_line_string.clone();
+            dedup_by_key(&mut multi_line_string.0, line_key);
+            multi_line_string.into()
+        }
+        Geometry::MultiPolygon(multi_polygon) => {
+            let mut multi_polygon = multi_polygon.clone();
+            dedup_by_key(&mut multi_polygon.0, polygon_key);
+            multi_polygon.into()
+        }
+        Geometry::GeometryCollection(collection) => {
+            let (points, lines, polygons) = flatten_geometry_collection(collection);
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                dedup_geometry(&points.into()),
+                dedup_geometry(&lines.into()),
+                dedup_geometry(&polygons.into()),
+            ]))
+        }
+        geometry => geometry.clone(),
+    }
+}
+
+/// Keep only the first occurrence of every element.
+fn dedup_by_key<T, K: Hash + Eq>(elements: &mut Vec<T>, key: impl Fn(&T) -> K) {
+    let mut seen = HashSet::new();
+    elements.retain(|element| seen.insert(key(element)));
+}
+
+fn line_key(line: &LineString<f64>) -> Vec<u64> {
+    line.coords()
+        .flat_map(|coord| [coord.x.to_bits(), coord.y.to_bits()])
+        .collect()
+}
+
+fn polygon_key(polygon: &Polygon<f64>) -> Vec<Vec<u64>> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(line_key)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{MultiPoint, MultiPolygon, Point, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::Coord;
+
+    #[test]
+    fn default_options_write_the_same_bytes() {
+        let geometry: Geometry<f64> = MultiPoint::from(vec![(1.0, 2.0), (1.0, 2.0)]).into();
+        let mut expected = Vec::new();
+        Zerometry::write_from_geometry(&mut expected, &geometry).unwrap();
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(
+            &mut buffer,
+            &geometry,
+            WriteOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn dedup_multi_shapes() {
+        let options = WriteOptions {
+            dedup: true,
+            ..WriteOptions::default()
+        };
+
+        let geometry: Geometry<f64> = MultiPoint::from(vec![
+            (1.0, 2.0),
+            (3.0, 4.0),
+            (1.0, 2.0),
+            (-0.0, 0.0),
+            (0.0, 0.0),
+        ])
+        .into();
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert_compact_debug_snapshot!(zerometry.to_geo(), @"MULTIPOINT(1.0 2.0,3.0 4.0,-0.0 0.0,0.0 0.0)");
+
+        let triangle = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)];
+        let other = polygon![(x: 5., y: 0.), (x: 6., y: 0.), (x: 6., y: 1.)];
+        let geometry: Geometry<f64> =
+            MultiPolygon::new(vec![triangle.clone(), other, triangle]).into();
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert_compact_debug_snapshot!(zerometry.to_geo(), @"MULTIPOLYGON(((0.0 0.0,1.0 0.0,1.0 1.0,0.0 0.0)),((5.0 0.0,6.0 0.0,6.0 1.0,5.0 0.0)))");
+    }
+
+    #[test]
+    fn dedup_nested_collections() {
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]);
+        let geometry = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            Point::new(1.0, 1.0).into(),
+            line.clone().into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Point::new(1.0, 1.0).into(),
+                line.into(),
+            ])),
+        ]));
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(
+            &mut buffer,
+            &geometry,
+            WriteOptions {
+                dedup: true,
+                ..WriteOptions::default()
+            },
+        )
+        .unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert_compact_debug_snapshot!(zerometry.to_geo(), @"GEOMETRYCOLLECTION(MULTIPOINT(1.0 1.0),MULTILINESTRING((0.0 0.0,1.0 1.0)),MULTIPOLYGON EMPTY)");
+    }
+
+    #[test]
+    fn sort_points() {
+        let points: Vec<_> = (0..50_u32)
+            .map(|i| ((i * 37 % 50) as f64, (i * 13 % 50) as f64))
+            .collect();
+        let geometry: Geometry<f64> = MultiPoint::from(points.clone()).into();
+        let options = WriteOptions {
+            dedup: true,
+            sort_points: true,
+            ..WriteOptions::default()
+        };
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        let Zerometry::MultiPoints(zulti_points) = zerometry else {
+            panic!("{zerometry:?}");
+        };
+        assert!(zulti_points.is_sorted());
+        assert_eq!(zerometry, geometry);
+        assert_eq!(zerometry.to_canonical_bytes().len(), buffer.len());
+
+        for (x, y) in points {
+            let coord = [x, y];
+            assert!(zulti_points.contains_coord(unsafe { Coord::from_slice(&coord) }));
+            let coord = [x + 0.5, y];
+            assert!(!zulti_points.contains_coord(unsafe { Coord::from_slice(&coord) }));
+        }
+        let coord = [60.0, 0.0];
+        assert!(!zulti_points.contains_coord(unsafe { Coord::from_slice(&coord) }));
+    }
+
+    #[test]
+    fn generation() {
+        let geometry: Geometry<f64> = MultiPoint::from(vec![(1.0, 2.0), (3.0, 4.0)]).into();
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &geometry).unwrap();
+        assert_eq!(Zerometry::read_generation(&buffer).unwrap(), None);
+
+        for sort_points in [false, true] {
+            let options = WriteOptions {
+                sort_points,
+                generation: Some(u64::MAX),
+                ..WriteOptions::default()
+            };
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(&mut buffer, &geometry, options).unwrap();
+            assert_eq!(Zerometry::read_generation(&buffer).unwrap(), Some(u64::MAX));
+            let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+            assert_eq!(zerometry, geometry);
+        }
+
+        let err = Zerometry::read_generation(&(1_u64 << 50).to_ne_bytes()).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidTag(1125899906842624)");
+        let err = Zerometry::read_generation(&(1_u64 << 32).to_ne_bytes()).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"Truncated { expected: 8, found: 0 }");
+    }
+
+    #[test]
+    fn convexity() {
+        let options = WriteOptions {
+            convexity: true,
+            coverage_mask: Some(4),
+            ..WriteOptions::default()
+        };
+        let square = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 0., y: 2.)];
+        let arrow = polygon![(x: 0., y: 0.), (x: 2., y: 1.), (x: 0., y: 2.), (x: 1., y: 1.)];
+        let read = |polygon: &Polygon<f64>| {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry_with_options(
+                &mut buffer,
+                &polygon.clone().into(),
+                options,
+            )
+            .unwrap();
+            let Zerometry::Polygon(zolygon) = Zerometry::try_from_bytes(&buffer).unwrap() else {
+                panic!()
+            };
+            let convex = zolygon.is_convex();
+            assert_eq!(convex, zolygon.compute_convexity());
+            assert!(zolygon.has_coverage_mask());
+            (convex, buffer)
+        };
+        let (convex, mut buffer) = read(&square);
+        assert!(convex);
+        let (convex, _) = read(&arrow);
+        assert!(!convex);
+
+        // A forged convexity is detected when the zerometry is validated, and trusted otherwise
+        let tag = u64::from_ne_bytes(buffer[..8].try_into().unwrap());
+        let mut arrow_buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut arrow_buffer, &arrow.into()).unwrap();
+        buffer.truncate(8);
+        buffer.extend_from_slice(&arrow_buffer[8..]);
+        buffer[..8].copy_from_slice(&(tag & !COVERAGE_MASK_FLAG).to_ne_bytes());
+        let err = Zerometry::try_from_bytes(&buffer).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidConvexity");
+        let zerometry = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
+        assert!(zerometry.to_polygon().unwrap().is_convex());
+
+        // The convex flag can't be set without the convexity one
+        buffer[..8].copy_from_slice(&(tag & !COVERAGE_MASK_FLAG & !CONVEXITY_FLAG).to_ne_bytes());
+        assert!(Zerometry::try_from_bytes(&buffer).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::{fmt, mem};
+
+use bytemuck::cast_slice;
+use geo_types::Geometry;
+
+use crate::{Zerometry, ZerometryError};
+
+/// An owned [`Zerometry`], bundling the shape with the buffer it's read from.
+///
+/// The bytes are stored in a `Vec<u64>` which guarantees they're aligned on 64 bits, and the zerometry
+/// is read again on every call to [`Self::as_zerometry`], which is cheap since nothing is copied.
+/// It makes it possible to store a zerometry in a struct or send it to another thread without keeping
+/// the buffer alive next to it.
+///
+/// ```
+/// use zerometry::{Zerometry, ZerometryBuf};
+/// use geo_types::{Geometry, Point};
+///
+/// struct Store {
+///     shape: ZerometryBuf,
+/// }
+///
+/// let point = Geometry::Point(Point::new(1.0, 2.0));
+/// let store = Store { shape: ZerometryBuf::from(point.clone()) };
+/// let zerometry: Zerometry<'_> = store.shape.as_zerometry();
+/// assert_eq!(zerometry.to_geo(), point);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ZerometryBuf {
+    words: Vec<u64>,
+}
+
+impl ZerometryBuf {
+    /// Convert the [`geo_types::Geometry`] to an owned zerometry, see [`Zerometry::write_from_geometry`].
+    pub fn from_geometry(geometry: &Geometry<f64>) -> Result<Self, ZerometryError> {
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, geometry)?;
+        Ok(Self::copy_aligned(&buffer))
+    }
+
+    /// Copy the bytes of a serialized zerometry, that don't need to be aligned, and validate them.
+    /// See [`Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ZerometryError> {
+        if data.len() % mem::size_of::<u64>() != 0 {
+            return Err(ZerometryError::TrailingBytes(
+                data.len() % mem::size_of::<u64>(),
+            ));
+        }
+        let buf = Self::copy_aligned(data);
+        Zerometry::try_from_bytes(buf.as_bytes())?;
+        Ok(buf)
+    }
+
+    fn copy_aligned(data: &[u8]) -> Self {
+        debug_assert_eq!(data.len() % mem::size_of::<u64>(), 0);
+        let words = data
+            .chunks_exact(mem::size_of::<u64>())
+            .map(|word| u64::from_ne_bytes(word.try_into().unwrap()))
+            .collect();
+        Self { words }
+    }
+
+    /// Return the zerometry stored in the buffer.
+    #[inline]
+    pub fn as_zerometry(&self) -> Zerometry<'_> {
+        // SAFETY: The buffer is aligned and always contains a valid zerometry
+        unsafe { Zerometry::from_bytes(self.as_bytes()) }
+            .expect("A ZerometryBuf always contains a valid zerometry")
+    }
+
+    /// Return the serialized bytes of the zerometry, they can be stored and read back with [`Zerometry::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        cast_slice(&self.words)
+    }
+}
+
+impl From<Geometry<f64>> for ZerometryBuf {
+    fn from(geometry: Geometry<f64>) -> Self {
+        Self::from(&geometry)
+    }
+}
+
+impl From<&Geometry<f64>> for ZerometryBuf {
+    fn from(geometry: &Geometry<f64>) -> Self {
+        // Writing in a `Vec` can't fail
+        Self::from_geometry(geometry).unwrap()
+    }
+}
+
+impl fmt::Debug for ZerometryBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ZerometryBuf")
+            .field(&self.as_zerometry())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, Point};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn owned_zerometry() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<ZerometryBuf>();
+
+        let line = Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]));
+        let buf = ZerometryBuf::from(&line);
+        let shapes = std::thread::spawn(move || vec![buf.clone(), buf])
+            .join()
+            .unwrap();
+        assert_eq!(shapes[0], shapes[1]);
+        assert_eq!(shapes[0].as_zerometry().to_geo(), line);
+        assert_compact_debug_snapshot!(shapes[0], @"ZerometryBuf(Line(Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 1.0, lat: 1.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 1.0, lat: 1.0 }] }))");
+
+        // The bytes are not aligned anymore once prefixed by a single byte
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(shapes[0].as_bytes());
+        let copy = ZerometryBuf::try_from_bytes(&bytes[1..]).unwrap();
+        assert_eq!(copy, shapes[0]);
+        let err = ZerometryBuf::try_from_bytes(&bytes[1..bytes.len() - 8]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"TrailingBytes(8)");
+        let err = ZerometryBuf::try_from_bytes(&bytes[..bytes.len() - 8]).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"TrailingBytes(1)");
+
+        let point = ZerometryBuf::from(Geometry::Point(Point::new(1.0, 2.0)));
+        assert_compact_debug_snapshot!(point.as_zerometry().to_geo(), @"POINT(1.0 2.0)");
+    }
+}
@@ -0,0 +1,257 @@
+use core::fmt;
+use std::{io, mem};
+
+use bytemuck::cast_slice;
+use geo::CoordsIter;
+use geo_types::{Geometry, Point};
+
+use crate::{
+    BoundingBox, Zerometry, ZerometryError,
+    aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    zulti_lines::{check_children, write_children},
+};
+
+/// Many independent zerometries stored in a single buffer, like all the shapes of one field of a document.
+///
+/// Its layout is the same as a [`crate::ZultiLines`]: the bounding box of all the shapes, their offsets and then
+/// the shapes. Every shape is stored as a whole [`Zerometry`], starting with its tag, and can be of any kind.
+/// The shapes keep the order they were written in and are accessed by index without reading the others.
+///
+/// ```
+/// use zerometry::{BoundingBox, ZerometrySet};
+/// use geo_types::{Geometry, LineString, Point};
+///
+/// let shapes = [
+///     Geometry::Point(Point::new(2.35, 48.85)),
+///     Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)])),
+/// ];
+/// let mut buffer = Vec::new();
+/// ZerometrySet::write_from_geometries(&mut buffer, &shapes).unwrap();
+///
+/// let set = ZerometrySet::try_from_bytes(&buffer).unwrap();
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(set.get(1).unwrap().to_geo(), shapes[1]);
+/// assert_eq!(set.iter().map(|shape| shape.to_geo()).collect::<Vec<_>>(), shapes);
+/// ```
+#[derive(Clone, Copy)]
+pub struct ZerometrySet<'a> {
+    bounding_box: &'a BoundingBox,
+    // Same as the multi lines, the number of offsets is stored before them and they're followed by
+    // a padding if there is an even number of offsets
+    offsets: &'a [u32],
+    bytes: &'a [u8],
+}
+
+impl<'a> ZerometrySet<'a> {
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometries`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        let bounding_box = unsafe { BoundingBox::from_bytes(&data[..BOUNDING_BOX_SIZE_IN_BYTES]) };
+        let data = &data[BOUNDING_BOX_SIZE_IN_BYTES..];
+        let offsets_count = u32::from_ne_bytes(data[..mem::size_of::<u32>()].try_into().unwrap());
+        let data = &data[mem::size_of::<u32>()..];
+        let size_of_offsets = offsets_count as usize * mem::size_of::<u32>();
+        let offsets: &[u32] = cast_slice(&data[..size_of_offsets]);
+        let data = &data[size_of_offsets..];
+        // Skip the padding
+        let bytes = if offsets_count % 2 == 0 {
+            &data[mem::size_of::<u32>()..]
+        } else {
+            data
+        };
+        Self {
+            bounding_box,
+            offsets,
+            bytes,
+        }
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_children(data, |shape| Zerometry::try_from_bytes(shape).map(drop))?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
+    /// Convert the specified geometries to a valid [`ZerometrySet`] slice of bytes in the input buffer.
+    /// Every geometry is converted with [`Zerometry::write_from_geometry`].
+    pub fn write_from_geometries(
+        writer: &mut impl io::Write,
+        geometries: &[Geometry<f64>],
+    ) -> Result<(), ZerometryError> {
+        let shapes = geometries
+            .iter()
+            .map(|geometry| {
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, geometry)?;
+                Ok(buffer)
+            })
+            .collect::<Result<Vec<_>, ZerometryError>>()?;
+        write_children(
+            writer,
+            geometries
+                .iter()
+                .flat_map(|geometry| geometry.exterior_coords_iter())
+                .map(Point::from),
+            shapes.iter().map(Vec::as_slice),
+        )?;
+        Ok(())
+    }
+
+    /// Return the bytes the set was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let offsets = (self.offsets.len() + 1).next_multiple_of(2) * mem::size_of::<u32>();
+        let len = BOUNDING_BOX_SIZE_IN_BYTES + offsets + self.bytes.len();
+        contiguous(self.bounding_box, self.bytes, len, 0)
+    }
+
+    /// Return the bounding box of all the shapes.
+    #[inline]
+    pub fn bounding_box(&self) -> &'a BoundingBox {
+        self.bounding_box
+    }
+
+    /// Return the number of shapes in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Return `true` if the set doesn't contain any shape.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return a shape by index, if the index doesn't exists, returns None
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<Zerometry<'a>> {
+        let offset = *self.offsets.get(index)? as usize;
+        let next_offset = self
+            .offsets
+            .get(index + 1)
+            .map_or(self.bytes.len(), |offset| *offset as usize);
+        // SAFETY: The shapes were written by `write_from_geometries` or validated by `try_from_bytes`
+        Some(unsafe { Zerometry::from_bytes(&self.bytes[offset..next_offset]) }.unwrap())
+    }
+
+    /// Iterate over the shapes in the order they were written.
+    pub fn iter(&self) -> impl Iterator<Item = Zerometry<'a>> + use<'a> {
+        let set = *self;
+        (0..set.len()).map(move |index| set.get(index).unwrap())
+    }
+
+    /// Iterate over the shapes whose bounding box touches the specified bounding box, along with their index.
+    /// The whole set is skipped at once if its own bounding box doesn't touch it.
+    ///
+    /// ```
+    /// use zerometry::{BoundingBox, ZerometrySet};
+    /// use geo_types::{Geometry, Point};
+    ///
+    /// let shapes = [Geometry::Point(Point::new(1.0, 1.0)), Geometry::Point(Point::new(5.0, 5.0))];
+    /// let mut buffer = Vec::new();
+    /// ZerometrySet::write_from_geometries(&mut buffer, &shapes).unwrap();
+    /// let set = ZerometrySet::try_from_bytes(&buffer).unwrap();
+    ///
+    /// let bounds = [0.0, 0.0, 2.0, 2.0];
+    /// let bounding_box = unsafe { BoundingBox::from_bytes(bytemuck::cast_slice(&bounds)) };
+    /// let found: Vec<_> = set.iter_in_bounding_box(bounding_box).map(|(index, _)| index).collect();
+    /// assert_eq!(found, [0]);
+    /// ```
+    pub fn iter_in_bounding_box<'b>(
+        &self,
+        bounding_box: &'b BoundingBox,
+    ) -> impl Iterator<Item = (usize, Zerometry<'a>)> + use<'a, 'b> {
+        let set = *self;
+        let touches = move |(left, bottom, right, top): (f64, f64, f64, f64)| {
+            !bounding_box.is_empty()
+                && left <= bounding_box.right()
+                && bounding_box.left() <= right
+                && bottom <= bounding_box.top()
+                && bounding_box.bottom() <= top
+        };
+        let bounds = (
+            set.bounding_box.left(),
+            set.bounding_box.bottom(),
+            set.bounding_box.right(),
+            set.bounding_box.top(),
+        );
+        let len = if set.is_empty() || !touches(bounds) {
+            0
+        } else {
+            set.len()
+        };
+        (0..len)
+            .map(move |index| (index, set.get(index).unwrap()))
+            .filter(move |(_, shape)| !shape.is_empty() && touches(shape.bounds()))
+    }
+}
+
+impl fmt::Debug for ZerometrySet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZerometrySet")
+            .field("bounding_box", &self.bounding_box)
+            .field("shapes", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, MultiPoint, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn set_of_shapes() {
+        let shapes = [
+            Geometry::Polygon(polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)]),
+            Geometry::MultiPoint(MultiPoint::from(Vec::<(f64, f64)>::new())),
+            Geometry::Point(Point::new(5.0, 5.0)),
+            Geometry::LineString(LineString::from(vec![(-1.0, 0.0), (0.0, 3.0)])),
+        ];
+        let mut buffer = Vec::new();
+        ZerometrySet::write_from_geometries(&mut buffer, &shapes).unwrap();
+        let set = ZerometrySet::try_from_bytes(&buffer).unwrap();
+        assert_eq!(set.as_bytes(), &buffer[..]);
+        assert_eq!(
+            set.iter().map(|shape| shape.to_geo()).collect::<Vec<_>>(),
+            shapes
+        );
+        assert_compact_debug_snapshot!(set.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: -1.0, lat: 0.0 }, top_right: Coord { lng: 5.0, lat: 5.0 } }");
+        assert_compact_debug_snapshot!(set.get(2), @"Some(Point(Zoint { lng: 5.0, lat: 5.0 }))");
+        assert!(set.get(4).is_none());
+
+        let in_bounding_box = |bounds: [f64; 4]| {
+            let bounding_box = unsafe { BoundingBox::from_bytes(cast_slice(&bounds)) };
+            set.iter_in_bounding_box(bounding_box)
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>()
+        };
+        // The empty multi points is never returned
+        assert_compact_debug_snapshot!(in_bounding_box([-10.0, -10.0, 10.0, 10.0]), @"[0, 2, 3]");
+        assert_compact_debug_snapshot!(in_bounding_box([0.5, 0.5, 5.0, 5.0]), @"[0, 2]");
+        assert_compact_debug_snapshot!(in_bounding_box([-3.0, 2.0, -2.0, 3.0]), @"[]");
+        assert_compact_debug_snapshot!(in_bounding_box([20.0, 20.0, 30.0, 30.0]), @"[]");
+
+        let mut buffer = Vec::new();
+        ZerometrySet::write_from_geometries(&mut buffer, &[]).unwrap();
+        let empty = ZerometrySet::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(empty, @"ZerometrySet { bounding_box: BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }, shapes: [] }");
+    }
+
+    #[test]
+    fn invalid_set() {
+        let shapes = [Geometry::Point(Point::new(5.0, 5.0))];
+        let mut buffer = Vec::new();
+        ZerometrySet::write_from_geometries(&mut buffer, &shapes).unwrap();
+        // Corrupt the tag of the point
+        let tag = buffer.len() - 24;
+        buffer[tag] = 0xff;
+        assert_compact_debug_snapshot!(ZerometrySet::try_from_bytes(&buffer).unwrap_err(), @"InvalidTag(144115188075856127)");
+    }
+}
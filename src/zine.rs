@@ -1,12 +1,15 @@
 use core::fmt;
 use std::io::{self, Write};
+use std::mem;
 
 use geo::{LineString, Point};
 
 use crate::{
-    BoundingBox, COORD_SIZE_IN_BYTES, Coords, InputRelation, OutputRelation, RelationBetweenShapes,
-    Segment, Zerometry, Zoint, Zollection, Zolygon, ZultiPoints, ZultiPolygons,
-    zulti_lines::ZultiLines,
+    BoundingBox, COORD_SIZE_IN_BYTES, Coord, Coords, InputRelation, OutputRelation,
+    RelationBetweenShapes, Segment, Zerometry, ZerometryError, Zoint, Zollection, Zolygon,
+    ZultiPoints, ZultiPolygons, aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES, error::check_bounding_box_and_coords,
+    subtype::Subtype, topology::relation_with_topology, zulti_lines::ZultiLines,
 };
 
 /// Equivalent of a [`geo_types::LineString`].
@@ -14,6 +17,8 @@ use crate::{
 pub struct Zine<'a> {
     bounding_box: &'a BoundingBox,
     coords: &'a Coords,
+    // Set when the line was written from a `geo_types::Line`, stored in the tag of the zerometry
+    subtype: Subtype,
 }
 
 impl<'a> Zine<'a> {
@@ -23,6 +28,7 @@ impl<'a> Zine<'a> {
         Self {
             bounding_box,
             coords,
+            subtype: Subtype::None,
         }
     }
 
@@ -35,6 +41,21 @@ impl<'a> Zine<'a> {
         Self::new(bounding_box, coords)
     }
 
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_bounding_box_and_coords(data)?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
+    pub(crate) fn with_subtype(self, subtype: Subtype) -> Self {
+        Self { subtype, ..self }
+    }
+
+    pub(crate) fn subtype(&self) -> Subtype {
+        self.subtype
+    }
+
     /// Convert the specified [`geo_types::LineString`] to a valid [`Zine`] slice of bytes in the input buffer.
     pub fn write_from_geometry(
         writer: &mut impl Write,
@@ -51,6 +72,16 @@ impl<'a> Zine<'a> {
         Ok(())
     }
 
+    /// Return the bytes the line was read from, they can be stored and read back with [`Self::from_bytes`].
+    ///
+    /// # Panics
+    /// If the line was created with [`Self::new`] from parts that don't follow each other in memory.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let len = BOUNDING_BOX_SIZE_IN_BYTES + mem::size_of_val(self.coords);
+        contiguous(self.bounding_box, self.coords, len, 0)
+    }
+
     /// Return the internal bounding box
     #[inline]
     pub fn bounding_box(&self) -> &'a BoundingBox {
@@ -69,6 +100,24 @@ impl<'a> Zine<'a> {
         self.len() == 0
     }
 
+    /// Return true if the coord lies exactly on one of the segments of the line, see [`Segment::contains_coord`].
+    ///
+    /// ```
+    /// use zerometry::{Coord, Zine};
+    /// use geo_types::LineString;
+    ///
+    /// let mut buffer = Vec::new();
+    /// Zine::write_from_geometry(&mut buffer, &LineString::from(vec![(0.0, 0.0), (2.0, 2.0), (4.0, 0.0)])).unwrap();
+    /// let zine = Zine::try_from_bytes(&buffer).unwrap();
+    /// let (on, off) = ([3.0, 1.0], [2.0, 1.0]);
+    /// assert!(zine.contains_coord(unsafe { Coord::from_slice(&on) }));
+    /// assert!(!zine.contains_coord(unsafe { Coord::from_slice(&off) }));
+    /// ```
+    pub fn contains_coord(&self, coord: &Coord) -> bool {
+        self.bounding_box.contains_coord(coord)
+            && self.segments().any(|segment| segment.contains_coord(coord))
+    }
+
     /// Return the internal coords
     #[inline]
     pub fn coords(&self) -> &'a Coords {
@@ -84,6 +133,53 @@ impl<'a> Zine<'a> {
             .map(|coords| unsafe { Segment::from_slice(coords) })
     }
 
+    /// Return the sum of the absolute angles, in radians, the line turns by at each of its points.
+    /// A straight line doesn't turn at all, while a winding trail accumulates several full turns.
+    /// The angles are computed between the consecutive segments in the `lng, lat` plane, the null segments are ignored.
+    ///
+    /// ```
+    /// use zerometry::Zine;
+    /// use geo_types::LineString;
+    ///
+    /// // Turns left by 90° then right by 90°
+    /// let stairs = LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (2.0, 1.0)]);
+    /// let mut buffer = Vec::new();
+    /// Zine::write_from_geometry(&mut buffer, &stairs).unwrap();
+    /// let zine = unsafe { Zine::from_bytes(&buffer) };
+    /// assert!((zine.total_turning() - std::f64::consts::PI).abs() < 1e-12);
+    /// assert!((zine.max_turn_angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    /// ```
+    pub fn total_turning(&self) -> f64 {
+        // The sum of an empty iterator of floats is -0
+        self.turn_angles().fold(0.0, |total, angle| total + angle)
+    }
+
+    /// Return the sharpest turn of the line, in radians, from `0` for a straight line to `π` for a U-turn.
+    /// See [`Self::total_turning`].
+    pub fn max_turn_angle(&self) -> f64 {
+        self.turn_angles().fold(0.0, f64::max)
+    }
+
+    /// Return the absolute angle between every pair of consecutive non-null segments.
+    fn turn_angles(&self) -> impl Iterator<Item = f64> + '_ {
+        let mut edges = self
+            .segments()
+            .map(|segment| {
+                (
+                    segment.end().lng() - segment.start().lng(),
+                    segment.end().lat() - segment.start().lat(),
+                )
+            })
+            .filter(|&edge| edge != (0.0, 0.0));
+        let first = edges.next();
+        edges.scan(first, |previous, edge| {
+            let (x, y) = previous.replace(edge)?;
+            let cross = x * edge.1 - y * edge.0;
+            let dot = x * edge.0 + y * edge.1;
+            Some(cross.atan2(dot).abs())
+        })
+    }
+
     /// Convert the [`Zine`] back to a [`geo_types::LineString`].
     pub fn to_geo(self) -> geo_types::LineString<f64> {
         geo_types::LineString::new(
@@ -110,37 +206,89 @@ impl<'a> fmt::Debug for Zine<'a> {
     }
 }
 
-// A point cannot contains or intersect with anything
+// A line contains the points lying on its segments
 impl<'a> RelationBetweenShapes<Zoint<'a>> for Zine<'a> {
-    fn relation(&self, _other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        if self.contains_coord(other.coord()) {
+            relation.to_false().make_strict_contains_if_set()
+        } else {
+            relation.to_false().make_disjoint_if_set()
+        }
     }
 }
 
 // A point cannot contains or intersect with anything
+// A line contains the points lying on its segments
 impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for Zine<'a> {
-    fn relation(&self, _other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        let mut output = relation.to_false();
+        if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
+        {
+            return output.make_disjoint_if_set();
+        }
+
+        let mut contains = 0;
+        for coord in other.coords().iter() {
+            if self.contains_coord(coord) {
+                contains += 1;
+                output = output.make_contains_if_set();
+                if !relation.strict_contains || relation.early_exit {
+                    return output;
+                }
+            }
+        }
+
+        if contains == other.len() {
+            output = output.make_strict_contains_if_set();
+        }
+
+        if output.any_relation() {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
     }
 }
 
 impl<'a> RelationBetweenShapes<Zine<'a>> for Zine<'a> {
     fn relation(&self, other: &Zine<'a>, relation: InputRelation) -> OutputRelation {
-        let relation = relation.to_false();
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        let mut output = relation.to_false();
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
         {
-            return relation.make_disjoint_if_set();
+            return output.make_disjoint_if_set();
         }
 
+        let mut intersect = false;
         for lhs in self.segments() {
             for rhs in other.segments() {
                 if lhs.intersects(&rhs) {
-                    return relation.make_intersect_if_set();
+                    intersect = true;
+                    output = output.make_intersect_if_set();
+                    // Only the overlapping segments can tell us more
+                    if !relation.overlaps || relation.early_exit {
+                        return output;
+                    }
+                    if lhs.overlaps(&rhs) {
+                        return output.make_overlaps_if_set();
+                    }
                 }
             }
         }
 
-        relation.make_disjoint_if_set()
+        if intersect {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
     }
 }
 
@@ -153,6 +301,9 @@ impl<'a> RelationBetweenShapes<ZultiLines<'a>> for Zine<'a> {
 
 impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zine<'a> {
     fn relation(&self, other: &Zolygon<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
         {
             return relation.to_false().make_disjoint_if_set();
@@ -182,6 +333,9 @@ impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zine<'a> {
 
 impl<'a> RelationBetweenShapes<ZultiPolygons<'a>> for Zine<'a> {
     fn relation(&self, other: &ZultiPolygons<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
         {
@@ -255,8 +409,8 @@ mod tests {
         let input: &[f64] = cast_slice(&buffer);
         assert_compact_debug_snapshot!(input, @"[1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 3.0, 4.0]");
         let zulti_points = unsafe { Zine::from_bytes(&buffer) };
-        assert_compact_debug_snapshot!(zulti_points.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 1.0, y: 2.0 }, top_right: Coord { x: 3.0, y: 4.0 } }");
-        assert_compact_debug_snapshot!(zulti_points.coords(), @"[Coord { x: 1.0, y: 2.0 }, Coord { x: 3.0, y: 4.0 }]");
+        assert_compact_debug_snapshot!(zulti_points.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 1.0, lat: 2.0 }, top_right: Coord { lng: 3.0, lat: 4.0 } }");
+        assert_compact_debug_snapshot!(zulti_points.coords(), @"[Coord { lng: 1.0, lat: 2.0 }, Coord { lng: 3.0, lat: 4.0 }]");
     }
 
     #[test]
@@ -282,7 +436,7 @@ mod tests {
         Zolygon::write_from_geometry(&mut buf, &polygon).unwrap();
         let zolygon = unsafe { Zolygon::from_bytes(&buf) };
 
-        assert_compact_debug_snapshot!(zine.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zine.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
     }
 
     #[test]
@@ -325,28 +479,177 @@ mod tests {
         let mut buf = Vec::new();
         ZultiPolygons::write_from_geometry(&mut buf, &multi_polygons_inside).unwrap();
         let inside = unsafe { ZultiPolygons::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zine.all_relation(&inside ), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zine.all_relation(&inside ), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPolygons::write_from_geometry(&mut buf, &multi_polygons_outside).unwrap();
         let multi_polygons_outside = unsafe { ZultiPolygons::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
+        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPolygons::write_from_geometry(&mut buf, &multi_polygons_intersect).unwrap();
         let multi_polygons_intersect = unsafe { ZultiPolygons::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPolygons::write_from_geometry(&mut buf, &multi_polygons_in_and_out).unwrap();
         let multi_polygons_in_and_out = unsafe { ZultiPolygons::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPolygons::write_from_geometry(&mut buf, &multi_polygons_all).unwrap();
         let multi_polygons_all = unsafe { ZultiPolygons::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(true), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(zine.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zine.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(zine.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn test_turning() {
+        let lines = [
+            LineString::new(Vec::new()),
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]),
+            // Straight with a duplicated point
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0), (1.0, 1.0), (2.0, 2.0)]),
+            // A U-turn
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 0.0)]),
+            // Two full turns around a square
+            LineString::from(vec![
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 0.0),
+                (1.0, 0.0),
+            ]),
+        ];
+        let stats: Vec<_> = lines
+            .iter()
+            .map(|line| {
+                let mut buffer = Vec::new();
+                Zine::write_from_geometry(&mut buffer, line).unwrap();
+                let zine = unsafe { Zine::from_bytes(&buffer) };
+                (
+                    zine.total_turning() / std::f64::consts::PI,
+                    zine.max_turn_angle() / std::f64::consts::PI,
+                )
+            })
+            .collect();
+        assert_compact_debug_snapshot!(stats, @"[(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (1.0, 1.0), (4.0, 0.5)]");
+    }
+
+    #[test]
+    fn test_overlapping_zines() {
+        let overlaps = |left: Vec<(f64, f64)>, right: Vec<(f64, f64)>| {
+            let mut left_buffer = Vec::new();
+            Zine::write_from_geometry(&mut left_buffer, &LineString::from(left)).unwrap();
+            let left = unsafe { Zine::from_bytes(&left_buffer) };
+            let mut right_buffer = Vec::new();
+            Zine::write_from_geometry(&mut right_buffer, &LineString::from(right)).unwrap();
+            let right = unsafe { Zine::from_bytes(&right_buffer) };
+            let output = left.all_relation(&right);
+            (output.intersect.unwrap(), output.overlaps.unwrap())
+        };
+        let route = vec![(0.0, 0.0), (2.0, 0.0), (4.0, 2.0)];
+        // Share a part of the second segment
+        assert_compact_debug_snapshot!(overlaps(route.clone(), vec![(3.0, 1.0), (5.0, 3.0), (5.0, 0.0)]), @"(true, true)");
+        // Cross the route
+        assert_compact_debug_snapshot!(overlaps(route.clone(), vec![(1.0, -1.0), (1.0, 1.0)]), @"(true, false)");
+        // Only touch the end of the first segment while being collinear
+        assert_compact_debug_snapshot!(overlaps(route.clone(), vec![(-2.0, 0.0), (0.0, 0.0)]), @"(true, false)");
+        // Contained in the route
+        assert_compact_debug_snapshot!(overlaps(route, vec![(0.5, 0.0), (1.5, 0.0)]), @"(true, true)");
+    }
+
+    #[test]
+    fn test_zine_and_zoint() {
+        let line = geo_types::Geometry::LineString(LineString::from(vec![
+            (0.0, 0.0),
+            (2.0, 2.0),
+            (4.0, 0.0),
+        ]));
+        let relations = |shape: &geo_types::Geometry<f64>| {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, shape).unwrap();
+            let shape = Zerometry::try_from_bytes(&buffer).unwrap();
+            [(0.3, 0.3), (3.0, 1.0), (4.0, 0.0), (2.0, 1.0), (5.0, -1.0)].map(|(lng, lat)| {
+                let point = geo_types::Geometry::Point(Point::new(lng, lat));
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, &point).unwrap();
+                let point = Zerometry::try_from_bytes(&buffer).unwrap();
+                let output = shape.all_relation(&point);
+                assert_eq!(output, point.all_relation(&shape).swap_contains_relation());
+                output.strict_contains.unwrap()
+            })
+        };
+        // On a segment, on the last point, close to the line and on the extension of a segment
+        assert_compact_debug_snapshot!(relations(&line), @"[true, true, true, false, false]");
+        let multi_lines =
+            geo_types::Geometry::MultiLineString(geo_types::MultiLineString::new(vec![
+                LineString::from(vec![(10.0, 10.0), (11.0, 11.0)]),
+                line.clone().try_into().unwrap(),
+            ]));
+        assert_compact_debug_snapshot!(relations(&multi_lines), @"[true, true, true, false, false]");
+        let collection =
+            geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection::new_from(vec![
+                Point::new(2.0, 1.0).into(),
+                line.clone(),
+            ]));
+        assert_compact_debug_snapshot!(relations(&collection), @"[true, true, true, false, false]");
+
+        let relation = |shape: &geo_types::Geometry<f64>, points: Vec<(f64, f64)>| {
+            let points = geo_types::Geometry::MultiPoint(points.into());
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, shape).unwrap();
+            let shape = Zerometry::try_from_bytes(&buffer).unwrap();
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &points).unwrap();
+            let points = Zerometry::try_from_bytes(&buffer).unwrap();
+            let output = shape.all_relation(&points);
+            assert_eq!(output, points.all_relation(&shape).swap_contains_relation());
+            (output.contains.unwrap(), output.strict_contains.unwrap())
+        };
+        // All the points, some of them and none of them are on the lines
+        assert_compact_debug_snapshot!(relation(&line, vec![(3.0, 1.0), (4.0, 0.0)]), @"(true, true)");
+        assert_compact_debug_snapshot!(relation(&line, vec![(3.0, 1.0), (2.0, 1.0)]), @"(true, false)");
+        assert_compact_debug_snapshot!(relation(&multi_lines, vec![(10.5, 10.5), (4.0, 0.0)]), @"(true, true)");
+        assert_compact_debug_snapshot!(relation(&multi_lines, vec![(5.0, -1.0), (2.0, 1.0)]), @"(false, false)");
+    }
+
+    #[test]
+    fn test_zine_and_zollection() {
+        let collection =
+            geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection::new_from(vec![
+                Point::new(5.0, 5.0).into(),
+                polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)].into(),
+            ]));
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &collection).unwrap();
+        let collection = Zerometry::try_from_bytes(&buffer).unwrap();
+
+        let relations = |line: LineString<f64>| {
+            let mut buffer = Vec::new();
+            Zerometry::write_from_geometry(&mut buffer, &line.into()).unwrap();
+            let line = Zerometry::try_from_bytes(&buffer).unwrap();
+            let output = line.all_relation(&collection);
+            assert_eq!(
+                output,
+                collection.all_relation(&line).swap_contains_relation()
+            );
+            output
+        };
+        // Contained in the polygon of the collection
+        let inside = LineString::from(vec![(1.0, 1.0), (2.0, 2.0)]);
+        assert_compact_debug_snapshot!(relations(inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        // Crosses the border of the polygon
+        let crossing = LineString::from(vec![(1.0, 1.0), (6.0, 2.0)]);
+        assert_compact_debug_snapshot!(relations(crossing), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        // Only close to the point, which never relates with a line
+        let outside = LineString::from(vec![(4.5, 5.0), (6.0, 5.0)]);
+        assert_compact_debug_snapshot!(relations(outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
     }
 
     // Prop test ensuring we can round trip from a multi-point to a zulti-points and back to a multi-point
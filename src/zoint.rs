@@ -4,8 +4,13 @@ use std::io::{self, Write};
 use geo_types::Point;
 
 use crate::{
-    Coord, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry, Zollection, Zolygon,
-    ZultiPoints, ZultiPolygons, zine::Zine, zulti_lines::ZultiLines,
+    COORD_SIZE_IN_BYTES, Coord, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry,
+    ZerometryError, Zollection, Zolygon, ZultiPoints, ZultiPolygons,
+    aligned_bytes::contiguous,
+    error::{check_aligned, check_empty, split_checked},
+    topology::relation_with_topology,
+    zine::Zine,
+    zulti_lines::ZultiLines,
 };
 
 /// Equivalent of a [`geo_types::Point`].
@@ -29,6 +34,15 @@ impl<'a> Zoint<'a> {
         Self::new(coord)
     }
 
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        let (coord, rest) = split_checked(data, COORD_SIZE_IN_BYTES)?;
+        check_empty(rest)?;
+        Ok(unsafe { Self::from_bytes(coord) })
+    }
+
     /// Convert the specified [`geo_types::Point`] to a valid [`Zoint`] slice of bytes in the input buffer.
     pub fn write_from_geometry(
         writer: &mut impl Write,
@@ -39,6 +53,12 @@ impl<'a> Zoint<'a> {
         Ok(())
     }
 
+    /// Return the bytes the point was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        contiguous(self.coord, self.coord, COORD_SIZE_IN_BYTES, 0)
+    }
+
     #[inline]
     pub(crate) fn coord(&self) -> &'a Coord {
         self.coord
@@ -55,12 +75,12 @@ impl<'a> Zoint<'a> {
         self.coord.lng()
     }
 
-    /// Return the x.
+    /// Return the x, which is the same as the longitude.
     #[inline]
     pub fn x(&self) -> f64 {
         self.coord.lng()
     }
-    /// Return the y.
+    /// Return the y, which is the same as the latitude.
     #[inline]
     pub fn y(&self) -> f64 {
         self.coord.lat()
@@ -88,36 +108,52 @@ impl PartialEq<geo_types::Point<f64>> for Zoint<'_> {
     }
 }
 
-// A point cannot contains or intersect with another point
+// Two points are only related when they're the same point, then they strictly contain each other
 impl<'a> RelationBetweenShapes<Zoint<'a>> for Zoint<'a> {
-    fn relation(&self, _other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        if self.lng() == other.lng() && self.lat() == other.lat() {
+            relation
+                .to_false()
+                .make_strict_contains_if_set()
+                .make_strict_contained_if_set()
+        } else {
+            relation.to_false().make_disjoint_if_set()
+        }
     }
 }
 
-// A point cannot contains or intersect with a multi point
 impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for Zoint<'a> {
-    fn relation(&self, _other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
+        other
+            .relation(self, relation.swap_contains_relation())
+            .swap_contains_relation()
     }
 }
 
-// A point cannot contains or intersect with a line
 impl<'a> RelationBetweenShapes<Zine<'a>> for Zoint<'a> {
-    fn relation(&self, _other: &Zine<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &Zine<'a>, relation: InputRelation) -> OutputRelation {
+        other
+            .relation(self, relation.swap_contains_relation())
+            .swap_contains_relation()
     }
 }
 
-// A point cannot contains or intersect with a line
 impl<'a> RelationBetweenShapes<ZultiLines<'a>> for Zoint<'a> {
-    fn relation(&self, _other: &ZultiLines<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &ZultiLines<'a>, relation: InputRelation) -> OutputRelation {
+        other
+            .relation(self, relation.swap_contains_relation())
+            .swap_contains_relation()
     }
 }
 
 impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zoint<'a> {
     fn relation(&self, other: &Zolygon<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         if other.strict_contains(self) {
             relation.to_false().make_strict_contained_if_set()
         } else {
@@ -164,7 +200,53 @@ mod tests {
         let input: &[f64] = cast_slice(&buffer);
         assert_compact_debug_snapshot!(input, @"[1.0, 2.0]");
         let zoint = unsafe { Zoint::from_bytes(&buffer) };
-        assert_compact_debug_snapshot!(zoint.coord(), @"Coord { x: 1.0, y: 2.0 }");
+        assert_compact_debug_snapshot!(zoint.coord(), @"Coord { lng: 1.0, lat: 2.0 }");
+    }
+
+    #[test]
+    fn test_relation_with_points() {
+        let relation =
+            |left: geo_types::Geometry<f64>, right: geo_types::Geometry<f64>, tolerance: f64| {
+                let (mut left_buffer, mut right_buffer) = (Vec::new(), Vec::new());
+                Zerometry::write_from_geometry(&mut left_buffer, &left).unwrap();
+                Zerometry::write_from_geometry(&mut right_buffer, &right).unwrap();
+                let left = Zerometry::try_from_bytes(&left_buffer).unwrap();
+                let right = Zerometry::try_from_bytes(&right_buffer).unwrap();
+                let input = InputRelation {
+                    tolerance,
+                    ..InputRelation::all_with_topology()
+                };
+                let output = left.relation(&right, input);
+                assert_eq!(
+                    output,
+                    right.relation(&left, input).swap_contains_relation()
+                );
+                // The relations computed without the topology must agree with it
+                let exact = left.relation(&right, InputRelation::all());
+                assert_eq!(exact.contains, output.contains);
+                (
+                    output.strict_contains,
+                    output.strict_contained,
+                    output.disjoint,
+                    output.covers,
+                    output.covered_by,
+                )
+            };
+        let point = |lng, lat| Point::new(lng, lat).into();
+
+        // The same point
+        assert_compact_debug_snapshot!(relation(point(1.0, 1.0), point(1.0, 1.0), 0.0), @"(Some(true), Some(true), Some(false), Some(true), Some(true))");
+        assert_compact_debug_snapshot!(relation(point(1.0, 1.0), point(2.0, 2.0), 0.0), @"(Some(false), Some(false), Some(true), Some(false), Some(false))");
+        // Close enough to be the same point
+        assert_compact_debug_snapshot!(relation(point(1.0, 1.0), point(1.0, 1.5), 1.0), @"(Some(false), Some(false), Some(false), Some(true), Some(true))");
+
+        // One of the points of the multi points
+        let multi_point = geo_types::MultiPoint::from(vec![(1.0, 1.0), (2.0, 2.0)]);
+        assert_compact_debug_snapshot!(relation(multi_point.clone().into(), point(1.0, 1.0), 0.0), @"(Some(true), Some(false), Some(false), Some(true), Some(false))");
+        assert_compact_debug_snapshot!(relation(multi_point.into(), point(3.0, 3.0), 0.0), @"(Some(false), Some(false), Some(true), Some(false), Some(false))");
+        // All the points of the multi points are the same
+        let multi_point = geo_types::MultiPoint::from(vec![(1.0, 1.0), (1.0, 1.0)]);
+        assert_compact_debug_snapshot!(relation(multi_point.into(), point(1.0, 1.0), 0.0), @"(Some(true), Some(true), Some(false), Some(true), Some(true))");
     }
 
     // Prop test ensuring we can round trip from a point to a zoint and back to a point
@@ -0,0 +1,135 @@
+use core::fmt;
+use std::io::{self, Write};
+use std::mem;
+
+use bytemuck::cast_slice;
+use geo_types::Point;
+
+use crate::{
+    ZerometryError,
+    error::{check_aligned, check_empty, split_checked},
+};
+
+/// The number of bytes used by a coordinate stored as two `f32`.
+pub(crate) const COORD32_SIZE_IN_BYTES: usize = 2 * mem::size_of::<f32>();
+
+/// Equivalent of a [`geo_types::Point`] storing its coordinate as two `f32` instead of two `f64`,
+/// it takes half the space of a [`crate::Zoint`] for the large datasets of points that don't need the precision.
+/// A `f32` keeps about 7 significant digits, which means around one meter at the scale of the earth.
+///
+/// The coordinate is converted back to `f64` when read, but the precision lost when writing is never recovered.
+///
+/// ```
+/// use zerometry::Zoint32;
+/// use geo_types::Point;
+///
+/// let mut buffer = Vec::new();
+/// Zoint32::write_from_geometry(&mut buffer, &Point::new(2.35, 48.85)).unwrap();
+/// assert_eq!(buffer.len(), 8);
+///
+/// let point = Zoint32::try_from_bytes(&buffer).unwrap();
+/// assert_eq!(point.lng(), 2.35_f32 as f64);
+/// assert!((point.lat() - 48.85).abs() < 1e-5);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Zoint32<'a> {
+    coord: &'a [f32; 2],
+}
+
+impl<'a> Zoint32<'a> {
+    /// Create a [`Zoint32`] from its longitude and latitude.
+    pub fn new(coord: &'a [f32; 2]) -> Self {
+        Self { coord }
+    }
+
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        let floats: &[f32] = cast_slice(data);
+        Self::new(floats.try_into().unwrap())
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        let (coord, rest) = split_checked(data, COORD32_SIZE_IN_BYTES)?;
+        check_empty(rest)?;
+        Ok(unsafe { Self::from_bytes(coord) })
+    }
+
+    /// Convert the specified [`geo_types::Point`] to a valid [`Zoint32`] slice of bytes in the input buffer,
+    /// the coordinate is rounded to the nearest `f32`.
+    pub fn write_from_geometry(
+        writer: &mut impl Write,
+        geometry: &Point<f64>,
+    ) -> Result<(), io::Error> {
+        writer.write_all(&(geometry.x() as f32).to_ne_bytes())?;
+        writer.write_all(&(geometry.y() as f32).to_ne_bytes())?;
+        Ok(())
+    }
+
+    /// Return the bytes the point was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        cast_slice(self.coord)
+    }
+
+    /// Return the latitude.
+    #[inline]
+    pub fn lat(&self) -> f64 {
+        self.coord[1] as f64
+    }
+    /// Return the longitude.
+    #[inline]
+    pub fn lng(&self) -> f64 {
+        self.coord[0] as f64
+    }
+
+    /// Return the x, which is the same as the longitude.
+    #[inline]
+    pub fn x(&self) -> f64 {
+        self.lng()
+    }
+    /// Return the y, which is the same as the latitude.
+    #[inline]
+    pub fn y(&self) -> f64 {
+        self.lat()
+    }
+
+    /// Convert the [`Zoint32`] back to a [`geo_types::Point`].
+    #[inline]
+    pub fn to_geo(&self) -> geo_types::Point<f64> {
+        geo_types::Point::new(self.lng(), self.lat())
+    }
+}
+
+impl fmt::Debug for Zoint32<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Zoint32")
+            .field("lng", &self.coord[0])
+            .field("lat", &self.coord[1])
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_zoint32() {
+        let mut buffer = Vec::new();
+        Zoint32::write_from_geometry(&mut buffer, &Point::new(1.0, 0.1)).unwrap();
+        let point = Zoint32::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(point, @"Zoint32 { lng: 1.0, lat: 0.1 }");
+        assert_compact_debug_snapshot!(point.to_geo(), @"POINT(1.0 0.10000000149011612)");
+        assert_eq!(point.as_bytes(), &buffer[..]);
+
+        let error = Zoint32::try_from_bytes(&buffer[..4]).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"Truncated { expected: 8, found: 4 }");
+    }
+}
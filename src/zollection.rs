@@ -1,10 +1,22 @@
-use std::{io, mem};
+use std::{borrow::Cow, io, mem};
 
-use geo::{GeometryCollection, MultiLineString, MultiPoint, MultiPolygon, Point};
+use geo::{
+    Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
 
 use crate::{
-    BoundingBox, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry, Zine, Zoint,
-    Zolygon, ZultiLines, ZultiPoints, ZultiPolygons, bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    BoundingBox, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry, ZerometryError,
+    Zine, Zoint, Zolygon, ZultiLines, ZultiPoints, ZultiPolygons,
+    aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    error::{check_aligned, split_bounding_box, split_u32},
+    groups::Groups,
+    header::{COLLECTION_TAG, IDS_FLAG, tag},
+    properties::Properties,
+    size::{children_size, coords_size},
+    topology::relation_with_topology,
+    zulti_lines::{write_children, write_offsets},
 };
 
 /// Equivalent of a [`geo_types::GeometryCollection`] except it only contains:
@@ -15,6 +27,16 @@ use crate::{
 /// Everything else gets pushed in one of these three types.
 /// If the collection contains other collection they all get flatenned to fit in these
 /// three types.
+///
+/// # Order
+///
+/// The order of the members is part of the format and never changes, it's safe to store the index of a member
+/// returned by [`Self::points`], [`Self::lines`] or [`Self::polygons`] alongside the zerometry.
+/// The members of each kind are kept in the order they're written:
+/// - The members of a multi shape are written one after the other, at the position of the multi shape.
+/// - The [`geo_types::Line`]s are written as lines, and the [`geo_types::Rect`]s and [`geo_types::Triangle`]s as polygons.
+/// - The nested collections are flattened after all the other members of the collection, from the last one to
+///   the first one, and a collection nested in them is flattened right after its parent.
 #[derive(Debug, Clone, Copy)]
 pub struct Zollection<'a> {
     bounding_box: &'a BoundingBox,
@@ -30,6 +52,9 @@ pub struct Zollection<'a> {
     points: ZultiPoints<'a>,
     lines: ZultiLines<'a>,
     polygons: ZultiPolygons<'a>,
+
+    // The opaque properties of the members, stored in the header of the zerometry
+    properties: Option<Properties<'a>>,
 }
 
 impl<'a> Zollection<'a> {
@@ -46,6 +71,7 @@ impl<'a> Zollection<'a> {
             points,
             lines,
             polygons,
+            properties: None,
         }
     }
 
@@ -81,61 +107,296 @@ impl<'a> Zollection<'a> {
             points,
             lines,
             polygons,
+            properties: None,
         }
     }
 
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        let (points, lines, polygons) = split_sections(data)?;
+        ZultiPoints::try_from_bytes(points)?;
+        ZultiLines::try_from_bytes(lines)?;
+        ZultiPolygons::try_from_bytes(polygons)?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
     /// Convert the specified [`geo_types::GeometryCollection`] to a valid [`Zollection`] slice of bytes in the input buffer.
     /// This is a destructive operation:
     /// - The collection will be flattened as a list of points, lines and polygons
     /// - If the polygons contains an interior, the information will be lost and ignored during operations.
+    ///
+    /// The writer can be any [`io::Write`], see [`ZultiLines::write_from_geometry`].
     pub fn write_from_geometry(
-        writer: &mut Vec<u8>,
+        writer: &mut impl io::Write,
         geometry: &GeometryCollection<f64>,
     ) -> Result<(), io::Error> {
-        let (points, lines, polygons) = flatten_geometry_collection(geometry);
+        Self::write_from_geometries(writer, geometry)
+    }
+
+    /// Convert the specified geometries to a valid [`Zollection`] slice of bytes in the input buffer, as if they
+    /// were the members of a [`geo_types::GeometryCollection`], see [`Self::write_from_geometry`].
+    /// The geometries are only borrowed, which avoids building a large collection when they're already stored elsewhere.
+    ///
+    /// ```
+    /// use zerometry::Zollection;
+    /// use geo_types::{Geometry, LineString, Point, polygon};
+    ///
+    /// let geometries: Vec<Geometry<f64>> = vec![
+    ///     Point::new(0.0, 0.0).into(),
+    ///     LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]).into(),
+    ///     polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+    /// ];
+    /// let mut buffer = Vec::new();
+    /// Zollection::write_from_geometries(&mut buffer, &geometries).unwrap();
+    ///
+    /// let zollection = Zollection::try_from_bytes(&buffer).unwrap();
+    /// assert_eq!(zollection.into_geometries().collect::<Vec<_>>(), geometries);
+    /// ```
+    pub fn write_from_geometries<'g>(
+        writer: &mut impl io::Write,
+        geometries: impl IntoIterator<Item = &'g Geometry<f64>>,
+    ) -> Result<(), io::Error> {
+        let Flattened {
+            points,
+            lines,
+            polygons,
+            ..
+        } = Flattened::new(geometries);
+        let exteriors = || polygons.iter().map(|polygon| polygon.exterior());
+
+        BoundingBox::write_from_geometry(
+            writer,
+            points.iter().copied().chain(
+                lines
+                    .iter()
+                    .map(|line| &**line)
+                    .chain(exteriors())
+                    .flat_map(|line| line.0.iter())
+                    .map(|coord| Point::from((coord.x, coord.y))),
+            ),
+        )?;
+
+        // The offsets are computed from the size of the points and lines to write everything in a single pass
+        let lines_offset = coords_size(points.len());
+        let polygons_offset = lines_offset
+            + children_size(
+                lines.len(),
+                lines.iter().map(|line| coords_size(line.0.len())).sum(),
+            );
+        writer.write_all(&(lines_offset as u32).to_ne_bytes())?;
+        writer.write_all(&(polygons_offset as u32).to_ne_bytes())?;
+
+        ZultiPoints::write_from_geometry(writer, &MultiPoint::new(points))?;
+
+        // The lines and polygons are written like `ZultiLines` and `ZultiPolygons` but without being moved to them
+        let lines_points = lines.iter().flat_map(|line| line.points());
+        BoundingBox::write_from_geometry(writer, lines_points)?;
+        write_offsets(writer, lines.iter().map(|line| coords_size(line.0.len())))?;
+        for line in &lines {
+            Zine::write_from_geometry(writer, line)?;
+        }
+
+        BoundingBox::write_from_geometry(writer, exteriors().flat_map(|line| line.points()))?;
+        write_offsets(writer, exteriors().map(|line| coords_size(line.0.len())))?;
+        for polygon in &polygons {
+            Zolygon::write_from_geometry(writer, polygon)?;
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over the points, then the lines and finally the polygons of the collection converted to
+    /// [`geo_types::Geometry`], one by one. It's the reverse of [`Self::write_from_geometries`] except
+    /// the geometries are flattened and grouped by kind.
+    pub fn into_geometries(self) -> impl Iterator<Item = Geometry<f64>> + use<'a> {
+        let (lines, polygons) = (self.lines, self.polygons);
+        self.points
+            .points_in_original_order()
+            .map(|point| Geometry::Point(point.to_geo()))
+            .chain(
+                (0..lines.len())
+                    .map(move |index| Geometry::LineString(lines.get(index).unwrap().to_geo())),
+            )
+            .chain(
+                (0..polygons.len())
+                    .map(move |index| Geometry::Polygon(polygons.get(index).unwrap().to_geo())),
+            )
+    }
+
+    /// Write a [`Zerometry::Collection`], starting with its tag, containing only the children matching the predicate,
+    /// in the same order. The predicate receives every point, line and polygon as a [`Zerometry`] along with its id,
+    /// see [`Zerometry::write_from_geometry_with_ids`], and the ids of the children retained are kept in the output.
+    /// The children are copied as-is and only the bounding boxes are recomputed, no conversion to geo is involved.
+    ///
+    /// ```
+    /// use zerometry::{Zerometry, Zollection};
+    /// use geo_types::{Geometry, GeometryCollection, polygon};
+    ///
+    /// let square = |x: f64| polygon![(x: x, y: 0.), (x: x + 1., y: 0.), (x: x + 1., y: 1.), (x: x, y: 1.)];
+    /// let regions = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+    ///     square(0.0).into(),
+    ///     square(5.0).into(),
+    /// ]));
+    /// let mut buffer = Vec::new();
+    /// Zerometry::write_from_geometry_with_ids(&mut buffer, &regions, &[4, 6]).unwrap();
+    /// let Zerometry::Collection(regions) = Zerometry::try_from_bytes(&buffer).unwrap() else { unreachable!() };
+    ///
+    /// // Only keep the regions of level 6
+    /// let mut filtered = Vec::new();
+    /// regions.filter_children(|_, id| id == Some(6), &mut filtered).unwrap();
+    /// let Zerometry::Collection(filtered) = Zerometry::try_from_bytes(&filtered).unwrap() else { unreachable!() };
+    /// assert_eq!(filtered.polygons().ids(), Some(&[6][..]));
+    /// assert_eq!(filtered.bounding_box().left(), 5.0);
+    /// ```
+    pub fn filter_children(
+        &self,
+        mut predicate: impl FnMut(&Zerometry<'a>, Option<u64>) -> bool,
+        writer: &mut Vec<u8>,
+    ) -> Result<(), io::Error> {
+        let (lines, polygons) = (self.lines, self.polygons);
+        let points: Vec<Zoint> = self
+            .points
+            .points_in_original_order()
+            .filter(|point| predicate(&Zerometry::Point(*point), None))
+            .collect();
+        let retained_lines: Vec<usize> = (0..lines.len())
+            .filter(|&index| {
+                predicate(&Zerometry::Line(lines.get(index).unwrap()), lines.id(index))
+            })
+            .collect();
+        let retained_polygons: Vec<usize> = (0..polygons.len())
+            .filter(|&index| {
+                predicate(
+                    &Zerometry::Polygon(polygons.get(index).unwrap()),
+                    polygons.id(index),
+                )
+            })
+            .collect();
+        let line_coords = || {
+            retained_lines
+                .iter()
+                .flat_map(|&index| lines.get(index).unwrap().coords().iter())
+                .map(|coord| Point::new(coord.lng(), coord.lat()))
+        };
+        let polygon_coords = || {
+            retained_polygons
+                .iter()
+                .flat_map(|&index| polygons.get(index).unwrap().coords().iter())
+                .map(|coord| Point::new(coord.lng(), coord.lat()))
+        };
+        let line_bytes = retained_lines
+            .iter()
+            .map(|&index| lines.get_bytes(index).unwrap());
+        let polygon_bytes = retained_polygons
+            .iter()
+            .map(|&index| polygons.get_bytes(index).unwrap());
+
+        // The ids of the children retained are kept in the header
+        let ids = lines
+            .ids()
+            .zip(polygons.ids())
+            .map(|(line_ids, polygon_ids)| {
+                let line_ids: Vec<u64> = retained_lines
+                    .iter()
+                    .map(|&index| line_ids[index])
+                    .collect();
+                let polygon_ids: Vec<u64> = retained_polygons
+                    .iter()
+                    .map(|&index| polygon_ids[index])
+                    .collect();
+                (line_ids, polygon_ids)
+            });
+        match ids {
+            Some((line_ids, polygon_ids)) => {
+                writer.extend_from_slice(&(tag(COLLECTION_TAG) | IDS_FLAG).to_ne_bytes());
+                for word in [line_ids.len() as u64, polygon_ids.len() as u64]
+                    .into_iter()
+                    .chain(line_ids)
+                    .chain(polygon_ids)
+                {
+                    writer.extend_from_slice(&word.to_ne_bytes());
+                }
+            }
+            None => writer.extend_from_slice(&tag(COLLECTION_TAG).to_ne_bytes()),
+        }
 
         BoundingBox::write_from_geometry(
             writer,
             points
                 .iter()
-                .copied()
-                .chain(
-                    lines
-                        .0
-                        .iter()
-                        .flat_map(|line| line.0.iter())
-                        .map(|coord| Point::from((coord.x, coord.y))),
-                )
-                .chain(
-                    polygons
-                        .iter()
-                        .flat_map(|polygon| polygon.exterior().0.iter())
-                        .map(|coord| Point::from((coord.x, coord.y))),
-                ),
+                .map(Zoint::to_geo)
+                .chain(line_coords())
+                .chain(polygon_coords()),
         )?;
+        let lines_offset = coords_size(points.len());
+        let polygons_offset = lines_offset
+            + children_size(
+                retained_lines.len(),
+                line_bytes.clone().map(<[u8]>::len).sum(),
+            );
+        writer.extend_from_slice(&(lines_offset as u32).to_ne_bytes());
+        writer.extend_from_slice(&(polygons_offset as u32).to_ne_bytes());
+        BoundingBox::write_from_geometry(writer, points.iter().map(Zoint::to_geo))?;
+        for point in &points {
+            writer.extend_from_slice(&point.lng().to_ne_bytes());
+            writer.extend_from_slice(&point.lat().to_ne_bytes());
+        }
+        write_children(writer, line_coords(), line_bytes)?;
+        write_children(writer, polygon_coords(), polygon_bytes)
+    }
 
-        let offsets_pos = writer.len();
-        // We'll update the offsets after writing the structures
-        writer.extend_from_slice(&0_u32.to_ne_bytes());
-        writer.extend_from_slice(&0_u32.to_ne_bytes());
-
-        let base_pos = writer.len();
+    /// Attach an id to every line and polygon of the collection, see [`Zerometry::write_from_geometry_with_ids`].
+    ///
+    /// # Panics
+    /// If there isn't exactly one id per line and per polygon.
+    pub fn with_ids(self, line_ids: &'a [u64], polygon_ids: &'a [u64]) -> Self {
+        Self {
+            lines: self.lines.with_ids(line_ids),
+            polygons: self.polygons.with_ids(polygon_ids),
+            ..self
+        }
+    }
 
-        // Write the points and update the offset
-        ZultiPoints::write_from_geometry(writer, &points)?;
-        let line_offset = ((writer.len() - base_pos) as u32).to_ne_bytes();
-        writer[offsets_pos..offsets_pos + mem::size_of::<u32>()].copy_from_slice(&line_offset);
+    /// Attach the bounding boxes of the groups of lines and polygons read from the header.
+    pub(crate) fn with_groups(self, line_groups: Groups<'a>, polygon_groups: Groups<'a>) -> Self {
+        Self {
+            lines: self.lines.with_groups(line_groups),
+            polygons: self.polygons.with_groups(polygon_groups),
+            ..self
+        }
+    }
 
-        // Write the lines and update the offset
-        ZultiLines::write_from_geometry(writer, &lines)?;
-        let polygon_offset = ((writer.len() - base_pos) as u32).to_ne_bytes();
-        writer[offsets_pos + mem::size_of::<u32>()..offsets_pos + mem::size_of::<u32>() * 2]
-            .copy_from_slice(&polygon_offset);
+    /// Attach the properties of the members read from the header.
+    pub(crate) fn with_properties(self, properties: Properties<'a>) -> Self {
+        Self {
+            properties: Some(properties),
+            ..self
+        }
+    }
 
-        // Write the polygons and nothing to update anymore
-        ZultiPolygons::write_from_geometry(writer, &polygons)?;
+    /// Return the properties of the member at the specified index in the original collection, or `None` if the
+    /// collection was written without properties or the index doesn't exist.
+    /// See [`Zerometry::write_from_geometry_with_properties`].
+    pub fn properties(&self, index: usize) -> Option<&'a [u8]> {
+        self.properties?.get(index)
+    }
 
-        Ok(())
+    /// Return the bytes the collection was read from, they can be stored and read back with [`Self::from_bytes`].
+    ///
+    /// # Panics
+    /// If the collection was created with [`Self::new`] from parts that don't follow each other in memory.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let polygons = self.polygons.as_bytes();
+        // The offsets of the lines and polygons sections
+        let len = BOUNDING_BOX_SIZE_IN_BYTES
+            + mem::size_of::<u32>() * 2
+            + self.points.as_bytes().len()
+            + self.lines.as_bytes().len()
+            + polygons.len();
+        contiguous(self.bounding_box, polygons, len, 0)
     }
 
     /// Return the internal bounding box
@@ -158,19 +419,19 @@ impl<'a> Zollection<'a> {
 
     /// Return all the points contained in the collection
     #[inline]
-    pub fn points(&'a self) -> ZultiPoints<'a> {
+    pub fn points(&self) -> ZultiPoints<'a> {
         self.points
     }
 
     /// Return all the lines contained in the collection
     #[inline]
-    pub fn lines(&'a self) -> ZultiLines<'a> {
+    pub fn lines(&self) -> ZultiLines<'a> {
         self.lines
     }
 
     /// Return all the polygons contained in the collection
     #[inline]
-    pub fn polygons(&'a self) -> ZultiPolygons<'a> {
+    pub fn polygons(&self) -> ZultiPolygons<'a> {
         self.polygons
     }
 
@@ -188,7 +449,85 @@ impl<'a> Zollection<'a> {
     }
 }
 
-fn flatten_geometry_collection(
+/// The bytes of the points, the lines and the polygons of a collection.
+type Sections<'a> = (&'a [u8], &'a [u8], &'a [u8]);
+
+/// Validate the layout of a collection and return the bytes of its points, lines and polygons,
+/// which are not validated. The data doesn't need to be aligned.
+pub(crate) fn split_sections(data: &[u8]) -> Result<Sections<'_>, ZerometryError> {
+    let rest = split_bounding_box(data)?;
+    let (lines_offset, rest) = split_u32(rest)?;
+    let (polygons_offset, rest) = split_u32(rest)?;
+    let (lines_offset, polygons_offset) = (lines_offset as usize, polygons_offset as usize);
+    for (offset, end) in [
+        (lines_offset, polygons_offset),
+        (polygons_offset, rest.len()),
+    ] {
+        if offset > end || offset % mem::size_of::<u64>() != 0 {
+            return Err(ZerometryError::InvalidOffset {
+                offset,
+                len: rest.len(),
+            });
+        }
+    }
+    Ok((
+        &rest[..lines_offset],
+        &rest[lines_offset..polygons_offset],
+        &rest[polygons_offset..],
+    ))
+}
+
+/// The members of the geometries written in a collection, the lines and polygons are only borrowed
+/// when possible, see [`Zollection::write_from_geometries`].
+#[derive(Default)]
+struct Flattened<'g> {
+    points: Vec<Point<f64>>,
+    lines: Vec<Cow<'g, LineString<f64>>>,
+    polygons: Vec<Cow<'g, Polygon<f64>>>,
+    nested: Vec<&'g GeometryCollection<f64>>,
+}
+
+impl<'g> Flattened<'g> {
+    /// Flatten the geometries in the same order as [`flatten_geometry_collection`]:
+    /// the nested collections are flattened after all the other members.
+    fn new(geometries: impl IntoIterator<Item = &'g Geometry<f64>>) -> Self {
+        let mut flattened = Self::default();
+        geometries
+            .into_iter()
+            .for_each(|geometry| flattened.push(geometry));
+        while let Some(collection) = flattened.nested.pop() {
+            collection
+                .iter()
+                .for_each(|geometry| flattened.push(geometry));
+        }
+        flattened
+    }
+
+    fn push(&mut self, geometry: &'g Geometry<f64>) {
+        match geometry {
+            Geometry::Point(point) => self.points.push(*point),
+            Geometry::MultiPoint(multi_point) => self.points.extend_from_slice(&multi_point.0),
+            Geometry::LineString(line_string) => self.lines.push(Cow::Borrowed(line_string)),
+            Geometry::MultiLineString(multi_line_string) => {
+                self.lines
+                    .extend(multi_line_string.iter().map(Cow::Borrowed));
+            }
+            Geometry::Polygon(polygon) => self.polygons.push(Cow::Borrowed(polygon)),
+            Geometry::MultiPolygon(multi_polygon) => {
+                self.polygons
+                    .extend(multi_polygon.iter().map(Cow::Borrowed));
+            }
+            Geometry::GeometryCollection(geometry_collection) => {
+                self.nested.push(geometry_collection);
+            }
+            Geometry::Line(line) => self.lines.push(Cow::Owned((*line).into())),
+            Geometry::Rect(rect) => self.polygons.push(Cow::Owned(rect.to_polygon())),
+            Geometry::Triangle(triangle) => self.polygons.push(Cow::Owned(triangle.to_polygon())),
+        }
+    }
+}
+
+pub(crate) fn flatten_geometry_collection(
     collection: &GeometryCollection,
 ) -> (MultiPoint, MultiLineString, MultiPolygon) {
     let mut points = MultiPoint::new(vec![]);
@@ -231,17 +570,30 @@ fn flatten_geometry_collection(
 
 impl<'a> RelationBetweenShapes<Zoint<'a>> for Zollection<'a> {
     fn relation(&self, other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
-        if self.is_empty() || self.bounding_box().contains_coord(other.coord()) {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        if self.is_empty() || !self.bounding_box().contains_coord(other.coord()) {
             return relation.to_false().make_disjoint_if_set();
         }
 
-        // points and lines can't have any relation with point
-        self.polygons().relation(other, relation)
+        // points can't have any relation with point
+        let mut output = self.lines().relation(other, relation.strip_disjoint());
+        output |= self.polygons().relation(other, relation.strip_disjoint());
+
+        if output.any_relation() {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
     }
 }
 
 impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for Zollection<'a> {
     fn relation(&self, other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         if self.is_empty() || self.bounding_box().disjoint(other.bounding_box()) {
             return relation.to_false().make_disjoint_if_set();
         }
@@ -253,6 +605,9 @@ impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for Zollection<'a> {
 
 impl<'a> RelationBetweenShapes<Zine<'a>> for Zollection<'a> {
     fn relation(&self, other: &Zine<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || self.bounding_box().disjoint(other.bounding_box()) {
@@ -272,6 +627,9 @@ impl<'a> RelationBetweenShapes<Zine<'a>> for Zollection<'a> {
 
 impl<'a> RelationBetweenShapes<ZultiLines<'a>> for Zollection<'a> {
     fn relation(&self, other: &ZultiLines<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || self.bounding_box().disjoint(other.bounding_box()) {
@@ -291,6 +649,9 @@ impl<'a> RelationBetweenShapes<ZultiLines<'a>> for Zollection<'a> {
 
 impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zollection<'a> {
     fn relation(&self, other: &Zolygon<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || self.bounding_box().disjoint(other.bounding_box()) {
@@ -328,6 +689,9 @@ impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zollection<'a> {
 
 impl<'a> RelationBetweenShapes<ZultiPolygons<'a>> for Zollection<'a> {
     fn relation(&self, other: &ZultiPolygons<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || self.bounding_box().disjoint(other.bounding_box()) {
@@ -362,6 +726,9 @@ impl<'a> RelationBetweenShapes<ZultiPolygons<'a>> for Zollection<'a> {
 
 impl<'a> RelationBetweenShapes<Zollection<'a>> for Zollection<'a> {
     fn relation(&self, other: &Zollection<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || self.bounding_box().disjoint(other.bounding_box()) {
@@ -464,8 +831,10 @@ mod tests {
         let mut writer = Vec::new();
 
         Zollection::write_from_geometry(&mut writer, &collection).unwrap();
-        // Debug everything at once just to make sure it never changes
-        assert_debug_snapshot!(writer);
+        // Debug everything at once in the canonical form just to make sure it never changes
+        let canonical =
+            Zerometry::Collection(unsafe { Zollection::from_bytes(&writer) }).to_canonical_bytes();
+        assert_debug_snapshot!(&canonical[8..]);
         let mut current_offset = 0;
         let expected_bounding_box: &[f64] =
             cast_slice(&writer[current_offset..BOUNDING_BOX_SIZE_IN_BYTES]);
@@ -491,198 +860,201 @@ mod tests {
         let points_f64: &[f64] = cast_slice(points_bytes);
         assert_compact_debug_snapshot!(points_f64, @"[1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 3.0, 4.0]");
         let points = unsafe { ZultiPoints::from_bytes(points_bytes) };
-        assert_compact_debug_snapshot!(points, @"ZultiPoints { bounding_box: BoundingBox { bottom_left: Coord { x: 1.0, y: 2.0 }, top_right: Coord { x: 3.0, y: 4.0 } }, points: [Zoint { lng: 1.0, lat: 2.0 }, Zoint { lng: 3.0, lat: 4.0 }] }");
+        assert_compact_debug_snapshot!(points, @"ZultiPoints { bounding_box: BoundingBox { bottom_left: Coord { lng: 1.0, lat: 2.0 }, top_right: Coord { lng: 3.0, lat: 4.0 } }, points: [Zoint { lng: 1.0, lat: 2.0 }, Zoint { lng: 3.0, lat: 4.0 }] }");
         assert_eq!(points, multi_points);
 
         // Now there should be the first multi lines at the offset line to the offset polygon
         let lines_bytes = &writer
             [current_offset + lines_offset as usize..current_offset + polygon_offset as usize];
-        assert_compact_debug_snapshot!(lines_bytes, @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 240, 63, 2, 0, 0, 0, 0, 0, 0, 0, 80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 240, 63]");
         let lines = unsafe { ZultiLines::from_bytes(lines_bytes) };
-        assert_compact_debug_snapshot!(lines, @"ZultiLines { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 4.0, y: 1.0 } }, zines: [Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 2.0, y: 1.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 1.0, lat: 1.0 }, Zoint { lng: 2.0, lat: 1.0 }] }, Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 3.0, y: 1.0 }, top_right: Coord { x: 4.0, y: 1.0 } }, points: [Zoint { lng: 3.0, lat: 1.0 }, Zoint { lng: 4.0, lat: 1.0 }] }] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::MultiLines(lines).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 240, 63, 2, 0, 0, 0, 0, 0, 0, 0, 80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 240, 63]");
+        assert_compact_debug_snapshot!(lines, @"ZultiLines { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 4.0, lat: 1.0 } }, zines: [Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 2.0, lat: 1.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 1.0, lat: 1.0 }, Zoint { lng: 2.0, lat: 1.0 }] }, Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 3.0, lat: 1.0 }, top_right: Coord { lng: 4.0, lat: 1.0 } }, points: [Zoint { lng: 3.0, lat: 1.0 }, Zoint { lng: 4.0, lat: 1.0 }] }] }");
         assert_eq!(lines, multi_lines);
 
         // Now there should be the first multi lines at the offset line to the offset polygon
         let polygons_bytes = &writer[current_offset + polygon_offset as usize..];
-        assert_compact_debug_snapshot!(polygons_bytes, @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 20, 64, 2, 0, 0, 0, 0, 0, 0, 0, 96, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 8, 64]");
         let polygons = unsafe { ZultiPolygons::from_bytes(polygons_bytes) };
-        assert_compact_debug_snapshot!(polygons, @"ZultiPolygons { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 5.0, y: 5.0 } }, zolygons: [Zolygon { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 2.0, y: 2.0 } }, coords: [Coord { x: 0.0, y: 0.0 }, Coord { x: 1.0, y: 1.0 }, Coord { x: 2.0, y: 2.0 }, Coord { x: 0.0, y: 0.0 }] }, Zolygon { bounding_box: BoundingBox { bottom_left: Coord { x: 3.0, y: 3.0 }, top_right: Coord { x: 5.0, y: 5.0 } }, coords: [Coord { x: 3.0, y: 3.0 }, Coord { x: 4.0, y: 4.0 }, Coord { x: 5.0, y: 5.0 }, Coord { x: 3.0, y: 3.0 }] }] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::MultiPolygon(polygons).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 20, 64, 2, 0, 0, 0, 0, 0, 0, 0, 96, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 16, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 20, 64, 0, 0, 0, 0, 0, 0, 8, 64, 0, 0, 0, 0, 0, 0, 8, 64]");
+        assert_compact_debug_snapshot!(polygons, @"ZultiPolygons { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 5.0, lat: 5.0 } }, zolygons: [Zolygon { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 2.0, lat: 2.0 } }, coords: [Coord { lng: 0.0, lat: 0.0 }, Coord { lng: 1.0, lat: 1.0 }, Coord { lng: 2.0, lat: 2.0 }, Coord { lng: 0.0, lat: 0.0 }] }, Zolygon { bounding_box: BoundingBox { bottom_left: Coord { lng: 3.0, lat: 3.0 }, top_right: Coord { lng: 5.0, lat: 5.0 } }, coords: [Coord { lng: 3.0, lat: 3.0 }, Coord { lng: 4.0, lat: 4.0 }, Coord { lng: 5.0, lat: 5.0 }, Coord { lng: 3.0, lat: 3.0 }] }] }");
         assert_eq!(polygons, multi_polygons);
 
         // Try to parse the whole collection
         let zollection = unsafe { Zollection::from_bytes(&writer) };
-        assert_compact_debug_snapshot!(zollection.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 5.0, y: 5.0 } }");
+        assert_compact_debug_snapshot!(zollection.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 5.0, lat: 5.0 } }");
         assert_debug_snapshot!(zollection, @r"
-        Zollection {
-            bounding_box: BoundingBox {
-                bottom_left: Coord {
-                    x: 0.0,
-                    y: 0.0,
-                },
-                top_right: Coord {
-                    x: 5.0,
-                    y: 5.0,
-                },
-            },
-            points: ZultiPoints {
+            Zollection {
                 bounding_box: BoundingBox {
                     bottom_left: Coord {
-                        x: 1.0,
-                        y: 2.0,
+                        lng: 0.0,
+                        lat: 0.0,
                     },
                     top_right: Coord {
-                        x: 3.0,
-                        y: 4.0,
+                        lng: 5.0,
+                        lat: 5.0,
                     },
                 },
-                points: [
-                    Zoint {
-                        lng: 1.0,
-                        lat: 2.0,
-                    },
-                    Zoint {
-                        lng: 3.0,
-                        lat: 4.0,
-                    },
-                ],
-            },
-            lines: ZultiLines {
-                bounding_box: BoundingBox {
-                    bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
-                    },
-                    top_right: Coord {
-                        x: 4.0,
-                        y: 1.0,
+                points: ZultiPoints {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: 1.0,
+                            lat: 2.0,
+                        },
+                        top_right: Coord {
+                            lng: 3.0,
+                            lat: 4.0,
+                        },
                     },
+                    points: [
+                        Zoint {
+                            lng: 1.0,
+                            lat: 2.0,
+                        },
+                        Zoint {
+                            lng: 3.0,
+                            lat: 4.0,
+                        },
+                    ],
                 },
-                zines: [
-                    Zine {
-                        bounding_box: BoundingBox {
-                            bottom_left: Coord {
-                                x: 0.0,
-                                y: 0.0,
-                            },
-                            top_right: Coord {
-                                x: 2.0,
-                                y: 1.0,
-                            },
+                lines: ZultiLines {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: 0.0,
+                            lat: 0.0,
+                        },
+                        top_right: Coord {
+                            lng: 4.0,
+                            lat: 1.0,
                         },
-                        points: [
-                            Zoint {
-                                lng: 0.0,
-                                lat: 0.0,
-                            },
-                            Zoint {
-                                lng: 1.0,
-                                lat: 1.0,
-                            },
-                            Zoint {
-                                lng: 2.0,
-                                lat: 1.0,
-                            },
-                        ],
                     },
-                    Zine {
-                        bounding_box: BoundingBox {
-                            bottom_left: Coord {
-                                x: 3.0,
-                                y: 1.0,
-                            },
-                            top_right: Coord {
-                                x: 4.0,
-                                y: 1.0,
+                    zines: [
+                        Zine {
+                            bounding_box: BoundingBox {
+                                bottom_left: Coord {
+                                    lng: 0.0,
+                                    lat: 0.0,
+                                },
+                                top_right: Coord {
+                                    lng: 2.0,
+                                    lat: 1.0,
+                                },
                             },
+                            points: [
+                                Zoint {
+                                    lng: 0.0,
+                                    lat: 0.0,
+                                },
+                                Zoint {
+                                    lng: 1.0,
+                                    lat: 1.0,
+                                },
+                                Zoint {
+                                    lng: 2.0,
+                                    lat: 1.0,
+                                },
+                            ],
                         },
-                        points: [
-                            Zoint {
-                                lng: 3.0,
-                                lat: 1.0,
-                            },
-                            Zoint {
-                                lng: 4.0,
-                                lat: 1.0,
+                        Zine {
+                            bounding_box: BoundingBox {
+                                bottom_left: Coord {
+                                    lng: 3.0,
+                                    lat: 1.0,
+                                },
+                                top_right: Coord {
+                                    lng: 4.0,
+                                    lat: 1.0,
+                                },
                             },
-                        ],
-                    },
-                ],
-            },
-            polygons: ZultiPolygons {
-                bounding_box: BoundingBox {
-                    bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
-                    },
-                    top_right: Coord {
-                        x: 5.0,
-                        y: 5.0,
-                    },
+                            points: [
+                                Zoint {
+                                    lng: 3.0,
+                                    lat: 1.0,
+                                },
+                                Zoint {
+                                    lng: 4.0,
+                                    lat: 1.0,
+                                },
+                            ],
+                        },
+                    ],
                 },
-                zolygons: [
-                    Zolygon {
-                        bounding_box: BoundingBox {
-                            bottom_left: Coord {
-                                x: 0.0,
-                                y: 0.0,
-                            },
-                            top_right: Coord {
-                                x: 2.0,
-                                y: 2.0,
-                            },
+                polygons: ZultiPolygons {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: 0.0,
+                            lat: 0.0,
+                        },
+                        top_right: Coord {
+                            lng: 5.0,
+                            lat: 5.0,
                         },
-                        coords: [
-                            Coord {
-                                x: 0.0,
-                                y: 0.0,
-                            },
-                            Coord {
-                                x: 1.0,
-                                y: 1.0,
-                            },
-                            Coord {
-                                x: 2.0,
-                                y: 2.0,
-                            },
-                            Coord {
-                                x: 0.0,
-                                y: 0.0,
-                            },
-                        ],
                     },
-                    Zolygon {
-                        bounding_box: BoundingBox {
-                            bottom_left: Coord {
-                                x: 3.0,
-                                y: 3.0,
-                            },
-                            top_right: Coord {
-                                x: 5.0,
-                                y: 5.0,
+                    zolygons: [
+                        Zolygon {
+                            bounding_box: BoundingBox {
+                                bottom_left: Coord {
+                                    lng: 0.0,
+                                    lat: 0.0,
+                                },
+                                top_right: Coord {
+                                    lng: 2.0,
+                                    lat: 2.0,
+                                },
                             },
+                            coords: [
+                                Coord {
+                                    lng: 0.0,
+                                    lat: 0.0,
+                                },
+                                Coord {
+                                    lng: 1.0,
+                                    lat: 1.0,
+                                },
+                                Coord {
+                                    lng: 2.0,
+                                    lat: 2.0,
+                                },
+                                Coord {
+                                    lng: 0.0,
+                                    lat: 0.0,
+                                },
+                            ],
                         },
-                        coords: [
-                            Coord {
-                                x: 3.0,
-                                y: 3.0,
-                            },
-                            Coord {
-                                x: 4.0,
-                                y: 4.0,
+                        Zolygon {
+                            bounding_box: BoundingBox {
+                                bottom_left: Coord {
+                                    lng: 3.0,
+                                    lat: 3.0,
+                                },
+                                top_right: Coord {
+                                    lng: 5.0,
+                                    lat: 5.0,
+                                },
                             },
-                            Coord {
-                                x: 5.0,
-                                y: 5.0,
-                            },
-                            Coord {
-                                x: 3.0,
-                                y: 3.0,
-                            },
-                        ],
-                    },
-                ],
-            },
-        }
+                            coords: [
+                                Coord {
+                                    lng: 3.0,
+                                    lat: 3.0,
+                                },
+                                Coord {
+                                    lng: 4.0,
+                                    lat: 4.0,
+                                },
+                                Coord {
+                                    lng: 5.0,
+                                    lat: 5.0,
+                                },
+                                Coord {
+                                    lng: 3.0,
+                                    lat: 3.0,
+                                },
+                            ],
+                        },
+                    ],
+                },
+                properties: None,
+            }
         ");
     }
 
@@ -693,12 +1065,14 @@ mod tests {
         let mut writer = Vec::new();
 
         Zollection::write_from_geometry(&mut writer, &collection).unwrap();
-        // Debug everything at once just to make sure it never changes
-        assert_debug_snapshot!(writer);
+        // Debug everything at once in the canonical form just to make sure it never changes
+        let canonical =
+            Zerometry::Collection(unsafe { Zollection::from_bytes(&writer) }).to_canonical_bytes();
+        assert_debug_snapshot!(&canonical[8..]);
         let mut current_offset = 0;
         let expected_bounding_box: &[f64] =
             cast_slice(&writer[current_offset..BOUNDING_BOX_SIZE_IN_BYTES]);
-        assert_compact_debug_snapshot!(expected_bounding_box, @"[0.0, 0.0, 0.0, 0.0]");
+        assert_compact_debug_snapshot!(expected_bounding_box, @"[inf, inf, -inf, -inf]");
         current_offset += BOUNDING_BOX_SIZE_IN_BYTES;
         let lines_offset: u32 = u32::from_ne_bytes(
             writer[current_offset..current_offset + mem::size_of::<u32>()]
@@ -718,85 +1092,126 @@ mod tests {
         // Now there should be the first multi points from the offset 0 to the offset line
         let points_bytes = &writer[current_offset..current_offset + lines_offset as usize];
         let points_f64: &[f64] = cast_slice(points_bytes);
-        assert_compact_debug_snapshot!(points_f64, @"[0.0, 0.0, 0.0, 0.0]");
+        assert_compact_debug_snapshot!(points_f64, @"[inf, inf, -inf, -inf]");
         let points = unsafe { ZultiPoints::from_bytes(points_bytes) };
-        assert_compact_debug_snapshot!(points, @"ZultiPoints { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 0.0, y: 0.0 } }, points: [] }");
+        assert_compact_debug_snapshot!(points, @"ZultiPoints { bounding_box: BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }, points: [] }");
         assert!(points.is_empty());
 
         // Now there should be the first multi lines at the offset line to the offset polygon
         let lines_bytes = &writer
             [current_offset + lines_offset as usize..current_offset + polygon_offset as usize];
-        assert_compact_debug_snapshot!(lines_bytes, @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]");
         let lines = unsafe { ZultiLines::from_bytes(lines_bytes) };
-        assert_compact_debug_snapshot!(lines, @"ZultiLines { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 0.0, y: 0.0 } }, zines: [] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::MultiLines(lines).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 240, 127, 0, 0, 0, 0, 0, 0, 240, 127, 0, 0, 0, 0, 0, 0, 240, 255, 0, 0, 0, 0, 0, 0, 240, 255, 0, 0, 0, 0, 0, 0, 0, 0]");
+        assert_compact_debug_snapshot!(lines, @"ZultiLines { bounding_box: BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }, zines: [] }");
         assert!(lines.is_empty());
 
         // Now there should be the first multi lines at the offset line to the offset polygon
         let polygons_bytes = &writer[current_offset + polygon_offset as usize..];
-        assert_compact_debug_snapshot!(polygons_bytes, @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]");
         let polygons = unsafe { ZultiPolygons::from_bytes(polygons_bytes) };
-        assert_compact_debug_snapshot!(polygons, @"ZultiPolygons { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 0.0, y: 0.0 } }, zolygons: [] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::MultiPolygon(polygons).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 240, 127, 0, 0, 0, 0, 0, 0, 240, 127, 0, 0, 0, 0, 0, 0, 240, 255, 0, 0, 0, 0, 0, 0, 240, 255, 0, 0, 0, 0, 0, 0, 0, 0]");
+        assert_compact_debug_snapshot!(polygons, @"ZultiPolygons { bounding_box: BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }, zolygons: [] }");
         assert!(polygons.is_empty());
 
         // Try to parse the whole collection
         let zollection = unsafe { Zollection::from_bytes(&writer) };
-        assert_compact_debug_snapshot!(zollection.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 0.0, y: 0.0 } }");
+        assert_compact_debug_snapshot!(zollection.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }");
         assert_debug_snapshot!(zollection, @r"
-        Zollection {
-            bounding_box: BoundingBox {
-                bottom_left: Coord {
-                    x: 0.0,
-                    y: 0.0,
-                },
-                top_right: Coord {
-                    x: 0.0,
-                    y: 0.0,
-                },
-            },
-            points: ZultiPoints {
+            Zollection {
                 bounding_box: BoundingBox {
                     bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                        lng: inf,
+                        lat: inf,
                     },
                     top_right: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                        lng: -inf,
+                        lat: -inf,
                     },
                 },
-                points: [],
-            },
-            lines: ZultiLines {
-                bounding_box: BoundingBox {
-                    bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
-                    },
-                    top_right: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                points: ZultiPoints {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: inf,
+                            lat: inf,
+                        },
+                        top_right: Coord {
+                            lng: -inf,
+                            lat: -inf,
+                        },
                     },
+                    points: [],
                 },
-                zines: [],
-            },
-            polygons: ZultiPolygons {
-                bounding_box: BoundingBox {
-                    bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                lines: ZultiLines {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: inf,
+                            lat: inf,
+                        },
+                        top_right: Coord {
+                            lng: -inf,
+                            lat: -inf,
+                        },
                     },
-                    top_right: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                    zines: [],
+                },
+                polygons: ZultiPolygons {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: inf,
+                            lat: inf,
+                        },
+                        top_right: Coord {
+                            lng: -inf,
+                            lat: -inf,
+                        },
                     },
+                    zolygons: [],
                 },
-                zolygons: [],
-            },
-        }
+                properties: None,
+            }
         ");
         assert!(zollection.is_empty());
     }
 
+    #[test]
+    fn write_from_geometries() {
+        let square: Polygon = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)];
+        let geometries: Vec<Geometry> = vec![
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                Point::new(9.0, 9.0).into(),
+                geo::Line::new((0.0, 5.0), (5.0, 5.0)).into(),
+            ])),
+            square.clone().into(),
+            line_string![(x: 1., y: 1.), (x: 2., y: 3.)].into(),
+            MultiPoint::from(vec![(1.0, 2.0), (3.0, 4.0)]).into(),
+            geo::Rect::new((2.0, 2.0), (3.0, 3.0)).into(),
+        ];
+        let mut buffer = Vec::new();
+        Zollection::write_from_geometries(&mut buffer, &geometries).unwrap();
+
+        let zollection = Zollection::try_from_bytes(&buffer).unwrap();
+
+        // The lines and polygons are written as if they were moved to a multi lines and a multi polygons
+        let collection = GeometryCollection::new_from(geometries.clone());
+        let (_, lines, polygons) = flatten_geometry_collection(&collection);
+        let mut expected = Vec::new();
+        ZultiLines::write_from_geometry(&mut expected, &lines).unwrap();
+        assert_eq!(zollection.lines().as_bytes(), expected);
+        let mut expected = Vec::new();
+        ZultiPolygons::write_from_geometry(&mut expected, &polygons).unwrap();
+        assert_eq!(zollection.polygons().as_bytes(), expected);
+
+        let geometries: Vec<Geometry> = zollection.into_geometries().collect();
+        assert_compact_debug_snapshot!(geometries, @"[POINT(1.0 2.0), POINT(3.0 4.0), POINT(9.0 9.0), LINESTRING(1.0 1.0,2.0 3.0), LINESTRING(0.0 5.0,5.0 5.0), POLYGON((0.0 0.0,1.0 0.0,1.0 1.0,0.0 0.0)), POLYGON((3.0 2.0,3.0 3.0,2.0 3.0,2.0 2.0,3.0 2.0))]");
+
+        let mut buffer = Vec::new();
+        Zollection::write_from_geometries(&mut buffer, []).unwrap();
+        let zollection = Zollection::try_from_bytes(&buffer).unwrap();
+        assert!(zollection.is_empty());
+        assert_eq!(zollection.into_geometries().count(), 0);
+    }
+
     #[test]
     fn nested_stuff() {
         let collection = GeometryCollection::new_from(vec![geo::Geometry::GeometryCollection(
@@ -818,72 +1233,111 @@ mod tests {
 
         // Try to parse the whole collection
         let zollection = unsafe { Zollection::from_bytes(&writer) };
-        assert_compact_debug_snapshot!(zollection.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 1.0, y: 2.0 }, top_right: Coord { x: 3.0, y: 4.0 } }");
+        assert_compact_debug_snapshot!(zollection.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 1.0, lat: 2.0 }, top_right: Coord { lng: 3.0, lat: 4.0 } }");
         assert_debug_snapshot!(zollection, @r"
-        Zollection {
-            bounding_box: BoundingBox {
-                bottom_left: Coord {
-                    x: 1.0,
-                    y: 2.0,
-                },
-                top_right: Coord {
-                    x: 3.0,
-                    y: 4.0,
-                },
-            },
-            points: ZultiPoints {
+            Zollection {
                 bounding_box: BoundingBox {
                     bottom_left: Coord {
-                        x: 1.0,
-                        y: 2.0,
+                        lng: 1.0,
+                        lat: 2.0,
                     },
                     top_right: Coord {
-                        x: 3.0,
-                        y: 4.0,
-                    },
-                },
-                points: [
-                    Zoint {
                         lng: 3.0,
                         lat: 4.0,
                     },
-                    Zoint {
-                        lng: 1.0,
-                        lat: 2.0,
-                    },
-                ],
-            },
-            lines: ZultiLines {
-                bounding_box: BoundingBox {
-                    bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
-                    },
-                    top_right: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                },
+                points: ZultiPoints {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: 1.0,
+                            lat: 2.0,
+                        },
+                        top_right: Coord {
+                            lng: 3.0,
+                            lat: 4.0,
+                        },
                     },
+                    points: [
+                        Zoint {
+                            lng: 3.0,
+                            lat: 4.0,
+                        },
+                        Zoint {
+                            lng: 1.0,
+                            lat: 2.0,
+                        },
+                    ],
                 },
-                zines: [],
-            },
-            polygons: ZultiPolygons {
-                bounding_box: BoundingBox {
-                    bottom_left: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                lines: ZultiLines {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: inf,
+                            lat: inf,
+                        },
+                        top_right: Coord {
+                            lng: -inf,
+                            lat: -inf,
+                        },
                     },
-                    top_right: Coord {
-                        x: 0.0,
-                        y: 0.0,
+                    zines: [],
+                },
+                polygons: ZultiPolygons {
+                    bounding_box: BoundingBox {
+                        bottom_left: Coord {
+                            lng: inf,
+                            lat: inf,
+                        },
+                        top_right: Coord {
+                            lng: -inf,
+                            lat: -inf,
+                        },
                     },
+                    zolygons: [],
                 },
-                zolygons: [],
-            },
-        }
+                properties: None,
+            }
         ");
         assert!(!zollection.is_empty());
     }
 
+    #[test]
+    fn flattening_order() {
+        let point = |i: f64| Geometry::Point(Point::new(i, i));
+        let collection = GeometryCollection::new_from(vec![
+            point(0.),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+                point(5.),
+                Geometry::GeometryCollection(GeometryCollection::new_from(vec![point(6.)])),
+            ])),
+            geo::MultiPoint::from(vec![(1., 1.), (2., 2.)]).into(),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![point(4.)])),
+            point(3.),
+            line_string![(x: 0., y: 0.), (x: 1., y: 1.)].into(),
+            geo::Line::new((2., 2.), (3., 3.)).into(),
+            polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)].into(),
+            geo::Rect::new((2., 2.), (3., 3.)).into(),
+        ]);
+        let mut writer = Vec::new();
+        Zollection::write_from_geometry(&mut writer, &collection).unwrap();
+        let zollection = Zollection::try_from_bytes(&writer).unwrap();
+
+        let points: Vec<_> = zollection
+            .points()
+            .points()
+            .map(|point| point.lng())
+            .collect();
+        assert_compact_debug_snapshot!(points, @"[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]");
+        let lines = zollection.lines();
+        let lines: Vec<_> = lines.lines().map(|line| line.coords()[0].lng()).collect();
+        assert_compact_debug_snapshot!(lines, @"[0.0, 2.0]");
+        let polygons = zollection.polygons();
+        let polygons: Vec<_> = polygons
+            .polygons()
+            .map(|polygon| polygon.coords().len())
+            .collect();
+        assert_compact_debug_snapshot!(polygons, @"[4, 5]");
+    }
+
     #[test]
     fn test_zollection() {
         let point = Point::new(0.5, 0.5);
@@ -909,7 +1363,7 @@ mod tests {
         )
         .unwrap();
         let zolygon = unsafe { Zollection::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zolygon.all_relation(&zolygon), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zolygon.all_relation(&zolygon), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(true), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         Zollection::write_from_geometry(
@@ -918,8 +1372,8 @@ mod tests {
         )
         .unwrap();
         let other = unsafe { Zollection::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(other.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(true), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(true), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(other.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(true), disjoint: Some(false), overlaps: Some(true), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         Zollection::write_from_geometry(
@@ -932,7 +1386,7 @@ mod tests {
         )
         .unwrap();
         let other = unsafe { Zollection::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(true), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         Zollection::write_from_geometry(
@@ -946,7 +1400,7 @@ mod tests {
         )
         .unwrap();
         let other = unsafe { Zollection::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(true), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         Zollection::write_from_geometry(
@@ -960,7 +1414,7 @@ mod tests {
         )
         .unwrap();
         let other = unsafe { Zollection::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         Zollection::write_from_geometry(
@@ -973,6 +1427,68 @@ mod tests {
         )
         .unwrap();
         let other = unsafe { Zollection::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(zolygon.all_relation(&other), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn filter_children() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        let line = line_string![(x: 5., y: 5.), (x: 6., y: 7.)];
+        let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            Point::new(-3.0, 2.0).into(),
+            line.clone().into(),
+            square.clone().into(),
+            Point::new(10.0, 2.0).into(),
+        ]));
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &collection).unwrap();
+        let Zerometry::Collection(zollection) = Zerometry::try_from_bytes(&buffer).unwrap() else {
+            unreachable!()
+        };
+
+        // Drop the first point and the polygon
+        let mut filtered = Vec::new();
+        zollection
+            .filter_children(
+                |child, _| match child {
+                    Zerometry::Point(point) => point.lng() > 0.0,
+                    child => child.to_polygon().is_none(),
+                },
+                &mut filtered,
+            )
+            .unwrap();
+        let expected = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            Point::new(10.0, 2.0).into(),
+            line.clone().into(),
+        ]));
+        let mut expected_bytes = Vec::new();
+        Zerometry::write_from_geometry(&mut expected_bytes, &expected).unwrap();
+        assert_eq!(filtered, expected_bytes);
+
+        // The ids are kept
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_ids(&mut buffer, &collection, &[1, 2, 3, 4]).unwrap();
+        let Zerometry::Collection(zollection) = Zerometry::try_from_bytes(&buffer).unwrap() else {
+            unreachable!()
+        };
+        let mut filtered = Vec::new();
+        zollection
+            .filter_children(|_, id| id != Some(2), &mut filtered)
+            .unwrap();
+        let Zerometry::Collection(filtered) = Zerometry::try_from_bytes(&filtered).unwrap() else {
+            unreachable!()
+        };
+        assert_compact_debug_snapshot!(filtered.to_geo(), @"GEOMETRYCOLLECTION(MULTIPOINT(-3.0 2.0,10.0 2.0),MULTILINESTRING EMPTY,MULTIPOLYGON(((0.0 0.0,1.0 0.0,1.0 1.0,0.0 1.0,0.0 0.0))))");
+        assert_compact_debug_snapshot!((filtered.lines().ids(), filtered.polygons().ids()), @"(Some([]), Some([3]))");
+
+        // Nothing retained
+        let mut filtered = Vec::new();
+        zollection
+            .filter_children(|_, _| false, &mut filtered)
+            .unwrap();
+        let header = Zerometry::header_size(&filtered).unwrap();
+        let filtered = Zollection::try_from_bytes(&filtered[header..]).unwrap();
+        assert!(filtered.is_empty());
+        assert!(filtered.bounding_box().is_empty());
     }
 }
@@ -1,13 +1,21 @@
 use core::fmt;
 use std::io::{self, Write};
+use std::mem;
 
 use geo::MultiPolygon;
-use geo_types::{Geometry, Polygon};
+use geo_types::Polygon;
 
 use crate::{
-    BoundingBox, COORD_SIZE_IN_BYTES, COORD_SIZE_IN_FLOATS, Coord, Coords, InputRelation,
-    OutputRelation, RelationBetweenShapes, Segment, Zerometry, Zoint, Zollection, ZultiLines,
-    ZultiPoints, ZultiPolygons, zine::Zine,
+    BoundingBox, BufferPool, COORD_SIZE_IN_BYTES, COORD_SIZE_IN_FLOATS, Coord, Coords,
+    InputRelation, OutputRelation, RelationBetweenShapes, Segment, Zerometry, ZerometryError,
+    Zoint, Zollection, ZultiLines, ZultiPoints, ZultiPolygons,
+    aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    coverage_mask::CoverageMask,
+    error::check_bounding_box_and_coords,
+    subtype::Subtype,
+    topology::{self, Predicate, relation_with_topology},
+    zine::Zine,
 };
 
 /// Equivalent of a [`geo_types::Polygon`].
@@ -22,6 +30,12 @@ use crate::{
 pub struct Zolygon<'a> {
     bounding_box: &'a BoundingBox,
     coords: &'a Coords,
+    coverage_mask: Option<CoverageMask<'a>>,
+    // Set when the convexity was stored in the tag of the zerometry, see `WriteOptions::convexity`
+    convex: Option<bool>,
+    // Set when the polygon was written from a `geo_types::Rect` or `geo_types::Triangle`,
+    // stored in the tag of the zerometry
+    subtype: Subtype,
 }
 
 impl<'a> Zolygon<'a> {
@@ -31,6 +45,9 @@ impl<'a> Zolygon<'a> {
         Self {
             bounding_box,
             coords,
+            coverage_mask: None,
+            convex: None,
+            subtype: Subtype::None,
         }
     }
 
@@ -55,6 +72,24 @@ impl<'a> Zolygon<'a> {
         Self::new(bounding_box, coords)
     }
 
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_bounding_box_and_coords(data)?;
+        let zolygon = unsafe { Self::from_bytes(data) };
+        let coords = zolygon.coords;
+        if !coords.is_empty()
+            && (coords[0].lng().to_bits(), coords[0].lat().to_bits())
+                != (
+                    coords[coords.len() - 1].lng().to_bits(),
+                    coords[coords.len() - 1].lat().to_bits(),
+                )
+        {
+            return Err(ZerometryError::UnclosedRing);
+        }
+        Ok(zolygon)
+    }
+
     /// Convert the specified [`geo_types::Polygon`] to a valid [`Zolygon`] slice of bytes in the input buffer.
     /// If the polygon contains an interior, the information will be lost and ignored during operations.
     pub fn write_from_geometry(
@@ -71,6 +106,51 @@ impl<'a> Zolygon<'a> {
         Ok(())
     }
 
+    pub(crate) fn with_coverage_mask(self, coverage_mask: CoverageMask<'a>) -> Self {
+        Self {
+            coverage_mask: Some(coverage_mask),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_convexity(self, convex: bool) -> Self {
+        Self {
+            convex: Some(convex),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_subtype(self, subtype: Subtype) -> Self {
+        Self { subtype, ..self }
+    }
+
+    pub(crate) fn subtype(&self) -> Subtype {
+        self.subtype
+    }
+
+    #[cfg(test)]
+    pub(crate) fn coverage_mask(&self) -> Option<CoverageMask<'a>> {
+        self.coverage_mask
+    }
+
+    /// Return `true` if the polygon was written with a coverage mask speeding up the point-in-polygon tests,
+    /// see [`crate::WriteOptions::coverage_mask`].
+    #[inline]
+    pub fn has_coverage_mask(&self) -> bool {
+        self.coverage_mask.is_some()
+    }
+
+    /// Return the bytes the polygon was read from, they can be stored and read back with [`Self::from_bytes`].
+    /// The coverage mask is stored in the header of the zerometry and isn't part of them.
+    ///
+    /// # Panics
+    /// If the polygon was created with [`Self::new`] from parts that don't follow each other in memory.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let len = BOUNDING_BOX_SIZE_IN_BYTES + mem::size_of_val(self.coords);
+        contiguous(self.bounding_box, self.coords, len, 0)
+    }
+
     /// Return the internal bounding box
     #[inline]
     pub fn bounding_box(&self) -> &'a BoundingBox {
@@ -91,12 +171,200 @@ impl<'a> Zolygon<'a> {
             .map(|coords| unsafe { Segment::from_slice(coords) })
     }
 
+    /// Return the exterior ring of the polygon as a [`Zine`].
+    /// Nothing is copied, the line shares the bounding box and coords of the polygon.
+    #[inline]
+    pub fn exterior_line(&self) -> Zine<'a> {
+        Zine::new(self.bounding_box, self.coords)
+    }
+
     /// Return `true` if the polygon doesn't contain any points
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.coords.len() == 0
     }
 
+    /// Return `true` if the segment touches the polygon, either by crossing its boundary or by lying inside of it.
+    pub fn intersects_segment(&self, segment: &Segment) -> bool {
+        if self.is_empty() || self.bounding_box.disjoint_from_segment(segment) {
+            return false;
+        }
+        self.segments().any(|edge| edge.intersects(segment))
+            || self
+                .relation(segment.start(), InputRelation::any())
+                .any_relation()
+    }
+
+    /// Return the first point of the segment, walking from its start to its end, that lies in the polygon.
+    /// It's the start of the segment if it's inside the polygon, or the closest intersection with the boundary otherwise.
+    pub fn first_intersection(&self, segment: &Segment) -> Option<geo_types::Coord<f64>> {
+        if self.is_empty() || self.bounding_box.disjoint_from_segment(segment) {
+            return None;
+        }
+        let start = segment.start().to_geo();
+        if self
+            .relation(segment.start(), InputRelation::any())
+            .any_relation()
+        {
+            return Some(start);
+        }
+
+        let line = geo_types::Line::new(start, segment.end().to_geo());
+        let squared_distance = |coord: geo_types::Coord<f64>| {
+            (coord.x - start.x).powi(2) + (coord.y - start.y).powi(2)
+        };
+        self.segments()
+            .filter_map(|edge| {
+                let edge = geo_types::Line::new(edge.start().to_geo(), edge.end().to_geo());
+                match geo::line_intersection::line_intersection(line, edge)? {
+                    geo::LineIntersection::SinglePoint { intersection, .. } => Some(intersection),
+                    geo::LineIntersection::Collinear { intersection } => Some(
+                        if squared_distance(intersection.start)
+                            <= squared_distance(intersection.end)
+                        {
+                            intersection.start
+                        } else {
+                            intersection.end
+                        },
+                    ),
+                }
+            })
+            .min_by(|a, b| squared_distance(*a).total_cmp(&squared_distance(*b)))
+    }
+
+    /// Return `true` if the polygon is convex.
+    /// It's read from the tag when it was stored with [`crate::WriteOptions::convexity`], otherwise it's computed
+    /// in a single pass by checking that all the consecutive edges turn in the same direction.
+    /// Empty and flat polygons are never convex.
+    pub fn is_convex(&self) -> bool {
+        self.convex.unwrap_or_else(|| self.compute_convexity())
+    }
+
+    pub(crate) fn compute_convexity(&self) -> bool {
+        let mut edges = self.edges();
+        let Some(first) = edges.next() else {
+            return false;
+        };
+
+        let mut orientation = 0.0;
+        // A self-intersecting polygon like a star can always turn in the same direction, but it'll
+        // change of horizontal direction more than twice.
+        let mut direction_changes = 0;
+        let mut previous = first;
+        for edge in edges.chain(std::iter::once(first)) {
+            let cross = previous.0 * edge.1 - previous.1 * edge.0;
+            if cross == 0.0 {
+                // The polygon goes back on itself
+                if previous.0 * edge.0 + previous.1 * edge.1 < 0.0 {
+                    return false;
+                }
+            } else {
+                if cross.signum() == -orientation {
+                    return false;
+                }
+                orientation = cross.signum();
+            }
+            if previous.0 != 0.0 && edge.0 != 0.0 && previous.0.signum() != edge.0.signum() {
+                direction_changes += 1;
+            }
+            previous = edge;
+        }
+
+        orientation != 0.0 && direction_changes <= 2
+    }
+
+    /// Return the winding number of the ring around the coord: the number of times the ring turns around it,
+    /// positive when the ring is counter-clockwise and negative otherwise. It's zero when the coord is outside.
+    ///
+    /// Summing the contributions of the shell and the holes of a polygon lets the caller pick its fill rule:
+    /// a coord is inside with the non-zero rule if the sum isn't zero and with the even-odd rule if it's odd.
+    ///
+    /// ```
+    /// use zerometry::{Coord, zolygon};
+    ///
+    /// let shell = zolygon![(0., 0.), (4., 0.), (4., 4.), (0., 4.)];
+    /// // A hole is clockwise
+    /// let hole = zolygon![(1., 1.), (1., 3.), (3., 3.), (3., 1.)];
+    /// let center = unsafe { Coord::from_slice(&[2.0, 2.0]) };
+    /// assert_eq!(shell.ring_winding_contribution(center), 1);
+    /// assert_eq!(shell.ring_winding_contribution(center) + hole.ring_winding_contribution(center), 0);
+    /// ```
+    pub fn ring_winding_contribution(&self, coord: &Coord) -> i32 {
+        if !self.bounding_box.contains_coord(coord) {
+            return 0;
+        }
+        let mut winding = 0;
+        for segment in self.segments() {
+            let (start, end) = (segment.start(), segment.end());
+            // Positive if the coord is on the left of the segment
+            let side = (end.lng() - start.lng()) * (coord.lat() - start.lat())
+                - (coord.lng() - start.lng()) * (end.lat() - start.lat());
+            if start.lat() <= coord.lat() {
+                if end.lat() > coord.lat() && side > 0.0 {
+                    winding += 1;
+                }
+            } else if end.lat() <= coord.lat() && side < 0.0 {
+                winding -= 1;
+            }
+        }
+        winding
+    }
+
+    /// Return the non-null edges of the polygon as `(dx, dy)` vectors.
+    fn edges(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.segments()
+            .map(|segment| {
+                (
+                    segment.end().lng() - segment.start().lng(),
+                    segment.end().lat() - segment.start().lat(),
+                )
+            })
+            .filter(|&edge| edge != (0.0, 0.0))
+    }
+
+    /// Separating axis test, return `true` if one of the edges of the polygon separates it from the other polygon.
+    /// Only valid if the polygon is convex.
+    fn has_separating_axis_with(&self, other: &Zolygon) -> bool {
+        self.segments().any(|segment| {
+            let origin = segment.start();
+            // The normal of the edge
+            let axis = (
+                origin.lat() - segment.end().lat(),
+                segment.end().lng() - origin.lng(),
+            );
+            let project = |coord: &Coord| {
+                (coord.lng() - origin.lng()) * axis.0 + (coord.lat() - origin.lat()) * axis.1
+            };
+            let (min, max) = min_max(self.coords().iter().map(project));
+            let (other_min, other_max) = min_max(other.coords().iter().map(project));
+            max < other_min || other_max < min
+        })
+    }
+
+    /// Return `true` if all the coords of the other polygon are strictly inside the polygon.
+    /// Only valid if the polygon is convex.
+    fn strictly_contains_all_coords_of(&self, other: &Zolygon) -> bool {
+        let mut orientation = 0.0;
+        for segment in self.segments() {
+            let edge = (
+                segment.end().lng() - segment.start().lng(),
+                segment.end().lat() - segment.start().lat(),
+            );
+            if edge == (0.0, 0.0) {
+                continue;
+            }
+            for coord in other.coords().iter() {
+                let cross = edge.0 * (coord.lat() - segment.start().lat())
+                    - edge.1 * (coord.lng() - segment.start().lng());
+                if cross == 0.0 || cross.signum() == -orientation {
+                    return false;
+                }
+                orientation = cross.signum();
+            }
+        }
+        true
+    }
+
     /// Convert the [`Zolygon`] back to a [`geo_types::Polygon`].
     pub fn to_geo(&self) -> geo_types::Polygon<f64> {
         geo_types::Polygon::new(
@@ -109,6 +377,12 @@ impl<'a> Zolygon<'a> {
     }
 }
 
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    })
+}
+
 impl<'a> fmt::Debug for Zolygon<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Zolygon")
@@ -120,9 +394,27 @@ impl<'a> fmt::Debug for Zolygon<'a> {
 
 impl<'a> RelationBetweenShapes<Coord> for Zolygon<'a> {
     fn relation(&self, other: &Coord, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            let exact = self.relation(other, topology::exact_relation(relation));
+            // A point only touches a polygon from its boundary and can't be both inside and outside of it
+            let on_ring = topology::on_ring(self, other.to_geo(), relation.tolerance);
+            let close = exact.disjoint == Some(true) && on_ring;
+            return topology::fill_topology(exact, relation, close, |predicate| match predicate {
+                Predicate::Touches => on_ring,
+                Predicate::Covers => on_ring || self.ring_winding_contribution(other) != 0,
+                Predicate::Crosses | Predicate::CoveredBy => false,
+            });
+        }
         if self.is_empty() || !self.bounding_box.contains_coord(other) {
             return relation.to_false().make_disjoint_if_set();
         }
+        if let Some(coverage_mask) = &self.coverage_mask {
+            match coverage_mask.classify(self.bounding_box, other) {
+                Some(true) => return relation.to_false().make_strict_contains_if_set(),
+                Some(false) => return relation.to_false().make_disjoint_if_set(),
+                None => (),
+            }
+        }
 
         // To find if a point is in a polygon we draw a ray from outside of the polygon to the point
         // and count the number of times the ray intersects with the polygon. If it's even it means
@@ -159,6 +451,9 @@ impl<'a> RelationBetweenShapes<Zoint<'a>> for Zolygon<'a> {
 // We don't need to know if everything is contained, only one point is enough for us.
 impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for Zolygon<'a> {
     fn relation(&self, other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         // If the bounding boxes are disjoint, the relation must be disjoint, we can early return.
@@ -208,6 +503,9 @@ impl<'a> RelationBetweenShapes<ZultiLines<'a>> for Zolygon<'a> {
 
 impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zolygon<'a> {
     fn relation(&self, other: &Zolygon<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let output = relation.to_false();
 
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
@@ -215,6 +513,20 @@ impl<'a> RelationBetweenShapes<Zolygon<'a>> for Zolygon<'a> {
             return output.make_disjoint_if_set();
         }
 
+        // When both polygons are convex the separating axis theorem let us answer by projecting the points
+        // on the normal of each edge, which is much cheaper than intersecting all the segments together.
+        if self.is_convex() && other.is_convex() {
+            if self.has_separating_axis_with(other) || other.has_separating_axis_with(self) {
+                return output.make_disjoint_if_set();
+            } else if self.strictly_contains_all_coords_of(other) {
+                return output.make_strict_contains_if_set();
+            } else if other.strictly_contains_all_coords_of(self) {
+                return output.make_strict_contained_if_set();
+            } else {
+                return output.make_intersect_if_set();
+            }
+        }
+
         // To know if two polygons intersect we check if any of the segments of the first polygon intersect with the second polygon.
         // That's O(n^2) but if you know a better algorithm please let me know.
         for segment in self.segments() {
@@ -267,19 +579,25 @@ impl<'a> RelationBetweenShapes<Zerometry<'a>> for Zolygon<'a> {
 
 impl<'a> RelationBetweenShapes<Polygon<f64>> for Zolygon<'a> {
     fn relation(&self, other: &Polygon<f64>, relation: InputRelation) -> OutputRelation {
-        let mut buffer = Vec::new();
-        Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(other.clone())).unwrap();
-        let other = unsafe { Zerometry::from_bytes(&buffer).unwrap() };
-        self.relation(&other, relation)
+        BufferPool::with_thread_local_buffer(|buffer| {
+            if Zolygon::write_from_geometry(buffer, other).is_err() {
+                return OutputRelation::default();
+            }
+            let other = unsafe { Zolygon::from_bytes(buffer) };
+            self.relation(&other, relation)
+        })
     }
 }
 
 impl<'a> RelationBetweenShapes<MultiPolygon<f64>> for Zolygon<'a> {
     fn relation(&self, other: &MultiPolygon<f64>, relation: InputRelation) -> OutputRelation {
-        let mut buffer = Vec::new();
-        ZultiPolygons::write_from_geometry(&mut buffer, other).unwrap();
-        let other = unsafe { ZultiPolygons::from_bytes(&buffer) };
-        self.relation(&other, relation)
+        BufferPool::with_thread_local_buffer(|buffer| {
+            if ZultiPolygons::write_from_geometry(buffer, other).is_err() {
+                return OutputRelation::default();
+            }
+            let other = unsafe { ZultiPolygons::from_bytes(buffer) };
+            self.relation(&other, relation)
+        })
     }
 }
 
@@ -352,40 +670,40 @@ mod tests {
         ");
         let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
         insta::assert_debug_snapshot!(zolygon.bounding_box(), @r"
-        BoundingBox {
-            bottom_left: Coord {
-                x: -10.0,
-                y: -10.0,
-            },
-            top_right: Coord {
-                x: 10.0,
-                y: 10.0,
-            },
-        }
+            BoundingBox {
+                bottom_left: Coord {
+                    lng: -10.0,
+                    lat: -10.0,
+                },
+                top_right: Coord {
+                    lng: 10.0,
+                    lat: 10.0,
+                },
+            }
         ");
         insta::assert_debug_snapshot!(zolygon.coords(), @r"
-        [
-            Coord {
-                x: -10.0,
-                y: 0.0,
-            },
-            Coord {
-                x: 10.0,
-                y: -10.0,
-            },
-            Coord {
-                x: 10.0,
-                y: 10.0,
-            },
-            Coord {
-                x: 0.0,
-                y: 10.0,
-            },
-            Coord {
-                x: -10.0,
-                y: 0.0,
-            },
-        ]
+            [
+                Coord {
+                    lng: -10.0,
+                    lat: 0.0,
+                },
+                Coord {
+                    lng: 10.0,
+                    lat: -10.0,
+                },
+                Coord {
+                    lng: 10.0,
+                    lat: 10.0,
+                },
+                Coord {
+                    lng: 0.0,
+                    lat: 10.0,
+                },
+                Coord {
+                    lng: -10.0,
+                    lat: 0.0,
+                },
+            ]
         ");
     }
 
@@ -400,24 +718,24 @@ mod tests {
         let input: &[f64] = cast_slice(&buffer);
         assert_debug_snapshot!(input, @r"
         [
-            0.0,
-            0.0,
-            0.0,
-            0.0,
+            inf,
+            inf,
+            -inf,
+            -inf,
         ]
         ");
         let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
         insta::assert_debug_snapshot!(zolygon.bounding_box(), @r"
-        BoundingBox {
-            bottom_left: Coord {
-                x: 0.0,
-                y: 0.0,
-            },
-            top_right: Coord {
-                x: 0.0,
-                y: 0.0,
-            },
-        }
+            BoundingBox {
+                bottom_left: Coord {
+                    lng: inf,
+                    lat: inf,
+                },
+                top_right: Coord {
+                    lng: -inf,
+                    lat: -inf,
+                },
+            }
         ");
         insta::assert_debug_snapshot!(zolygon.coords(), @"[]");
     }
@@ -450,11 +768,11 @@ mod tests {
         let point_outside = unsafe { Zoint::from_bytes(&buffer[zoint_outside_bytes..]) };
         assert_compact_debug_snapshot!(
             zolygon.all_relation(&point_inside),
-            @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             zolygon.all_relation(&point_outside),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
@@ -493,11 +811,11 @@ mod tests {
         let second_zolygon = unsafe { Zolygon::from_bytes(&buffer[first..second]) };
         assert_compact_debug_snapshot!(
             first_zolygon.all_relation(&second_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             second_zolygon.all_relation(&first_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
@@ -536,11 +854,11 @@ mod tests {
         let second_zolygon = unsafe { Zolygon::from_bytes(&buffer[first..second]) };
         assert_compact_debug_snapshot!(
             first_zolygon.all_relation(&second_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             second_zolygon.all_relation(&first_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
@@ -579,11 +897,11 @@ mod tests {
         let second_zolygon = unsafe { Zolygon::from_bytes(&buffer[first..second]) };
         assert_compact_debug_snapshot!(
             first_zolygon.all_relation(&second_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             second_zolygon.all_relation(&first_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
@@ -622,11 +940,11 @@ mod tests {
         let second_zolygon = unsafe { Zolygon::from_bytes(&buffer[first..second]) };
         assert_compact_debug_snapshot!(
             first_zolygon.all_relation(&second_zolygon),
-            @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             second_zolygon.all_relation(&first_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
@@ -666,11 +984,11 @@ mod tests {
         let second_zolygon = unsafe { Zolygon::from_bytes(&buffer[first..second]) };
         assert_compact_debug_snapshot!(
             first_zolygon.all_relation(&second_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             second_zolygon.all_relation(&first_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
@@ -707,14 +1025,143 @@ mod tests {
         let second_zolygon = unsafe { Zolygon::from_bytes(&buffer[first..second]) };
         assert_compact_debug_snapshot!(
             first_zolygon.all_relation(&second_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
         assert_compact_debug_snapshot!(
             second_zolygon.all_relation(&first_zolygon),
-            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }"
+            @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }"
         );
     }
 
+    #[test]
+    fn test_zolygon_exterior_line() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.)];
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, &polygon).unwrap();
+        let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
+        let zine = zolygon.exterior_line();
+        assert_eq!(zine.coords().len(), 4);
+        assert!(std::ptr::eq(zine.coords(), zolygon.coords()));
+        assert_compact_debug_snapshot!(zine.to_geo(), @"LINESTRING(0.0 0.0,1.0 0.0,1.0 1.0,0.0 0.0)");
+    }
+
+    #[test]
+    fn test_zolygon_segment_intersection() {
+        let polygon = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(&mut buffer, &polygon).unwrap();
+        let zolygon = unsafe { Zolygon::from_bytes(&buffer) };
+
+        let crossing = unsafe { Segment::from_slice(&[-2.0, 1.0, 6.0, 3.0]) };
+        assert!(zolygon.intersects_segment(&crossing));
+        assert_compact_debug_snapshot!(zolygon.first_intersection(&crossing), @"Some(COORD(0.0 1.5))");
+        let reversed = unsafe { Segment::from_slice(&[6.0, 3.0, -2.0, 1.0]) };
+        assert_compact_debug_snapshot!(zolygon.first_intersection(&reversed), @"Some(COORD(4.0 2.5))");
+
+        let inside = unsafe { Segment::from_slice(&[1.0, 1.0, 2.0, 3.0]) };
+        assert!(zolygon.intersects_segment(&inside));
+        assert_compact_debug_snapshot!(zolygon.first_intersection(&inside), @"Some(COORD(1.0 1.0))");
+
+        let along_edge = unsafe { Segment::from_slice(&[6.0, 0.0, 2.0, 0.0]) };
+        assert!(zolygon.intersects_segment(&along_edge));
+        assert_compact_debug_snapshot!(zolygon.first_intersection(&along_edge), @"Some(COORD(4.0 0.0))");
+
+        // The bounding boxes intersect but the segment passes next to the polygon
+        let outside = unsafe { Segment::from_slice(&[3.0, 6.0, 6.0, 3.5]) };
+        assert!(!zolygon.intersects_segment(&outside));
+        assert_compact_debug_snapshot!(zolygon.first_intersection(&outside), @"None");
+        let far = unsafe { Segment::from_slice(&[10.0, 10.0, 12.0, 12.0]) };
+        assert!(!zolygon.intersects_segment(&far));
+    }
+
+    #[test]
+    fn test_zolygon_is_convex() {
+        let is_convex = |polygon: Polygon<f64>| {
+            let mut buffer = Vec::new();
+            Zolygon::write_from_geometry(&mut buffer, &polygon).unwrap();
+            unsafe { Zolygon::from_bytes(&buffer) }.is_convex()
+        };
+
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+        assert!(is_convex(square));
+        // Clockwise, with a duplicated and a collinear point
+        let triangle = polygon![(x: 0., y: 0.), (x: 0., y: 2.), (x: 0., y: 2.), (x: 1., y: 1.), (x: 2., y: 0.)];
+        assert!(is_convex(triangle));
+        let l_shape = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 1.), (x: 1., y: 1.), (x: 1., y: 2.), (x: 0., y: 2.)];
+        assert!(!is_convex(l_shape));
+        // The pentagram always turns in the same direction but intersects itself
+        let star = polygon![(x: 0., y: 3.), (x: 2., y: -3.), (x: -3., y: 1.), (x: 3., y: 1.), (x: -2., y: -3.)];
+        assert!(!is_convex(star));
+        let flat = polygon![(x: 0., y: 0.), (x: 1., y: 1.), (x: 2., y: 2.)];
+        assert!(!is_convex(flat));
+        assert!(!is_convex(Polygon::new(
+            LineString::new(Vec::new()),
+            Vec::new()
+        )));
+    }
+
+    #[test]
+    fn test_ring_winding_contribution() {
+        let zolygon = |polygon: Polygon<f64>| {
+            let mut buffer = Vec::new();
+            Zolygon::write_from_geometry(&mut buffer, &polygon).unwrap();
+            buffer
+        };
+        let winding = |buffer: &[u8], x: f64, y: f64| {
+            let coord = [x, y];
+            unsafe { Zolygon::from_bytes(buffer) }
+                .ring_winding_contribution(unsafe { Coord::from_slice(&coord) })
+        };
+
+        let counter_clockwise =
+            zolygon(polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 0., y: 2.)]);
+        let clockwise =
+            zolygon(polygon![(x: 0., y: 0.), (x: 0., y: 2.), (x: 2., y: 2.), (x: 2., y: 0.)]);
+        // The pentagram turns twice around its center
+        let star = zolygon(
+            polygon![(x: 0., y: 3.), (x: 2., y: -3.), (x: -3., y: 1.), (x: 3., y: 1.), (x: -2., y: -3.)],
+        );
+        let windings = [
+            winding(&counter_clockwise, 1., 1.),
+            winding(&clockwise, 1., 1.),
+            winding(&clockwise, 3., 1.),
+            winding(&star, 0., 0.),
+            winding(&star, 0., 2.),
+            winding(
+                &zolygon(Polygon::new(LineString::new(Vec::new()), Vec::new())),
+                0.,
+                0.,
+            ),
+        ];
+        assert_compact_debug_snapshot!(windings, @"[1, -1, 0, -2, -1, 0]");
+    }
+
+    #[test]
+    fn test_convex_zolygons_relation() {
+        let relation = |left: Polygon<f64>, right: Polygon<f64>| {
+            let mut buffer = Vec::new();
+            Zolygon::write_from_geometry(&mut buffer, &left).unwrap();
+            let split = buffer.len();
+            Zolygon::write_from_geometry(&mut buffer, &right).unwrap();
+            let left = unsafe { Zolygon::from_bytes(&buffer[..split]) };
+            let right = unsafe { Zolygon::from_bytes(&buffer[split..]) };
+            assert!(left.is_convex() && right.is_convex());
+            left.all_relation(&right)
+        };
+
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        // The bounding boxes overlap but only the separating axis of the diagonal can tell they're disjoint
+        let corner = polygon![(x: 3.5, y: 5.), (x: 5., y: 3.5), (x: 5., y: 5.)];
+        assert_compact_debug_snapshot!(relation(square.clone(), corner), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let inside = polygon![(x: 1., y: 1.), (x: 3., y: 1.), (x: 2., y: 3.)];
+        assert_compact_debug_snapshot!(relation(square.clone(), inside.clone()), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(relation(inside, square.clone()), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let touching = polygon![(x: 4., y: 1.), (x: 6., y: 1.), (x: 6., y: 3.)];
+        assert_compact_debug_snapshot!(relation(square.clone(), touching), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        let overlapping = polygon![(x: 3., y: 3.), (x: 6., y: 3.), (x: 6., y: 6.)];
+        assert_compact_debug_snapshot!(relation(square, overlapping), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
     #[test]
     fn test_multi_points_and_polygon() {
         let polygon = polygon![
@@ -745,42 +1192,42 @@ mod tests {
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_strict_inside).unwrap();
         let mp_strict_inside = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_strict_inside.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(mp_strict_inside.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_strict_inside_2).unwrap();
         let mp_strict_inside_2 = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_strict_inside_2.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(mp_strict_inside_2.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_strict_outside).unwrap();
         let mp_strict_outside = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_strict_outside.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
+        assert_compact_debug_snapshot!(mp_strict_outside.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_strict_outside_2).unwrap();
         let mp_strict_outside_2 = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_strict_outside_2.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
+        assert_compact_debug_snapshot!(mp_strict_outside_2.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_inside).unwrap();
         let mp_inside = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_inside.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(mp_inside.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_inside2).unwrap();
         let mp_inside2 = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_inside2.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(mp_inside2.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_inside4).unwrap();
         let mp_inside4 = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_inside4.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(mp_inside4.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
 
         let mut buf = Vec::new();
         ZultiPoints::write_from_geometry(&mut buf, &mp_inside3).unwrap();
         let mp_inside3 = unsafe { ZultiPoints::from_bytes(&buf) };
-        assert_compact_debug_snapshot!(mp_inside3.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(mp_inside3.all_relation(&zolygon), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
     }
 
     // Prop test ensuring we can round trip from a polygon to a zolygon and back to a polygon
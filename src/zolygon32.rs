@@ -0,0 +1,232 @@
+use core::fmt;
+use std::io::{self, Write};
+use std::mem;
+
+use bytemuck::cast_slice;
+use geo_types::{Polygon, Rect};
+
+use crate::{
+    ZerometryError,
+    aligned_bytes::contiguous,
+    bounding_box::{EMPTY_BOUNDING_BOX, is_valid_bounding_box},
+    error::{check_aligned, split_checked},
+    zoint32::COORD32_SIZE_IN_BYTES,
+};
+
+/// The number of bytes used by a bounding box stored as four `f32`.
+const BOUNDING_BOX32_SIZE_IN_BYTES: usize = 2 * COORD32_SIZE_IN_BYTES;
+
+/// Equivalent of a [`geo_types::Polygon`] storing its coordinates as `f32` instead of `f64`, it takes half the
+/// space of a [`crate::Zolygon`], see [`crate::Zoint32`] for the precision.
+///
+/// Its layout is the same as a [`crate::Zolygon`]: the bounding box followed by the coordinates of the exterior ring.
+/// The bounding box is computed on the rounded coordinates, which means it always bounds the stored polygon.
+///
+/// ```
+/// use zerometry::Zolygon32;
+/// use geo_types::{coord, polygon};
+///
+/// let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+/// let mut buffer = Vec::new();
+/// Zolygon32::write_from_geometry(&mut buffer, &square).unwrap();
+/// assert_eq!(buffer.len(), 16 + 5 * 8);
+///
+/// let zolygon = Zolygon32::try_from_bytes(&buffer).unwrap();
+/// assert!(zolygon.contains_coord(coord! { x: 0.5, y: 0.5 }));
+/// assert_eq!(zolygon.to_geo(), square);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Zolygon32<'a> {
+    bounding_box: &'a [f32; 4],
+    coords: &'a [[f32; 2]],
+}
+
+impl<'a> Zolygon32<'a> {
+    /// Create a [`Zolygon32`] from its bounding box, stored as `[left, bottom, right, top]`, and coords.
+    /// If the bounding box doesn't properly bound the polygon all the operation will breaks.
+    pub fn new(bounding_box: &'a [f32; 4], coords: &'a [[f32; 2]]) -> Self {
+        Self {
+            bounding_box,
+            coords,
+        }
+    }
+
+    /// # Safety
+    /// The data must be generated from the [`Self::write_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_bytes(data: &'a [u8]) -> Self {
+        let (bounding_box, coords) = data.split_at(BOUNDING_BOX32_SIZE_IN_BYTES);
+        let bounding_box: &[f32] = cast_slice(bounding_box);
+        Self::new(bounding_box.try_into().unwrap(), cast_slice(coords))
+    }
+
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        let (_, coords) = split_checked(data, BOUNDING_BOX32_SIZE_IN_BYTES)?;
+        if coords.len() % COORD32_SIZE_IN_BYTES != 0 {
+            return Err(ZerometryError::TrailingBytes(
+                coords.len() % COORD32_SIZE_IN_BYTES,
+            ));
+        }
+        let zolygon = unsafe { Self::from_bytes(data) };
+        let bounding_box = zolygon.bounding_box.map(f64::from);
+        if !is_valid_bounding_box(bounding_box) {
+            return Err(ZerometryError::InvalidBoundingBox);
+        }
+        if let (Some(first), Some(last)) = (zolygon.coords.first(), zolygon.coords.last())
+            && first.map(f32::to_bits) != last.map(f32::to_bits)
+        {
+            return Err(ZerometryError::UnclosedRing);
+        }
+        Ok(zolygon)
+    }
+
+    /// Convert the specified [`geo_types::Polygon`] to a valid [`Zolygon32`] slice of bytes in the input buffer,
+    /// the coordinates are rounded to the nearest `f32`.
+    /// If the polygon contains an interior, the information will be lost and ignored during operations.
+    pub fn write_from_geometry(
+        writer: &mut impl Write,
+        geometry: &Polygon<f64>,
+    ) -> Result<(), io::Error> {
+        let coords: Vec<[f32; 2]> = geometry
+            .exterior()
+            .coords()
+            .map(|coord| [coord.x as f32, coord.y as f32])
+            .collect();
+        // Without coords the bounding box is empty like the one of a `Zolygon`
+        let bounding_box = coords.iter().fold(
+            EMPTY_BOUNDING_BOX.map(|value| value as f32),
+            |[left, bottom, right, top], [x, y]| {
+                [left.min(*x), bottom.min(*y), right.max(*x), top.max(*y)]
+            },
+        );
+        writer.write_all(cast_slice(&bounding_box))?;
+        writer.write_all(cast_slice(&coords))?;
+        Ok(())
+    }
+
+    /// Return the bytes the polygon was read from, they can be stored and read back with [`Self::from_bytes`].
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let len = BOUNDING_BOX32_SIZE_IN_BYTES + mem::size_of_val(self.coords);
+        contiguous(self.bounding_box, self.coords, len, 0)
+    }
+
+    /// Return the bounding box of the polygon, see [`crate::BoundingBox::to_geo`] when the polygon is empty.
+    #[inline]
+    pub fn bounding_box(&self) -> Rect<f64> {
+        let [left, bottom, right, top] = self.bounding_box.map(f64::from);
+        Rect::new((left, bottom), (right, top))
+    }
+
+    /// Return the number of coordinates of the exterior ring, including the one closing it.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Return `true` if the polygon has no coordinates.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
+    }
+
+    /// Return the coordinate at the specified index converted to `f64`.
+    #[inline]
+    pub fn coord(&self, index: usize) -> Option<geo_types::Coord<f64>> {
+        self.coords.get(index).map(|&coord| to_geo_coord(coord))
+    }
+
+    /// Iterate over the coordinates of the exterior ring converted to `f64`.
+    #[inline]
+    pub fn coords(&self) -> impl ExactSizeIterator<Item = geo_types::Coord<f64>> + use<'a> {
+        self.coords.iter().map(|&coord| to_geo_coord(coord))
+    }
+
+    /// Return `true` if the coordinate is strictly inside the polygon, the coordinates are compared in `f64`.
+    pub fn contains_coord(&self, coord: geo_types::Coord<f64>) -> bool {
+        let [left, bottom, right, top] = self.bounding_box.map(f64::from);
+        if self.is_empty()
+            || !(left..=right).contains(&coord.x)
+            || !(bottom..=top).contains(&coord.y)
+        {
+            return false;
+        }
+        // Count the number of edges crossed by an horizontal ray going from the coordinate to the right
+        let mut inside = false;
+        for edge in self.coords.windows(2) {
+            let (a, b) = (to_geo_coord(edge[0]), to_geo_coord(edge[1]));
+            if (a.y > coord.y) != (b.y > coord.y) {
+                let x = a.x + (coord.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if coord.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Convert the [`Zolygon32`] back to a [`geo_types::Polygon`].
+    pub fn to_geo(&self) -> geo_types::Polygon<f64> {
+        geo_types::Polygon::new(self.coords().collect(), Vec::new())
+    }
+}
+
+fn to_geo_coord([x, y]: [f32; 2]) -> geo_types::Coord<f64> {
+    geo_types::Coord {
+        x: x as f64,
+        y: y as f64,
+    }
+}
+
+impl fmt::Debug for Zolygon32<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Zolygon32")
+            .field("bounding_box", &self.bounding_box)
+            .field("coords", &self.coords)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{LineString, coord, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+
+    #[test]
+    fn test_zolygon32() {
+        let triangle = polygon![(x: 0.1, y: 0.), (x: 2., y: 0.), (x: 1., y: 2.)];
+        let mut buffer = Vec::new();
+        Zolygon32::write_from_geometry(&mut buffer, &triangle).unwrap();
+        let zolygon = Zolygon32::try_from_bytes(&buffer).unwrap();
+        assert_compact_debug_snapshot!(zolygon, @"Zolygon32 { bounding_box: [0.1, 0.0, 2.0, 2.0], coords: [[0.1, 0.0], [2.0, 0.0], [1.0, 2.0], [0.1, 0.0]] }");
+        assert_compact_debug_snapshot!(zolygon.bounding_box(), @"RECT(0.10000000149011612 0.0,2.0 2.0)");
+        assert_compact_debug_snapshot!(zolygon.to_geo(), @"POLYGON((0.10000000149011612 0.0,2.0 0.0,1.0 2.0,0.10000000149011612 0.0))");
+        assert_eq!(zolygon.as_bytes(), &buffer[..]);
+
+        assert!(zolygon.contains_coord(coord! { x: 1.0, y: 1.0 }));
+        assert!(!zolygon.contains_coord(coord! { x: 0.2, y: 1.5 }));
+        assert!(!zolygon.contains_coord(coord! { x: 5.0, y: 1.0 }));
+
+        let mut empty = Vec::new();
+        Zolygon32::write_from_geometry(
+            &mut empty,
+            &Polygon::new(LineString::new(Vec::new()), Vec::new()),
+        )
+        .unwrap();
+        let empty = Zolygon32::try_from_bytes(&empty).unwrap();
+        assert!(empty.is_empty());
+        assert!(!empty.contains_coord(coord! { x: 0.0, y: 0.0 }));
+
+        // Remove the coord closing the ring
+        let len = buffer.len() - COORD32_SIZE_IN_BYTES;
+        let error = Zolygon32::try_from_bytes(&buffer[..len]).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"UnclosedRing");
+        let error = Zolygon32::try_from_bytes(&buffer[..len - 4]).unwrap_err();
+        assert_compact_debug_snapshot!(error, @"TrailingBytes(4)");
+    }
+}
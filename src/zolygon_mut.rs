@@ -0,0 +1,276 @@
+use std::mem;
+
+use bytemuck::cast_slice_mut;
+
+use crate::{
+    BOUNDING_BOX_SIZE_IN_BYTES, BoundingBox, COORD_SIZE_IN_BYTES, COORD_SIZE_IN_FLOATS, Coord,
+    Coords, ZerometryError, Zolygon, checksum::CHECKSUM_SIZE_IN_BYTES, error::check_aligned,
+    header::Header,
+};
+
+/// The number of floats used to store a bounding box.
+const BOUNDING_BOX_SIZE_IN_FLOATS: usize = BOUNDING_BOX_SIZE_IN_BYTES / mem::size_of::<f64>();
+
+/// Tell how the bounding box of a [`ZolygonMut`] is kept consistent when a coord is moved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BoundingBoxUpdate {
+    /// Recompute the bounding box from all the coords, it always stays as tight as possible.
+    #[default]
+    Recompute,
+    /// Only widen the bounding box to include the new coord, which is faster on large polygons
+    /// but the bounding box never shrinks. Call [`ZolygonMut::recompute_bounding_box`] once done.
+    WidenOnly,
+}
+
+/// A mutable view over a list of coordinates stored in a slice of bytes.
+pub struct CoordsMut<'a> {
+    data: &'a mut [f64],
+}
+
+impl<'a> CoordsMut<'a> {
+    /// Create a mutable view over the coordinates, the data must be aligned on 64 bits and only contain whole coordinates.
+    pub fn try_from_bytes(data: &'a mut [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        if data.len() % COORD_SIZE_IN_BYTES != 0 {
+            return Err(ZerometryError::TrailingBytes(
+                data.len() % COORD_SIZE_IN_BYTES,
+            ));
+        }
+        let data = cast_slice_mut(data);
+        Ok(Self { data })
+    }
+
+    /// Return the coordinates.
+    #[inline]
+    pub fn as_coords(&self) -> &Coords {
+        unsafe { Coords::from_slice(self.data) }
+    }
+
+    /// Return the number of coordinates.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len() / COORD_SIZE_IN_FLOATS
+    }
+
+    /// Return true if there is no coordinate.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Replace the coordinate at the specified index and return the previous one.
+    pub fn set(
+        &mut self,
+        index: usize,
+        coord: geo_types::Coord<f64>,
+    ) -> Result<geo_types::Coord<f64>, ZerometryError> {
+        let len = self.len();
+        let slot = self
+            .data
+            .get_mut(index * COORD_SIZE_IN_FLOATS..(index + 1) * COORD_SIZE_IN_FLOATS)
+            .ok_or(ZerometryError::InvalidIndex { index, len })?;
+        let coord_mut = unsafe { Coord::from_slice_mut(slot) };
+        let previous = coord_mut.to_geo();
+        *coord_mut.lng_mut() = coord.x;
+        *coord_mut.lat_mut() = coord.y;
+        Ok(previous)
+    }
+}
+
+/// A mutable view over a serialized [`Zolygon`] to correct some of its coordinates in place, without
+/// serializing the whole polygon again. The bounding box is kept consistent according to the
+/// [`BoundingBoxUpdate`] strategy, and the first and last coordinates stay equal to keep the ring closed.
+///
+/// ```
+/// use zerometry::{ZolygonMut, Zerometry};
+/// use geo_types::{Geometry, coord, polygon};
+///
+/// let mut buffer = Vec::new();
+/// let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+/// Zerometry::write_from_geometry(&mut buffer, &Geometry::Polygon(square)).unwrap();
+///
+/// let mut zolygon = ZolygonMut::try_from_zerometry_bytes(&mut buffer).unwrap();
+/// zolygon.set_coord(2, coord! { x: 2.0, y: 2.0 }).unwrap();
+///
+/// let zerometry = Zerometry::try_from_bytes(&buffer).unwrap();
+/// let expected = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 2., y: 2.), (x: 0., y: 1.)];
+/// assert_eq!(zerometry.to_geo(), Geometry::Polygon(expected));
+/// assert_eq!(zerometry.to_polygon().unwrap().bounding_box().right(), 2.0);
+/// ```
+pub struct ZolygonMut<'a> {
+    bounding_box: &'a mut [f64],
+    coords: CoordsMut<'a>,
+    update: BoundingBoxUpdate,
+}
+
+impl<'a> ZolygonMut<'a> {
+    /// Create a mutable view over the bytes of a [`Zolygon`], see [`Zolygon::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a mut [u8]) -> Result<Self, ZerometryError> {
+        Zolygon::try_from_bytes(data)?;
+        let (bounding_box, coords) = data.split_at_mut(BOUNDING_BOX_SIZE_IN_BYTES);
+        Ok(Self {
+            // The alignment has been checked by the validation
+            bounding_box: cast_slice_mut(bounding_box),
+            coords: CoordsMut::try_from_bytes(coords)?,
+            update: BoundingBoxUpdate::default(),
+        })
+    }
+
+    /// Create a mutable view over the polygon stored in the bytes of a [`crate::Zerometry`].
+    /// If the polygon was written with a [`crate::WriteOptions::coverage_mask`], the mask is cleared
+    /// since it can't be kept up to date, and every point-in-polygon test falls back to the ray casting.
+    /// If it was written with a [`crate::WriteOptions::checksum`], the checksum must be updated with
+    /// [`crate::Zerometry::refresh_checksum`] once the polygon is edited.
+    pub fn try_from_zerometry_bytes(data: &'a mut [u8]) -> Result<Self, ZerometryError> {
+        let header = Header::parse(data)?;
+        if header.kind != 2 {
+            return Err(ZerometryError::InvalidTag(header.kind));
+        }
+        let trailer_len = header.checksum.map_or(0, |_| CHECKSUM_SIZE_IN_BYTES);
+        let body_end = data.len() - trailer_len;
+        let body_start = body_end - header.body.len();
+        let mask_len = header
+            .coverage_mask
+            .map_or(0, |mask| mask.bitmaps_len_in_bytes());
+        let (header, body) = data[..body_end].split_at_mut(body_start);
+        let mask_start = header.len() - mask_len;
+        header[mask_start..].fill(0);
+        Self::try_from_bytes(body)
+    }
+
+    /// Change how the bounding box is kept consistent when a coord is moved.
+    pub fn with_bounding_box_update(self, update: BoundingBoxUpdate) -> Self {
+        Self { update, ..self }
+    }
+
+    /// Return the immutable polygon, as it's currently stored.
+    #[inline]
+    pub fn as_zolygon(&self) -> Zolygon<'_> {
+        let bounding_box = unsafe { BoundingBox::from_slice(self.bounding_box) };
+        Zolygon::new(bounding_box, self.coords.as_coords())
+    }
+
+    /// Return the number of coordinates of the polygon, including the closing one.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Return true if the polygon doesn't contain any coordinate.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
+    }
+
+    /// Move the coordinate at the specified index and update the bounding box.
+    /// Since the first and last coordinates close the ring, moving one of them moves the other.
+    pub fn set_coord(
+        &mut self,
+        index: usize,
+        coord: geo_types::Coord<f64>,
+    ) -> Result<geo_types::Coord<f64>, ZerometryError> {
+        let previous = self.coords.set(index, coord)?;
+        let last = self.len() - 1;
+        if index == 0 || index == last {
+            self.coords.set(last - index, coord)?;
+        }
+        match self.update {
+            BoundingBoxUpdate::Recompute => self.recompute_bounding_box(),
+            BoundingBoxUpdate::WidenOnly => {
+                let [left, bottom, right, top] = &mut self.bounding_box[..] else {
+                    unreachable!()
+                };
+                *left = left.min(coord.x);
+                *bottom = bottom.min(coord.y);
+                *right = right.max(coord.x);
+                *top = top.max(coord.y);
+            }
+        }
+        Ok(previous)
+    }
+
+    /// Recompute the bounding box from all the coordinates of the polygon.
+    pub fn recompute_bounding_box(&mut self) {
+        let mut coords = self.coords.as_coords().iter();
+        let Some(first) = coords.next() else {
+            return;
+        };
+        let mut bounding_box = [first.lng(), first.lat(), first.lng(), first.lat()];
+        for coord in coords {
+            bounding_box[0] = bounding_box[0].min(coord.lng());
+            bounding_box[1] = bounding_box[1].min(coord.lat());
+            bounding_box[2] = bounding_box[2].max(coord.lng());
+            bounding_box[3] = bounding_box[3].max(coord.lat());
+        }
+        debug_assert_eq!(bounding_box.len(), BOUNDING_BOX_SIZE_IN_FLOATS);
+        self.bounding_box.copy_from_slice(&bounding_box);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{Geometry, coord, polygon};
+    use insta::assert_compact_debug_snapshot;
+
+    use super::*;
+    use crate::{RelationBetweenShapes, WriteOptions, Zerometry};
+
+    fn square(options: WriteOptions) -> Vec<u8> {
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry_with_options(&mut buffer, &square.into(), options).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn update_the_bounding_box() {
+        let mut buffer = square(WriteOptions::default());
+        let mut zolygon = ZolygonMut::try_from_zerometry_bytes(&mut buffer).unwrap();
+        let previous = zolygon.set_coord(0, coord! { x: -2.0, y: -2.0 }).unwrap();
+        assert_compact_debug_snapshot!(previous, @"COORD(0.0 0.0)");
+        assert_compact_debug_snapshot!(zolygon.as_zolygon().to_geo(), @"POLYGON((-2.0 -2.0,4.0 0.0,4.0 4.0,0.0 4.0,-2.0 -2.0))");
+        assert_compact_debug_snapshot!(zolygon.as_zolygon().bounding_box(), @"BoundingBox { bottom_left: Coord { lng: -2.0, lat: -2.0 }, top_right: Coord { lng: 4.0, lat: 4.0 } }");
+
+        // Moving the coord back shrinks the bounding box
+        zolygon.set_coord(4, coord! { x: 0.0, y: 0.0 }).unwrap();
+        assert_compact_debug_snapshot!(zolygon.as_zolygon().bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 4.0, lat: 4.0 } }");
+
+        let mut zolygon = zolygon.with_bounding_box_update(BoundingBoxUpdate::WidenOnly);
+        zolygon.set_coord(2, coord! { x: 8.0, y: 4.0 }).unwrap();
+        zolygon.set_coord(2, coord! { x: 2.0, y: 2.0 }).unwrap();
+        assert_compact_debug_snapshot!(zolygon.as_zolygon().bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 8.0, lat: 4.0 } }");
+        zolygon.recompute_bounding_box();
+        assert_compact_debug_snapshot!(zolygon.as_zolygon().bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 4.0, lat: 4.0 } }");
+
+        let err = zolygon.set_coord(5, coord! { x: 0.0, y: 0.0 }).unwrap_err();
+        assert_compact_debug_snapshot!(err, @"InvalidIndex { index: 5, len: 5 }");
+        Zerometry::try_from_bytes(&buffer).unwrap();
+    }
+
+    #[test]
+    fn clear_the_coverage_mask() {
+        let options = WriteOptions {
+            coverage_mask: Some(4),
+            generation: Some(1),
+            ..WriteOptions::default()
+        };
+        let mut buffer = square(options);
+        let mut zolygon = ZolygonMut::try_from_zerometry_bytes(&mut buffer).unwrap();
+        zolygon.set_coord(2, coord! { x: 8.0, y: 8.0 }).unwrap();
+
+        let Zerometry::Polygon(zolygon) = Zerometry::try_from_bytes(&buffer).unwrap() else {
+            unreachable!()
+        };
+        // The point was outside of the original square, in a cell entirely outside of the polygon
+        let point = [3.5, 5.5];
+        assert!(zolygon.contains(unsafe { Coord::from_slice(&point) }));
+        assert_eq!(Zerometry::read_generation(&buffer).unwrap(), Some(1));
+
+        let mut buffer = Vec::new();
+        Zerometry::write_from_geometry(&mut buffer, &Geometry::Point((1.0, 2.0).into())).unwrap();
+        let err = ZolygonMut::try_from_zerometry_bytes(&mut buffer)
+            .err()
+            .unwrap();
+        assert_compact_debug_snapshot!(err, @"InvalidTag(0)");
+    }
+}
@@ -4,8 +4,14 @@ use bytemuck::cast_slice;
 use geo_types::{MultiLineString, Point};
 
 use crate::{
-    BoundingBox, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry, Zoint,
-    Zollection, Zolygon, ZultiPoints, ZultiPolygons, bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    BoundingBox, InputRelation, OutputRelation, RelationBetweenShapes, Zerometry, ZerometryError,
+    Zoint, Zollection, Zolygon, ZultiPoints, ZultiPolygons,
+    aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    error::{check_aligned, checked_size, split_bounding_box, split_checked, split_u32},
+    groups::{Groups, candidates},
+    size::coords_size,
+    topology::relation_with_topology,
     zine::Zine,
 };
 
@@ -18,6 +24,10 @@ pub struct ZultiLines<'a> {
     // If it's odd it means we also inserted one extra offset at the end for padding that should not ends up in the slice
     offsets: &'a [u32],
     bytes: &'a [u8],
+    // The optional id of every line, stored in the header of the zerometry
+    ids: Option<&'a [u64]>,
+    // The optional bounding boxes of the groups of lines, stored in the header of the zerometry
+    groups: Option<Groups<'a>>,
 }
 
 impl<'a> ZultiLines<'a> {
@@ -28,6 +38,8 @@ impl<'a> ZultiLines<'a> {
             bounding_box,
             offsets,
             bytes,
+            ids: None,
+            groups: None,
         }
     }
 
@@ -62,12 +74,23 @@ impl<'a> ZultiLines<'a> {
             bounding_box,
             offsets,
             bytes,
+            ids: None,
+            groups: None,
         }
     }
 
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_children(data, |line| Zine::try_from_bytes(line).map(drop))?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
     /// Convert the specified [`geo_types::MultiLineString`] to a valid [`ZultiLines`] slice of bytes in the input buffer.
+    /// The offsets of the lines are computed beforehand, so the bytes are written in a single pass and the writer can be
+    /// any [`io::Write`], like a `&mut [u8]` of the size returned by [`Zerometry::serialized_size`].
     pub fn write_from_geometry(
-        writer: &mut Vec<u8>,
+        writer: &mut impl io::Write,
         geometry: &MultiLineString<f64>,
     ) -> Result<(), io::Error> {
         BoundingBox::write_from_geometry(
@@ -78,33 +101,29 @@ impl<'a> ZultiLines<'a> {
                 .flat_map(|line| line.0.iter())
                 .map(|coord| Point::from((coord.x, coord.y))),
         )?;
-        // Write the number of offsets to expect
-        writer.extend((geometry.0.len() as u32).to_ne_bytes());
-        let offsets_addr = writer.len();
-        // We must leave an empty space to write the offsets later
-        writer.extend(std::iter::repeat_n(
-            0,
-            geometry.0.len() * mem::size_of::<u32>(),
-        ));
-        if geometry.0.len() % 2 == 0 {
-            // If we have an even number of lines, we must add an extra offset at the end for padding
-            writer.extend(0_u32.to_ne_bytes());
-        }
-        let start = writer.len();
-        let mut offsets = Vec::new();
+        write_offsets(
+            writer,
+            geometry.0.iter().map(|line| coords_size(line.0.len())),
+        )?;
         for line in geometry.iter() {
-            offsets.push(writer.len() as u32 - start as u32);
             Zine::write_from_geometry(writer, line)?;
         }
-
-        for (i, offset) in offsets.iter().enumerate() {
-            let offset_addr = offsets_addr + i * mem::size_of::<u32>();
-            writer[offset_addr..offset_addr + mem::size_of::<u32>()]
-                .copy_from_slice(&offset.to_ne_bytes());
-        }
         Ok(())
     }
 
+    /// Return the bytes the multi lines were read from, they can be stored and read back with [`Self::from_bytes`].
+    /// The ids and the groups are stored in the header of the zerometry and aren't part of them.
+    ///
+    /// # Panics
+    /// If the multi lines were created with [`Self::new`] from parts that don't follow each other in memory.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        // The number of offsets, the offsets and the padding
+        let offsets = (self.offsets.len() + 1).next_multiple_of(2) * mem::size_of::<u32>();
+        let len = BOUNDING_BOX_SIZE_IN_BYTES + offsets + self.bytes.len();
+        contiguous(self.bounding_box, self.bytes, len, 0)
+    }
+
     /// Return the internal bounding box
     #[inline]
     pub fn bounding_box(&self) -> &'a BoundingBox {
@@ -114,13 +133,117 @@ impl<'a> ZultiLines<'a> {
     /// Return a line by index, if the index doesn't exists, returns None
     #[inline]
     pub fn get(&self, index: usize) -> Option<Zine<'a>> {
+        let bytes = self.get_bytes(index)?;
+        Some(unsafe { Zine::from_bytes(bytes) })
+    }
+
+    /// Attach an id to every line, see [`Zerometry::write_from_geometry_with_ids`].
+    ///
+    /// # Panics
+    /// If there isn't exactly one id per line.
+    pub fn with_ids(self, ids: &'a [u64]) -> Self {
+        assert_eq!(
+            self.len(),
+            ids.len(),
+            "There must be exactly one id per line"
+        );
+        Self {
+            ids: Some(ids),
+            ..self
+        }
+    }
+
+    /// Attach the bounding boxes of the groups of lines read from the header.
+    pub(crate) fn with_groups(self, groups: Groups<'a>) -> Self {
+        Self {
+            groups: Some(groups),
+            ..self
+        }
+    }
+
+    /// Return `true` if the bounding boxes of the groups of lines were stored, see [`crate::WriteOptions::bounding_box_groups`].
+    #[inline]
+    pub fn has_bounding_box_groups(&self) -> bool {
+        self.groups.is_some()
+    }
+
+    /// Return the lines whose group bounding box matches the predicate, the others can't be
+    /// related to a shape outside of these bounding boxes. Without groups every line is returned.
+    pub(crate) fn candidate_lines<'b>(
+        &self,
+        keep: impl Fn(&BoundingBox) -> bool + 'b,
+    ) -> impl Iterator<Item = Zine<'a>> + 'b
+    where
+        'a: 'b,
+    {
+        let this = *self;
+        candidates(self.groups, self.len(), keep).map(move |index| this.get(index).unwrap())
+    }
+
+    /// Return the ids of the lines, or `None` if they were written without ids.
+    #[inline]
+    pub fn ids(&self) -> Option<&'a [u64]> {
+        self.ids
+    }
+
+    /// Return the id of a line by index, if the index doesn't exists or the lines don't have ids, returns None
+    #[inline]
+    pub fn id(&self, index: usize) -> Option<u64> {
+        self.ids?.get(index).copied()
+    }
+
+    /// Return a line by index along with its id, if the index doesn't exists, returns None
+    #[inline]
+    pub fn get_with_id(&self, index: usize) -> Option<(Zine<'a>, Option<u64>)> {
+        Some((self.get(index)?, self.id(index)))
+    }
+
+    /// Return the ids of the lines having the specified relation with the other shape, to find
+    /// which of the original features matched.
+    /// The lines written without ids are ignored.
+    pub fn matching_ids<Other>(&self, other: &Other, relation: InputRelation) -> Vec<u64>
+    where
+        Zine<'a>: RelationBetweenShapes<Other>,
+    {
+        (0..self.len())
+            .filter_map(|index| {
+                let id = self.id(index)?;
+                let line = self.get(index).unwrap();
+                line.relation(other, relation).any_relation().then_some(id)
+            })
+            .collect()
+    }
+
+    /// Return the serialized bytes of a line by index, if the index doesn't exists, returns None.
+    /// They can be copied as-is and read back with [`Zine::from_bytes`], without going through geo.
+    #[inline]
+    pub fn get_bytes(&self, index: usize) -> Option<&'a [u8]> {
         let offset = *self.offsets.get(index)?;
         let next_offset = *self
             .offsets
             .get(index + 1)
             .unwrap_or(&(self.bytes.len() as u32));
-        let bytes = &self.bytes[offset as usize..next_offset as usize];
-        Some(unsafe { Zine::from_bytes(bytes) })
+        Some(&self.bytes[offset as usize..next_offset as usize])
+    }
+
+    /// Write a copy of the multi lines containing only the lines matching the predicate, in the same order.
+    /// The lines are copied as-is and only the bounding box is recomputed, no conversion to geo is involved.
+    pub fn retain(
+        &self,
+        mut predicate: impl FnMut(&Zine<'a>) -> bool,
+        writer: &mut impl io::Write,
+    ) -> Result<(), io::Error> {
+        let retained: Vec<_> = (0..self.len())
+            .filter(|&index| predicate(&self.get(index).unwrap()))
+            .collect();
+        write_children(
+            writer,
+            retained
+                .iter()
+                .flat_map(|&index| self.get(index).unwrap().coords().iter())
+                .map(|coord| Point::new(coord.lng(), coord.lat())),
+            retained.iter().map(|&index| self.get_bytes(index).unwrap()),
+        )
     }
 
     /// Return the number of lines contained in the multi-line
@@ -136,6 +259,7 @@ impl<'a> ZultiLines<'a> {
     }
 
     /// Returns the individual [`Zine`]s that compose the [`ZultiLines`]
+    /// They're always returned in the order they were written, which is also the order of their indices in [`Self::get`].
     #[inline]
     pub fn lines(&'a self) -> impl Iterator<Item = Zine<'a>> {
         (0..self.len()).map(move |index| self.get(index).unwrap())
@@ -147,6 +271,92 @@ impl<'a> ZultiLines<'a> {
     }
 }
 
+/// Validate a multi shape: the bounding box, the number of children, their offsets, the padding
+/// and every child with the specified function.
+pub(crate) fn check_children(
+    data: &[u8],
+    check_child: impl Fn(&[u8]) -> Result<(), ZerometryError>,
+) -> Result<(), ZerometryError> {
+    check_aligned(data)?;
+    split_children(data)?.into_iter().try_for_each(check_child)
+}
+
+/// Write the number of children of a multi shape followed by their offsets, computed from their sizes.
+/// An extra offset is written when there is an even number of children to stay aligned on 64 bits.
+pub(crate) fn write_offsets(
+    writer: &mut impl io::Write,
+    sizes: impl ExactSizeIterator<Item = usize>,
+) -> Result<(), io::Error> {
+    let count = sizes.len();
+    writer.write_all(&(count as u32).to_ne_bytes())?;
+    let mut offset = 0;
+    for size in sizes {
+        writer.write_all(&(offset as u32).to_ne_bytes())?;
+        offset += size;
+    }
+    if count % 2 == 0 {
+        writer.write_all(&0_u32.to_ne_bytes())?;
+    }
+    Ok(())
+}
+
+/// Validate the layout of a multi shape and return the bytes of its children, which are not validated.
+/// The data doesn't need to be aligned.
+pub(crate) fn split_children(data: &[u8]) -> Result<Vec<&[u8]>, ZerometryError> {
+    let data = split_bounding_box(data)?;
+    let (count, data) = split_u32(data)?;
+    let size = checked_size(count as u64, mem::size_of::<u32>(), data)?;
+    let (offsets, data) = split_checked(data, size)?;
+    let padding = if count % 2 == 0 {
+        mem::size_of::<u32>()
+    } else {
+        0
+    };
+    let (padding, bytes) = split_checked(data, padding)?;
+    if padding.iter().any(|byte| *byte != 0) {
+        return Err(ZerometryError::InvalidPadding);
+    }
+
+    let offsets: Vec<usize> = offsets
+        .chunks_exact(mem::size_of::<u32>())
+        .map(|offset| u32::from_ne_bytes(offset.try_into().unwrap()) as usize)
+        .collect();
+    let mut previous = 0;
+    for &offset in &offsets {
+        // The children must follow each other and stay aligned on 64 bits
+        if offset < previous || offset > bytes.len() || offset % mem::size_of::<u64>() != 0 {
+            return Err(ZerometryError::InvalidOffset {
+                offset,
+                len: bytes.len(),
+            });
+        }
+        previous = offset;
+    }
+    Ok(offsets
+        .iter()
+        .enumerate()
+        .map(|(index, &offset)| {
+            let next = offsets.get(index + 1).copied().unwrap_or(bytes.len());
+            &bytes[offset..next]
+        })
+        .collect())
+}
+
+/// Write a multi shape made of already serialized children: the bounding box of the points,
+/// the number of children, their offsets, the padding and finally the children.
+pub(crate) fn write_children<'c>(
+    writer: &mut impl io::Write,
+    points: impl Iterator<Item = Point<f64>>,
+    children: impl ExactSizeIterator<Item = &'c [u8]> + Clone,
+) -> Result<(), io::Error> {
+    BoundingBox::write_from_geometry(writer, points)?;
+    write_offsets(writer, children.clone().map(<[u8]>::len))?;
+    for child in children {
+        writer.write_all(child)?;
+    }
+    Ok(())
+}
+
 impl<'a> fmt::Debug for ZultiLines<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         struct ZinesDebug<'b, 'a>(&'b ZultiLines<'a>);
@@ -164,57 +374,146 @@ impl<'a> fmt::Debug for ZultiLines<'a> {
     }
 }
 
-// points and line have nothing in common
 impl<'a> RelationBetweenShapes<Zoint<'a>> for ZultiLines<'a> {
-    fn relation(&self, _other: &Zoint, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &Zoint, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        let coord = other.coord();
+        if self
+            .candidate_lines(|bounding_box| bounding_box.contains_coord(coord))
+            .any(|line| line.contains_coord(coord))
+        {
+            relation.to_false().make_strict_contains_if_set()
+        } else {
+            relation.to_false().make_disjoint_if_set()
+        }
     }
 }
 
 impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for ZultiLines<'a> {
-    fn relation(&self, _other: &ZultiPoints, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &ZultiPoints, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        let mut output = relation.to_false();
+        if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
+        {
+            return output.make_disjoint_if_set();
+        }
+
+        let mut contains = 0;
+        for coord in other.coords().iter() {
+            if self
+                .candidate_lines(|bounding_box| bounding_box.contains_coord(coord))
+                .any(|line| line.contains_coord(coord))
+            {
+                contains += 1;
+                output = output.make_contains_if_set();
+                if !relation.strict_contains || relation.early_exit {
+                    return output;
+                }
+            }
+        }
+
+        if contains == other.len() {
+            output = output.make_strict_contains_if_set();
+        }
+
+        if output.any_relation() {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
     }
 }
 
 impl<'a> RelationBetweenShapes<Zine<'a>> for ZultiLines<'a> {
     fn relation(&self, other: &Zine, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
         {
             return relation.to_false().make_disjoint_if_set();
         }
 
-        for line in self.lines() {
-            if line.intersects(other) {
-                return relation.to_false().make_intersect_if_set();
+        let mut output = relation.to_false();
+        let mut intersect = false;
+        for line in
+            self.candidate_lines(|bounding_box| !bounding_box.disjoint(other.bounding_box()))
+        {
+            let line_relation = line.relation(other, lines_relation(relation));
+            intersect |= line_relation.intersect.unwrap_or_default();
+            output |= line_relation;
+            if done_with_lines(intersect, &output, relation) {
+                return output;
             }
         }
 
-        relation.to_false().make_disjoint_if_set()
+        if intersect {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
     }
 }
 
 impl<'a> RelationBetweenShapes<ZultiLines<'a>> for ZultiLines<'a> {
     fn relation(&self, other: &ZultiLines, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
         {
             return relation.to_false().make_disjoint_if_set();
         }
 
-        for left in self.lines() {
-            for right in other.lines() {
-                if left.intersects(&right) {
-                    return relation.to_false().make_intersect_if_set();
+        let mut output = relation.to_false();
+        let mut intersect = false;
+        for left in
+            self.candidate_lines(|bounding_box| !bounding_box.disjoint(other.bounding_box()))
+        {
+            for right in
+                other.candidate_lines(|bounding_box| !bounding_box.disjoint(left.bounding_box()))
+            {
+                let line_relation = left.relation(&right, lines_relation(relation));
+                intersect |= line_relation.intersect.unwrap_or_default();
+                output |= line_relation;
+                if done_with_lines(intersect, &output, relation) {
+                    return output;
                 }
             }
         }
 
-        relation.to_false().make_disjoint_if_set()
+        if intersect {
+            output
+        } else {
+            output.make_disjoint_if_set()
+        }
+    }
+}
+
+/// The relation to compute between the lines of the multi lines, the intersection is always needed to
+/// know if the multi lines are disjoint.
+fn lines_relation(relation: InputRelation) -> InputRelation {
+    InputRelation {
+        intersect: true,
+        ..relation.strip_disjoint()
     }
 }
 
+/// Return `true` once nothing more can be learned by relating other lines: the lines intersect and
+/// either the overlap was found or isn't requested.
+fn done_with_lines(intersect: bool, output: &OutputRelation, relation: InputRelation) -> bool {
+    intersect && (output.overlaps.unwrap_or_default() || !relation.overlaps || relation.early_exit)
+}
+
 impl<'a> RelationBetweenShapes<Zolygon<'a>> for ZultiLines<'a> {
     fn relation(&self, other: &Zolygon, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
@@ -223,7 +522,9 @@ impl<'a> RelationBetweenShapes<Zolygon<'a>> for ZultiLines<'a> {
         }
 
         let mut contained = 0;
-        for line in self.lines() {
+        for line in
+            self.candidate_lines(|bounding_box| !bounding_box.disjoint(other.bounding_box()))
+        {
             let r = line.relation(other, relation.strip_strict().strip_disjoint());
             output |= r;
             if r.contained.unwrap_or_default() {
@@ -249,6 +550,9 @@ impl<'a> RelationBetweenShapes<Zolygon<'a>> for ZultiLines<'a> {
 
 impl<'a> RelationBetweenShapes<ZultiPolygons<'a>> for ZultiLines<'a> {
     fn relation(&self, other: &ZultiPolygons, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
         let mut output = relation.to_false();
 
         if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
@@ -256,8 +560,12 @@ impl<'a> RelationBetweenShapes<ZultiPolygons<'a>> for ZultiLines<'a> {
             return output.make_disjoint_if_set();
         }
         let mut contained = 0;
-        for line in self.lines() {
-            for polygon in other.polygons() {
+        for line in
+            self.candidate_lines(|bounding_box| !bounding_box.disjoint(other.bounding_box()))
+        {
+            for polygon in
+                other.candidate_polygons(|bounding_box| !bounding_box.disjoint(line.bounding_box()))
+            {
                 let r = line.relation(&polygon, relation.strip_strict().strip_disjoint());
                 output |= r;
                 if r.contained.unwrap_or_default() {
@@ -331,8 +639,10 @@ mod tests {
         let mut writer = Vec::new();
 
         ZultiLines::write_from_geometry(&mut writer, &geometry).unwrap();
-        // Debug everything at once just to make sure it never changes
-        assert_debug_snapshot!(writer);
+        // Debug everything at once in the canonical form just to make sure it never changes
+        let canonical =
+            Zerometry::MultiLines(unsafe { ZultiLines::from_bytes(&writer) }).to_canonical_bytes();
+        assert_debug_snapshot!(&canonical[8..]);
         let mut current_offset = 0;
         let expected_bounding_box: &[f64] =
             cast_slice(&writer[current_offset..BOUNDING_BOX_SIZE_IN_BYTES]);
@@ -359,91 +669,93 @@ mod tests {
         // Now there should be the first zine at the offset 0
         let first_zine_bytes = &writer[current_offset + expected_offsets[0] as usize
             ..current_offset + expected_offsets[1] as usize];
-        assert_compact_debug_snapshot!(first_zine_bytes, @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64]");
         let first_zine = unsafe { Zine::from_bytes(first_zine_bytes) };
-        assert_compact_debug_snapshot!(first_zine, @"Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 10.0, y: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::Line(first_zine).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64]");
+        assert_compact_debug_snapshot!(first_zine, @"Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 10.0, lat: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
         assert_eq!(first_zine, first_line);
         let second_zine_bytes = &writer[current_offset + expected_offsets[1] as usize..];
-        assert_compact_debug_snapshot!(second_zine_bytes, @"[0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 52, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 64, 0, 0, 0, 0, 0, 0, 36, 64]");
         let second_zine = unsafe { Zine::from_bytes(second_zine_bytes) };
-        assert_compact_debug_snapshot!(second_zine, @"Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 10.0, y: 0.0 }, top_right: Coord { x: 20.0, y: 10.0 } }, points: [Zoint { lng: 10.0, lat: 10.0 }, Zoint { lng: 20.0, lat: 0.0 }, Zoint { lng: 20.0, lat: 10.0 }] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::Line(second_zine).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 52, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 64, 0, 0, 0, 0, 0, 0, 36, 64]");
+        assert_compact_debug_snapshot!(second_zine, @"Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 10.0, lat: 0.0 }, top_right: Coord { lng: 20.0, lat: 10.0 } }, points: [Zoint { lng: 10.0, lat: 10.0 }, Zoint { lng: 20.0, lat: 0.0 }, Zoint { lng: 20.0, lat: 10.0 }] }");
         assert_eq!(second_zine, second_line);
 
         // Try to parse the zulti lines
         let zulti_lines = unsafe { ZultiLines::from_bytes(&writer) };
         assert_snapshot!(zulti_lines.len(), @"2");
-        assert_compact_debug_snapshot!(zulti_lines.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 20.0, y: 10.0 } }");
+        assert_compact_debug_snapshot!(zulti_lines.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 20.0, lat: 10.0 } }");
         assert_compact_debug_snapshot!(zulti_lines.offsets, @"[0, 80]");
-        assert_compact_debug_snapshot!(zulti_lines.get(0).unwrap(), @"Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 10.0, y: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
-        assert_compact_debug_snapshot!(zulti_lines.get(1).unwrap(), @"Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 10.0, y: 0.0 }, top_right: Coord { x: 20.0, y: 10.0 } }, points: [Zoint { lng: 10.0, lat: 10.0 }, Zoint { lng: 20.0, lat: 0.0 }, Zoint { lng: 20.0, lat: 10.0 }] }");
+        assert_compact_debug_snapshot!(zulti_lines.get(0).unwrap(), @"Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 10.0, lat: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
+        assert_compact_debug_snapshot!(zulti_lines.get(1).unwrap(), @"Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 10.0, lat: 0.0 }, top_right: Coord { lng: 20.0, lat: 10.0 } }, points: [Zoint { lng: 10.0, lat: 10.0 }, Zoint { lng: 20.0, lat: 0.0 }, Zoint { lng: 20.0, lat: 10.0 }] }");
         assert_compact_debug_snapshot!(zulti_lines.get(2), @"None");
         assert_debug_snapshot!(zulti_lines, @r"
-        ZultiLines {
-            bounding_box: BoundingBox {
-                bottom_left: Coord {
-                    x: 0.0,
-                    y: 0.0,
-                },
-                top_right: Coord {
-                    x: 20.0,
-                    y: 10.0,
-                },
-            },
-            zines: [
-                Zine {
-                    bounding_box: BoundingBox {
-                        bottom_left: Coord {
-                            x: 0.0,
-                            y: 0.0,
-                        },
-                        top_right: Coord {
-                            x: 10.0,
-                            y: 10.0,
-                        },
+            ZultiLines {
+                bounding_box: BoundingBox {
+                    bottom_left: Coord {
+                        lng: 0.0,
+                        lat: 0.0,
+                    },
+                    top_right: Coord {
+                        lng: 20.0,
+                        lat: 10.0,
                     },
-                    points: [
-                        Zoint {
-                            lng: 0.0,
-                            lat: 0.0,
-                        },
-                        Zoint {
-                            lng: 10.0,
-                            lat: 0.0,
-                        },
-                        Zoint {
-                            lng: 0.0,
-                            lat: 10.0,
-                        },
-                    ],
                 },
-                Zine {
-                    bounding_box: BoundingBox {
-                        bottom_left: Coord {
-                            x: 10.0,
-                            y: 0.0,
-                        },
-                        top_right: Coord {
-                            x: 20.0,
-                            y: 10.0,
+                zines: [
+                    Zine {
+                        bounding_box: BoundingBox {
+                            bottom_left: Coord {
+                                lng: 0.0,
+                                lat: 0.0,
+                            },
+                            top_right: Coord {
+                                lng: 10.0,
+                                lat: 10.0,
+                            },
                         },
+                        points: [
+                            Zoint {
+                                lng: 0.0,
+                                lat: 0.0,
+                            },
+                            Zoint {
+                                lng: 10.0,
+                                lat: 0.0,
+                            },
+                            Zoint {
+                                lng: 0.0,
+                                lat: 10.0,
+                            },
+                        ],
                     },
-                    points: [
-                        Zoint {
-                            lng: 10.0,
-                            lat: 10.0,
-                        },
-                        Zoint {
-                            lng: 20.0,
-                            lat: 0.0,
+                    Zine {
+                        bounding_box: BoundingBox {
+                            bottom_left: Coord {
+                                lng: 10.0,
+                                lat: 0.0,
+                            },
+                            top_right: Coord {
+                                lng: 20.0,
+                                lat: 10.0,
+                            },
                         },
-                        Zoint {
-                            lng: 20.0,
-                            lat: 10.0,
-                        },
-                    ],
-                },
-            ],
-        }
+                        points: [
+                            Zoint {
+                                lng: 10.0,
+                                lat: 10.0,
+                            },
+                            Zoint {
+                                lng: 20.0,
+                                lat: 0.0,
+                            },
+                            Zoint {
+                                lng: 20.0,
+                                lat: 10.0,
+                            },
+                        ],
+                    },
+                ],
+            }
         ");
     }
 
@@ -459,8 +771,10 @@ mod tests {
         let mut writer = Vec::new();
 
         ZultiLines::write_from_geometry(&mut writer, &geometry).unwrap();
-        // Debug everything at once just to make sure it never changes
-        assert_debug_snapshot!(writer);
+        // Debug everything at once in the canonical form just to make sure it never changes
+        let canonical =
+            Zerometry::MultiLines(unsafe { ZultiLines::from_bytes(&writer) }).to_canonical_bytes();
+        assert_debug_snapshot!(&canonical[8..]);
         let mut current_offset = 0;
         let expected_bounding_box: &[f64] =
             cast_slice(&writer[current_offset..BOUNDING_BOX_SIZE_IN_BYTES]);
@@ -484,59 +798,60 @@ mod tests {
         // -
         // Now there should be the first zine at the offset 0
         let first_zine_bytes = &writer[current_offset + expected_offsets[0] as usize..];
-        assert_compact_debug_snapshot!(first_zine_bytes, @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64]");
         let first_zine = unsafe { Zine::from_bytes(first_zine_bytes) };
-        assert_compact_debug_snapshot!(first_zine, @"Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 10.0, y: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
+        // Skip the tag to only keep the canonical bytes of the shape
+        assert_compact_debug_snapshot!(&Zerometry::Line(first_zine).to_canonical_bytes()[8..], @"[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64]");
+        assert_compact_debug_snapshot!(first_zine, @"Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 10.0, lat: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
         assert_eq!(first_zine, first_line);
 
         // Try to parse the zulti lines
         let zulti_polygon = unsafe { ZultiLines::from_bytes(&writer) };
         assert_snapshot!(zulti_polygon.len(), @"1");
-        assert_compact_debug_snapshot!(zulti_polygon.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 10.0, y: 10.0 } }");
+        assert_compact_debug_snapshot!(zulti_polygon.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 10.0, lat: 10.0 } }");
         assert_compact_debug_snapshot!(zulti_polygon.offsets, @"[0]");
-        assert_compact_debug_snapshot!(zulti_polygon.get(0).unwrap(), @"Zine { bounding_box: BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 10.0, y: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
+        assert_compact_debug_snapshot!(zulti_polygon.get(0).unwrap(), @"Zine { bounding_box: BoundingBox { bottom_left: Coord { lng: 0.0, lat: 0.0 }, top_right: Coord { lng: 10.0, lat: 10.0 } }, points: [Zoint { lng: 0.0, lat: 0.0 }, Zoint { lng: 10.0, lat: 0.0 }, Zoint { lng: 0.0, lat: 10.0 }] }");
         assert_compact_debug_snapshot!(zulti_polygon.get(1), @"None");
         assert_debug_snapshot!(zulti_polygon, @r"
-        ZultiLines {
-            bounding_box: BoundingBox {
-                bottom_left: Coord {
-                    x: 0.0,
-                    y: 0.0,
-                },
-                top_right: Coord {
-                    x: 10.0,
-                    y: 10.0,
+            ZultiLines {
+                bounding_box: BoundingBox {
+                    bottom_left: Coord {
+                        lng: 0.0,
+                        lat: 0.0,
+                    },
+                    top_right: Coord {
+                        lng: 10.0,
+                        lat: 10.0,
+                    },
                 },
-            },
-            zines: [
-                Zine {
-                    bounding_box: BoundingBox {
-                        bottom_left: Coord {
-                            x: 0.0,
-                            y: 0.0,
-                        },
-                        top_right: Coord {
-                            x: 10.0,
-                            y: 10.0,
+                zines: [
+                    Zine {
+                        bounding_box: BoundingBox {
+                            bottom_left: Coord {
+                                lng: 0.0,
+                                lat: 0.0,
+                            },
+                            top_right: Coord {
+                                lng: 10.0,
+                                lat: 10.0,
+                            },
                         },
+                        points: [
+                            Zoint {
+                                lng: 0.0,
+                                lat: 0.0,
+                            },
+                            Zoint {
+                                lng: 10.0,
+                                lat: 0.0,
+                            },
+                            Zoint {
+                                lng: 0.0,
+                                lat: 10.0,
+                            },
+                        ],
                     },
-                    points: [
-                        Zoint {
-                            lng: 0.0,
-                            lat: 0.0,
-                        },
-                        Zoint {
-                            lng: 10.0,
-                            lat: 0.0,
-                        },
-                        Zoint {
-                            lng: 0.0,
-                            lat: 10.0,
-                        },
-                    ],
-                },
-            ],
-        }
+                ],
+            }
         ");
     }
 
@@ -547,12 +862,14 @@ mod tests {
         let mut writer = Vec::new();
 
         ZultiLines::write_from_geometry(&mut writer, &geometry).unwrap();
-        // Debug everything at once just to make sure it never changes
-        assert_debug_snapshot!(writer);
+        // Debug everything at once in the canonical form just to make sure it never changes
+        let canonical =
+            Zerometry::MultiLines(unsafe { ZultiLines::from_bytes(&writer) }).to_canonical_bytes();
+        assert_debug_snapshot!(&canonical[8..]);
         let mut current_offset = 0;
         let expected_bounding_box: &[f64] =
             cast_slice(&writer[current_offset..BOUNDING_BOX_SIZE_IN_BYTES]);
-        assert_compact_debug_snapshot!(expected_bounding_box, @"[0.0, 0.0, 0.0, 0.0]");
+        assert_compact_debug_snapshot!(expected_bounding_box, @"[inf, inf, -inf, -inf]");
         current_offset += BOUNDING_BOX_SIZE_IN_BYTES;
         let expected_nb_offsets: u32 = u32::from_ne_bytes(
             writer[current_offset..current_offset + mem::size_of::<u32>()]
@@ -575,23 +892,23 @@ mod tests {
         // Try to parse the zulti lines
         let zulti_lines = unsafe { ZultiLines::from_bytes(&writer) };
         assert_snapshot!(zulti_lines.len(), @"0");
-        assert_compact_debug_snapshot!(zulti_lines.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 0.0, y: 0.0 }, top_right: Coord { x: 0.0, y: 0.0 } }");
+        assert_compact_debug_snapshot!(zulti_lines.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: inf, lat: inf }, top_right: Coord { lng: -inf, lat: -inf } }");
         assert_compact_debug_snapshot!(zulti_lines.offsets, @"[]");
         assert_compact_debug_snapshot!(zulti_lines.get(0), @"None");
         assert_debug_snapshot!(zulti_lines, @r"
-        ZultiLines {
-            bounding_box: BoundingBox {
-                bottom_left: Coord {
-                    x: 0.0,
-                    y: 0.0,
-                },
-                top_right: Coord {
-                    x: 0.0,
-                    y: 0.0,
+            ZultiLines {
+                bounding_box: BoundingBox {
+                    bottom_left: Coord {
+                        lng: inf,
+                        lat: inf,
+                    },
+                    top_right: Coord {
+                        lng: -inf,
+                        lat: -inf,
+                    },
                 },
-            },
-            zines: [],
-        }
+                zines: [],
+            }
         ");
     }
 
@@ -668,25 +985,61 @@ mod tests {
         ZultiPolygons::write_from_geometry(&mut buf, &multi_polygons_all).unwrap();
         let multi_polygons_all = unsafe { ZultiPolygons::from_bytes(&buf) };
 
-        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(true), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_strict_inside.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
-
-        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_outside.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-
-        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true) }");
-        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false) }");
-        assert_compact_debug_snapshot!(multi_line_inside.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false) }");
+        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_strict_inside.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(true), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_strict_inside.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+
+        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_outside.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_outside.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+
+        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_inside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_outside), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_intersect), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_in_and_out), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_inside.all_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        assert_compact_debug_snapshot!(multi_line_inside.any_relation(&multi_polygons_all), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+    }
+
+    #[test]
+    fn test_retain() {
+        let multi_line = MultiLineString::new(vec![
+            LineString::from(vec![(0., 0.), (1., 1.)]),
+            LineString::from(vec![(5., 5.), (6., 5.), (7., 8.)]),
+            LineString::from(vec![(-1., 2.), (0., 3.)]),
+        ]);
+        let mut buffer = Vec::new();
+        ZultiLines::write_from_geometry(&mut buffer, &multi_line).unwrap();
+        let zulti_lines = unsafe { ZultiLines::from_bytes(&buffer) };
+
+        let mut retained = Vec::new();
+        zulti_lines
+            .retain(|line| line.coords().len() == 2, &mut retained)
+            .unwrap();
+        let retained = unsafe { ZultiLines::from_bytes(&retained) };
+        assert_compact_debug_snapshot!(retained.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: -1.0, lat: 0.0 }, top_right: Coord { lng: 1.0, lat: 3.0 } }");
+        assert_compact_debug_snapshot!(retained.to_geo(), @"MULTILINESTRING((0.0 0.0,1.0 1.0),(-1.0 2.0,0.0 3.0))");
+    }
+
+    #[test]
+    fn test_get_bytes() {
+        let multi_line = MultiLineString::new(vec![
+            LineString::from(vec![(0., 0.), (1., 1.)]),
+            LineString::from(vec![(5., 5.), (6., 5.), (7., 8.)]),
+        ]);
+        let mut buffer = Vec::new();
+        ZultiLines::write_from_geometry(&mut buffer, &multi_line).unwrap();
+        let zulti_lines = ZultiLines::try_from_bytes(&buffer).unwrap();
+
+        let copy = zulti_lines.get_bytes(1).unwrap().to_vec();
+        let zine = Zine::try_from_bytes(&copy).unwrap();
+        assert_compact_debug_snapshot!(zine.to_geo(), @"LINESTRING(5.0 5.0,6.0 5.0,7.0 8.0)");
+        assert!(zulti_lines.get_bytes(2).is_none());
     }
 }
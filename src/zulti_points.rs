@@ -1,18 +1,35 @@
 use core::fmt;
+use std::collections::BTreeMap;
 use std::io::{self, Write};
+use std::mem;
+
+use bytemuck::cast_slice;
 
 use geo_types::MultiPoint;
 
 use crate::{
-    BoundingBox, COORD_SIZE_IN_BYTES, Coords, InputRelation, OutputRelation, RelationBetweenShapes,
-    Zerometry, Zoint, Zollection, Zolygon, ZultiPolygons, zine::Zine, zulti_lines::ZultiLines,
+    BoundingBox, COORD_SIZE_IN_BYTES, Coord, Coords, InputRelation, OutputRelation,
+    RelationBetweenShapes, Zerometry, ZerometryError, Zoint, Zollection, Zolygon, ZultiPolygons,
+    aligned_bytes::contiguous,
+    bounding_box::BOUNDING_BOX_SIZE_IN_BYTES,
+    error::{
+        check_aligned, check_bounding_box_and_coords, check_coords, check_empty, checked_size,
+        split_bounding_box, split_checked, split_u64,
+    },
+    topology::relation_with_topology,
+    zine::Zine,
+    zulti_lines::ZultiLines,
 };
 
 /// Equivalent of a [`geo_types::MultiPoint`].
+///
+/// The points can optionally be stored sorted by their Morton code, see [`Self::write_sorted_from_geometry`].
 #[derive(Clone, Copy)]
 pub struct ZultiPoints<'a> {
     bounding_box: &'a BoundingBox,
     coords: &'a Coords,
+    /// When the points are sorted by Morton code, the original index of every stored point.
+    original_indices: Option<&'a [u32]>,
 }
 
 impl<'a> ZultiPoints<'a> {
@@ -21,6 +38,7 @@ impl<'a> ZultiPoints<'a> {
         Self {
             bounding_box,
             coords,
+            original_indices: None,
         }
     }
 
@@ -33,6 +51,13 @@ impl<'a> ZultiPoints<'a> {
         Self::new(bounding_box, coords)
     }
 
+    /// Same as [`Self::from_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_bounding_box_and_coords(data)?;
+        Ok(unsafe { Self::from_bytes(data) })
+    }
+
     /// Convert the specified [`geo_types::MultiPoint`] to a valid [`ZultiPoints`] slice of bytes in the input buffer.
     pub fn write_from_geometry(
         writer: &mut impl Write,
@@ -46,6 +71,181 @@ impl<'a> ZultiPoints<'a> {
         Ok(())
     }
 
+    /// # Safety
+    /// The data must be generated from the [`Self::write_sorted_from_geometry`] method and be aligned on 64 bits
+    #[inline]
+    pub unsafe fn from_sorted_bytes(data: &'a [u8]) -> Self {
+        let (bounding_box, data) = data.split_at(COORD_SIZE_IN_BYTES * 2);
+        let bounding_box = unsafe { BoundingBox::from_bytes(bounding_box) };
+        let (len, data) = data.split_at(mem::size_of::<u64>());
+        let len = u64::from_ne_bytes(len.try_into().unwrap()) as usize;
+        let (coords, data) = data.split_at(len * COORD_SIZE_IN_BYTES);
+        let coords = unsafe { Coords::from_bytes(coords) };
+        let original_indices = cast_slice(&data[..len * mem::size_of::<u32>()]);
+        Self {
+            bounding_box,
+            coords,
+            original_indices: Some(original_indices),
+        }
+    }
+
+    /// Same as [`Self::from_sorted_bytes`] but the data is validated first, which makes it safe to call on untrusted data.
+    /// See [`crate::Zerometry::try_from_bytes`].
+    pub fn try_from_sorted_bytes(data: &'a [u8]) -> Result<Self, ZerometryError> {
+        check_aligned(data)?;
+        split_sorted(data)?;
+        Ok(unsafe { Self::from_sorted_bytes(data) })
+    }
+
+    /// Convert the specified [`geo_types::MultiPoint`] to a valid [`ZultiPoints`] slice of bytes in the input buffer,
+    /// with the points sorted by their Morton code in the bounding box.
+    ///
+    /// It makes [`Self::contains_coord`] run in `O(log n)` instead of a linear scan. The original index of every point
+    /// is also stored so [`Self::to_geo`] and [`Self::points_in_original_order`] still return the points in their original order.
+    /// The layout is different from the one of [`Self::write_from_geometry`] and must be read with [`Self::from_sorted_bytes`]:
+    /// the bounding box, the number of points as a `u64`, the sorted coords, the original indices as `u32` and
+    /// a padding of 4 bytes if the number of points is odd.
+    pub fn write_sorted_from_geometry(
+        writer: &mut impl Write,
+        geometry: &MultiPoint<f64>,
+    ) -> Result<(), io::Error> {
+        let mut buffer = Vec::new();
+        BoundingBox::write_from_geometry(&mut buffer, geometry.iter().copied())?;
+        let bounding_box = unsafe { BoundingBox::from_bytes(&buffer) };
+
+        let mut sorted: Vec<_> = geometry
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let code = morton_code(bounding_box, point.x(), point.y());
+                (code, index as u32, *point)
+            })
+            .collect();
+        sorted.sort_unstable_by_key(|(code, index, _)| (*code, *index));
+
+        writer.write_all(&buffer)?;
+        writer.write_all(&(sorted.len() as u64).to_ne_bytes())?;
+        for (_, _, point) in &sorted {
+            writer.write_all(&point.x().to_ne_bytes())?;
+            writer.write_all(&point.y().to_ne_bytes())?;
+        }
+        for (_, index, _) in &sorted {
+            writer.write_all(&index.to_ne_bytes())?;
+        }
+        if sorted.len() % 2 == 1 {
+            writer.write_all(&0_u32.to_ne_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Write a copy of the multi points containing only the points matching the predicate, in their original order.
+    /// The output uses the layout of [`Self::write_from_geometry`] even if the points were sorted.
+    pub fn retain(
+        &self,
+        mut predicate: impl FnMut(&Zoint<'a>) -> bool,
+        writer: &mut Vec<u8>,
+    ) -> Result<(), io::Error> {
+        let retained: Vec<_> = self
+            .points_in_original_order()
+            .filter(|point| predicate(point))
+            .collect();
+        BoundingBox::write_from_geometry(writer, retained.iter().map(Zoint::to_geo))?;
+        for point in retained {
+            writer.extend_from_slice(&point.lng().to_ne_bytes());
+            writer.extend_from_slice(&point.lat().to_ne_bytes());
+        }
+        Ok(())
+    }
+
+    /// Return `true` if the points are stored sorted by their Morton code.
+    #[inline]
+    pub fn is_sorted(&self) -> bool {
+        self.original_indices.is_some()
+    }
+
+    /// Return `true` if one of the points is exactly at the specified coord.
+    /// It's a binary search if the points are sorted and a linear scan otherwise.
+    pub fn contains_coord(&self, coord: &Coord) -> bool {
+        let same = |other: &Coord| other.lng() == coord.lng() && other.lat() == coord.lat();
+        if self.is_empty() || !self.bounding_box.contains_coord(coord) {
+            return false;
+        }
+        if !self.is_sorted() {
+            return self.coords.iter().any(same);
+        }
+
+        let code = morton_code(self.bounding_box, coord.lng(), coord.lat());
+        let code_of = |index: usize| {
+            let other = &self.coords[index];
+            morton_code(self.bounding_box, other.lng(), other.lat())
+        };
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if code_of(mid) < code {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        (low..self.len())
+            .take_while(|&index| code_of(index) == code)
+            .any(|index| same(&self.coords[index]))
+    }
+
+    /// Return the original index of every stored point, empty if the points are not sorted.
+    #[inline]
+    pub(crate) fn original_indices(&self) -> &'a [u32] {
+        self.original_indices.unwrap_or_default()
+    }
+
+    /// Return the points in the order they were in the original [`geo_types::MultiPoint`].
+    /// It's the same as [`Self::points`] unless the points are sorted.
+    pub fn points_in_original_order(&self) -> impl Iterator<Item = Zoint<'a>> + use<'a> {
+        let coords = self.coords;
+        // The position in the stored points of every original index
+        let positions = self.original_indices.map(|original_indices| {
+            let mut positions = vec![0; original_indices.len()];
+            for (position, &index) in original_indices.iter().enumerate() {
+                positions[index as usize] = position;
+            }
+            positions
+        });
+        (0..self.len()).map(move |index| {
+            let position = positions
+                .as_ref()
+                .map_or(index, |positions| positions[index]);
+            Zoint::new(&coords[position])
+        })
+    }
+
+    /// Return the bytes the multi points were read from, they can be stored and read back with [`Self::from_bytes`],
+    /// or [`Self::from_sorted_bytes`] if they were sorted.
+    ///
+    /// # Panics
+    /// If the multi points were created with [`Self::new`] from parts that don't follow each other in memory.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        let coords = mem::size_of_val(self.coords);
+        match self.original_indices {
+            None => contiguous(
+                self.bounding_box,
+                self.coords,
+                BOUNDING_BOX_SIZE_IN_BYTES + coords,
+                0,
+            ),
+            Some(original_indices) => {
+                let len = BOUNDING_BOX_SIZE_IN_BYTES
+                    + mem::size_of::<u64>()
+                    + coords
+                    + mem::size_of_val(original_indices);
+                // The indices are padded to stay aligned on 64 bits
+                let padding = original_indices.len() % 2 * mem::size_of::<u32>();
+                contiguous(self.bounding_box, original_indices, len, padding)
+            }
+        }
+    }
+
     /// Return the bounding box containing all polygons
     #[inline]
     pub fn bounding_box(&self) -> &'a BoundingBox {
@@ -72,7 +272,7 @@ impl<'a> ZultiPoints<'a> {
     /// Return a zoint by index, if the index doesn't exists, returns None
     #[inline]
     pub fn get(&self, index: usize) -> Option<Zoint<'a>> {
-        if index > self.coords.len() {
+        if index >= self.coords.len() {
             None
         } else {
             let coord = &self.coords()[index];
@@ -81,22 +281,111 @@ impl<'a> ZultiPoints<'a> {
     }
 
     /// Returns the individual [`Zoint`]s that compose the [`ZultiPoints`]
+    /// They're always returned in the order they're stored, which is also the order of their indices in [`Self::get`].
+    /// It's the order they were written unless they're [sorted](Self::is_sorted), see [`Self::points_in_original_order`].
     #[inline]
     pub fn points(&'a self) -> impl Iterator<Item = Zoint<'a>> {
         (0..self.len()).map(move |index| self.get(index).unwrap())
     }
 
+    /// Count the points falling in each cell of a grid of `cell_size` by `cell_size` anchored at `(0, 0)`.
+    /// The cells are identified by their `(column, row)`, the cell `(0, 0)` goes from `(0, 0)` included to
+    /// `(cell_size, cell_size)` excluded. Only the non-empty cells are returned.
+    ///
+    /// If a polygon is specified, only the points contained in it are counted.
+    ///
+    /// # Panics
+    /// If the `cell_size` is not strictly positive.
+    pub fn point_density_grid(
+        &self,
+        cell_size: f64,
+        clip: Option<&Zolygon>,
+    ) -> BTreeMap<(i64, i64), usize> {
+        assert!(cell_size > 0.0, "The cell size must be strictly positive");
+        let mut grid = BTreeMap::new();
+
+        if let Some(clip) = clip
+            && clip.bounding_box().disjoint(self.bounding_box())
+        {
+            return grid;
+        }
+
+        for coord in self.coords.iter() {
+            if clip.is_some_and(|clip| !clip.contains(coord)) {
+                continue;
+            }
+            let cell = (
+                (coord.lng() / cell_size).floor() as i64,
+                (coord.lat() / cell_size).floor() as i64,
+            );
+            *grid.entry(cell).or_default() += 1;
+        }
+
+        grid
+    }
+
     /// Convert the [`ZultiPoints`] back to a [`geo_types::MultiPoint`].
+    /// The points are returned in their original order, even if they're stored sorted.
     pub fn to_geo(&self) -> geo_types::MultiPoint<f64> {
         geo_types::MultiPoint::new(
-            self.coords
-                .iter()
-                .map(|coord| geo_types::Point::new(coord.lng(), coord.lat()))
+            self.points_in_original_order()
+                .map(|point| point.to_geo())
                 .collect(),
         )
     }
 }
 
+/// Validate the layout of the sorted multi points and return the bytes of the coords and of the original indices.
+/// The data doesn't need to be aligned.
+pub(crate) fn split_sorted(data: &[u8]) -> Result<(&[u8], &[u8]), ZerometryError> {
+    let rest = split_bounding_box(data)?;
+    let (len, rest) = split_u64(rest)?;
+    let (coords, rest) = split_checked(rest, checked_size(len, COORD_SIZE_IN_BYTES, rest)?)?;
+    check_coords(coords)?;
+    let len = len as usize;
+    let (original_indices, rest) = split_checked(rest, len * mem::size_of::<u32>())?;
+    let padding = if len % 2 == 1 {
+        mem::size_of::<u32>()
+    } else {
+        0
+    };
+    let (_padding, rest) = split_checked(rest, padding)?;
+    check_empty(rest)?;
+    if let Some(index) = original_indices
+        .chunks_exact(mem::size_of::<u32>())
+        .map(|index| u32::from_ne_bytes(index.try_into().unwrap()) as usize)
+        .find(|&index| index >= len)
+    {
+        return Err(ZerometryError::InvalidIndex { index, len });
+    }
+    Ok((coords, original_indices))
+}
+
+/// Compute the Morton code of a coordinate, the coordinates are first scaled to a `u32` in the bounding box
+/// and their bits are then interleaved.
+fn morton_code(bounding_box: &BoundingBox, lng: f64, lat: f64) -> u64 {
+    fn scale(value: f64, min: f64, max: f64) -> u64 {
+        if max > min {
+            ((value - min) / (max - min) * u32::MAX as f64) as u32 as u64
+        } else {
+            0
+        }
+    }
+
+    fn spread(value: u64) -> u64 {
+        let mut value = value;
+        value = (value | (value << 16)) & 0x0000_ffff_0000_ffff;
+        value = (value | (value << 8)) & 0x00ff_00ff_00ff_00ff;
+        value = (value | (value << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+        value = (value | (value << 2)) & 0x3333_3333_3333_3333;
+        (value | (value << 1)) & 0x5555_5555_5555_5555
+    }
+
+    let x = scale(lng, bounding_box.left(), bounding_box.right());
+    let y = scale(lat, bounding_box.bottom(), bounding_box.top());
+    spread(x) | (spread(y) << 1)
+}
+
 impl<'a> fmt::Debug for ZultiPoints<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ZultiPoints")
@@ -109,31 +398,93 @@ impl<'a> fmt::Debug for ZultiPoints<'a> {
     }
 }
 
-// A point cannot contains or intersect with anything
+// The multi points are compared member-wise: they contain each other as soon as they share a point and
+// one of them is strictly contained in the other when all its points are shared.
+// They only intersect when they share some of their points but neither is strictly contained in the other.
 impl<'a> RelationBetweenShapes<ZultiPoints<'a>> for ZultiPoints<'a> {
-    fn relation(&self, _other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &ZultiPoints<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        let output = relation.to_false();
+        if self.is_empty() || other.is_empty() || self.bounding_box().disjoint(other.bounding_box())
+        {
+            return output.make_disjoint_if_set();
+        }
+
+        let shared = other
+            .coords()
+            .iter()
+            .filter(|coord| self.contains_coord(coord))
+            .count();
+        if shared == 0 {
+            return output.make_disjoint_if_set();
+        }
+        let mut output = output.make_contains_if_set().make_contained_if_set();
+        if relation.early_exit {
+            return output;
+        }
+
+        let all_of_other = shared == other.len();
+        let all_of_self = (relation.strict_contained || relation.intersect)
+            && self
+                .coords()
+                .iter()
+                .all(|coord| other.contains_coord(coord));
+        if all_of_other {
+            output = output.make_strict_contains_if_set();
+        }
+        if all_of_self {
+            output = output.make_strict_contained_if_set();
+        }
+        if !all_of_other && !all_of_self {
+            output = output.make_intersect_if_set();
+        }
+        output
     }
 }
 
-// A point cannot contains or intersect with anything
+// The point is compared like a multi points with a single member: when it's one of the points they contain each
+// other, and the multi points is strictly contained in the point when all its points are the same.
 impl<'a> RelationBetweenShapes<Zoint<'a>> for ZultiPoints<'a> {
-    fn relation(&self, _other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &Zoint<'a>, relation: InputRelation) -> OutputRelation {
+        if relation.needs_topology() {
+            return relation_with_topology(self, other, relation);
+        }
+        let output = relation.to_false();
+        if !self.contains_coord(other.coord()) {
+            return output.make_disjoint_if_set();
+        }
+        let output = output.make_strict_contains_if_set().make_contained_if_set();
+        if relation.early_exit || !relation.strict_contained {
+            return output;
+        }
+
+        if self
+            .coords()
+            .iter()
+            .all(|coord| coord.lng() == other.lng() && coord.lat() == other.lat())
+        {
+            output.make_strict_contained_if_set()
+        } else {
+            output
+        }
     }
 }
 
-// A point cannot contains or intersect with anything
 impl<'a> RelationBetweenShapes<Zine<'a>> for ZultiPoints<'a> {
-    fn relation(&self, _other: &Zine<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &Zine<'a>, relation: InputRelation) -> OutputRelation {
+        other
+            .relation(self, relation.swap_contains_relation())
+            .swap_contains_relation()
     }
 }
 
-// A point cannot contains or intersect with anything
 impl<'a> RelationBetweenShapes<ZultiLines<'a>> for ZultiPoints<'a> {
-    fn relation(&self, _other: &ZultiLines<'a>, relation: InputRelation) -> OutputRelation {
-        relation.to_false().make_disjoint_if_set()
+    fn relation(&self, other: &ZultiLines<'a>, relation: InputRelation) -> OutputRelation {
+        other
+            .relation(self, relation.swap_contains_relation())
+            .swap_contains_relation()
     }
 }
 
@@ -171,8 +522,7 @@ impl<'a> RelationBetweenShapes<Zerometry<'a>> for ZultiPoints<'a> {
 
 impl<'a> PartialEq<MultiPoint<f64>> for ZultiPoints<'a> {
     fn eq(&self, other: &MultiPoint<f64>) -> bool {
-        self.coords
-            .iter()
+        self.points_in_original_order()
             .zip(other.iter())
             .all(|(a, b)| a.lng() == b.x() && a.lat() == b.y())
     }
@@ -181,7 +531,7 @@ impl<'a> PartialEq<MultiPoint<f64>> for ZultiPoints<'a> {
 #[cfg(test)]
 mod tests {
     use bytemuck::cast_slice;
-    use geo_types::Point;
+    use geo_types::{Point, polygon};
     use insta::assert_compact_debug_snapshot;
 
     use super::*;
@@ -197,8 +547,141 @@ mod tests {
         let input: &[f64] = cast_slice(&buffer);
         assert_compact_debug_snapshot!(input, @"[1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 3.0, 4.0]");
         let zulti_points = unsafe { ZultiPoints::from_bytes(&buffer) };
-        assert_compact_debug_snapshot!(zulti_points.bounding_box(), @"BoundingBox { bottom_left: Coord { x: 1.0, y: 2.0 }, top_right: Coord { x: 3.0, y: 4.0 } }");
-        assert_compact_debug_snapshot!(zulti_points.coords(), @"[Coord { x: 1.0, y: 2.0 }, Coord { x: 3.0, y: 4.0 }]");
+        assert_compact_debug_snapshot!(zulti_points.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 1.0, lat: 2.0 }, top_right: Coord { lng: 3.0, lat: 4.0 } }");
+        assert_compact_debug_snapshot!(zulti_points.coords(), @"[Coord { lng: 1.0, lat: 2.0 }, Coord { lng: 3.0, lat: 4.0 }]");
+    }
+
+    #[test]
+    fn test_zulti_points_sorted_binary_format() {
+        let mut buffer = Vec::new();
+        ZultiPoints::write_sorted_from_geometry(
+            &mut buffer,
+            &MultiPoint::from(vec![(3.0, 4.0), (1.0, 2.0), (3.0, 2.0)]),
+        )
+        .unwrap();
+        let bounding_box: &[f64] = cast_slice(&buffer[..32]);
+        assert_compact_debug_snapshot!(bounding_box, @"[1.0, 2.0, 3.0, 4.0]");
+        let len: &[u64] = cast_slice(&buffer[32..40]);
+        assert_compact_debug_snapshot!(len, @"[3]");
+        let coords: &[f64] = cast_slice(&buffer[40..88]);
+        assert_compact_debug_snapshot!(coords, @"[1.0, 2.0, 3.0, 2.0, 3.0, 4.0]");
+        // The original indices followed by the padding
+        let indices: &[u32] = cast_slice(&buffer[88..]);
+        assert_compact_debug_snapshot!(indices, @"[1, 2, 0, 0]");
+        let zulti_points = unsafe { ZultiPoints::from_sorted_bytes(&buffer) };
+        assert!(zulti_points.is_sorted());
+        assert_compact_debug_snapshot!(zulti_points.coords(), @"[Coord { lng: 1.0, lat: 2.0 }, Coord { lng: 3.0, lat: 2.0 }, Coord { lng: 3.0, lat: 4.0 }]");
+        assert_compact_debug_snapshot!(zulti_points.to_geo(), @"MULTIPOINT(3.0 4.0,1.0 2.0,3.0 2.0)");
+    }
+
+    #[test]
+    fn test_point_density_grid() {
+        let mut buffer = Vec::new();
+        ZultiPoints::write_from_geometry(
+            &mut buffer,
+            &MultiPoint::from(vec![
+                (0.5, 0.5),
+                (0.7, 0.2),
+                (1.5, 0.5),
+                (-0.5, 3.2),
+                (9.0, 9.0),
+            ]),
+        )
+        .unwrap();
+        let zulti_points = unsafe { ZultiPoints::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(zulti_points.point_density_grid(1.0, None), @"{(-1, 3): 1, (0, 0): 2, (1, 0): 1, (9, 9): 1}");
+        assert_compact_debug_snapshot!(zulti_points.point_density_grid(10.0, None), @"{(-1, 0): 1, (0, 0): 4}");
+
+        let mut buffer = Vec::new();
+        Zolygon::write_from_geometry(
+            &mut buffer,
+            &polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 0., y: 2.)],
+        )
+        .unwrap();
+        let clip = unsafe { Zolygon::from_bytes(&buffer) };
+        assert_compact_debug_snapshot!(zulti_points.point_density_grid(1.0, Some(&clip)), @"{(0, 0): 2, (1, 0): 1}");
+    }
+
+    #[test]
+    fn test_retain() {
+        let multi_point = MultiPoint::from(vec![(3.0, 4.0), (1.0, 2.0), (3.0, 2.0), (0.0, 0.0)]);
+        let mut buffer = Vec::new();
+        ZultiPoints::write_sorted_from_geometry(&mut buffer, &multi_point).unwrap();
+        let zulti_points = unsafe { ZultiPoints::from_sorted_bytes(&buffer) };
+
+        let mut retained = Vec::new();
+        zulti_points
+            .retain(|point| point.lng() > 0.0, &mut retained)
+            .unwrap();
+        let retained = unsafe { ZultiPoints::from_bytes(&retained) };
+        assert_compact_debug_snapshot!(retained.bounding_box(), @"BoundingBox { bottom_left: Coord { lng: 1.0, lat: 2.0 }, top_right: Coord { lng: 3.0, lat: 4.0 } }");
+        assert_compact_debug_snapshot!(retained.to_geo(), @"MULTIPOINT(3.0 4.0,1.0 2.0,3.0 2.0)");
+    }
+
+    #[test]
+    fn test_relation_with_every_shape() {
+        let mut buffer = Vec::new();
+        let multi_point = MultiPoint::from(vec![(1.0, 1.0), (10.0, 10.0)]);
+        ZultiPoints::write_from_geometry(&mut buffer, &multi_point).unwrap();
+        let zulti_points = unsafe { ZultiPoints::from_bytes(&buffer) };
+
+        let square = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 4.)];
+        let line = geo_types::LineString::from(vec![(0.0, 0.0), (2.0, 2.0)]);
+        let shapes: [geo_types::Geometry<f64>; 7] = [
+            Point::new(1.0, 1.0).into(),
+            multi_point.clone().into(),
+            line.clone().into(),
+            geo_types::MultiLineString::new(vec![line]).into(),
+            square.clone().into(),
+            geo_types::MultiPolygon::new(vec![square.clone()]).into(),
+            geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection::new_from(vec![
+                square.into(),
+            ])),
+        ];
+        let relations: Vec<_> = shapes
+            .iter()
+            .map(|shape| {
+                let mut buffer = Vec::new();
+                Zerometry::write_from_geometry(&mut buffer, shape).unwrap();
+                let shape = Zerometry::try_from_bytes(&buffer).unwrap();
+                let relation = zulti_points.all_relation(&shape);
+                assert_eq!(
+                    relation,
+                    Zerometry::MultiPoints(zulti_points).all_relation(&shape)
+                );
+                (
+                    relation.contained,
+                    relation.strict_contained,
+                    relation.disjoint,
+                )
+            })
+            .collect();
+        // The point, lines and polygons contain some of the points, and the same multi points shares all of them
+        assert_compact_debug_snapshot!(relations, @"[(Some(true), Some(false), Some(false)), (Some(true), Some(true), Some(false)), (Some(true), Some(false), Some(false)), (Some(true), Some(false), Some(false)), (Some(true), Some(false), Some(false)), (Some(true), Some(false), Some(false)), (Some(true), Some(false), Some(false))]");
+    }
+
+    #[test]
+    fn test_shared_members() {
+        let relation = |left: Vec<(f64, f64)>, right: Vec<(f64, f64)>| {
+            let mut left_buffer = Vec::new();
+            ZultiPoints::write_sorted_from_geometry(&mut left_buffer, &MultiPoint::from(left))
+                .unwrap();
+            let left = unsafe { ZultiPoints::from_sorted_bytes(&left_buffer) };
+            let mut right_buffer = Vec::new();
+            ZultiPoints::write_from_geometry(&mut right_buffer, &MultiPoint::from(right)).unwrap();
+            let right = unsafe { ZultiPoints::from_bytes(&right_buffer) };
+            let output = left.all_relation(&right);
+            assert_eq!(output, right.all_relation(&left).swap_contains_relation());
+            output
+        };
+        // All the members of the right one are in the left one
+        assert_compact_debug_snapshot!(relation(vec![(1.0, 1.0), (2.0, 2.0), (3.0, 3.0)], vec![(3.0, 3.0), (1.0, 1.0)]), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(true), strict_contained: Some(false), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        // They share only one of their members
+        assert_compact_debug_snapshot!(relation(vec![(1.0, 1.0), (2.0, 2.0)], vec![(2.0, 2.0), (3.0, 3.0)]), @"OutputRelation { contains: Some(true), strict_contains: Some(false), contained: Some(true), strict_contained: Some(false), intersect: Some(true), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        // Same members, the duplicates don't matter
+        assert_compact_debug_snapshot!(relation(vec![(1.0, 1.0), (2.0, 2.0)], vec![(2.0, 2.0), (1.0, 1.0), (2.0, 2.0)]), @"OutputRelation { contains: Some(true), strict_contains: Some(true), contained: Some(true), strict_contained: Some(true), intersect: Some(false), disjoint: Some(false), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
+        // Overlapping bounding boxes but nothing in common
+        assert_compact_debug_snapshot!(relation(vec![(1.0, 1.0), (3.0, 3.0)], vec![(2.0, 2.0)]), @"OutputRelation { contains: Some(false), strict_contains: Some(false), contained: Some(false), strict_contained: Some(false), intersect: Some(false), disjoint: Some(true), overlaps: Some(false), touches: None, crosses: None, covers: None, covered_by: None, approximate: false }");
     }
 
     // Prop test ensuring we can round trip from a multi-point to a zulti-points and back to a multi-point